@@ -0,0 +1,45 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use meteora_fee_router::state::{DistributionProgress, PolicyConfig};
+use meteora_fee_router_client::{QuoteLedger, VaultAccounts};
+
+use crate::error::CrankResult;
+
+/// A vault's on-chain state as seen by the crank
+#[derive(Clone)]
+pub struct VaultState {
+    pub policy_config: PolicyConfig,
+    /// Addresses of the cranked quote mint's treasury and day ledger
+    pub ledger: QuoteLedger,
+    /// The cranked quote mint's day ledger
+    pub progress: DistributionProgress,
+    /// Unix timestamp of the cluster clock
+    pub cluster_time: i64,
+}
+
+/// Outcome of simulating a page without submitting it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimulationReport {
+    pub error: Option<String>,
+    pub logs: Vec<String>,
+    pub units_consumed: Option<u64>,
+}
+
+/// The cluster operations the crank relies on
+///
+/// `RpcChain` talks to a real cluster (or `solana-test-validator`); tests substitute an
+/// in-memory implementation.
+pub trait Chain {
+    /// Fetch the vault's policy, the day ledger of `quote_mint` (the policy's primary quote
+    /// mint when `None`) and the cluster time
+    fn vault_state(&self, vault_accounts: &VaultAccounts, quote_mint: Option<Pubkey>) -> CrankResult<VaultState>;
+
+    /// Whether each address holds an account
+    fn accounts_exist(&self, addresses: &[Pubkey]) -> CrankResult<Vec<bool>>;
+
+    /// Sign and submit the instruction, returning the transaction signature
+    fn send(&self, instruction: Instruction) -> CrankResult<String>;
+
+    /// Simulate the instruction against the current cluster state
+    fn simulate(&self, instruction: Instruction) -> CrankResult<SimulationReport>;
+}
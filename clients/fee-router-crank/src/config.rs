@@ -0,0 +1,108 @@
+use anchor_lang::prelude::Pubkey;
+use serde::Deserialize;
+use std::{path::Path, str::FromStr, time::Duration};
+
+use crate::error::{CrankError, CrankResult};
+
+/// An entry of the investors file
+///
+/// The file is a JSON array of `{ "wallet": "...", "weight_accounts": ["..."] }`; the
+/// weight accounts are the investor's Streamflow (or stake) accounts.
+#[derive(Debug, Clone, Deserialize)]
+pub struct InvestorEntry {
+    pub wallet: String,
+    pub weight_accounts: Vec<String>,
+}
+
+/// An investor with its weight accounts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Investor {
+    pub wallet: Pubkey,
+    pub weight_accounts: Vec<Pubkey>,
+}
+
+/// Settings of a crank run
+#[derive(Debug, Clone)]
+pub struct CrankSettings {
+    pub vault: Pubkey,
+    /// Quote mint whose day ledger is cranked (the policy's primary quote mint when unset)
+    pub quote_mint: Option<Pubkey>,
+    /// Signer paying for and submitting the crank transactions
+    pub crank_caller: Pubkey,
+    /// Simulate pages instead of submitting them
+    pub dry_run: bool,
+    /// Attempts per page before giving up
+    pub max_retries: u32,
+    pub retry_delay: Duration,
+    /// Longest sleep between checks of the distribution window
+    pub poll_interval: Duration,
+    /// Transaction account slots kept free for other instructions
+    pub reserved_accounts: usize,
+    /// Pool the creator share is bought back through, required when the policy's creator
+    /// payout mode is buyback and burn
+    pub buyback_pool: Option<BuybackPool>,
+    /// Pass the caller's quote ATA so pages pay the policy's crank reward (the ATA must exist)
+    pub claim_crank_reward: bool,
+}
+
+/// A pool trading the policy's base mint against the cranked quote mint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuybackPool {
+    pub pool: Pubkey,
+    pub base_vault: Pubkey,
+    pub quote_vault: Pubkey,
+}
+
+fn parse_pubkey(value: &str) -> CrankResult<Pubkey> {
+    Pubkey::from_str(value).map_err(|error| CrankError::Config(format!("invalid pubkey {}: {}", value, error)))
+}
+
+/// Parse the investors file contents
+pub fn parse_investors(contents: &str) -> CrankResult<Vec<Investor>> {
+    let entries: Vec<InvestorEntry> = serde_json::from_str(contents)
+        .map_err(|error| CrankError::Config(format!("invalid investors file: {}", error)))?;
+
+    entries
+        .iter()
+        .map(|entry| {
+            Ok(Investor {
+                wallet: parse_pubkey(&entry.wallet)?,
+                weight_accounts: entry
+                    .weight_accounts
+                    .iter()
+                    .map(|address| parse_pubkey(address))
+                    .collect::<CrankResult<Vec<_>>>()?,
+            })
+        })
+        .collect()
+}
+
+/// Load the investors file
+pub fn load_investors(path: &Path) -> CrankResult<Vec<Investor>> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|error| CrankError::Config(format!("cannot read {}: {}", path.display(), error)))?;
+    parse_investors(&contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_investors() {
+        let wallet = Pubkey::new_unique();
+        let stream = Pubkey::new_unique();
+        let contents = format!(r#"[{{ "wallet": "{}", "weight_accounts": ["{}"] }}]"#, wallet, stream);
+
+        let investors = parse_investors(&contents).unwrap();
+
+        assert_eq!(investors, vec![Investor { wallet, weight_accounts: vec![stream] }]);
+    }
+
+    #[test]
+    fn test_parse_investors_rejects_bad_keys() {
+        let contents = r#"[{ "wallet": "not-a-key", "weight_accounts": [] }]"#;
+        assert!(matches!(parse_investors(contents), Err(CrankError::Config(_))));
+        assert!(matches!(parse_investors("{}"), Err(CrankError::Config(_))));
+    }
+}
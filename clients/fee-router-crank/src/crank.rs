@@ -0,0 +1,638 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use meteora_fee_router::state::{CrankReward, CreatorPayoutMode, DistributionProgress};
+use meteora_fee_router_client::{
+    create_quote_ata, distribute_fees, investor_registry, paginate_investors, quote_ata, BuybackKeys,
+    DistributeFeesKeys, DistributionPage, InvestorAccounts, PageLimits, VaultAccounts,
+};
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+use crate::{
+    chain::{Chain, SimulationReport, VaultState},
+    config::{CrankSettings, Investor},
+    error::{CrankError, CrankResult},
+};
+
+/// Result of one pass of the crank over a vault
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DayOutcome {
+    /// The next distribution window opens in `seconds`
+    Waiting { seconds: i64 },
+    /// The last page landed and the day is complete
+    Completed { pages: usize },
+    /// The day opened but nothing was paged (no fees or no investor distribution)
+    NothingToDistribute,
+    /// Dry run: the next pending page was simulated, the rest only planned
+    DryRun {
+        simulation: SimulationReport,
+        pending_pages: Vec<DistributionPage>,
+    },
+}
+
+/// Drives a vault's `distribute_fees` pagination
+pub struct Crank<C: Chain> {
+    chain: C,
+    settings: CrankSettings,
+    vault_accounts: VaultAccounts,
+    investors: Vec<Investor>,
+    /// Day (by `last_distribution_ts`) found to have nothing to page, so the crank waits
+    /// for the next window instead of reopening it every poll
+    idle_day_ts: Option<i64>,
+}
+
+impl<C: Chain> Crank<C> {
+    pub fn new(chain: C, settings: CrankSettings, investors: Vec<Investor>) -> CrankResult<Self> {
+        if investors.is_empty() {
+            return Err(CrankError::Config("investors file lists no investors".to_string()));
+        }
+
+        Ok(Self {
+            chain,
+            vault_accounts: VaultAccounts::new(settings.vault),
+            settings,
+            investors,
+            idle_day_ts: None,
+        })
+    }
+
+    pub fn chain(&self) -> &C {
+        &self.chain
+    }
+
+    /// Run until stopped, sleeping until each distribution window opens
+    pub fn run(&mut self) -> CrankResult<()> {
+        loop {
+            let outcome = self.run_day()?;
+            let sleep_secs = match outcome {
+                DayOutcome::Waiting { seconds } => seconds,
+                DayOutcome::DryRun { .. } => return Ok(()),
+                DayOutcome::Completed { .. } | DayOutcome::NothingToDistribute => 0,
+            };
+            let sleep = Duration::from_secs(sleep_secs.max(1) as u64).min(self.settings.poll_interval);
+            std::thread::sleep(sleep);
+        }
+    }
+
+    /// One pass: wait for the window, or drive the day's pagination to completion
+    pub fn run_day(&mut self) -> CrankResult<DayOutcome> {
+        let state = self.chain.vault_state(&self.vault_accounts, self.settings.quote_mint)?;
+        let now = state.cluster_time;
+        let period_secs = state.policy_config.distribution_period_secs;
+        let progress = &state.progress;
+
+        let opens_day = progress.can_start_new_day(now, period_secs);
+        let idle = self.idle_day_ts == Some(progress.last_distribution_ts);
+        if !opens_day && (idle || !progress.can_continue_same_day(now, period_secs)) {
+            return Ok(DayOutcome::Waiting {
+                seconds: progress.time_until_next_distribution(now, period_secs),
+            });
+        }
+
+        // Opening the next day carries what an unfinished day left unpaid
+        if let (true, Ok(Some(unfinished))) = (opens_day, progress.unfinished_day()) {
+            log::warn!("Day {} was left at cursor {}: carrying {} to investors, {} to the creator, {} of claim",
+                       unfinished.epoch_index, unfinished.pagination_cursor, unfinished.investor_carried,
+                       unfinished.creator_carried, unfinished.claim_carried);
+        }
+
+        let start_cursor = if opens_day { 0 } else { progress.pagination_cursor };
+        let pending = self.plan_day(&state, start_cursor)?;
+
+        if self.settings.dry_run {
+            let simulation = match pending.first() {
+                Some(page) => self.chain.simulate(self.page_instruction(&state, page))?,
+                None => SimulationReport { error: None, logs: Vec::new(), units_consumed: None },
+            };
+            return Ok(DayOutcome::DryRun { simulation, pending_pages: pending });
+        }
+
+        // Pages pay investors into their quote ATAs, which must exist first
+        self.create_missing_quote_atas(&state)?;
+
+        let mut state = state;
+        for (index, page) in pending.iter().enumerate() {
+            self.submit_page(&state, page, opens_day && index == 0)?;
+
+            state = self.chain.vault_state(&self.vault_accounts, self.settings.quote_mint)?;
+            if state.progress.day_complete {
+                return Ok(DayOutcome::Completed { pages: index + 1 });
+            }
+            if state.progress.pagination_cursor <= page.cursor {
+                break;
+            }
+        }
+
+        log::info!("Day opened at {} has nothing left to page", state.progress.last_distribution_ts);
+        self.idle_day_ts = Some(state.progress.last_distribution_ts);
+        Ok(DayOutcome::NothingToDistribute)
+    }
+
+    /// Pages of the day still to run from `start_cursor`
+    fn plan_day(&self, state: &VaultState, start_cursor: u32) -> CrankResult<Vec<DistributionPage>> {
+        let ledger = &state.ledger;
+
+        // Without a priced buyback pool the day still closes, holding the creator share
+        if state.policy_config.creator_payout_mode == CreatorPayoutMode::BuybackAndBurn
+            && self.buyback_keys(state).is_none()
+        {
+            log::warn!("No priced buyback pool: the day's creator share is held for a later day");
+        }
+
+        let registry = investor_registry(
+            &self.investors
+                .iter()
+                .map(|investor| InvestorAccounts {
+                    wallet: investor.wallet,
+                    weight_accounts: investor.weight_accounts.clone(),
+                })
+                .collect::<Vec<_>>(),
+        );
+
+        // The crank reward is paid into the caller's quote ATA, which the program cannot create
+        if self.claims_crank_reward(state) {
+            let reward_ata = quote_ata(&self.settings.crank_caller, &ledger.quote_mint);
+            if !self.chain.accounts_exist(&[reward_ata])?[0] {
+                return Err(CrankError::Config(format!(
+                    "crank reward ATA {} does not exist: create it or stop claiming the crank reward",
+                    reward_ata
+                )));
+            }
+        }
+
+        let limits = PageLimits {
+            reserved_accounts: self.settings.reserved_accounts,
+            optional_accounts: self.distribute_fees_keys(state).optional_account_count(),
+            ..PageLimits::default()
+        };
+        Ok(paginate_investors(
+            ledger,
+            &state.policy_config.honorary_positions_for(&ledger.quote_mint),
+            &registry,
+            start_cursor,
+            limits,
+        )?)
+    }
+
+    /// Create the investors' quote ATAs that do not exist yet, paid for by the crank caller
+    fn create_missing_quote_atas(&self, state: &VaultState) -> CrankResult<()> {
+        let quote_mint = state.ledger.quote_mint;
+        let wallets: Vec<Pubkey> = self.investors
+            .iter()
+            .map(|investor| investor.wallet)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+
+        let addresses: Vec<Pubkey> = wallets.iter().map(|wallet| quote_ata(wallet, &quote_mint)).collect();
+        let exists = self.chain.accounts_exist(&addresses)?;
+        for (wallet, _) in wallets.iter().zip(exists).filter(|(_, exists)| !exists) {
+            self.chain.send(create_quote_ata(self.settings.crank_caller, *wallet, quote_mint))?;
+        }
+        Ok(())
+    }
+
+    fn page_instruction(&self, state: &VaultState, page: &DistributionPage) -> Instruction {
+        distribute_fees(&self.vault_accounts, &state.ledger, &self.distribute_fees_keys(state), page)
+    }
+
+    /// Named accounts of the day's pages
+    fn distribute_fees_keys(&self, state: &VaultState) -> DistributeFeesKeys {
+        DistributeFeesKeys {
+            crank_caller: self.settings.crank_caller,
+            creator_wallet: state.policy_config.creator_wallet,
+            base_fee_destination: self.vault_accounts.base_fee_destination(&state.policy_config),
+            buyback: self.buyback_keys(state),
+            crank_reward: self.claims_crank_reward(state),
+        }
+    }
+
+    /// Whether pages claim the crank reward: the crank is set to and the policy pays one
+    fn claims_crank_reward(&self, state: &VaultState) -> bool {
+        self.settings.claim_crank_reward && state.policy_config.crank_reward != CrankReward::None
+    }
+
+    /// Buyback accounts of the configured pool, if the policy buys back the creator share
+    /// and the authority has set the pool's reference price
+    fn buyback_keys(&self, state: &VaultState) -> Option<BuybackKeys> {
+        if state.policy_config.creator_payout_mode != CreatorPayoutMode::BuybackAndBurn {
+            return None;
+        }
+        let buyback_pool = self.settings.buyback_pool?;
+        let priced = state.policy_config
+            .honorary_pools()
+            .iter()
+            .any(|honorary_pool| honorary_pool.pool == buyback_pool.pool && honorary_pool.reference_price_q64 > 0);
+        priced.then_some(BuybackKeys {
+            base_mint: state.policy_config.base_mint,
+            pool: buyback_pool.pool,
+            pool_base_vault: buyback_pool.base_vault,
+            pool_quote_vault: buyback_pool.quote_vault,
+        })
+    }
+
+    /// Submit a page, retrying failed sends
+    ///
+    /// The page pins its `cursor_position`, so a resend of a page that already landed is
+    /// a no-op on-chain; the progress account is still checked first to save the transaction.
+    fn submit_page(
+        &self,
+        state: &VaultState,
+        page: &DistributionPage,
+        opens_day: bool,
+    ) -> CrankResult<()> {
+        let day_ts = state.progress.last_distribution_ts;
+        let instruction = self.page_instruction(state, page);
+        let mut last_error = String::new();
+
+        for attempt in 1..=self.settings.max_retries {
+            match self.chain.send(instruction.clone()) {
+                Ok(signature) => {
+                    log::info!("Page {}..{} landed: {}", page.cursor, page.cursor + page.page_size, signature);
+                    return Ok(());
+                }
+                Err(error) => {
+                    log::warn!("Page at cursor {} failed (attempt {}/{}): {}",
+                               page.cursor, attempt, self.settings.max_retries, error);
+                    last_error = error.to_string();
+
+                    // A send can fail after the transaction landed
+                    if let Ok(current) = self.chain.vault_state(&self.vault_accounts, self.settings.quote_mint) {
+                        if page_landed(&current.progress, page, opens_day, day_ts) {
+                            return Ok(());
+                        }
+                    }
+                    if attempt < self.settings.max_retries {
+                        std::thread::sleep(self.settings.retry_delay);
+                    }
+                }
+            }
+        }
+
+        Err(CrankError::PageFailed {
+            cursor: page.cursor,
+            attempts: self.settings.max_retries,
+            last_error,
+        })
+    }
+}
+
+/// Whether the progress account shows the page was processed
+fn page_landed(progress: &DistributionProgress, page: &DistributionPage, opens_day: bool, day_ts: i64) -> bool {
+    if opens_day {
+        progress.last_distribution_ts != day_ts
+    } else {
+        progress.last_distribution_ts == day_ts
+            && (progress.pagination_cursor > page.cursor || progress.day_complete)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::BuybackPool;
+    use anchor_lang::AnchorDeserialize;
+    use meteora_fee_router::{
+        constants::*,
+        state::{CrankReward, CreatorPayoutMode, HonoraryPool, PolicyConfig},
+        DistributeFeesParams,
+    };
+    use std::{cell::RefCell, collections::HashSet};
+
+    /// How the mock handles the next sends
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    enum SendFault {
+        /// Reject the transaction
+        Drop,
+        /// Process the transaction but report a failure
+        LandThenFail,
+    }
+
+    /// In-memory cluster applying the program's pagination rules to the progress account
+    struct MockChain {
+        state: RefCell<VaultState>,
+        investor_count: u32,
+        existing: RefCell<HashSet<Pubkey>>,
+        /// Whether opening a day pages investors (false models a day without fees)
+        has_fees: bool,
+        faults: RefCell<Vec<SendFault>>,
+        sent: RefCell<Vec<DistributeFeesParams>>,
+        simulated: RefCell<usize>,
+    }
+
+    impl MockChain {
+        fn apply(&self, params: &DistributeFeesParams) -> CrankResult<()> {
+            let mut state = self.state.borrow_mut();
+            let now = state.cluster_time;
+            let period_secs = state.policy_config.distribution_period_secs;
+            let epoch_anchor_ts = state.policy_config.epoch_anchor_ts;
+            let progress = &mut state.progress;
+            let timing = progress
+                .prepare_for_distribution(now, period_secs, epoch_anchor_ts)
+                .map_err(|error| CrankError::Rpc(error.to_string()))?;
+
+            if let Some(cursor) = params.cursor_position {
+                let is_retry = progress
+                    .validate_cursor_for_retry(cursor)
+                    .map_err(|error| CrankError::Rpc(error.to_string()))?;
+                if is_retry {
+                    return Ok(());
+                }
+            }
+            if matches!(timing, meteora_fee_router::state::DistributionTimingState::NewDay) && !self.has_fees {
+                return Ok(());
+            }
+
+            let cursor = (progress.pagination_cursor + params.page_size).min(self.investor_count);
+            progress.pagination_cursor = cursor;
+            if cursor >= self.investor_count {
+                progress.complete_day();
+            }
+            Ok(())
+        }
+    }
+
+    impl Chain for MockChain {
+        fn vault_state(&self, _vault_accounts: &VaultAccounts, _quote_mint: Option<Pubkey>) -> CrankResult<VaultState> {
+            Ok(self.state.borrow().clone())
+        }
+
+        fn accounts_exist(&self, addresses: &[Pubkey]) -> CrankResult<Vec<bool>> {
+            let existing = self.existing.borrow();
+            Ok(addresses.iter().map(|address| existing.contains(address)).collect())
+        }
+
+        fn send(&self, instruction: Instruction) -> CrankResult<String> {
+            // Quote ATA creations only add the account
+            if instruction.program_id != meteora_fee_router::ID {
+                self.existing.borrow_mut().insert(instruction.accounts[1].pubkey);
+                return Ok("signature".to_string());
+            }
+
+            let params = DistributeFeesParams::try_from_slice(&instruction.data[8..]).unwrap();
+            let fault = {
+                let mut faults = self.faults.borrow_mut();
+                if faults.is_empty() { None } else { Some(faults.remove(0)) }
+            };
+
+            let result = match fault {
+                Some(SendFault::Drop) => Err(CrankError::Rpc("blockhash expired".to_string())),
+                Some(SendFault::LandThenFail) => self
+                    .apply(&params)
+                    .and(Err(CrankError::Rpc("confirmation timed out".to_string()))),
+                None => self.apply(&params).map(|_| "signature".to_string()),
+            };
+            self.sent.borrow_mut().push(params);
+            result
+        }
+
+        fn simulate(&self, _instruction: Instruction) -> CrankResult<SimulationReport> {
+            *self.simulated.borrow_mut() += 1;
+            Ok(SimulationReport { error: None, logs: vec!["Program log: ok".to_string()], units_consumed: Some(1) })
+        }
+    }
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn setup(investor_count: usize) -> (Crank<MockChain>, Vec<Investor>) {
+        let vault = Pubkey::new_unique();
+        let vault_accounts = VaultAccounts::new(vault);
+        let investors: Vec<Investor> = (0..investor_count)
+            .map(|_| Investor { wallet: Pubkey::new_unique(), weight_accounts: vec![Pubkey::new_unique()] })
+            .collect();
+
+
+        let mut policy_config = PolicyConfig {
+            vault,
+            quote_mint: Pubkey::new_unique(),
+            creator_wallet: Pubkey::new_unique(),
+            investor_fee_share_bps: 5_000,
+            min_payout_lamports: 1_000,
+            y0_total_allocation: 1_000_000,
+            bump: 255,
+            vesting_programs: [Pubkey::default(); MAX_VESTING_PROGRAMS],
+            vesting_program_count: 0,
+            ..PolicyConfig::default()
+        };
+        policy_config.set_honorary_positions(&[Pubkey::new_unique()]).unwrap();
+
+        // Every investor's quote ATA exists; with its accrual PDA each costs two payout
+        // slots, so pages split
+        let existing = investors
+            .iter()
+            .map(|investor| quote_ata(&investor.wallet, &policy_config.quote_mint))
+            .collect();
+        let progress = DistributionProgress {
+            vault,
+            bump: 255,
+            ..DistributionProgress::default()
+        };
+
+        let chain = MockChain {
+            state: RefCell::new(VaultState {
+                ledger: vault_accounts.quote_ledger(&policy_config, policy_config.quote_mint),
+                policy_config,
+                progress,
+                cluster_time: NOW,
+            }),
+            investor_count: investor_count as u32,
+            existing: RefCell::new(existing),
+            has_fees: true,
+            faults: RefCell::new(Vec::new()),
+            sent: RefCell::new(Vec::new()),
+            simulated: RefCell::new(0),
+        };
+        let settings = CrankSettings {
+            vault,
+            quote_mint: None,
+            crank_caller: Pubkey::new_unique(),
+            dry_run: false,
+            max_retries: 3,
+            retry_delay: Duration::ZERO,
+            poll_interval: Duration::ZERO,
+            reserved_accounts: 0,
+            buyback_pool: None,
+            claim_crank_reward: false,
+        };
+
+        (Crank::new(chain, settings, investors.clone()).unwrap(), investors)
+    }
+
+    fn sent_cursors(crank: &Crank<MockChain>) -> Vec<Option<u32>> {
+        crank.chain().sent.borrow().iter().map(|params| params.cursor_position).collect()
+    }
+
+    #[test]
+    fn test_new_day_runs_every_page() {
+        // A legacy transaction holds 18 remaining accounts beside the one position: the
+        // opening page's 12 weight accounts leave room for 3 investors' payout accounts, and
+        // later pages carry only their own investors' weight accounts
+        let (mut crank, _) = setup(12);
+
+        assert_eq!(crank.run_day().unwrap(), DayOutcome::Completed { pages: 3 });
+        assert_eq!(sent_cursors(&crank), vec![Some(0), Some(3), Some(9)]);
+
+        // The next window is a day away
+        assert_eq!(crank.run_day().unwrap(), DayOutcome::Waiting { seconds: TWENTY_FOUR_HOURS });
+
+        // Once it opens the crank starts over from the first page
+        crank.chain().state.borrow_mut().cluster_time += TWENTY_FOUR_HOURS;
+        assert_eq!(crank.run_day().unwrap(), DayOutcome::Completed { pages: 3 });
+        assert_eq!(sent_cursors(&crank)[3..], [Some(0), Some(3), Some(9)]);
+    }
+
+    #[test]
+    fn test_waits_for_configured_period() {
+        let (mut crank, _) = setup(4);
+        crank.chain().state.borrow_mut().policy_config.distribution_period_secs = 3600;
+
+        assert_eq!(crank.run_day().unwrap(), DayOutcome::Completed { pages: 1 });
+        assert_eq!(crank.run_day().unwrap(), DayOutcome::Waiting { seconds: 3600 });
+
+        crank.chain().state.borrow_mut().cluster_time += 3600;
+        assert_eq!(crank.run_day().unwrap(), DayOutcome::Completed { pages: 1 });
+        assert_eq!(sent_cursors(&crank), vec![Some(0), Some(0)]);
+    }
+
+    #[test]
+    fn test_resumes_mid_day_from_stored_cursor() {
+        let (mut crank, _) = setup(12);
+        {
+            let mut state = crank.chain().state.borrow_mut();
+            state.progress.last_distribution_ts = NOW - 100;
+            state.progress.pagination_cursor = 4;
+        }
+
+        assert_eq!(crank.run_day().unwrap(), DayOutcome::Completed { pages: 2 });
+        assert_eq!(sent_cursors(&crank), vec![Some(4), Some(10)]);
+    }
+
+    #[test]
+    fn test_retries_dropped_sends() {
+        let (mut crank, _) = setup(12);
+        *crank.chain().faults.borrow_mut() = vec![SendFault::Drop, SendFault::Drop];
+
+        assert_eq!(crank.run_day().unwrap(), DayOutcome::Completed { pages: 3 });
+        assert_eq!(sent_cursors(&crank), vec![Some(0), Some(0), Some(0), Some(3), Some(9)]);
+    }
+
+    #[test]
+    fn test_landed_page_is_not_resent() {
+        let (mut crank, _) = setup(12);
+        *crank.chain().faults.borrow_mut() = vec![SendFault::LandThenFail, SendFault::LandThenFail];
+
+        assert_eq!(crank.run_day().unwrap(), DayOutcome::Completed { pages: 3 });
+        assert_eq!(sent_cursors(&crank), vec![Some(0), Some(3), Some(9)]);
+    }
+
+    #[test]
+    fn test_gives_up_after_max_retries() {
+        let (mut crank, _) = setup(4);
+        *crank.chain().faults.borrow_mut() = vec![SendFault::Drop; 3];
+
+        let result = crank.run_day();
+
+        assert!(matches!(result, Err(CrankError::PageFailed { cursor: 0, attempts: 3, .. })));
+    }
+
+    #[test]
+    fn test_dry_run_simulates_without_sending() {
+        let (mut crank, _) = setup(12);
+        crank.settings.dry_run = true;
+
+        let outcome = crank.run_day().unwrap();
+
+        match outcome {
+            DayOutcome::DryRun { simulation, pending_pages } => {
+                assert_eq!(simulation.error, None);
+                assert_eq!(pending_pages.len(), 3);
+            }
+            other => panic!("unexpected outcome {:?}", other),
+        }
+        assert!(crank.chain().sent.borrow().is_empty());
+        assert_eq!(*crank.chain().simulated.borrow(), 1);
+    }
+
+    #[test]
+    fn test_idle_day_waits_for_next_window() {
+        let (crank, investors) = setup(4);
+        let MockChain { state, existing, faults, sent, simulated, investor_count, .. } = crank.chain;
+        let chain = MockChain { state, investor_count, existing, has_fees: false, faults, sent, simulated };
+        let mut crank = Crank::new(chain, crank.settings, investors).unwrap();
+
+        assert_eq!(crank.run_day().unwrap(), DayOutcome::NothingToDistribute);
+        assert_eq!(crank.run_day().unwrap(), DayOutcome::Waiting { seconds: TWENTY_FOUR_HOURS });
+        assert_eq!(crank.chain().sent.borrow().len(), 1);
+    }
+
+    #[test]
+    fn test_creates_missing_quote_atas() {
+        let (mut crank, investors) = setup(4);
+        let quote_mint = crank.chain().state.borrow().ledger.quote_mint;
+        let missing = quote_ata(&investors[2].wallet, &quote_mint);
+        crank.chain().existing.borrow_mut().remove(&missing);
+
+        assert_eq!(crank.run_day().unwrap(), DayOutcome::Completed { pages: 1 });
+        assert!(crank.chain().existing.borrow().contains(&missing));
+    }
+
+    /// A crank for a buyback policy whose one honorary pool has `reference_price_q64`
+    fn setup_buyback(reference_price_q64: u128) -> Crank<MockChain> {
+        let (mut crank, _) = setup(4);
+        let buyback_pool = BuybackPool {
+            pool: Pubkey::new_unique(),
+            base_vault: Pubkey::new_unique(),
+            quote_vault: Pubkey::new_unique(),
+        };
+        {
+            let mut state = crank.chain().state.borrow_mut();
+            state.policy_config.base_mint = Pubkey::new_unique();
+            state.policy_config.creator_payout_mode = CreatorPayoutMode::BuybackAndBurn;
+            state.policy_config.set_honorary_pools(&[HonoraryPool {
+                pool: buyback_pool.pool,
+                base_vault: buyback_pool.base_vault,
+                quote_vault: buyback_pool.quote_vault,
+                base_is_token_a: true,
+                reference_price_q64,
+            }]).unwrap();
+        }
+        crank.settings.buyback_pool = Some(buyback_pool);
+        crank
+    }
+
+    #[test]
+    fn test_buyback_needs_priced_pool() {
+        // The eight buyback accounts take room from the pages
+        let mut crank = setup_buyback(1 << 64);
+        assert_eq!(crank.run_day().unwrap(), DayOutcome::Completed { pages: 2 });
+    }
+
+    #[test]
+    fn test_unpriced_buyback_still_closes_the_day() {
+        // Without a reference price the pages leave the buyback accounts out and the
+        // program holds the creator share
+        let mut crank = setup_buyback(0);
+        assert_eq!(crank.run_day().unwrap(), DayOutcome::Completed { pages: 1 });
+
+        // Likewise with no buyback pool configured
+        let mut crank = setup_buyback(1 << 64);
+        crank.settings.buyback_pool = None;
+        assert_eq!(crank.run_day().unwrap(), DayOutcome::Completed { pages: 1 });
+    }
+
+    #[test]
+    fn test_crank_reward_requires_ata() {
+        let (mut crank, _) = setup(4);
+        crank.chain().state.borrow_mut().policy_config.crank_reward = CrankReward::FlatLamports { lamports: 1_000 };
+        crank.settings.claim_crank_reward = true;
+
+        assert!(matches!(crank.run_day(), Err(CrankError::Config(_))));
+        assert!(crank.chain().sent.borrow().is_empty());
+
+        let reward_ata = quote_ata(&crank.settings.crank_caller, &crank.chain().state.borrow().ledger.quote_mint);
+        let Crank { chain, settings, investors, .. } = crank;
+        chain.existing.borrow_mut().insert(reward_ata);
+        let mut crank = Crank::new(chain, settings, investors).unwrap();
+        assert_eq!(crank.run_day().unwrap(), DayOutcome::Completed { pages: 1 });
+    }
+}
@@ -0,0 +1,26 @@
+use anchor_lang::prelude::Pubkey;
+use meteora_fee_router_client::ClientError;
+use thiserror::Error;
+
+/// Errors raised while cranking a vault
+#[derive(Debug, Error)]
+pub enum CrankError {
+    #[error("RPC request failed: {0}")]
+    Rpc(String),
+    #[error("Account {0} not found")]
+    AccountNotFound(Pubkey),
+    #[error("Account {address} could not be decoded: {reason}")]
+    InvalidAccount { address: Pubkey, reason: String },
+    #[error("Invalid configuration: {0}")]
+    Config(String),
+    #[error(transparent)]
+    Client(#[from] ClientError),
+    #[error("Page at cursor {cursor} failed after {attempts} attempts: {last_error}")]
+    PageFailed {
+        cursor: u32,
+        attempts: u32,
+        last_error: String,
+    },
+}
+
+pub type CrankResult<T> = std::result::Result<T, CrankError>;
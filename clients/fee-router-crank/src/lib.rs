@@ -0,0 +1,17 @@
+//! Crank daemon for the Meteora Fee Router
+//!
+//! Watches a vault's `DistributionProgress`, opens each day once the 24h window
+//! elapses and pages `distribute_fees` through the investor registry, retrying failed
+//! sends with the page's `cursor_position` pinned so replays are no-ops.
+
+pub mod error;
+pub mod config;
+pub mod chain;
+pub mod crank;
+pub mod rpc;
+
+pub use error::*;
+pub use config::*;
+pub use chain::*;
+pub use crank::*;
+pub use rpc::*;
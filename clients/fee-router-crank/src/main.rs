@@ -0,0 +1,131 @@
+use anchor_lang::prelude::Pubkey;
+use clap::Parser;
+use fee_router_crank::{load_investors, BuybackPool, Crank, CrankSettings, DayOutcome, RpcChain};
+use solana_sdk::signature::read_keypair_file;
+use std::{path::PathBuf, process::ExitCode, time::Duration};
+
+/// Crank the Meteora Fee Router's 24h fee distribution for a vault
+#[derive(Debug, Parser)]
+#[command(name = "fee-router-crank", version)]
+struct Args {
+    /// JSON RPC endpoint (defaults to a local solana-test-validator)
+    #[arg(long, default_value = "http://127.0.0.1:8899")]
+    rpc_url: String,
+
+    /// Keypair of the crank caller, paying for the transactions
+    #[arg(long)]
+    keypair: PathBuf,
+
+    /// Vault the fee router was initialized for
+    #[arg(long)]
+    vault: Pubkey,
+
+    /// Quote mint whose day ledger to crank (defaults to the policy's primary quote mint)
+    #[arg(long)]
+    quote_mint: Option<Pubkey>,
+
+    /// JSON file listing investors and their weight accounts
+    #[arg(long)]
+    investors: PathBuf,
+
+    /// Simulate the next page and print the remaining plan instead of submitting
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Run a single pass and exit
+    #[arg(long)]
+    once: bool,
+
+    /// Attempts per page before giving up
+    #[arg(long, default_value_t = 5)]
+    max_retries: u32,
+
+    /// Delay between attempts, in milliseconds
+    #[arg(long, default_value_t = 2_000)]
+    retry_delay_ms: u64,
+
+    /// Longest sleep between checks of the distribution window, in seconds
+    #[arg(long, default_value_t = 60)]
+    poll_interval_secs: u64,
+
+    /// Transaction account slots kept free for other instructions
+    #[arg(long, default_value_t = 0)]
+    reserved_accounts: usize,
+
+    /// Pool the creator share is bought back through (policies that buy back and burn)
+    #[arg(long, requires_all = ["buyback_pool_base_vault", "buyback_pool_quote_vault"])]
+    buyback_pool: Option<Pubkey>,
+
+    /// Base token vault of the buyback pool
+    #[arg(long, requires = "buyback_pool")]
+    buyback_pool_base_vault: Option<Pubkey>,
+
+    /// Quote token vault of the buyback pool
+    #[arg(long, requires = "buyback_pool")]
+    buyback_pool_quote_vault: Option<Pubkey>,
+
+    /// Claim the policy's crank reward into the keypair's quote ATA
+    #[arg(long)]
+    claim_crank_reward: bool,
+}
+
+fn run(args: Args) -> Result<(), Box<dyn std::error::Error>> {
+    let payer = read_keypair_file(&args.keypair)
+        .map_err(|error| format!("cannot read keypair {}: {}", args.keypair.display(), error))?;
+    let chain = RpcChain::new(args.rpc_url, payer);
+    let settings = CrankSettings {
+        vault: args.vault,
+        quote_mint: args.quote_mint,
+        crank_caller: chain.payer(),
+        dry_run: args.dry_run,
+        max_retries: args.max_retries.max(1),
+        retry_delay: Duration::from_millis(args.retry_delay_ms),
+        poll_interval: Duration::from_secs(args.poll_interval_secs),
+        reserved_accounts: args.reserved_accounts,
+        buyback_pool: match (args.buyback_pool, args.buyback_pool_base_vault, args.buyback_pool_quote_vault) {
+            (Some(pool), Some(base_vault), Some(quote_vault)) => Some(BuybackPool { pool, base_vault, quote_vault }),
+            _ => None,
+        },
+        claim_crank_reward: args.claim_crank_reward,
+    };
+    let mut crank = Crank::new(chain, settings, load_investors(&args.investors)?)?;
+
+    if !args.once && !args.dry_run {
+        crank.run()?;
+        return Ok(());
+    }
+
+    match crank.run_day()? {
+        DayOutcome::Waiting { seconds } => println!("Next distribution window opens in {}s", seconds),
+        DayOutcome::Completed { pages } => println!("Day completed in {} pages", pages),
+        DayOutcome::NothingToDistribute => println!("Day opened with nothing to distribute"),
+        DayOutcome::DryRun { simulation, pending_pages } => {
+            for line in &simulation.logs {
+                println!("  {}", line);
+            }
+            match simulation.error {
+                Some(error) => println!("Simulated page failed: {}", error),
+                None => println!("Simulated page succeeded ({:?} compute units)", simulation.units_consumed),
+            }
+            for page in &pending_pages {
+                println!("Pending page {}..{}: {} weight accounts, {} payout accounts, {} transaction accounts",
+                         page.cursor, page.cursor + page.page_size, page.weight_accounts.len(),
+                         page.payout_accounts.len(), page.transaction_account_count());
+            }
+        }
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    // The crank logs each page and retry; `RUST_LOG` overrides the level
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+
+    match run(Args::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("fee-router-crank: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
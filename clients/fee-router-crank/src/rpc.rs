@@ -0,0 +1,115 @@
+use anchor_lang::prelude::{Clock, Pubkey};
+use anchor_lang::solana_program::{instruction::Instruction, sysvar};
+use anchor_lang::AccountDeserialize;
+use meteora_fee_router::state::{DistributionProgress, PolicyConfig};
+use meteora_fee_router_client::VaultAccounts;
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_sdk::{
+    account::{from_account, Account},
+    commitment_config::CommitmentConfig,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+use crate::{
+    chain::{Chain, SimulationReport, VaultState},
+    error::{CrankError, CrankResult},
+};
+
+/// Most accounts `getMultipleAccounts` returns per request
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+/// `Chain` backed by a JSON RPC endpoint
+pub struct RpcChain {
+    client: RpcClient,
+    payer: Keypair,
+}
+
+impl RpcChain {
+    pub fn new(rpc_url: String, payer: Keypair) -> Self {
+        Self {
+            client: RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed()),
+            payer,
+        }
+    }
+
+    pub fn payer(&self) -> Pubkey {
+        self.payer.pubkey()
+    }
+
+    fn signed_transaction(&self, instruction: Instruction) -> CrankResult<Transaction> {
+        let blockhash = self.client.get_latest_blockhash().map_err(rpc_error)?;
+        Ok(Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&self.payer.pubkey()),
+            &[&self.payer],
+            blockhash,
+        ))
+    }
+}
+
+fn rpc_error(error: impl std::fmt::Display) -> CrankError {
+    CrankError::Rpc(error.to_string())
+}
+
+fn decode<T: AccountDeserialize>(address: Pubkey, account: Option<Account>) -> CrankResult<T> {
+    let account = account.ok_or(CrankError::AccountNotFound(address))?;
+    T::try_deserialize(&mut account.data.as_slice()).map_err(|error| CrankError::InvalidAccount {
+        address,
+        reason: error.to_string(),
+    })
+}
+
+impl Chain for RpcChain {
+    fn vault_state(&self, vault_accounts: &VaultAccounts, quote_mint: Option<Pubkey>) -> CrankResult<VaultState> {
+        let addresses = [vault_accounts.policy_config, sysvar::clock::ID];
+        let mut accounts = self.client.get_multiple_accounts(&addresses).map_err(rpc_error)?.into_iter();
+
+        let policy_config: PolicyConfig = decode(addresses[0], accounts.next().flatten())?;
+        let clock: Clock = accounts
+            .next()
+            .flatten()
+            .and_then(|account| from_account(&account))
+            .ok_or(CrankError::AccountNotFound(sysvar::clock::ID))?;
+
+        // The ledger's address depends on whether the quote mint is the policy's primary one
+        let ledger = vault_accounts.quote_ledger(&policy_config, quote_mint.unwrap_or(policy_config.quote_mint));
+        let progress_account = self.client.get_account(&ledger.distribution_progress).ok();
+        let progress: DistributionProgress = decode(ledger.distribution_progress, progress_account)?;
+
+        Ok(VaultState {
+            policy_config,
+            ledger,
+            progress,
+            cluster_time: clock.unix_timestamp,
+        })
+    }
+
+    fn accounts_exist(&self, addresses: &[Pubkey]) -> CrankResult<Vec<bool>> {
+        let mut exists = Vec::with_capacity(addresses.len());
+        for chunk in addresses.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            let accounts = self.client.get_multiple_accounts(chunk).map_err(rpc_error)?;
+            exists.extend(accounts.iter().map(Option::is_some));
+        }
+        Ok(exists)
+    }
+
+    fn send(&self, instruction: Instruction) -> CrankResult<String> {
+        let transaction = self.signed_transaction(instruction)?;
+        self.client
+            .send_and_confirm_transaction(&transaction)
+            .map(|signature| signature.to_string())
+            .map_err(rpc_error)
+    }
+
+    fn simulate(&self, instruction: Instruction) -> CrankResult<SimulationReport> {
+        let transaction = self.signed_transaction(instruction)?;
+        let result = self.client.simulate_transaction(&transaction).map_err(rpc_error)?.value;
+
+        Ok(SimulationReport {
+            error: result.err.map(|error| error.to_string()),
+            logs: result.logs.unwrap_or_default(),
+            units_consumed: result.units_consumed,
+        })
+    }
+}
@@ -0,0 +1,257 @@
+//! End-to-end crank run against `solana-test-validator`
+//!
+//! The validator is started with the built program and mocked accounts: Streamflow
+//! streams, the quote mint and token accounts, and a vault whose day is already open
+//! (so no cp-amm fee claim is needed). Run with:
+//!
+//!     anchor build
+//!     cargo test -p fee-router-crank --test local_validator -- --ignored
+//!
+//! `FEE_ROUTER_PROGRAM_SO` overrides the path of the built program.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::AccountSerialize;
+use anchor_spl::token::spl_token;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use fee_router_crank::{Chain, Crank, CrankSettings, DayOutcome, Investor, RpcChain};
+use meteora_fee_router::{
+    constants::*,
+    state::{DistributionProgress, PolicyConfig},
+    utils::{pda::PdaUtils, streamflow::{StreamflowStream, STREAMFLOW_PROGRAM_ID}},
+};
+use meteora_fee_router_client::{quote_ata, VaultAccounts};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_sdk::signature::{Keypair, Signer};
+use std::{
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+const RPC_PORT: u16 = 18899;
+const INVESTOR_COUNT: usize = 4;
+const CLAIMED: u64 = 1_000_000;
+const INVESTOR_SHARE: u64 = 400_000;
+const RENT_EXEMPT_LAMPORTS: u64 = 10_000_000;
+
+/// Kills the validator when the test ends
+struct Validator(Child);
+
+impl Drop for Validator {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+struct Fixtures {
+    dir: PathBuf,
+    accounts: Vec<(Pubkey, PathBuf)>,
+}
+
+impl Fixtures {
+    fn add(&mut self, address: Pubkey, owner: Pubkey, data: Vec<u8>) {
+        let path = self.dir.join(format!("{}.json", address));
+        let account = serde_json::json!({
+            "pubkey": address.to_string(),
+            "account": {
+                "lamports": RENT_EXEMPT_LAMPORTS,
+                "data": [STANDARD.encode(&data), "base64"],
+                "owner": owner.to_string(),
+                "executable": false,
+                "rentEpoch": 0,
+                "space": data.len(),
+            }
+        });
+        std::fs::write(&path, account.to_string()).unwrap();
+        self.accounts.push((address, path));
+    }
+
+    fn add_token_account(&mut self, address: Pubkey, mint: Pubkey, owner: Pubkey, amount: u64) {
+        let account = spl_token::state::Account {
+            mint,
+            owner,
+            amount,
+            state: spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut data = vec![0u8; spl_token::state::Account::LEN];
+        account.pack_into_slice(&mut data);
+        self.add(address, spl_token::ID, data);
+    }
+}
+
+/// Streamflow stream fully locked for the next weeks
+fn stream_data(recipient: Pubkey, mint: Pubkey, now: i64) -> Vec<u8> {
+    let start = now + 10 * TWENTY_FOUR_HOURS;
+    let mut data = vec![0u8; StreamflowStream::MIN_ACCOUNT_SIZE];
+    data[0..8].copy_from_slice(&1u64.to_le_bytes());
+    data[33..41].copy_from_slice(&(start + 10 * TWENTY_FOUR_HOURS).to_le_bytes());
+    data[113..145].copy_from_slice(recipient.as_ref());
+    data[177..209].copy_from_slice(mint.as_ref());
+    data[409..417].copy_from_slice(&start.to_le_bytes());
+    data[417..425].copy_from_slice(&1_000_000u64.to_le_bytes());
+    data[425..433].copy_from_slice(&TWENTY_FOUR_HOURS.to_le_bytes());
+    data[433..441].copy_from_slice(&100_000u64.to_le_bytes());
+    data[441..449].copy_from_slice(&start.to_le_bytes());
+    data
+}
+
+fn program_so() -> PathBuf {
+    std::env::var("FEE_ROUTER_PROGRAM_SO").map(PathBuf::from).unwrap_or_else(|_| {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../../target/deploy/meteora_fee_router.so")
+    })
+}
+
+fn start_validator(fixtures: &Fixtures, payer: &Pubkey) -> Validator {
+    let program = program_so();
+    assert!(program.exists(), "{} not found; run `anchor build` first", program.display());
+
+    let mut command = Command::new("solana-test-validator");
+    command
+        .arg("--reset")
+        .arg("--quiet")
+        .arg("--ledger").arg(fixtures.dir.join("ledger"))
+        .arg("--rpc-port").arg(RPC_PORT.to_string())
+        .arg("--mint").arg(payer.to_string())
+        .arg("--bpf-program").arg(meteora_fee_router::ID.to_string()).arg(&program);
+    for (address, path) in &fixtures.accounts {
+        command.arg("--account").arg(address.to_string()).arg(path);
+    }
+
+    let child = command
+        .stdout(Stdio::null())
+        .spawn()
+        .expect("solana-test-validator must be on PATH");
+    Validator(child)
+}
+
+fn wait_until_healthy(rpc_url: &str) {
+    let client = RpcClient::new(rpc_url.to_string());
+    let deadline = Instant::now() + Duration::from_secs(60);
+    while client.get_health().is_err() {
+        assert!(Instant::now() < deadline, "validator did not become healthy");
+        std::thread::sleep(Duration::from_millis(500));
+    }
+}
+
+#[test]
+#[ignore = "needs solana-test-validator and the built program"]
+fn crank_completes_open_day_on_local_validator() {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64;
+    let dir = std::env::temp_dir().join(format!("fee-router-crank-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut fixtures = Fixtures { dir, accounts: Vec::new() };
+
+    let payer = Keypair::new();
+    let vault = Pubkey::new_unique();
+    let vault_accounts = VaultAccounts::new(vault);
+    let quote_mint = Pubkey::new_unique();
+    let creator_wallet = Pubkey::new_unique();
+    let honorary_position = Pubkey::new_unique();
+    let (_, position_owner_bump) = PdaUtils::derive_position_owner_pda(&meteora_fee_router::ID, &vault);
+
+    // Quote mint, treasury holding the day's claim, creator ATA
+    let mint = spl_token::state::Mint { decimals: 6, is_initialized: true, ..Default::default() };
+    let mut mint_data = vec![0u8; spl_token::state::Mint::LEN];
+    mint.pack_into_slice(&mut mint_data);
+    fixtures.add(quote_mint, spl_token::ID, mint_data);
+    fixtures.add_token_account(vault_accounts.treasury_ata(&quote_mint), quote_mint, vault_accounts.position_owner, CLAIMED);
+    let creator_ata = quote_ata(&creator_wallet, &quote_mint);
+    fixtures.add_token_account(creator_ata, quote_mint, creator_wallet, 0);
+
+    // Mocked Streamflow streams, one per investor
+    let investors: Vec<Investor> = (0..INVESTOR_COUNT)
+        .map(|_| {
+            let wallet = Pubkey::new_unique();
+            let stream = Pubkey::new_unique();
+            fixtures.add(stream, STREAMFLOW_PROGRAM_ID, stream_data(wallet, quote_mint, now));
+            Investor { wallet, weight_accounts: vec![stream] }
+        })
+        .collect();
+
+    // Policy, and a progress account whose day opened a minute ago with the claim recorded
+    let mut policy_config = PolicyConfig {
+        vault,
+        quote_mint,
+        creator_wallet,
+        investor_fee_share_bps: 4_000,
+        min_payout_lamports: 1,
+        y0_total_allocation: 12_000_000,
+        // The creator transfer signs for the position owner with the stored bump
+        bump: position_owner_bump,
+        ..PolicyConfig::default()
+    };
+    policy_config.set_honorary_positions(&[honorary_position]).unwrap();
+    let mut policy_data = Vec::new();
+    policy_config.try_serialize(&mut policy_data).unwrap();
+    policy_data.resize(8 + PolicyConfig::INIT_SPACE, 0);
+    fixtures.add(vault_accounts.policy_config, meteora_fee_router::ID, policy_data);
+
+    let opened_at = now - 60;
+    let progress = DistributionProgress {
+        vault,
+        last_distribution_ts: opened_at,
+        bump: 255,
+        weight_window_start_ts: opened_at,
+        day_investor_pool: INVESTOR_SHARE,
+        day_claimed_quote: CLAIMED,
+        day_investor_share: INVESTOR_SHARE,
+        // The treasury was empty when the day opened and the claim funded it
+        day_opening_treasury: 0,
+        day_treasury_inflow: CLAIMED,
+        quote_mint,
+        ..DistributionProgress::default()
+    };
+    let mut progress_data = Vec::new();
+    progress.try_serialize(&mut progress_data).unwrap();
+    progress_data.resize(8 + DistributionProgress::INIT_SPACE, 0);
+    fixtures.add(vault_accounts.distribution_progress, meteora_fee_router::ID, progress_data);
+
+    let _validator = start_validator(&fixtures, &payer.pubkey());
+    let rpc_url = format!("http://127.0.0.1:{}", RPC_PORT);
+    wait_until_healthy(&rpc_url);
+
+    let settings = CrankSettings {
+        vault,
+        quote_mint: None,
+        crank_caller: payer.pubkey(),
+        dry_run: true,
+        max_retries: 3,
+        retry_delay: Duration::from_secs(1),
+        poll_interval: Duration::from_secs(1),
+        reserved_accounts: 0,
+        buyback_pool: None,
+        claim_crank_reward: false,
+    };
+
+    // Dry run simulates the first page and leaves the chain untouched
+    let chain = RpcChain::new(rpc_url.clone(), payer.insecure_clone());
+    let mut crank = Crank::new(chain, settings.clone(), investors.clone()).unwrap();
+    match crank.run_day().unwrap() {
+        DayOutcome::DryRun { simulation, pending_pages } => {
+            assert_eq!(simulation.error, None, "logs: {:#?}", simulation.logs);
+            assert_eq!(pending_pages.len(), 1);
+        }
+        other => panic!("unexpected outcome {:?}", other),
+    }
+
+    // A real run pages through and closes the day
+    let chain = RpcChain::new(rpc_url.clone(), payer.insecure_clone());
+    let mut crank = Crank::new(chain, CrankSettings { dry_run: false, ..settings }, investors).unwrap();
+    assert_eq!(crank.run_day().unwrap(), DayOutcome::Completed { pages: 1 });
+
+    let state = crank.chain().vault_state(&vault_accounts, None).unwrap();
+    assert!(state.progress.day_complete);
+    assert_eq!(state.progress.pagination_cursor, INVESTOR_COUNT as u32);
+
+    // The creator received the remainder of the claim
+    let client = RpcClient::new(rpc_url);
+    let creator_account = client.get_account(&creator_ata).unwrap();
+    let creator_balance = spl_token::state::Account::unpack(&creator_account.data).unwrap().amount;
+    assert_eq!(creator_balance, CLAIMED - INVESTOR_SHARE);
+
+    // The window is closed until the next day
+    assert!(matches!(crank.run_day().unwrap(), DayOutcome::Waiting { .. }));
+}
@@ -0,0 +1,192 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::associated_token::get_associated_token_address;
+use meteora_fee_router::state::PolicyConfig;
+use meteora_fee_router::utils::pda::PdaUtils;
+
+/// Program addresses of a fee router vault
+///
+/// Every PDA is derived through the program's own `PdaUtils`, so the client cannot
+/// drift from the seeds the program checks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VaultAccounts {
+    pub program_id: Pubkey,
+    pub vault: Pubkey,
+    pub policy_config: Pubkey,
+    pub distribution_progress: Pubkey,
+    pub position_owner: Pubkey,
+}
+
+impl VaultAccounts {
+    /// Addresses of `vault` under the deployed program id
+    pub fn new(vault: Pubkey) -> Self {
+        Self::with_program_id(meteora_fee_router::ID, vault)
+    }
+
+    /// Addresses of `vault` under another deployment of the program
+    pub fn with_program_id(program_id: Pubkey, vault: Pubkey) -> Self {
+        let (policy_config, _) = PdaUtils::derive_policy_config_pda(&program_id, &vault);
+        let (distribution_progress, _) = PdaUtils::derive_distribution_progress_pda(&program_id, &vault);
+        let (position_owner, _) = PdaUtils::derive_position_owner_pda(&program_id, &vault);
+
+        Self {
+            program_id,
+            vault,
+            policy_config,
+            distribution_progress,
+            position_owner,
+        }
+    }
+
+    /// An investor's accrual PDA in the primary quote mint's ledger
+    pub fn investor_accrual(&self, investor: &Pubkey) -> Pubkey {
+        PdaUtils::derive_investor_accrual_pda(&self.program_id, &self.vault, investor).0
+    }
+
+    /// The treasury and day ledger of one of the policy's quote mints
+    pub fn quote_ledger(&self, policy_config: &PolicyConfig, quote_mint: Pubkey) -> QuoteLedger {
+        let is_primary = policy_config.ledger_seed(&quote_mint).is_empty();
+        QuoteLedger::new(self, quote_mint, is_primary)
+    }
+
+    /// The ledger of the primary quote mint, known before the policy exists
+    pub fn primary_ledger(&self, quote_mint: Pubkey) -> QuoteLedger {
+        QuoteLedger::new(self, quote_mint, true)
+    }
+
+    /// Token account base fees are collected into under the policy's base fee policy:
+    /// the base treasury (the position owner's base ATA) or the creator's base ATA
+    pub fn base_fee_destination(&self, policy_config: &PolicyConfig) -> Option<Pubkey> {
+        policy_config
+            .base_fee_destination_owner(&self.position_owner)
+            .map(|owner| get_associated_token_address(&owner, &policy_config.base_mint))
+    }
+
+    /// The treasury: the position owner's quote ATA, where claimed fees land
+    pub fn treasury_ata(&self, quote_mint: &Pubkey) -> Pubkey {
+        get_associated_token_address(&self.position_owner, quote_mint)
+    }
+
+    /// The base treasury: the position owner's base ATA, where quarantined base fees land
+    pub fn base_treasury_ata(&self, base_mint: &Pubkey) -> Pubkey {
+        get_associated_token_address(&self.position_owner, base_mint)
+    }
+}
+
+/// Addresses of one quote mint's treasury and day ledger under a vault
+///
+/// The primary quote mint's ledger sits at the vault's distribution progress PDA; the
+/// ledgers and accruals of other quote mints are additionally seeded by the mint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuoteLedger {
+    pub program_id: Pubkey,
+    pub vault: Pubkey,
+    pub quote_mint: Pubkey,
+    pub is_primary: bool,
+    pub distribution_progress: Pubkey,
+    pub treasury_ata: Pubkey,
+}
+
+impl QuoteLedger {
+    fn new(vault_accounts: &VaultAccounts, quote_mint: Pubkey, is_primary: bool) -> Self {
+        let mut ledger = Self {
+            program_id: vault_accounts.program_id,
+            vault: vault_accounts.vault,
+            quote_mint,
+            is_primary,
+            distribution_progress: Pubkey::default(),
+            treasury_ata: vault_accounts.treasury_ata(&quote_mint),
+        };
+        ledger.distribution_progress =
+            PdaUtils::derive_quote_ledger_pda(&ledger.program_id, &ledger.vault, ledger.ledger_seed()).0;
+        ledger
+    }
+
+    /// Extra PDA seed of the ledger, matching `PolicyConfig::ledger_seed`
+    pub fn ledger_seed(&self) -> &[u8] {
+        if self.is_primary {
+            &[]
+        } else {
+            self.quote_mint.as_ref()
+        }
+    }
+
+    /// An investor's accrual PDA in this ledger
+    pub fn investor_accrual(&self, investor: &Pubkey) -> Pubkey {
+        PdaUtils::derive_ledger_accrual_pda(&self.program_id, &self.vault, investor, self.ledger_seed()).0
+    }
+}
+
+/// A wallet's quote ATA (creator or investor payout account)
+pub fn quote_ata(wallet: &Pubkey, quote_mint: &Pubkey) -> Pubkey {
+    get_associated_token_address(wallet, quote_mint)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use meteora_fee_router::constants::*;
+
+    #[test]
+    fn test_vault_accounts_match_program_seeds() {
+        let vault = Pubkey::new_unique();
+        let accounts = VaultAccounts::new(vault);
+        let program_id = meteora_fee_router::ID;
+
+        let (policy_config, _) = Pubkey::find_program_address(&[POLICY_SEED, vault.as_ref()], &program_id);
+        let (progress, _) = Pubkey::find_program_address(&[PROGRESS_SEED, vault.as_ref()], &program_id);
+        let (position_owner, _) = Pubkey::find_program_address(
+            &[VAULT_SEED, vault.as_ref(), b"investor_fee_pos_owner"],
+            &program_id,
+        );
+        assert_eq!(accounts.policy_config, policy_config);
+        assert_eq!(accounts.distribution_progress, progress);
+        assert_eq!(accounts.position_owner, position_owner);
+
+        let investor = Pubkey::new_unique();
+        let (accrual, _) = Pubkey::find_program_address(
+            &[ACCRUAL_SEED, vault.as_ref(), investor.as_ref()],
+            &program_id,
+        );
+        assert_eq!(accounts.investor_accrual(&investor), accrual);
+
+        let quote_mint = Pubkey::new_unique();
+        assert_eq!(accounts.treasury_ata(&quote_mint), quote_ata(&position_owner, &quote_mint));
+        let base_mint = Pubkey::new_unique();
+        assert_eq!(accounts.base_treasury_ata(&base_mint), quote_ata(&position_owner, &base_mint));
+    }
+
+    #[test]
+    fn test_quote_ledgers() {
+        let vault = Pubkey::new_unique();
+        let accounts = VaultAccounts::new(vault);
+        let program_id = meteora_fee_router::ID;
+        let primary = accounts.primary_ledger(Pubkey::new_unique());
+        let usdc = Pubkey::new_unique();
+        let other = QuoteLedger::new(&accounts, usdc, false);
+        let investor = Pubkey::new_unique();
+
+        // The primary ledger keeps the vault's progress and accrual PDAs
+        assert_eq!(primary.distribution_progress, accounts.distribution_progress);
+        assert_eq!(primary.investor_accrual(&investor), accounts.investor_accrual(&investor));
+
+        // Other quote mints are seeded by the mint and have their own treasury
+        let (progress, _) = Pubkey::find_program_address(&[PROGRESS_SEED, vault.as_ref(), usdc.as_ref()], &program_id);
+        let (accrual, _) = Pubkey::find_program_address(
+            &[ACCRUAL_SEED, vault.as_ref(), investor.as_ref(), usdc.as_ref()],
+            &program_id,
+        );
+        assert_eq!(other.distribution_progress, progress);
+        assert_eq!(other.investor_accrual(&investor), accrual);
+        assert_eq!(other.treasury_ata, accounts.treasury_ata(&usdc));
+    }
+
+    #[test]
+    fn test_other_program_id_changes_addresses() {
+        let vault = Pubkey::new_unique();
+        let deployed = VaultAccounts::new(vault);
+        let other = VaultAccounts::with_program_id(Pubkey::new_unique(), vault);
+
+        assert_ne!(deployed.policy_config, other.policy_config);
+        assert_ne!(deployed.position_owner, other.position_owner);
+    }
+}
@@ -0,0 +1,20 @@
+use thiserror::Error;
+
+/// Errors raised while planning fee router transactions
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ClientError {
+    #[error("Investor list is empty")]
+    NoInvestors,
+    #[error("Weight accounts need {required} transaction slots but only {available} are available")]
+    TooManyWeightAccounts { required: usize, available: usize },
+    #[error("Investor {wallet} needs {required} page accounts but a page only has room for {available}")]
+    PageTooSmall {
+        wallet: anchor_lang::prelude::Pubkey,
+        required: usize,
+        available: usize,
+    },
+    #[error("Investor registry is not in the program's order")]
+    UnorderedRegistry,
+}
+
+pub type ClientResult<T> = std::result::Result<T, ClientError>;
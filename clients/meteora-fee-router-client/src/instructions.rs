@@ -0,0 +1,672 @@
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use anchor_lang::solana_program::{instruction::Instruction, system_program};
+use anchor_lang::{InstructionData, ToAccountMetas};
+use meteora_fee_router::{
+    state::HonoraryPool,
+    utils::swap::{DAMM_V2_EVENT_AUTHORITY, DAMM_V2_POOL_AUTHORITY, DAMM_V2_PROGRAM_ID},
+    ConvertBaseFeesParams, InitializeHonoraryPositionParams, MigrateAccountsParams,
+    SetReferencePriceParams,
+};
+
+use crate::{
+    accounts::{quote_ata, QuoteLedger, VaultAccounts},
+    pagination::DistributionPage,
+};
+
+/// Accounts of `initialize_honorary_position` that are not derived from the vault
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InitializeHonoraryPositionKeys {
+    pub payer: Pubkey,
+    pub pool: Pubkey,
+    pub pool_config: Pubkey,
+    pub quote_vault: Pubkey,
+    pub base_vault: Pubkey,
+    pub cp_amm_program: Pubkey,
+}
+
+/// Accounts of `distribute_fees` that are not derived from the vault
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DistributeFeesKeys {
+    pub crank_caller: Pubkey,
+    /// The creator wallet; its quote ATA receives the creator remainder
+    pub creator_wallet: Pubkey,
+    /// Where base fees are collected, if the policy routes them (see `VaultAccounts::base_fee_destination`)
+    pub base_fee_destination: Option<Pubkey>,
+    /// Pool the creator share is bought back through, if the policy's creator payout mode
+    /// is buyback and burn
+    pub buyback: Option<BuybackKeys>,
+    /// Whether the crank caller's quote ATA is passed to receive the policy's crank reward
+    pub crank_reward: bool,
+}
+
+impl DistributeFeesKeys {
+    /// Optional named accounts `distribute_fees` passes for these keys, which pages are
+    /// sized with (`PageLimits::optional_accounts`)
+    pub fn optional_account_count(&self) -> usize {
+        usize::from(self.base_fee_destination.is_some())
+            + if self.buyback.is_some() { 8 } else { 0 }
+            + usize::from(self.crank_reward)
+    }
+}
+
+/// Accounts of a creator buyback: the policy's base mint and a pool trading it against
+/// the ledger's quote mint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuybackKeys {
+    pub base_mint: Pubkey,
+    pub pool: Pubkey,
+    pub pool_base_vault: Pubkey,
+    pub pool_quote_vault: Pubkey,
+}
+
+/// Accounts of `convert_base_fees` that are not derived from the vault
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConvertBaseFeesKeys {
+    /// The policy authority
+    pub authority: Pubkey,
+    /// The policy's base mint; its base treasury is swapped from
+    pub base_mint: Pubkey,
+    /// Honorary pool trading the base mint against the ledger's quote mint, with the
+    /// vaults the policy recorded for it
+    pub pool: Pubkey,
+    pub pool_base_vault: Pubkey,
+    pub pool_quote_vault: Pubkey,
+}
+
+/// Build `initialize_honorary_position` for a vault, passing each honorary position with the
+/// token account holding its NFT (`position_nft_accounts`, in position order), then every
+/// honorary pool other than `keys.pool`, as remaining accounts for the program to check
+pub fn initialize_honorary_position(
+    vault_accounts: &VaultAccounts,
+    keys: &InitializeHonoraryPositionKeys,
+    position_nft_accounts: &[Pubkey],
+    params: InitializeHonoraryPositionParams,
+) -> Instruction {
+    let accounts = meteora_fee_router::accounts::InitializeHonoraryPosition {
+        payer: keys.payer,
+        policy_config: vault_accounts.policy_config,
+        distribution_progress: vault_accounts.distribution_progress,
+        position_owner_pda: vault_accounts.position_owner,
+        vault: vault_accounts.vault,
+        pool: keys.pool,
+        pool_config: keys.pool_config,
+        quote_vault: keys.quote_vault,
+        base_vault: keys.base_vault,
+        cp_amm_program: keys.cp_amm_program,
+        system_program: system_program::ID,
+        token_program: anchor_spl::token::ID,
+    };
+
+    let mut metas = accounts.to_account_metas(None);
+    metas.extend(honorary_position_accounts(&params.honorary_positions, position_nft_accounts));
+    metas.extend(honorary_pool_accounts(&keys.pool, &params.honorary_pools));
+
+    Instruction {
+        program_id: vault_accounts.program_id,
+        accounts: metas,
+        data: meteora_fee_router::instruction::InitializeHonoraryPosition { params }.data(),
+    }
+}
+
+/// `[position, position_nft_account]` pairs, read-only, for the program to check each
+/// position is held by the vault's position owner
+fn honorary_position_accounts(positions: &[Pubkey], position_nft_accounts: &[Pubkey]) -> Vec<AccountMeta> {
+    positions
+        .iter()
+        .zip(position_nft_accounts)
+        .flat_map(|(position, nft_account)| {
+            [AccountMeta::new_readonly(*position, false), AccountMeta::new_readonly(*nft_account, false)]
+        })
+        .collect()
+}
+
+/// Distinct honorary pools other than `pool`, read-only
+fn honorary_pool_accounts(pool: &Pubkey, honorary_pools: &[HonoraryPool]) -> Vec<AccountMeta> {
+    let mut pools: Vec<Pubkey> = Vec::new();
+    for honorary_pool in honorary_pools {
+        if honorary_pool.pool != *pool && !pools.contains(&honorary_pool.pool) {
+            pools.push(honorary_pool.pool);
+        }
+    }
+
+    pools.into_iter().map(|address| AccountMeta::new_readonly(address, false)).collect()
+}
+
+/// Build `initialize_investor_accrual` for an investor of a vault in a quote mint's ledger
+pub fn initialize_investor_accrual(
+    vault_accounts: &VaultAccounts,
+    ledger: &QuoteLedger,
+    payer: Pubkey,
+    investor: Pubkey,
+) -> Instruction {
+    let accounts = meteora_fee_router::accounts::InitializeInvestorAccrual {
+        payer,
+        policy_config: vault_accounts.policy_config,
+        investor_accrual: ledger.investor_accrual(&investor),
+        investor,
+        vault: vault_accounts.vault,
+        system_program: system_program::ID,
+    };
+
+    Instruction {
+        program_id: vault_accounts.program_id,
+        accounts: accounts.to_account_metas(None),
+        data: meteora_fee_router::instruction::InitializeInvestorAccrual { quote_mint: ledger.quote_mint }.data(),
+    }
+}
+
+/// Build `initialize_quote_ledger` for one of the policy's additional quote mints
+pub fn initialize_quote_ledger(
+    vault_accounts: &VaultAccounts,
+    ledger: &QuoteLedger,
+    payer: Pubkey,
+) -> Instruction {
+    let accounts = meteora_fee_router::accounts::InitializeQuoteLedger {
+        payer,
+        policy_config: vault_accounts.policy_config,
+        distribution_progress: ledger.distribution_progress,
+        vault: vault_accounts.vault,
+        system_program: system_program::ID,
+    };
+
+    Instruction {
+        program_id: vault_accounts.program_id,
+        accounts: accounts.to_account_metas(None),
+        data: meteora_fee_router::instruction::InitializeQuoteLedger { quote_mint: ledger.quote_mint }.data(),
+    }
+}
+
+/// Build `distribute_fees` for one page of a quote mint's day
+pub fn distribute_fees(
+    vault_accounts: &VaultAccounts,
+    ledger: &QuoteLedger,
+    keys: &DistributeFeesKeys,
+    page: &DistributionPage,
+) -> Instruction {
+    let accounts = meteora_fee_router::accounts::DistributeFees {
+        crank_caller: keys.crank_caller,
+        policy_config: vault_accounts.policy_config,
+        distribution_progress: ledger.distribution_progress,
+        position_owner_pda: vault_accounts.position_owner,
+        vault: vault_accounts.vault,
+        treasury_ata: ledger.treasury_ata,
+        creator_ata: quote_ata(&keys.creator_wallet, &ledger.quote_mint),
+        base_fee_destination: keys.base_fee_destination,
+        buyback_pool: keys.buyback.map(|buyback| buyback.pool),
+        buyback_pool_base_vault: keys.buyback.map(|buyback| buyback.pool_base_vault),
+        buyback_pool_quote_vault: keys.buyback.map(|buyback| buyback.pool_quote_vault),
+        base_mint: keys.buyback.map(|buyback| buyback.base_mint),
+        base_treasury_ata: keys.buyback.map(|buyback| vault_accounts.base_treasury_ata(&buyback.base_mint)),
+        buyback_quote_mint: keys.buyback.map(|_| ledger.quote_mint),
+        buyback_pool_authority: keys.buyback.map(|_| DAMM_V2_POOL_AUTHORITY),
+        buyback_event_authority: keys.buyback.map(|_| DAMM_V2_EVENT_AUTHORITY),
+        crank_reward_ata: keys.crank_reward.then(|| quote_ata(&keys.crank_caller, &ledger.quote_mint)),
+        cp_amm_program: DAMM_V2_PROGRAM_ID,
+        token_program: anchor_spl::token::ID,
+        associated_token_program: anchor_spl::associated_token::ID,
+        system_program: system_program::ID,
+    };
+
+    let mut metas = accounts.to_account_metas(None);
+    metas.extend(page.remaining_accounts());
+
+    Instruction {
+        program_id: vault_accounts.program_id,
+        accounts: metas,
+        data: page.instruction_data(),
+    }
+}
+
+/// Build `convert_base_fees`, swapping the base treasury into a quote mint's treasury
+pub fn convert_base_fees(
+    vault_accounts: &VaultAccounts,
+    ledger: &QuoteLedger,
+    keys: &ConvertBaseFeesKeys,
+    amount_in: Option<u64>,
+    minimum_amount_out: u64,
+) -> Instruction {
+    let accounts = meteora_fee_router::accounts::ConvertBaseFees {
+        authority: keys.authority,
+        policy_config: vault_accounts.policy_config,
+        distribution_progress: ledger.distribution_progress,
+        position_owner_pda: vault_accounts.position_owner,
+        vault: vault_accounts.vault,
+        base_treasury_ata: vault_accounts.base_treasury_ata(&keys.base_mint),
+        treasury_ata: ledger.treasury_ata,
+        pool: keys.pool,
+        pool_base_vault: keys.pool_base_vault,
+        pool_quote_vault: keys.pool_quote_vault,
+        base_mint: keys.base_mint,
+        quote_mint: ledger.quote_mint,
+        pool_authority: DAMM_V2_POOL_AUTHORITY,
+        event_authority: DAMM_V2_EVENT_AUTHORITY,
+        cp_amm_program: DAMM_V2_PROGRAM_ID,
+        token_program: anchor_spl::token::ID,
+    };
+    let params = ConvertBaseFeesParams {
+        quote_mint: ledger.quote_mint,
+        amount_in,
+        minimum_amount_out,
+    };
+
+    Instruction {
+        program_id: vault_accounts.program_id,
+        accounts: accounts.to_account_metas(None),
+        data: meteora_fee_router::instruction::ConvertBaseFees { params }.data(),
+    }
+}
+
+/// Build `abort_day`, closing a quote mint's stuck day (signed by the policy authority)
+pub fn abort_day(vault_accounts: &VaultAccounts, ledger: &QuoteLedger, authority: Pubkey) -> Instruction {
+    let accounts = meteora_fee_router::accounts::AbortDay {
+        authority,
+        policy_config: vault_accounts.policy_config,
+        distribution_progress: ledger.distribution_progress,
+        vault: vault_accounts.vault,
+    };
+
+    Instruction {
+        program_id: vault_accounts.program_id,
+        accounts: accounts.to_account_metas(None),
+        data: meteora_fee_router::instruction::AbortDay { quote_mint: ledger.quote_mint }.data(),
+    }
+}
+
+/// Build `set_reference_price`, setting the quote per base (Q64.64) that bounds buybacks
+/// through an honorary pool (signed by the policy authority)
+pub fn set_reference_price(
+    vault_accounts: &VaultAccounts,
+    authority: Pubkey,
+    pool: Pubkey,
+    reference_price_q64: u128,
+) -> Instruction {
+    let accounts = meteora_fee_router::accounts::SetReferencePrice {
+        authority,
+        policy_config: vault_accounts.policy_config,
+        vault: vault_accounts.vault,
+    };
+    let params = SetReferencePriceParams {
+        pool,
+        reference_price_q64,
+    };
+
+    Instruction {
+        program_id: vault_accounts.program_id,
+        accounts: accounts.to_account_metas(None),
+        data: meteora_fee_router::instruction::SetReferencePrice { params }.data(),
+    }
+}
+
+/// Build `claim_investor_accrual`, releasing an investor's accrued balance in a quote
+/// mint's ledger to their quote ATA (signed by the investor, between days)
+pub fn claim_investor_accrual(
+    vault_accounts: &VaultAccounts,
+    ledger: &QuoteLedger,
+    investor: Pubkey,
+) -> Instruction {
+    let accounts = meteora_fee_router::accounts::ClaimInvestorAccrual {
+        investor,
+        policy_config: vault_accounts.policy_config,
+        distribution_progress: ledger.distribution_progress,
+        investor_accrual: ledger.investor_accrual(&investor),
+        position_owner_pda: vault_accounts.position_owner,
+        treasury_ata: ledger.treasury_ata,
+        investor_ata: quote_ata(&investor, &ledger.quote_mint),
+        vault: vault_accounts.vault,
+        token_program: anchor_spl::token::ID,
+    };
+
+    Instruction {
+        program_id: vault_accounts.program_id,
+        accounts: accounts.to_account_metas(None),
+        data: meteora_fee_router::instruction::ClaimInvestorAccrual { quote_mint: ledger.quote_mint }.data(),
+    }
+}
+
+/// Build an idempotent creation of a wallet's quote ATA, which pages pay the wallet's
+/// payouts into
+pub fn create_quote_ata(payer: Pubkey, wallet: Pubkey, quote_mint: Pubkey) -> Instruction {
+    anchor_spl::associated_token::spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+        &payer,
+        &wallet,
+        &quote_mint,
+        &anchor_spl::token::ID,
+    )
+}
+
+/// Build `migrate_accounts`, bringing a vault's policy and primary day ledger onto the
+/// current layout (signed by the policy's creator wallet, who pays the added rent); each
+/// honorary position is passed with the token account holding its NFT
+pub fn migrate_accounts(
+    vault_accounts: &VaultAccounts,
+    authority: Pubkey,
+    position_nft_accounts: &[Pubkey],
+    params: MigrateAccountsParams,
+) -> Instruction {
+    let accounts = meteora_fee_router::accounts::MigrateAccounts {
+        authority,
+        policy_config: vault_accounts.policy_config,
+        distribution_progress: vault_accounts.distribution_progress,
+        vault: vault_accounts.vault,
+        system_program: system_program::ID,
+    };
+
+    let mut metas = accounts.to_account_metas(None);
+    metas.extend(honorary_position_accounts(&params.honorary_positions, position_nft_accounts));
+
+    Instruction {
+        program_id: vault_accounts.program_id,
+        accounts: metas,
+        data: meteora_fee_router::instruction::MigrateAccounts { params }.data(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pagination::DISTRIBUTE_FEES_NAMED_ACCOUNTS;
+    use anchor_lang::{AccountDeserialize, AnchorDeserialize, Discriminator};
+    use meteora_fee_router::DistributeFeesParams;
+
+    #[test]
+    fn test_distribute_fees_layout() {
+        let vault_accounts = VaultAccounts::new(Pubkey::new_unique());
+        let ledger = vault_accounts.primary_ledger(Pubkey::new_unique());
+        let keys = DistributeFeesKeys {
+            crank_caller: Pubkey::new_unique(),
+            creator_wallet: Pubkey::new_unique(),
+            base_fee_destination: None,
+            buyback: None,
+            crank_reward: false,
+        };
+        let page = DistributionPage {
+            quote_mint: ledger.quote_mint,
+            cursor: 7,
+            page_size: 3,
+            honorary_positions: vec![Pubkey::new_unique()],
+            weight_accounts: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+            payout_accounts: vec![Pubkey::new_unique()],
+            optional_accounts: 0,
+            next_registry_hash: [0; 32],
+        };
+
+        let instruction = distribute_fees(&vault_accounts, &ledger, &keys, &page);
+
+        // Named accounts in declaration order, then positions, weight and payout accounts
+        let keys_in_order: Vec<Pubkey> = instruction.accounts.iter().map(|meta| meta.pubkey).collect();
+        assert_eq!(keys_in_order[0], keys.crank_caller);
+        assert!(instruction.accounts[0].is_signer);
+        assert_eq!(keys_in_order[1], vault_accounts.policy_config);
+        assert_eq!(keys_in_order[2], vault_accounts.distribution_progress);
+        assert_eq!(keys_in_order[5], vault_accounts.treasury_ata(&ledger.quote_mint));
+        assert_eq!(keys_in_order[6], quote_ata(&keys.creator_wallet, &ledger.quote_mint));
+        // No base fee destination, buyback or crank reward: the program id stands in for
+        // optional accounts
+        assert!(keys_in_order[7..17].iter().all(|key| *key == vault_accounts.program_id));
+        let fixed = DISTRIBUTE_FEES_NAMED_ACCOUNTS;
+        assert_eq!(keys_in_order[fixed], page.honorary_positions[0]);
+        assert!(instruction.accounts[fixed].is_writable);
+        assert_eq!(&keys_in_order[fixed + 1..fixed + 3], &page.weight_accounts[..]);
+        assert_eq!(keys_in_order[fixed + 3], page.payout_accounts[0]);
+
+        // Discriminator, then the page parameters
+        let discriminator = meteora_fee_router::instruction::DistributeFees::DISCRIMINATOR;
+        assert_eq!(&instruction.data[..discriminator.len()], discriminator);
+        let params = DistributeFeesParams::try_from_slice(&instruction.data[discriminator.len()..]).unwrap();
+        assert_eq!(params.page_size, 3);
+        assert_eq!(params.cursor_position, Some(7));
+        assert_eq!(params.quote_mint, ledger.quote_mint);
+    }
+
+    #[test]
+    fn test_distribute_fees_buyback_accounts() {
+        let vault_accounts = VaultAccounts::new(Pubkey::new_unique());
+        let ledger = vault_accounts.primary_ledger(Pubkey::new_unique());
+        let buyback = BuybackKeys {
+            base_mint: Pubkey::new_unique(),
+            pool: Pubkey::new_unique(),
+            pool_base_vault: Pubkey::new_unique(),
+            pool_quote_vault: Pubkey::new_unique(),
+        };
+        let keys = DistributeFeesKeys {
+            crank_caller: Pubkey::new_unique(),
+            creator_wallet: Pubkey::new_unique(),
+            base_fee_destination: None,
+            buyback: Some(buyback),
+            crank_reward: false,
+        };
+        let page = DistributionPage {
+            quote_mint: ledger.quote_mint,
+            cursor: 0,
+            page_size: 1,
+            honorary_positions: vec![Pubkey::new_unique()],
+            weight_accounts: vec![Pubkey::new_unique()],
+            payout_accounts: vec![],
+            optional_accounts: 0,
+            next_registry_hash: [0; 32],
+        };
+
+        let instruction = distribute_fees(&vault_accounts, &ledger, &keys, &page);
+
+        // Pool, its vaults, the base mint and the base treasury follow the base fee destination
+        let buyback_accounts: Vec<Pubkey> = instruction.accounts[8..13].iter().map(|meta| meta.pubkey).collect();
+        assert_eq!(
+            buyback_accounts,
+            vec![
+                buyback.pool,
+                buyback.pool_base_vault,
+                buyback.pool_quote_vault,
+                buyback.base_mint,
+                vault_accounts.base_treasury_ata(&buyback.base_mint),
+            ]
+        );
+        assert!(instruction.accounts[8..13].iter().all(|meta| meta.is_writable));
+
+        // Then the quote mint and the DAMM V2 authorities the swap takes
+        let swap_accounts: Vec<Pubkey> = instruction.accounts[13..16].iter().map(|meta| meta.pubkey).collect();
+        assert_eq!(swap_accounts, vec![ledger.quote_mint, DAMM_V2_POOL_AUTHORITY, DAMM_V2_EVENT_AUTHORITY]);
+    }
+
+    #[test]
+    fn test_distribute_fees_crank_reward_account() {
+        let vault_accounts = VaultAccounts::new(Pubkey::new_unique());
+        let ledger = vault_accounts.primary_ledger(Pubkey::new_unique());
+        let keys = DistributeFeesKeys {
+            crank_caller: Pubkey::new_unique(),
+            creator_wallet: Pubkey::new_unique(),
+            base_fee_destination: None,
+            buyback: None,
+            crank_reward: true,
+        };
+        let page = DistributionPage {
+            quote_mint: ledger.quote_mint,
+            cursor: 0,
+            page_size: 1,
+            honorary_positions: vec![Pubkey::new_unique()],
+            weight_accounts: vec![Pubkey::new_unique()],
+            payout_accounts: vec![],
+            optional_accounts: 0,
+            next_registry_hash: [0; 32],
+        };
+
+        let instruction = distribute_fees(&vault_accounts, &ledger, &keys, &page);
+
+        // The caller's quote ATA follows the buyback accounts
+        assert_eq!(instruction.accounts[16].pubkey, quote_ata(&keys.crank_caller, &ledger.quote_mint));
+        assert!(instruction.accounts[16].is_writable);
+        assert_eq!(instruction.accounts.len(), DISTRIBUTE_FEES_NAMED_ACCOUNTS + 2);
+    }
+
+    #[test]
+    fn test_claim_investor_accrual_layout() {
+        let vault_accounts = VaultAccounts::new(Pubkey::new_unique());
+        let ledger = vault_accounts.primary_ledger(Pubkey::new_unique());
+        let investor = Pubkey::new_unique();
+
+        let instruction = claim_investor_accrual(&vault_accounts, &ledger, investor);
+
+        assert_eq!(instruction.accounts[0].pubkey, investor);
+        assert!(instruction.accounts[0].is_signer);
+        assert_eq!(instruction.accounts[3].pubkey, ledger.investor_accrual(&investor));
+        assert!(instruction.accounts[3].is_writable);
+        assert_eq!(instruction.accounts[5].pubkey, ledger.treasury_ata);
+        assert_eq!(instruction.accounts[6].pubkey, quote_ata(&investor, &ledger.quote_mint));
+
+        let discriminator = meteora_fee_router::instruction::ClaimInvestorAccrual::DISCRIMINATOR;
+        assert_eq!(&instruction.data[..discriminator.len()], discriminator);
+        assert_eq!(Pubkey::try_from_slice(&instruction.data[discriminator.len()..]).unwrap(), ledger.quote_mint);
+    }
+
+    #[test]
+    fn test_convert_base_fees_layout() {
+        let vault_accounts = VaultAccounts::new(Pubkey::new_unique());
+        let ledger = vault_accounts.primary_ledger(Pubkey::new_unique());
+        let keys = ConvertBaseFeesKeys {
+            authority: Pubkey::new_unique(),
+            base_mint: Pubkey::new_unique(),
+            pool: Pubkey::new_unique(),
+            pool_base_vault: Pubkey::new_unique(),
+            pool_quote_vault: Pubkey::new_unique(),
+        };
+
+        let instruction = convert_base_fees(&vault_accounts, &ledger, &keys, None, 9_850);
+
+        assert_eq!(instruction.accounts[0].pubkey, keys.authority);
+        assert!(instruction.accounts[0].is_signer);
+        assert_eq!(instruction.accounts[2].pubkey, ledger.distribution_progress);
+        assert!(instruction.accounts[2].is_writable);
+        assert_eq!(instruction.accounts[5].pubkey, vault_accounts.base_treasury_ata(&keys.base_mint));
+        assert_eq!(instruction.accounts[6].pubkey, ledger.treasury_ata);
+
+        // The swap's mints and the pinned DAMM V2 accounts follow the pool vaults
+        let swap_accounts: Vec<Pubkey> = instruction.accounts[10..15].iter().map(|meta| meta.pubkey).collect();
+        assert_eq!(
+            swap_accounts,
+            vec![keys.base_mint, ledger.quote_mint, DAMM_V2_POOL_AUTHORITY, DAMM_V2_EVENT_AUTHORITY, DAMM_V2_PROGRAM_ID]
+        );
+
+        let discriminator = meteora_fee_router::instruction::ConvertBaseFees::DISCRIMINATOR;
+        let params = ConvertBaseFeesParams::try_from_slice(&instruction.data[discriminator.len()..]).unwrap();
+        assert_eq!(params.quote_mint, ledger.quote_mint);
+        assert_eq!(params.amount_in, None);
+        assert_eq!(params.minimum_amount_out, 9_850);
+    }
+
+    #[test]
+    fn test_abort_day_accounts() {
+        let vault_accounts = VaultAccounts::new(Pubkey::new_unique());
+        let ledger = vault_accounts.primary_ledger(Pubkey::new_unique());
+        let authority = Pubkey::new_unique();
+
+        let instruction = abort_day(&vault_accounts, &ledger, authority);
+
+        assert_eq!(instruction.accounts[0].pubkey, authority);
+        assert!(instruction.accounts[0].is_signer);
+        assert_eq!(instruction.accounts[2].pubkey, ledger.distribution_progress);
+        assert!(instruction.accounts[2].is_writable);
+    }
+
+    #[test]
+    fn test_set_reference_price_layout() {
+        let vault_accounts = VaultAccounts::new(Pubkey::new_unique());
+        let authority = Pubkey::new_unique();
+        let pool = Pubkey::new_unique();
+
+        let instruction = set_reference_price(&vault_accounts, authority, pool, 3 << 64);
+
+        assert_eq!(instruction.accounts[0].pubkey, authority);
+        assert!(instruction.accounts[0].is_signer);
+        assert_eq!(instruction.accounts[1].pubkey, vault_accounts.policy_config);
+        assert!(instruction.accounts[1].is_writable);
+
+        let discriminator = meteora_fee_router::instruction::SetReferencePrice::DISCRIMINATOR;
+        let params = SetReferencePriceParams::try_from_slice(&instruction.data[discriminator.len()..]).unwrap();
+        assert_eq!(params.pool, pool);
+        assert_eq!(params.reference_price_q64, 3 << 64);
+    }
+
+    #[test]
+    fn test_honorary_pool_accounts() {
+        let pool = Pubkey::new_unique();
+        let other_pool = Pubkey::new_unique();
+        let honorary_pool = |address: Pubkey| HonoraryPool {
+            pool: address,
+            base_vault: Pubkey::new_unique(),
+            quote_vault: Pubkey::new_unique(),
+            base_is_token_a: true,
+            reference_price_q64: 1 << 64,
+        };
+
+        let metas = honorary_pool_accounts(
+            &pool,
+            &[honorary_pool(pool), honorary_pool(other_pool), honorary_pool(other_pool)],
+        );
+
+        assert_eq!(metas, vec![AccountMeta::new_readonly(other_pool, false)]);
+    }
+
+    #[test]
+    fn test_migrate_accounts_layout() {
+        let vault_accounts = VaultAccounts::new(Pubkey::new_unique());
+        let authority = Pubkey::new_unique();
+        let position = Pubkey::new_unique();
+        let position_nft_account = Pubkey::new_unique();
+
+        let instruction = migrate_accounts(
+            &vault_accounts,
+            authority,
+            &[position_nft_account],
+            MigrateAccountsParams { honorary_positions: vec![position] },
+        );
+
+        assert_eq!(instruction.accounts[0].pubkey, authority);
+        assert!(instruction.accounts[0].is_signer && instruction.accounts[0].is_writable);
+        assert_eq!(instruction.accounts[1].pubkey, vault_accounts.policy_config);
+        assert_eq!(instruction.accounts[2].pubkey, vault_accounts.distribution_progress);
+        assert!(instruction.accounts[1].is_writable && instruction.accounts[2].is_writable);
+
+        // Each position is followed by the token account holding its NFT
+        assert_eq!(
+            instruction.accounts[5..],
+            [AccountMeta::new_readonly(position, false), AccountMeta::new_readonly(position_nft_account, false)]
+        );
+
+        let discriminator = meteora_fee_router::instruction::MigrateAccounts::DISCRIMINATOR;
+        let params = MigrateAccountsParams::try_from_slice(&instruction.data[discriminator.len()..]).unwrap();
+        assert_eq!(params.honorary_positions, vec![position]);
+    }
+
+    #[test]
+    fn test_initialize_investor_accrual_accounts() {
+        let vault_accounts = VaultAccounts::new(Pubkey::new_unique());
+        let payer = Pubkey::new_unique();
+        let investor = Pubkey::new_unique();
+
+        let ledger = vault_accounts.primary_ledger(Pubkey::new_unique());
+
+        let instruction = initialize_investor_accrual(&vault_accounts, &ledger, payer, investor);
+
+        assert_eq!(instruction.program_id, meteora_fee_router::ID);
+        assert_eq!(instruction.accounts[0].pubkey, payer);
+        assert_eq!(instruction.accounts[2].pubkey, vault_accounts.investor_accrual(&investor));
+        assert!(instruction.accounts[2].is_writable);
+    }
+
+    #[test]
+    fn test_initialize_quote_ledger_accounts() {
+        let vault_accounts = VaultAccounts::new(Pubkey::new_unique());
+        let mut policy_config = meteora_fee_router::state::PolicyConfig::try_deserialize_unchecked(
+            &mut &[0u8; 8 + meteora_fee_router::state::PolicyConfig::INIT_SPACE][..],
+        )
+        .unwrap();
+        policy_config.quote_mint = Pubkey::new_unique();
+        let ledger = vault_accounts.quote_ledger(&policy_config, Pubkey::new_unique());
+
+        let instruction = initialize_quote_ledger(&vault_accounts, &ledger, Pubkey::new_unique());
+
+        assert!(!ledger.is_primary);
+        assert_ne!(ledger.distribution_progress, vault_accounts.distribution_progress);
+        assert_eq!(instruction.accounts[2].pubkey, ledger.distribution_progress);
+        assert!(instruction.accounts[2].is_writable);
+    }
+}
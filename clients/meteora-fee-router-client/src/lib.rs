@@ -0,0 +1,18 @@
+//! Off-chain client for the Meteora Fee Router program
+//!
+//! Derives a vault's program addresses (per quote mint ledger), builds
+//! `initialize_honorary_position`, `initialize_quote_ledger`, `initialize_investor_accrual`,
+//! `distribute_fees`, `convert_base_fees`, `set_reference_price`, `abort_day` and `migrate_accounts`
+//! instructions, and splits the investor registry into `distribute_fees` pages that fit in a transaction.
+
+pub mod error;
+pub mod accounts;
+pub mod instructions;
+pub mod pagination;
+
+pub use error::*;
+pub use accounts::*;
+pub use instructions::*;
+pub use pagination::*;
+
+pub use meteora_fee_router::ID as PROGRAM_ID;
@@ -0,0 +1,535 @@
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use anchor_lang::InstructionData;
+use meteora_fee_router::{
+    constants::MAX_PAGE_SIZE, state::registry_hash, utils::vesting::InvestorData, DistributeFeesParams,
+};
+use std::collections::{BTreeMap, HashSet};
+
+use crate::{
+    accounts::{quote_ata, QuoteLedger},
+    error::{ClientError, ClientResult},
+};
+
+/// Largest serialized transaction the cluster accepts (the packet data size)
+pub const MAX_TRANSACTION_SIZE: usize = 1232;
+
+/// Named accounts of the `distribute_fees` instruction, optional ones included
+pub const DISTRIBUTE_FEES_NAMED_ACCOUNTS: usize = 21;
+
+/// Named accounts of `distribute_fees` that may be omitted (base fee destination, the
+/// eight buyback accounts and the crank reward ATA)
+pub const DISTRIBUTE_FEES_OPTIONAL_ACCOUNTS: usize = 10;
+
+/// Keys a `distribute_fees` transaction references outside its remaining and passed
+/// optional accounts: the required named accounts plus the fee router program itself,
+/// which also stands in for every omitted optional account
+pub const DISTRIBUTE_FEES_FIXED_ACCOUNTS: usize =
+    DISTRIBUTE_FEES_NAMED_ACCOUNTS - DISTRIBUTE_FEES_OPTIONAL_ACCOUNTS + 1;
+
+/// `distribute_fees` instruction data: the 8 byte discriminator, then `DistributeFeesParams`
+/// with a pinned cursor (page size 4, `Some` cursor 1 + 4, quote mint 32, next registry
+/// hash 32)
+pub const DISTRIBUTE_FEES_DATA_LEN: usize = 8 + 4 + 5 + 32 + 32;
+
+/// Bytes a reserved account costs a transaction: its key and one instruction index
+const RESERVED_ACCOUNT_SIZE: usize = 33;
+
+/// Serialized size of a legacy transaction signed by its fee payer alone and carrying one
+/// instruction, over `account_keys` distinct keys (the instruction's program included) of
+/// which `instruction_accounts` are passed to the instruction
+pub fn legacy_transaction_size(account_keys: usize, instruction_accounts: usize, data_len: usize) -> usize {
+    compact_u16_len(1) + 64 // signatures
+        + 3 // message header
+        + compact_u16_len(account_keys) + 32 * account_keys
+        + 32 // recent blockhash
+        + compact_u16_len(1) + 1 // instructions, program index
+        + compact_u16_len(instruction_accounts) + instruction_accounts
+        + compact_u16_len(data_len) + data_len
+}
+
+/// Length of a value's compact-u16 encoding
+fn compact_u16_len(value: usize) -> usize {
+    match value {
+        0..=0x7f => 1,
+        0x80..=0x3fff => 2,
+        _ => 3,
+    }
+}
+
+/// Serialized size of a `distribute_fees` transaction passing `optional_accounts` of its
+/// optional named accounts and `remaining_accounts`, every passed account a distinct key
+fn distribute_fees_transaction_size(optional_accounts: usize, remaining_accounts: usize) -> usize {
+    legacy_transaction_size(
+        DISTRIBUTE_FEES_FIXED_ACCOUNTS + optional_accounts + remaining_accounts,
+        DISTRIBUTE_FEES_NAMED_ACCOUNTS + remaining_accounts,
+        DISTRIBUTE_FEES_DATA_LEN,
+    )
+}
+
+/// An investor's accounts as known to the crank
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvestorAccounts {
+    pub wallet: Pubkey,
+    /// Vesting or stake accounts carrying the investor's weight
+    pub weight_accounts: Vec<Pubkey>,
+}
+
+/// Transaction budget pages are sized against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageLimits {
+    /// Largest serialized transaction, in bytes
+    pub max_transaction_size: usize,
+    /// Slots kept free for other instructions (e.g. compute budget)
+    pub reserved_accounts: usize,
+    /// Optional named accounts pages pass (see `DistributeFeesKeys::optional_account_count`)
+    pub optional_accounts: usize,
+}
+
+impl Default for PageLimits {
+    fn default() -> Self {
+        Self {
+            max_transaction_size: MAX_TRANSACTION_SIZE,
+            reserved_accounts: 0,
+            optional_accounts: 0,
+        }
+    }
+}
+
+impl PageLimits {
+    /// Most remaining accounts a page's transaction can pass within the size budget
+    pub fn max_remaining_accounts(&self) -> usize {
+        let budget = self
+            .max_transaction_size
+            .saturating_sub(self.reserved_accounts * RESERVED_ACCOUNT_SIZE);
+        let mut accounts = 0;
+        while distribute_fees_transaction_size(self.optional_accounts, accounts + 1) <= budget {
+            accounts += 1;
+        }
+        accounts
+    }
+}
+
+/// One `distribute_fees` call over a slice of the investor registry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DistributionPage {
+    /// Quote mint whose day ledger the page distributes
+    pub quote_mint: Pubkey,
+    /// Registry index of the page's first investor
+    pub cursor: u32,
+    pub page_size: u32,
+    /// The honorary positions quoted in `quote_mint`, in policy order, leading every page
+    pub honorary_positions: Vec<Pubkey>,
+    /// Weight accounts: every one of the day on the opening page (cursor 0), where the
+    /// program commits the registry, and only the page's investors' on later pages
+    pub weight_accounts: Vec<Pubkey>,
+    /// Accrual PDAs (created or not, the program requires every one) and quote ATAs of
+    /// the page's investors
+    pub payout_accounts: Vec<Pubkey>,
+    /// Optional named accounts the page was sized to pass beside its remaining accounts
+    pub optional_accounts: usize,
+    /// Registry hash of the investors after the page (all zero on the last page), which
+    /// the program checks the page's investors against before paying them
+    pub next_registry_hash: [u8; 32],
+}
+
+impl DistributionPage {
+    /// Instruction parameters, pinning the cursor so a replayed page is a no-op
+    pub fn params(&self) -> DistributeFeesParams {
+        DistributeFeesParams {
+            page_size: self.page_size,
+            cursor_position: Some(self.cursor),
+            quote_mint: self.quote_mint,
+            next_registry_hash: self.next_registry_hash,
+        }
+    }
+
+    /// Remaining accounts in the order the program splits them: honorary positions,
+    /// weight accounts, then payout accounts
+    pub fn remaining_accounts(&self) -> Vec<AccountMeta> {
+        self.honorary_positions
+            .iter()
+            .map(|address| AccountMeta::new(*address, false))
+            .chain(self.weight_accounts.iter().map(|address| AccountMeta::new_readonly(*address, false)))
+            .chain(self.payout_accounts.iter().map(|address| AccountMeta::new(*address, false)))
+            .collect()
+    }
+
+    /// Accounts the page's transaction references
+    pub fn transaction_account_count(&self) -> usize {
+        DISTRIBUTE_FEES_FIXED_ACCOUNTS + self.optional_accounts + self.remaining_account_count()
+    }
+
+    /// Serialized size of the page's transaction, every passed account a distinct key
+    pub fn transaction_size(&self) -> usize {
+        distribute_fees_transaction_size(self.optional_accounts, self.remaining_account_count())
+    }
+
+    /// `distribute_fees` instruction data for the page
+    pub fn instruction_data(&self) -> Vec<u8> {
+        meteora_fee_router::instruction::DistributeFees { params: self.params() }.data()
+    }
+
+    fn remaining_account_count(&self) -> usize {
+        self.honorary_positions.len() + self.weight_accounts.len() + self.payout_accounts.len()
+    }
+}
+
+/// Merge investor entries per wallet into the program's registry order (sorted by wallet)
+pub fn investor_registry(investors: &[InvestorAccounts]) -> Vec<InvestorAccounts> {
+    let mut registry: BTreeMap<Pubkey, InvestorAccounts> = BTreeMap::new();
+
+    for investor in investors {
+        let entry = registry.entry(investor.wallet).or_insert_with(|| InvestorAccounts {
+            wallet: investor.wallet,
+            weight_accounts: Vec::new(),
+        });
+        for weight_account in &investor.weight_accounts {
+            if !entry.weight_accounts.contains(weight_account) {
+                entry.weight_accounts.push(*weight_account);
+            }
+        }
+    }
+
+    registry.into_values().collect()
+}
+
+/// Registry hash of entries in the program's registry order, as the program commits it
+pub fn investor_registry_hash(registry: &[InvestorAccounts]) -> ClientResult<[u8; 32]> {
+    let entries: Vec<InvestorData> = registry
+        .iter()
+        .map(|investor| InvestorData {
+            wallet: investor.wallet,
+            locked_amount: 0,
+            total_allocation: 0,
+            stream_accounts: investor.weight_accounts.clone(),
+        })
+        .collect();
+    registry_hash(&entries).map_err(|_| ClientError::UnorderedRegistry)
+}
+
+/// Split the investor list into the pages of a quote mint's day, from `start_cursor`
+///
+/// Pages follow the program's registry order and hold at most `MAX_PAGE_SIZE`
+/// investors, each page as many as fit beside the honorary positions in a legacy
+/// transaction of `limits.max_transaction_size` bytes.
+/// The opening page (cursor 0) carries every weight account of the day so the program
+/// can commit the registry; later pages carry only their own investors' weight accounts.
+pub fn paginate_investors(
+    ledger: &QuoteLedger,
+    honorary_positions: &[Pubkey],
+    investors: &[InvestorAccounts],
+    start_cursor: u32,
+    limits: PageLimits,
+) -> ClientResult<Vec<DistributionPage>> {
+    let registry = investor_registry(investors);
+    if registry.is_empty() {
+        return Err(ClientError::NoInvestors);
+    }
+
+    let available = limits.max_remaining_accounts().saturating_sub(honorary_positions.len());
+
+    let mut seen = HashSet::new();
+    let day_weight_accounts: Vec<Pubkey> = registry
+        .iter()
+        .flat_map(|investor| investor.weight_accounts.iter().copied())
+        .filter(|address| seen.insert(*address))
+        .collect();
+    if start_cursor == 0 && day_weight_accounts.len() > available {
+        return Err(ClientError::TooManyWeightAccounts {
+            required: day_weight_accounts.len(),
+            available,
+        });
+    }
+
+    let payout_accounts_of = |investor: &InvestorAccounts| {
+        vec![
+            ledger.investor_accrual(&investor.wallet),
+            quote_ata(&investor.wallet, &ledger.quote_mint),
+        ]
+    };
+
+    let mut pages = Vec::new();
+    let mut cursor = start_cursor as usize;
+    while cursor < registry.len() {
+        let opening = cursor == 0;
+        let mut weight_accounts = if opening { day_weight_accounts.clone() } else { Vec::new() };
+        let mut payout_accounts = Vec::new();
+        let mut end = cursor;
+
+        while end < registry.len() && end - cursor < MAX_PAGE_SIZE as usize {
+            let investor = &registry[end];
+            let investor_weight_accounts = if opening { 0 } else { investor.weight_accounts.len() };
+            let investor_accounts = payout_accounts_of(investor);
+            let required = investor_weight_accounts + investor_accounts.len();
+            if weight_accounts.len() + payout_accounts.len() + required > available {
+                if end == cursor {
+                    return Err(ClientError::PageTooSmall {
+                        wallet: investor.wallet,
+                        required,
+                        available: available - weight_accounts.len(),
+                    });
+                }
+                break;
+            }
+            if !opening {
+                weight_accounts.extend(investor.weight_accounts.iter().copied());
+            }
+            payout_accounts.extend(investor_accounts);
+            end += 1;
+        }
+
+        pages.push(DistributionPage {
+            quote_mint: ledger.quote_mint,
+            cursor: cursor as u32,
+            page_size: (end - cursor) as u32,
+            honorary_positions: honorary_positions.to_vec(),
+            weight_accounts,
+            payout_accounts,
+            optional_accounts: limits.optional_accounts,
+            next_registry_hash: investor_registry_hash(&registry[end..])?,
+        });
+        cursor = end;
+    }
+
+    Ok(pages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        accounts::VaultAccounts,
+        instructions::{distribute_fees, BuybackKeys, DistributeFeesKeys},
+    };
+    use meteora_fee_router::state::DistributionProgress;
+    use solana_sdk::message::Message;
+
+    fn investor(weight_accounts: usize) -> InvestorAccounts {
+        InvestorAccounts {
+            wallet: Pubkey::new_unique(),
+            weight_accounts: (0..weight_accounts).map(|_| Pubkey::new_unique()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_registry_merges_and_sorts_wallets() {
+        let first = investor(1);
+        let mut second = investor(1);
+        let mut repeat = first.clone();
+        repeat.weight_accounts.push(Pubkey::new_unique());
+        second.wallet = Pubkey::new_from_array([0; 32]);
+
+        let registry = investor_registry(&[first.clone(), second.clone(), repeat]);
+
+        assert_eq!(registry.len(), 2);
+        assert_eq!(registry[0].wallet, second.wallet);
+        assert_eq!(registry[1].wallet, first.wallet);
+        assert_eq!(registry[1].weight_accounts.len(), 2);
+    }
+
+    #[test]
+    fn test_pages_cover_registry_within_limits() {
+        let ledger = VaultAccounts::new(Pubkey::new_unique()).primary_ledger(Pubkey::new_unique());
+        let positions = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let investors: Vec<InvestorAccounts> = (0..6)
+            .map(|_| investor(1))
+            .collect();
+        let limits = PageLimits { reserved_accounts: 1, ..PageLimits::default() };
+
+        let pages = paginate_investors(&ledger, &positions, &investors, 0, limits).unwrap();
+
+        // Pages are contiguous and cover the registry
+        let mut cursor = 0;
+        for page in &pages {
+            assert_eq!(page.cursor, cursor);
+            assert_eq!(page.params().quote_mint, ledger.quote_mint);
+            assert!(page.page_size > 0);
+            assert_eq!(page.honorary_positions, positions);
+            assert!(page.transaction_size() + limits.reserved_accounts * 33 <= MAX_TRANSACTION_SIZE);
+            cursor += page.page_size;
+        }
+        assert_eq!(cursor, 6);
+        assert!(pages.len() > 1);
+
+        // The opening page carries every weight account, later pages only their investors'
+        let registry = investor_registry(&investors);
+        assert_eq!(pages[0].weight_accounts.len(), 6);
+        for page in &pages {
+            let start = page.cursor as usize;
+            let page_investors = &registry[start..start + page.page_size as usize];
+            if start > 0 {
+                let expected: Vec<Pubkey> = page_investors
+                    .iter()
+                    .flat_map(|investor| investor.weight_accounts.iter().copied())
+                    .collect();
+                assert_eq!(page.weight_accounts, expected);
+            }
+
+            // Each page carries exactly its investors' accrual PDAs and quote ATAs
+            let expected: Vec<Pubkey> = page_investors
+                .iter()
+                .flat_map(|investor| {
+                    [ledger.investor_accrual(&investor.wallet), quote_ata(&investor.wallet, &ledger.quote_mint)]
+                })
+                .collect();
+            assert_eq!(page.payout_accounts, expected);
+        }
+    }
+
+    #[test]
+    fn test_pages_chain_to_committed_registry() {
+        let ledger = VaultAccounts::new(Pubkey::new_unique()).primary_ledger(Pubkey::new_unique());
+        let investors: Vec<InvestorAccounts> = (0..12).map(|_| investor(1)).collect();
+        let pages = paginate_investors(&ledger, &[Pubkey::new_unique()], &investors, 0, PageLimits::default()).unwrap();
+        assert!(pages.len() > 1);
+
+        // The program accepts each page against the registry the opening page commits
+        let registry: Vec<InvestorData> = investor_registry(&investors)
+            .into_iter()
+            .map(|investor| InvestorData {
+                wallet: investor.wallet,
+                locked_amount: 1,
+                total_allocation: 1,
+                stream_accounts: investor.weight_accounts,
+            })
+            .collect();
+        let mut progress = DistributionProgress::default();
+        progress.commit_day_registry(&registry, 1_000).unwrap();
+        for page in &pages {
+            let start = page.cursor as usize;
+            let page_investors = &registry[start..start + page.page_size as usize];
+            progress.record_registry_page(page_investors, page.next_registry_hash).unwrap();
+            progress.advance_cursor(page.page_size).unwrap();
+        }
+        assert_eq!(pages.last().unwrap().next_registry_hash, [0; 32]);
+        progress.finish_day_registry(1_000).unwrap();
+    }
+
+    #[test]
+    fn test_pages_resume_from_cursor() {
+        let ledger = VaultAccounts::new(Pubkey::new_unique()).primary_ledger(Pubkey::new_unique());
+        let investors: Vec<InvestorAccounts> = (0..30).map(|_| investor(1)).collect();
+        let positions = [Pubkey::new_unique()];
+
+        let pages = paginate_investors(&ledger, &positions, &investors, 10, PageLimits::default()).unwrap();
+
+        // Continuation pages start at the cursor and never carry the whole registry
+        assert_eq!(pages[0].cursor, 10);
+        assert_eq!(pages.iter().map(|page| page.page_size).sum::<u32>(), 20);
+        assert!(pages.iter().all(|page| page.weight_accounts.len() == page.page_size as usize));
+
+        // A cursor past the registry leaves nothing to page
+        assert!(paginate_investors(&ledger, &positions, &investors, 30, PageLimits::default()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_pages_respect_max_page_size() {
+        let ledger = VaultAccounts::new(Pubkey::new_unique()).primary_ledger(Pubkey::new_unique());
+        let investors: Vec<InvestorAccounts> = (0..(MAX_PAGE_SIZE as usize + 5))
+            .map(|_| investor(0))
+            .collect();
+
+        let limits = PageLimits { max_transaction_size: 4 * MAX_TRANSACTION_SIZE, ..PageLimits::default() };
+
+        let pages = paginate_investors(&ledger, &[Pubkey::new_unique()], &investors, 0, limits).unwrap();
+
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].page_size, MAX_PAGE_SIZE);
+        assert_eq!(pages[1].page_size, 5);
+        assert_eq!(pages[1].params().cursor_position, Some(MAX_PAGE_SIZE));
+    }
+
+    #[test]
+    fn test_too_many_weight_accounts() {
+        let ledger = VaultAccounts::new(Pubkey::new_unique()).primary_ledger(Pubkey::new_unique());
+        let investors = vec![investor(60)];
+
+        let result = paginate_investors(&ledger, &[Pubkey::new_unique()], &investors, 0, PageLimits::default());
+
+        assert_eq!(
+            result.unwrap_err(),
+            ClientError::TooManyWeightAccounts { required: 60, available: 18 }
+        );
+        assert_eq!(
+            paginate_investors(&ledger, &[Pubkey::new_unique()], &[], 0, PageLimits::default()).unwrap_err(),
+            ClientError::NoInvestors
+        );
+    }
+
+    #[test]
+    fn test_full_page_serializes_within_packet() {
+        let vault_accounts = VaultAccounts::new(Pubkey::new_unique());
+        let ledger = vault_accounts.primary_ledger(Pubkey::new_unique());
+        let bare = DistributeFeesKeys {
+            crank_caller: Pubkey::new_unique(),
+            creator_wallet: Pubkey::new_unique(),
+            base_fee_destination: None,
+            buyback: None,
+            crank_reward: false,
+        };
+        // Every optional account passed, so each named account is a distinct key
+        let full = DistributeFeesKeys {
+            base_fee_destination: Some(Pubkey::new_unique()),
+            buyback: Some(BuybackKeys {
+                base_mint: Pubkey::new_unique(),
+                pool: Pubkey::new_unique(),
+                pool_base_vault: Pubkey::new_unique(),
+                pool_quote_vault: Pubkey::new_unique(),
+            }),
+            crank_reward: true,
+            ..bare
+        };
+        assert_eq!(bare.optional_account_count(), 0);
+        assert_eq!(full.optional_account_count(), DISTRIBUTE_FEES_OPTIONAL_ACCOUNTS);
+
+        let investors: Vec<InvestorAccounts> = (0..30).map(|_| investor(1)).collect();
+        let positions = [Pubkey::new_unique()];
+
+        for (keys, full_page_size) in [(bare, 6), (full, 2)] {
+            let limits = PageLimits { optional_accounts: keys.optional_account_count(), ..PageLimits::default() };
+            let pages = paginate_investors(&ledger, &positions, &investors, 4, limits).unwrap();
+            let serialized_size = |page: &DistributionPage| {
+                let instruction = distribute_fees(&vault_accounts, &ledger, &keys, page);
+                let message = Message::new(&[instruction], Some(&keys.crank_caller));
+                assert_eq!(message.header.num_required_signatures, 1);
+                1 + 64 + message.serialize().len()
+            };
+
+            // A full page fills the packet as far as another investor's accounts allow
+            let page = &pages[0];
+            assert_eq!(page.page_size, full_page_size);
+            assert_eq!(page.instruction_data().len(), DISTRIBUTE_FEES_DATA_LEN);
+            assert_eq!(serialized_size(page), page.transaction_size());
+            assert!(serialized_size(page) <= MAX_TRANSACTION_SIZE);
+
+            let mut overfull = page.clone();
+            overfull.page_size += 1;
+            overfull.weight_accounts.push(Pubkey::new_unique());
+            overfull.payout_accounts.extend([Pubkey::new_unique(), Pubkey::new_unique()]);
+            assert!(serialized_size(&overfull) > MAX_TRANSACTION_SIZE);
+        }
+    }
+
+    #[test]
+    fn test_remaining_accounts_order() {
+        let page = DistributionPage {
+            quote_mint: Pubkey::new_unique(),
+            cursor: 0,
+            page_size: 1,
+            honorary_positions: vec![Pubkey::new_unique()],
+            weight_accounts: vec![Pubkey::new_unique()],
+            payout_accounts: vec![Pubkey::new_unique()],
+            optional_accounts: 0,
+            next_registry_hash: [0; 32],
+        };
+
+        let metas = page.remaining_accounts();
+        assert_eq!(metas.len(), 3);
+        assert_eq!(metas[0].pubkey, page.honorary_positions[0]);
+        assert!(metas[0].is_writable);
+        assert_eq!(metas[1].pubkey, page.weight_accounts[0]);
+        assert!(!metas[1].is_writable);
+        assert!(metas[2].is_writable);
+        assert_eq!(metas[2].pubkey, page.payout_accounts[0]);
+    }
+}
@@ -0,0 +1,308 @@
+//! Randomized multi-page days checked against the day-close treasury identity
+//!
+//! Each simulated day runs the same state transitions as `distribute_fees` (day start,
+//! claim, pool opening, every page via `plan_investor_page`, crank rewards, and the day
+//! close via `settle_day` and `close_day`). The treasury balance is modelled from the
+//! transfers the instruction makes: each investor payout, the page's dust, the crank
+//! reward and the creator payout.
+
+use anchor_lang::prelude::*;
+use std::collections::HashMap;
+
+use crate::{
+    constants::*,
+    error::ErrorCode,
+    state::{
+        registry_hash, CrankReward, DistributionProgress, DistributionTimingState, DustPolicy,
+        InvestorAccrual, PolicyConfig,
+    },
+    utils::{
+        investor_distribution::InvestorDistribution,
+        math::calculate_distribution,
+        vesting::InvestorData,
+    },
+};
+
+/// Small deterministic generator so failures reproduce from the seed
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self, bound: u64) -> u64 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (self.0 >> 11) % bound.max(1)
+    }
+
+    fn chance(&mut self, percent: u64) -> bool {
+        self.next(100) < percent
+    }
+
+    fn optional(&mut self, percent: u64, bound: u64) -> Option<u64> {
+        if self.chance(percent) {
+            Some(self.next(bound) + 1)
+        } else {
+            None
+        }
+    }
+}
+
+struct Harness {
+    policy_config: PolicyConfig,
+    progress: DistributionProgress,
+    accruals: HashMap<Pubkey, InvestorAccrual>,
+    wallets: Vec<Pubkey>,
+    treasury_balance: u64,
+}
+
+impl Harness {
+    fn new(rng: &mut Lcg) -> Self {
+        let dust_policy = match rng.next(4) {
+            0 => DustPolicy::CarryForward,
+            1 => DustPolicy::CreatorAtDayClose,
+            2 => DustPolicy::LargestWeightInvestor,
+            _ => DustPolicy::RoundRobin,
+        };
+        let mut policy_config = PolicyConfig {
+            vault: Pubkey::new_unique(),
+            quote_mint: Pubkey::new_unique(),
+            creator_wallet: Pubkey::new_unique(),
+            investor_fee_share_bps: rng.next(MAX_BASIS_POINTS as u64 + 1) as u16,
+            daily_cap_lamports: rng.optional(30, 5_000_000),
+            min_payout_lamports: rng.next(5_000) + 1,
+            y0_total_allocation: rng.next(50_000_000) + 1,
+            bump: 255,
+            per_investor_daily_cap_lamports: rng.optional(25, 200_000),
+            rolling_cap_lamports: rng.optional(25, 20_000_000),
+            dust_policy,
+            ..PolicyConfig::default()
+        };
+        if rng.chance(30) {
+            let reward = CrankReward::FlatLamports { lamports: rng.next(5_000) + 1 };
+            policy_config.configure_crank_reward(reward, rng.next(20_000) + 1).unwrap();
+        }
+
+        let mut progress = DistributionProgress {
+            ..DistributionProgress::default()
+        };
+        progress.initialize(policy_config.vault, policy_config.quote_mint, 0).unwrap();
+
+        // A fixed population; each day a random subset holds locks
+        let wallets: Vec<Pubkey> = (0..rng.next(60) + 1).map(|_| Pubkey::new_unique()).collect();
+        let mut accruals = HashMap::new();
+        for wallet in &wallets {
+            if rng.chance(40) {
+                let mut accrual = InvestorAccrual {
+                    vault: Pubkey::default(),
+                    investor: Pubkey::default(),
+                    accrued_amount: 0,
+                    total_released: 0,
+                    last_update_ts: 0,
+                    bump: 0,
+                };
+                accrual.initialize(policy_config.vault, *wallet, 0).unwrap();
+                accruals.insert(*wallet, accrual);
+            }
+        }
+
+        Self {
+            policy_config,
+            progress,
+            accruals,
+            wallets,
+            treasury_balance: 0,
+        }
+    }
+
+    fn day_investors(&self, rng: &mut Lcg) -> Vec<InvestorData> {
+        let mut investors = Vec::new();
+        for (index, wallet) in self.wallets.iter().enumerate() {
+            // A day always has at least one investor, as the instruction requires
+            if index > 0 && !rng.chance(80) {
+                continue;
+            }
+            // Mostly small locks, with the occasional whale
+            let locked_amount = if rng.chance(10) {
+                rng.next(10_000_000_000)
+            } else {
+                rng.next(100_000)
+            };
+            investors.push(InvestorData {
+                wallet: *wallet,
+                locked_amount,
+                total_allocation: locked_amount,
+                stream_accounts: Vec::new(),
+            });
+        }
+        investors.sort_by_key(|investor| investor.wallet);
+        investors
+    }
+
+    /// Run one full day the way `distribute_fees` does and close it with the invariant checks
+    /// Returns the creator payout
+    fn run_day(&mut self, rng: &mut Lcg, current_timestamp: i64) -> Result<u64> {
+        self.run_day_pages(rng, current_timestamp, usize::MAX)
+            .map(|creator_paid| creator_paid.expect("every page ran"))
+    }
+
+    /// Run at most `max_pages` pages of a day, closing it only if every page ran
+    /// Returns the creator payout when the day closed
+    fn run_day_pages(&mut self, rng: &mut Lcg, current_timestamp: i64, max_pages: usize) -> Result<Option<u64>> {
+        let timing_state = self.progress.prepare_for_distribution(
+            current_timestamp,
+            self.policy_config.distribution_period_secs,
+            self.policy_config.epoch_anchor_ts,
+        )?;
+        assert_eq!(timing_state, DistributionTimingState::NewDay);
+
+        // Claim into the treasury, snapshotted as the day opens
+        self.progress.record_treasury_opening(self.treasury_balance);
+        let claimed = if rng.chance(15) { 0 } else { rng.next(10_000_000) };
+        self.treasury_balance += claimed;
+        self.progress.record_treasury_inflow(claimed)?;
+        // A claim carried from an unfinished day is already in the treasury
+        let claimed = claimed + self.progress.take_carried_claim_quote();
+        self.progress.record_day_claim(claimed)?;
+
+        // Open the day's pool
+        let day_investors = self.day_investors(rng);
+        let total_locked_amount: u64 = day_investors.iter().map(|investor| investor.locked_amount).sum();
+        let (investor_share, _) = calculate_distribution(
+            claimed,
+            total_locked_amount,
+            self.policy_config.y0_total_allocation,
+            self.policy_config.investor_fee_share_bps,
+        )?;
+        self.progress.open_day(investor_share, &self.policy_config)?;
+        let total_investor_amount = self.progress.payable_investor_pool()?;
+        self.progress.commit_day_registry(&day_investors, total_investor_amount)?;
+
+        // Every page of the day
+        let page_size = rng.next(MAX_PAGE_SIZE as u64) as usize + 1;
+        let mut pages = 0;
+        while (self.progress.pagination_cursor as usize) < day_investors.len() {
+            if pages == max_pages {
+                return Ok(None);
+            }
+            pages += 1;
+            let page_start = self.progress.pagination_cursor as usize;
+            let page_investors = InvestorDistribution::investor_page(&day_investors, page_start, page_size)?;
+            let next_registry_hash = registry_hash(&day_investors[page_start + page_investors.len()..])?;
+            let page = InvestorDistribution::plan_investor_page(
+                &self.policy_config,
+                &mut self.progress,
+                page_investors,
+                next_registry_hash,
+                total_investor_amount,
+                &mut self.accruals,
+                current_timestamp,
+            )?;
+            for payout in &page.payouts {
+                self.treasury_balance -= payout.payout_amount;
+            }
+            self.treasury_balance -= page.dust_paid;
+
+            // The crank reward is paid after the page's payouts
+            let crank_reward = self.progress.crank_reward_due(&self.policy_config, page_start as u32)?;
+            self.progress.record_crank_reward(crank_reward)?;
+            self.treasury_balance -= crank_reward;
+        }
+
+        // Close: the creator payout leaves the treasury before the balance is checked
+        let settlement = self.progress.settle_day(self.policy_config.dust_policy, total_investor_amount)?;
+        let creator_paid = settlement.creator_payout()?;
+        self.treasury_balance -= creator_paid;
+        self.progress.close_day(self.treasury_balance, creator_paid)?;
+
+        Ok(Some(creator_paid))
+    }
+
+    fn accrued_in_pdas(&self) -> u64 {
+        self.accruals.values().map(|accrual| accrual.accrued_amount).sum()
+    }
+}
+
+#[test]
+fn fuzz_multi_page_days_conserve_funds() {
+    for seed in 0..200u64 {
+        let mut rng = Lcg(seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) ^ 0x2545_f491_4f6c_dd1d);
+        let mut harness = Harness::new(&mut rng);
+        let mut current_timestamp = 1_700_000_000i64;
+
+        for _ in 0..rng.next(6) + 1 {
+            harness.run_day(&mut rng, current_timestamp)
+                .unwrap_or_else(|error| panic!("seed {}: {:?}", seed, error));
+
+            // The treasury holds exactly what is still owed
+            assert_eq!(
+                harness.treasury_balance,
+                harness.progress.outstanding_liabilities().unwrap(),
+                "seed {}", seed
+            );
+
+            // The progress account's accrual total matches the PDAs
+            assert_eq!(harness.progress.investor_accrued_total, harness.accrued_in_pdas(), "seed {}", seed);
+
+            current_timestamp += TWENTY_FOUR_HOURS + rng.next(3_600) as i64;
+        }
+    }
+}
+
+#[test]
+fn fuzz_unfinished_days_are_carried() {
+    for seed in 0..200u64 {
+        let mut rng = Lcg(seed.wrapping_mul(0xbf58_476d_1ce4_e5b9) ^ 0x94d0_49bb_1331_11eb);
+        let mut harness = Harness::new(&mut rng);
+        let mut current_timestamp = 1_700_000_000i64;
+
+        for _ in 0..rng.next(6) + 2 {
+            // Some days are left after a few pages, as a crashed crank would
+            let max_pages = if rng.chance(40) { rng.next(3) as usize + 1 } else { usize::MAX };
+            let closed = harness.run_day_pages(&mut rng, current_timestamp, max_pages)
+                .unwrap_or_else(|error| panic!("seed {}: {:?}", seed, error))
+                .is_some();
+
+            // Everything an unfinished day left is still in the treasury, owed or to be claimed
+            if !closed {
+                let unfinished = harness.progress.unfinished_day().unwrap();
+                let unsettled = unfinished.map_or(0, |day| {
+                    day.investor_carried + day.creator_carried + day.claim_carried
+                });
+                assert_eq!(
+                    harness.treasury_balance,
+                    harness.progress.outstanding_liabilities().unwrap() + unsettled,
+                    "seed {}", seed
+                );
+            } else {
+                assert_eq!(
+                    harness.treasury_balance,
+                    harness.progress.outstanding_liabilities().unwrap(),
+                    "seed {}", seed
+                );
+            }
+
+            current_timestamp += TWENTY_FOUR_HOURS + rng.next(3_600) as i64;
+        }
+    }
+}
+
+#[test]
+fn conservation_detects_unaccounted_payouts() {
+    let mut rng = Lcg(42);
+    let mut harness = Harness::new(&mut rng);
+    let creator_paid = harness.run_day(&mut rng, 1_700_000_000).unwrap();
+    let balance = harness.treasury_balance;
+    harness.progress.verify_day_conservation(balance, creator_paid).unwrap();
+
+    // A lamport transferred out of the treasury without being recorded breaks the identity
+    let result = harness.progress.verify_day_conservation(balance - 1, creator_paid);
+    assert_eq!(result.unwrap_err(), ErrorCode::AccountingInvariantViolated.into());
+
+    // So does a payout recorded without its transfer
+    harness.progress.current_day_distributed += 1;
+    assert!(harness.progress.verify_day_conservation(balance, creator_paid).is_err());
+    harness.progress.current_day_distributed -= 1;
+
+    // And a treasury that no longer covers what is owed
+    harness.progress.carry_over_dust += 1;
+    let result = harness.progress.verify_day_conservation(balance, creator_paid);
+    assert_eq!(result.unwrap_err(), ErrorCode::AccountingInvariantViolated.into());
+}
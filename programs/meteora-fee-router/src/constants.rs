@@ -6,12 +6,31 @@ pub const POLICY_SEED: &[u8] = b"policy";
 /// Seed for distribution progress PDA
 pub const PROGRESS_SEED: &[u8] = b"progress";
 
+/// Seed for per-investor accrual PDAs
+pub const ACCRUAL_SEED: &[u8] = b"investor_accrual";
+
 /// Seed for vault-related PDAs
 pub const VAULT_SEED: &[u8] = b"vault";
 
+/// Layout version of policy and day ledger accounts; accounts written before versioning
+/// read as 0 and are brought up to date by `migrate_accounts`
+pub const ACCOUNT_LAYOUT_VERSION: u8 = 1;
+
 /// 24 hours in seconds
 pub const TWENTY_FOUR_HOURS: i64 = 86400;
 
+/// Shortest distribution period a policy can configure (one hour)
+pub const MIN_DISTRIBUTION_PERIOD_SECS: i64 = 3600;
+
+/// Longest distribution period a policy can configure (30 days)
+pub const MAX_DISTRIBUTION_PERIOD_SECS: i64 = 30 * TWENTY_FOUR_HOURS;
+
+/// Most epochs a catch-up day is split into; older missed epochs fold into the first
+pub const MAX_CATCH_UP_EPOCHS: u64 = 7;
+
+/// Largest crank reward per day, in basis points of the day's claimed quote (1%)
+pub const MAX_CRANK_REWARD_BPS: u16 = 100;
+
 /// Maximum basis points (100%)
 pub const MAX_BASIS_POINTS: u16 = 10000;
 
@@ -22,4 +41,20 @@ pub const WEIGHT_PRECISION: u128 = 1_000_000;
 pub const DEFAULT_MIN_PAYOUT: u64 = 1000;
 
 /// Maximum page size for pagination
-pub const MAX_PAGE_SIZE: u32 = 50;
\ No newline at end of file
+pub const MAX_PAGE_SIZE: u32 = 50;
+
+/// Maximum number of vesting programs a policy can accept investor locks from
+pub const MAX_VESTING_PROGRAMS: usize = 4;
+
+/// Length of the rolling distribution cap window (7 days)
+pub const ROLLING_CAP_WINDOW_SECS: i64 = 7 * TWENTY_FOUR_HOURS;
+
+/// Ring buffer slots of the rolling cap window, one per distribution period; a policy
+/// with a rolling cap needs a period that divides the window into at most this many
+pub const ROLLING_CAP_SLOTS: usize = 7;
+
+/// Maximum number of honorary positions a vault can claim fees from
+pub const MAX_HONORARY_POSITIONS: usize = 4;
+
+/// Maximum number of quote mints a vault keeps separate treasuries and day ledgers for
+pub const MAX_QUOTE_MINTS: usize = 3;
\ No newline at end of file
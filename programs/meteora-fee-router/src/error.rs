@@ -8,7 +8,7 @@ pub enum ErrorCode {
     #[msg("Base fees detected - quote-only enforcement failed")]
     BaseFeeDetected,
     
-    #[msg("24-hour cooldown not elapsed")]
+    #[msg("Distribution period cooldown not elapsed")]
     CooldownNotElapsed,
     
     #[msg("Daily distribution cap exceeded")]
@@ -106,4 +106,100 @@ pub enum ErrorCode {
     
     #[msg("Invalid Streamflow account owner - not owned by Streamflow program")]
     InvalidStreamflowAccountOwner,
+    
+    #[msg("Vesting account is not owned by a supported vesting program")]
+    UnsupportedVestingProgram,
+    
+    #[msg("Jupiter Lock vesting escrow validation failed")]
+    InvalidVestingEscrow,
+    
+    #[msg("Vesting program is not in the policy's allowed registry")]
+    VestingProgramNotAllowed,
+    
+    #[msg("Invalid vesting program registry")]
+    InvalidVestingProgramRegistry,
+    
+    #[msg("Invalid staking account layout")]
+    InvalidStakingLayout,
+    
+    #[msg("Stake account validation failed")]
+    InvalidStakeAccount,
+
+    #[msg("Dust recipient ATA missing from the payout accounts")]
+    InvalidDustRecipient,
+
+    #[msg("Investor accrual account validation failed")]
+    InvalidInvestorAccrual,
+
+    #[msg("Day accounting invariant violated")]
+    AccountingInvariantViolated,
+
+    #[msg("Invalid honorary position list")]
+    InvalidHonoraryPositions,
+
+    #[msg("Invalid quote mint registry")]
+    InvalidQuoteMintRegistry,
+
+    #[msg("Base fee destination validation failed")]
+    InvalidBaseFeeDestination,
+
+    #[msg("Invalid swap slippage basis points")]
+    InvalidSwapSlippage,
+
+    #[msg("Swap minimum output is below the policy's slippage bound")]
+    SwapSlippageExceeded,
+
+    #[msg("Swap returned less than the minimum output")]
+    SwapOutputBelowMinimum,
+
+    #[msg("Creator buyback account validation failed")]
+    InvalidBuybackAccounts,
+    
+    #[msg("Distribution period is outside the allowed bounds")]
+    InvalidDistributionPeriod,
+    
+    #[msg("Rolling cap needs a distribution period dividing 7 days into at most 7 periods")]
+    InvalidRollingCapWindow,
+    
+    #[msg("Invalid distribution epoch anchor")]
+    InvalidEpochAnchor,
+    
+    #[msg("No distribution day is in progress")]
+    NoDayInProgress,
+    
+    #[msg("Signer is not the policy authority")]
+    UnauthorizedAuthority,
+    
+    #[msg("Invalid crank reward configuration")]
+    InvalidCrankReward,
+    
+    #[msg("Crank reward ATA validation failed")]
+    InvalidCrankRewardAta,
+    
+    #[msg("Account is already on the current layout")]
+    AccountAlreadyMigrated,
+    
+    #[msg("Day ledger cannot be migrated while a day is in progress")]
+    MigrationDuringDay,
+    
+    #[msg("Page investors do not match the day's committed registry")]
+    InvestorRegistryMismatch,
+    
+    #[msg("Investor accrual PDA missing from the payout accounts")]
+    MissingInvestorAccrual,
+    
+    #[msg("Accrued balances cannot be claimed while a day is in progress")]
+    ClaimDuringDay,
+    
+    #[msg("Investor has no accrued balance to claim")]
+    NoAccruedBalance,
+    
+    #[msg("Investor ATA validation failed")]
+    InvalidInvestorAta,
+    
+    #[msg("Swap pool, vaults or DAMM V2 accounts do not match the policy's honorary pool")]
+    InvalidSwapPool,
+    
+    #[msg("Swap pool has no reference price set by the policy authority")]
+    ReferencePriceUnset,
 }
\ No newline at end of file
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::*,
+    state::{DistributionProgress, PolicyConfig},
+    error::ErrorCode,
+    DayAborted,
+};
+
+#[derive(Accounts)]
+#[instruction(quote_mint: Pubkey)]
+pub struct AbortDay<'info> {
+    /// The policy's authority
+    #[account(
+        constraint = authority.key() == policy_config.authority @ ErrorCode::UnauthorizedAuthority
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [POLICY_SEED, vault.key().as_ref()],
+        bump
+    )]
+    pub policy_config: Account<'info, PolicyConfig>,
+
+    #[account(
+        mut,
+        seeds = [PROGRESS_SEED, vault.key().as_ref(), policy_config.ledger_seed(&quote_mint)],
+        bump
+    )]
+    pub distribution_progress: Account<'info, DistributionProgress>,
+
+    /// The vault account (used as seed for PDAs)
+    /// CHECK: Used only as seed, validation handled by PDA derivation
+    pub vault: UncheckedAccount<'info>,
+}
+
+pub fn abort_day_handler(mut ctx: Context<AbortDay>, quote_mint: Pubkey) -> Result<()> {
+    let accounts = &mut ctx.accounts;
+
+    require!(
+        accounts.policy_config.vault == accounts.vault.key(),
+        ErrorCode::InvalidVaultAccount
+    );
+    require!(
+        accounts.policy_config.is_quote_mint(&quote_mint)
+            && accounts.distribution_progress.quote_mint == quote_mint,
+        ErrorCode::InvalidQuoteMint
+    );
+
+    // Carry what the stuck day left into the next day and close it
+    let progress = &mut accounts.distribution_progress;
+    let unfinished = progress.abort_day()?;
+
+    emit!(DayAborted {
+        vault: accounts.policy_config.vault,
+        quote_mint,
+        authority: accounts.authority.key(),
+        epoch_index: progress.epoch_index,
+        pagination_cursor: progress.pagination_cursor,
+        investor_carried: unfinished.map_or(0, |day| day.investor_carried),
+        creator_carried: unfinished.map_or(0, |day| day.creator_carried),
+        claim_carried: unfinished.map_or(0, |day| day.claim_carried),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Aborted day {} of {} at cursor {}", progress.epoch_index, quote_mint, progress.pagination_cursor);
+
+    Ok(())
+}
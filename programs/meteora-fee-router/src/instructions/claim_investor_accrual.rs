@@ -0,0 +1,134 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{transfer, Token, TokenAccount, Transfer};
+
+use crate::{
+    constants::*,
+    state::{DistributionProgress, InvestorAccrual, PolicyConfig},
+    error::ErrorCode,
+    InvestorAccrualClaimed,
+};
+
+#[derive(Accounts)]
+#[instruction(quote_mint: Pubkey)]
+pub struct ClaimInvestorAccrual<'info> {
+    /// Investor wallet the accrual belongs to
+    pub investor: Signer<'info>,
+
+    #[account(
+        seeds = [POLICY_SEED, vault.key().as_ref()],
+        bump
+    )]
+    pub policy_config: Account<'info, PolicyConfig>,
+
+    #[account(
+        mut,
+        seeds = [PROGRESS_SEED, vault.key().as_ref(), policy_config.ledger_seed(&quote_mint)],
+        bump
+    )]
+    pub distribution_progress: Account<'info, DistributionProgress>,
+
+    #[account(
+        mut,
+        seeds = [ACCRUAL_SEED, vault.key().as_ref(), investor.key().as_ref(), policy_config.ledger_seed(&quote_mint)],
+        bump = investor_accrual.bump
+    )]
+    pub investor_accrual: Account<'info, InvestorAccrual>,
+
+    #[account(
+        seeds = [VAULT_SEED, vault.key().as_ref(), b"investor_fee_pos_owner"],
+        bump
+    )]
+    pub position_owner_pda: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub treasury_ata: Account<'info, TokenAccount>,
+
+    /// Investor's quote ATA the accrued balance is released to
+    #[account(mut)]
+    pub investor_ata: Account<'info, TokenAccount>,
+
+    /// The vault account (used as seed for PDAs)
+    /// CHECK: Used only as seed, validation handled by PDA derivation
+    pub vault: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn claim_investor_accrual_handler(
+    mut ctx: Context<ClaimInvestorAccrual>,
+    quote_mint: Pubkey,
+) -> Result<()> {
+    let accounts = &mut ctx.accounts;
+
+    require!(
+        accounts.policy_config.vault == accounts.vault.key(),
+        ErrorCode::InvalidVaultAccount
+    );
+    require!(
+        accounts.policy_config.is_quote_mint(&quote_mint)
+            && accounts.distribution_progress.quote_mint == quote_mint,
+        ErrorCode::InvalidQuoteMint
+    );
+    require!(
+        accounts.treasury_ata.owner == accounts.position_owner_pda.key(),
+        ErrorCode::InvalidTreasuryAta
+    );
+    require!(
+        accounts.treasury_ata.mint == quote_mint,
+        ErrorCode::InvalidQuoteMint
+    );
+    require!(
+        accounts.investor_ata.owner == accounts.investor.key()
+            && accounts.investor_ata.mint == quote_mint,
+        ErrorCode::InvalidInvestorAta
+    );
+
+    // Pages pay accruals out of the day's treasury balance, so claims wait for the day
+    // to close
+    require!(
+        !accounts.distribution_progress.is_day_in_progress(),
+        ErrorCode::ClaimDuringDay
+    );
+
+    let timestamp = Clock::get()?.unix_timestamp;
+    let amount = accounts.investor_accrual.release(timestamp)?;
+    require!(amount > 0, ErrorCode::NoAccruedBalance);
+    require!(
+        accounts.treasury_ata.amount >= amount,
+        ErrorCode::InsufficientFunds
+    );
+    accounts.distribution_progress.update_investor_accrued(0, amount)?;
+
+    let bump_seed = [accounts.policy_config.bump];
+    let signer_seeds: &[&[u8]] = &[
+        VAULT_SEED,
+        accounts.policy_config.vault.as_ref(),
+        b"investor_fee_pos_owner",
+        &bump_seed,
+    ];
+    let signer_seeds_slice = &[signer_seeds];
+
+    let transfer_ctx = CpiContext::new_with_signer(
+        accounts.token_program.to_account_info(),
+        Transfer {
+            from: accounts.treasury_ata.to_account_info(),
+            to: accounts.investor_ata.to_account_info(),
+            authority: accounts.position_owner_pda.to_account_info(),
+        },
+        signer_seeds_slice,
+    );
+    transfer(transfer_ctx, amount)?;
+
+    emit!(InvestorAccrualClaimed {
+        vault: accounts.vault.key(),
+        quote_mint,
+        investor: accounts.investor.key(),
+        amount,
+        total_released: accounts.investor_accrual.total_released,
+        timestamp,
+    });
+
+    msg!("Released {} accrued {} to {}", amount, quote_mint, accounts.investor.key());
+
+    Ok(())
+}
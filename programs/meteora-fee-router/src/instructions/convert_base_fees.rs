@@ -0,0 +1,210 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+use crate::{
+    constants::*,
+    state::{DistributionProgress, PolicyConfig},
+    error::ErrorCode,
+    utils::swap::{policy_minimum_out, swap_through_pool, validate_minimum_out, PoolSwapAccounts, DAMM_V2_EVENT_AUTHORITY, DAMM_V2_POOL_AUTHORITY, DAMM_V2_PROGRAM_ID},
+    BaseFeesConverted,
+};
+
+#[derive(Accounts)]
+#[instruction(params: ConvertBaseFeesParams)]
+pub struct ConvertBaseFees<'info> {
+    /// The policy authority; it alone picks when to swap and the minimum out
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [POLICY_SEED, vault.key().as_ref()],
+        bump
+    )]
+    pub policy_config: Account<'info, PolicyConfig>,
+
+    #[account(
+        mut,
+        seeds = [PROGRESS_SEED, vault.key().as_ref(), policy_config.ledger_seed(&params.quote_mint)],
+        bump
+    )]
+    pub distribution_progress: Account<'info, DistributionProgress>,
+
+    /// CHECK: PDA for position ownership
+    #[account(
+        seeds = [VAULT_SEED, vault.key().as_ref(), b"investor_fee_pos_owner"],
+        bump
+    )]
+    pub position_owner_pda: SystemAccount<'info>,
+
+    /// The vault account (used as seed for PDAs)
+    /// CHECK: Used only as seed, validation handled by PDA derivation
+    pub vault: UncheckedAccount<'info>,
+
+    /// Base treasury: the position owner's base ATA holding quarantined base fees
+    #[account(mut)]
+    pub base_treasury_ata: Account<'info, TokenAccount>,
+
+    /// Treasury of `params.quote_mint`, receiving the swapped quote
+    #[account(mut)]
+    pub treasury_ata: Account<'info, TokenAccount>,
+
+    // DAMM V2 Pool accounts
+    /// CHECK: Pool of an honorary position quoted in `params.quote_mint`, checked against
+    /// the policy's recorded pools in the handler
+    #[account(mut)]
+    pub pool: UncheckedAccount<'info>,
+    /// Pool's base token vault (the swap's input side)
+    #[account(mut)]
+    pub pool_base_vault: Account<'info, TokenAccount>,
+    /// Pool's quote token vault (the swap's output side)
+    #[account(mut)]
+    pub pool_quote_vault: Account<'info, TokenAccount>,
+
+    /// The policy's base mint (the swap's input mint)
+    pub base_mint: Account<'info, Mint>,
+    /// `params.quote_mint` (the swap's output mint)
+    pub quote_mint: Account<'info, Mint>,
+    /// CHECK: DAMM V2 pool authority PDA
+    #[account(address = DAMM_V2_POOL_AUTHORITY)]
+    pub pool_authority: UncheckedAccount<'info>,
+    /// CHECK: DAMM V2 event authority PDA
+    #[account(address = DAMM_V2_EVENT_AUTHORITY)]
+    pub event_authority: UncheckedAccount<'info>,
+
+    // Programs
+    /// CHECK: DAMM V2 Program for swap CPI
+    #[account(address = DAMM_V2_PROGRAM_ID)]
+    pub cp_amm_program: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct ConvertBaseFeesParams {
+    /// Quote mint whose treasury and next day's claim receive the swapped quote
+    pub quote_mint: Pubkey,
+    /// Base amount to swap (the whole base treasury when `None`)
+    pub amount_in: Option<u64>,
+    /// Minimum quote out, set by the authority and no lower than the policy's slippage bound
+    /// under the pool's reference price (the pool's spot price is not trusted)
+    pub minimum_amount_out: u64,
+}
+
+/// Validate the base treasury, quote treasury and pool vaults against the policy
+fn validate_conversion_accounts(ctx: &Context<ConvertBaseFees>, quote_mint: &Pubkey) -> Result<()> {
+    let accounts = &ctx.accounts;
+    let policy_config = &accounts.policy_config;
+    let position_owner = accounts.position_owner_pda.key();
+
+    require!(
+        policy_config.vault == accounts.vault.key(),
+        ErrorCode::InvalidVaultAccount
+    );
+    require!(
+        policy_config.authority == accounts.authority.key(),
+        ErrorCode::UnauthorizedAuthority
+    );
+    require!(
+        policy_config.is_quote_mint(quote_mint)
+            && accounts.quote_mint.key() == *quote_mint
+            && accounts.distribution_progress.quote_mint == *quote_mint,
+        ErrorCode::InvalidQuoteMint
+    );
+
+    // Base fees are only ever quarantined into the position owner's base ATA
+    require!(
+        policy_config.base_mint != Pubkey::default()
+            && accounts.base_treasury_ata.mint == policy_config.base_mint
+            && accounts.base_treasury_ata.owner == position_owner,
+        ErrorCode::InvalidBaseFeeDestination
+    );
+    require!(
+        accounts.treasury_ata.mint == *quote_mint && accounts.treasury_ata.owner == position_owner,
+        ErrorCode::InvalidTreasuryAta
+    );
+
+    // The pool must be an honorary pool of this quote mint trading it against the base mint
+    require!(
+        accounts.base_mint.key() == policy_config.base_mint
+            && accounts.pool_base_vault.mint == policy_config.base_mint
+            && accounts.pool_quote_vault.mint == *quote_mint,
+        ErrorCode::InvalidQuoteMint
+    );
+    let honorary_pool = policy_config.honorary_pool_for(quote_mint, &accounts.pool.key())?;
+    require!(
+        honorary_pool.input_is_base(
+            &accounts.pool.key(),
+            &accounts.pool_base_vault.key(),
+            &accounts.pool_quote_vault.key(),
+        )?,
+        ErrorCode::InvalidSwapPool
+    );
+
+    Ok(())
+}
+
+pub fn convert_base_fees_handler(
+    mut ctx: Context<ConvertBaseFees>,
+    params: ConvertBaseFeesParams,
+) -> Result<()> {
+    validate_conversion_accounts(&ctx, &params.quote_mint)?;
+
+    let accounts = &mut ctx.accounts;
+    let policy_config = &accounts.policy_config;
+
+    let amount_in = params.amount_in.unwrap_or(accounts.base_treasury_ata.amount);
+    require!(
+        amount_in > 0 && amount_in <= accounts.base_treasury_ata.amount,
+        ErrorCode::InsufficientFunds
+    );
+
+    // The pool's reserves can be moved within the transaction, so the minimum out may not
+    // go below the policy's slippage bound under the pool's reference price
+    let honorary_pool = policy_config.honorary_pool_for(&params.quote_mint, &accounts.pool.key())?;
+    let policy_floor = policy_minimum_out(honorary_pool, amount_in, true, policy_config.max_swap_slippage_bps)?;
+    validate_minimum_out(params.minimum_amount_out, policy_floor)?;
+
+    let quote_amount_out = swap_through_pool(
+        PoolSwapAccounts {
+            honorary_pool,
+            pool: &accounts.pool.to_account_info(),
+            pool_authority: &accounts.pool_authority.to_account_info(),
+            position_owner_pda: &accounts.position_owner_pda.to_account_info(),
+            input_account: &accounts.base_treasury_ata,
+            output_account: &accounts.treasury_ata,
+            pool_input_vault: &accounts.pool_base_vault,
+            pool_output_vault: &accounts.pool_quote_vault,
+            input_mint: &accounts.base_mint.to_account_info(),
+            output_mint: &accounts.quote_mint.to_account_info(),
+            event_authority: &accounts.event_authority.to_account_info(),
+            cp_amm_program: &accounts.cp_amm_program.to_account_info(),
+            token_program: &accounts.token_program,
+        },
+        &policy_config.vault,
+        policy_config.bump,
+        amount_in,
+        params.minimum_amount_out,
+    )?;
+
+    // The swapped quote joins the next day's claim; a day in progress counts it as
+    // treasury inflow when it closes
+    let progress = &mut accounts.distribution_progress;
+    progress.credit_converted_base_quote(quote_amount_out)?;
+    if progress.is_day_in_progress() {
+        progress.record_treasury_inflow(quote_amount_out)?;
+    }
+
+    emit!(BaseFeesConverted {
+        vault: policy_config.vault,
+        quote_mint: params.quote_mint,
+        base_mint: policy_config.base_mint,
+        base_amount_in: amount_in,
+        quote_amount_out,
+        minimum_amount_out: params.minimum_amount_out,
+        pending_converted_quote: accounts.distribution_progress.converted_base_quote,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Converted {} base fees into {} {} for the next day's claim",
+         amount_in, quote_amount_out, params.quote_mint);
+
+    Ok(())
+}
@@ -1,21 +1,28 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount, Transfer, transfer};
+use anchor_spl::token::{Mint, Token, TokenAccount};
 use anchor_spl::associated_token::AssociatedToken;
 
+use std::collections::HashMap;
+
 use crate::{
     constants::*,
-    state::{PolicyConfig, DistributionProgress, DistributionTimingState},
+    state::{PolicyConfig, DistributionProgress, DistributionTimingState, InvestorAccrual},
     error::ErrorCode,
     utils::{
-        fee_claiming::{claim_position_fees, ensure_treasury_ata, FeeClaimResult},
-        investor_distribution::InvestorDistribution,
-        creator_distribution::CreatorDistribution,
-        streamflow::StreamflowIntegration,
+        fee_claiming::{claim_position_fees, ensure_treasury_ata, split_position_accounts, BaseFeeRouting, PositionClaimAccounts},
+        investor_distribution::{BatchPayoutResult, InvestorDistribution, PayoutAccounts},
+        weights::{InvestorWeightSource, WeightWindow},
+        creator_distribution::{BuybackAccounts, CreatorDistribution, CreatorPayoutAccounts},
+        day_distribution::{run_distribution_step, DistributionAccounts, PageStep},
+        swap::{DAMM_V2_EVENT_AUTHORITY, DAMM_V2_POOL_AUTHORITY, DAMM_V2_PROGRAM_ID},
+        vesting::InvestorData,
     },
-    QuoteFeesClaimed, InvestorPayoutPage,
+    QuoteFeesClaimed, BaseFeesHandled, DistributionEpochsMissed, CatchUpEpochDistributed,
+    UnfinishedDayCarried, InvestorPayoutPage, CrankRewardPaid,
 };
 
 #[derive(Accounts)]
+#[instruction(params: DistributeFeesParams)]
 pub struct DistributeFees<'info> {
     #[account(mut)]
     pub crank_caller: Signer<'info>,
@@ -29,7 +36,7 @@ pub struct DistributeFees<'info> {
     
     #[account(
         mut,
-        seeds = [PROGRESS_SEED, vault.key().as_ref()],
+        seeds = [PROGRESS_SEED, vault.key().as_ref(), policy_config.ledger_seed(&params.quote_mint)],
         bump
     )]
     pub distribution_progress: Account<'info, DistributionProgress>,
@@ -45,43 +52,91 @@ pub struct DistributeFees<'info> {
     /// CHECK: Used only as seed, validation handled by PDA derivation
     pub vault: UncheckedAccount<'info>,
     
-    /// CHECK: DAMM V2 Position account
-    #[account(mut)]
-    pub honorary_position: UncheckedAccount<'info>,
-    
     #[account(mut)]
     pub treasury_ata: Account<'info, TokenAccount>,
     
     #[account(mut)]
     pub creator_ata: Account<'info, TokenAccount>,
     
+    /// Base token account base fees are collected into (base treasury or creator base ATA);
+    /// required only when a position owes base fees and the policy routes them
+    #[account(mut)]
+    pub base_fee_destination: Option<Account<'info, TokenAccount>>,
+    
+    // Buyback accounts, required only at day close when the creator payout mode is
+    // BuybackAndBurn
+    /// CHECK: Honorary DAMM V2 pool trading the base mint against `params.quote_mint` -
+    /// must be the pool recorded for its honorary position, with these vaults
+    #[account(mut)]
+    pub buyback_pool: Option<UncheckedAccount<'info>>,
+    /// Buyback pool's base token vault (the swap's output side)
+    #[account(mut)]
+    pub buyback_pool_base_vault: Option<Account<'info, TokenAccount>>,
+    /// Buyback pool's quote token vault (the swap's input side)
+    #[account(mut)]
+    pub buyback_pool_quote_vault: Option<Account<'info, TokenAccount>>,
+    /// The policy's base mint, burned from after the buyback
+    #[account(mut)]
+    pub base_mint: Option<Account<'info, Mint>>,
+    /// Base treasury: the position owner's base ATA the buyback passes through
+    #[account(mut)]
+    pub base_treasury_ata: Option<Account<'info, TokenAccount>>,
+    /// `params.quote_mint` (the buyback swap's input mint)
+    pub buyback_quote_mint: Option<Account<'info, Mint>>,
+    /// CHECK: DAMM V2 pool authority PDA
+    #[account(address = DAMM_V2_POOL_AUTHORITY)]
+    pub buyback_pool_authority: Option<UncheckedAccount<'info>>,
+    /// CHECK: DAMM V2 event authority PDA
+    #[account(address = DAMM_V2_EVENT_AUTHORITY)]
+    pub buyback_event_authority: Option<UncheckedAccount<'info>>,
+    
+    /// Crank caller's quote ATA the policy's crank reward is paid to; the reward is
+    /// skipped when omitted
+    #[account(mut)]
+    pub crank_reward_ata: Option<Account<'info, TokenAccount>>,
+    
     // Programs
-    /// CHECK: DAMM V2 Program for fee claiming CPI
+    /// CHECK: DAMM V2 Program for fee claiming and buyback CPIs
+    #[account(address = DAMM_V2_PROGRAM_ID)]
     pub cp_amm_program: UncheckedAccount<'info>,
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
     
-    // Streamflow accounts will be passed as remaining accounts for pagination
+    // Remaining accounts: the honorary positions quoted in `params.quote_mint` (in policy
+    // order), then weight accounts for pagination, then payout accounts
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
 pub struct DistributeFeesParams {
     pub page_size: u32,
     pub cursor_position: Option<u32>, // For idempotent retries
+    /// Quote mint whose treasury and day ledger this call distributes
+    pub quote_mint: Pubkey,
+    /// Registry hash of the day's investors after this page (all zero when it ends the
+    /// registry), which the page's investors are checked against before they are paid;
+    /// the page that opens the day hashes them itself
+    pub next_registry_hash: [u8; 32],
 }
 
 /// Comprehensive validation of all accounts required for distribution
-fn validate_distribution_accounts(ctx: &Context<DistributeFees>) -> Result<()> {
+fn validate_distribution_accounts(ctx: &Context<DistributeFees>, quote_mint: &Pubkey) -> Result<()> {
     let policy_config = &ctx.accounts.policy_config;
     
+    // The quote mint must have its own treasury and day ledger under the policy
+    require!(
+        policy_config.is_quote_mint(quote_mint)
+            && ctx.accounts.distribution_progress.quote_mint == *quote_mint,
+        ErrorCode::InvalidQuoteMint
+    );
+    
     // Validate treasury ATA belongs to position owner PDA and has correct mint
     require!(
         ctx.accounts.treasury_ata.owner == ctx.accounts.position_owner_pda.key(),
         ErrorCode::InvalidTreasuryAta
     );
     require!(
-        ctx.accounts.treasury_ata.mint == policy_config.quote_mint,
+        ctx.accounts.treasury_ata.mint == *quote_mint,
         ErrorCode::InvalidQuoteMint
     );
     
@@ -91,7 +146,7 @@ fn validate_distribution_accounts(ctx: &Context<DistributeFees>) -> Result<()> {
         ErrorCode::InvalidCreatorAta
     );
     require!(
-        ctx.accounts.creator_ata.mint == policy_config.quote_mint,
+        ctx.accounts.creator_ata.mint == *quote_mint,
         ErrorCode::InvalidQuoteMint
     );
     
@@ -112,292 +167,425 @@ fn validate_distribution_accounts(ctx: &Context<DistributeFees>) -> Result<()> {
 }
 
 pub fn distribute_fees_handler<'info>(
-    mut ctx: Context<'_, '_, '_, 'info, DistributeFees<'info>>,
+    ctx: Context<'_, '_, '_, 'info, DistributeFees<'info>>,
     params: DistributeFeesParams,
 ) -> Result<()> {
     let current_timestamp = Clock::get()?.unix_timestamp;
     
     // Comprehensive account validation
-    validate_distribution_accounts(&ctx)?;
+    validate_distribution_accounts(&ctx, &params.quote_mint)?;
     
-    // Validate page size
-    require!(
-        params.page_size > 0 && params.page_size <= MAX_PAGE_SIZE,
-        ErrorCode::InvalidPaginationCursor
-    );
+    // The quote mint's honorary positions lead the remaining accounts on every page, then
+    // weight accounts, then payout accounts (quote ATAs, accrual PDAs)
+    let (position_accounts, investor_accounts) =
+        split_position_accounts(&ctx.accounts.policy_config, &params.quote_mint, ctx.remaining_accounts)?;
+    let (weight_accounts, payout_accounts) =
+        InvestorDistribution::split_remaining_accounts(investor_accounts)?;
     
-    // Validate and prepare timing for distribution
-    let timing_state = ctx.accounts.distribution_progress.prepare_for_distribution(current_timestamp)?;
+    let DistributeFees {
+        crank_caller,
+        policy_config,
+        distribution_progress,
+        position_owner_pda,
+        treasury_ata,
+        creator_ata,
+        base_fee_destination,
+        buyback_pool,
+        buyback_pool_base_vault,
+        buyback_pool_quote_vault,
+        base_mint,
+        base_treasury_ata,
+        buyback_quote_mint,
+        buyback_pool_authority,
+        buyback_event_authority,
+        crank_reward_ata,
+        cp_amm_program,
+        token_program,
+        ..
+    } = &mut *ctx.accounts;
+    let policy_config: &PolicyConfig = policy_config;
+    let quote_mint = distribution_progress.quote_mint;
+    
+    let mut accounts = InstructionAccounts {
+        policy_config,
+        quote_mint,
+        crank_caller: crank_caller.key(),
+        position_owner_pda: position_owner_pda.to_account_info(),
+        treasury_ata,
+        creator_ata,
+        base_fee_destination: base_fee_destination.as_ref(),
+        buyback_pool: buyback_pool.as_ref().map(|pool| pool.to_account_info()),
+        buyback_pool_base_vault: buyback_pool_base_vault.as_ref(),
+        buyback_pool_quote_vault: buyback_pool_quote_vault.as_ref(),
+        base_mint: base_mint.as_ref(),
+        base_treasury_ata: base_treasury_ata.as_ref(),
+        buyback_quote_mint: buyback_quote_mint.as_ref().map(|mint| mint.to_account_info()),
+        buyback_pool_authority: buyback_pool_authority.as_ref().map(|authority| authority.to_account_info()),
+        buyback_event_authority: buyback_event_authority.as_ref().map(|authority| authority.to_account_info()),
+        crank_reward_ata: crank_reward_ata.as_ref(),
+        cp_amm_program: cp_amm_program.to_account_info(),
+        token_program,
+        position_accounts,
+        weight_accounts,
+        payout_accounts,
+        accrual_accounts: HashMap::new(),
+        next_registry_hash: params.next_registry_hash,
+        timestamp: current_timestamp,
+    };
     
-    // Handle cursor position for idempotent operations
-    if let Some(requested_cursor) = params.cursor_position {
-        let is_retry = ctx.accounts.distribution_progress.validate_cursor_for_retry(requested_cursor)?;
-        if is_retry {
-            msg!("Idempotent retry detected for cursor position: {}", requested_cursor);
-            return Ok(()); // Already processed, return success
-        }
+    let step = run_distribution_step(
+        policy_config,
+        distribution_progress,
+        &mut accounts,
+        params.page_size,
+        params.cursor_position,
+        current_timestamp,
+    )?;
+    if step.replayed {
+        return Ok(());
     }
     
-    // Log timing information
-    match timing_state {
+    let progress: &DistributionProgress = distribution_progress;
+    let period_secs = policy_config.distribution_period_secs;
+    match step.timing {
         DistributionTimingState::NewDay => {
-            msg!("Starting new 24-hour distribution period at timestamp: {}", current_timestamp);
+            msg!("Started new {}s distribution period (epoch {}) at timestamp: {}",
+                 period_secs, progress.epoch_index, progress.last_distribution_ts);
+            
+            // Report epochs no crank opened, so skipped days are never silent
+            if progress.missed_epochs > 0 {
+                emit!(DistributionEpochsMissed {
+                    vault: policy_config.vault,
+                    quote_mint,
+                    epoch_index: progress.epoch_index,
+                    missed_epochs: progress.missed_epochs,
+                    day_start_ts: progress.last_distribution_ts,
+                    timestamp: current_timestamp,
+                });
+            }
+            
+            // An abandoned day's pages are not dropped: its remainder was carried forward
+            if let Some(unfinished) = step.unfinished_day {
+                emit!(UnfinishedDayCarried {
+                    vault: policy_config.vault,
+                    quote_mint,
+                    epoch_index: unfinished.epoch_index,
+                    pagination_cursor: unfinished.pagination_cursor,
+                    investor_carried: unfinished.investor_carried,
+                    creator_carried: unfinished.creator_carried,
+                    claim_carried: unfinished.claim_carried,
+                    timestamp: current_timestamp,
+                });
+            }
+            
+            if step.claimed_quote > 0 {
+                msg!("Claimed {} {} fees for distribution across {} positions",
+                     step.claimed_quote, quote_mint, position_accounts.len());
+            }
         },
         DistributionTimingState::ContinueSameDay => {
-            msg!("Continuing same-day distribution, cursor: {}", ctx.accounts.distribution_progress.pagination_cursor);
+            msg!("Continued same-day distribution, cursor: {}", progress.pagination_cursor);
         }
     }
     
-    // Get distribution period info for logging
-    let period_info = ctx.accounts.distribution_progress.get_distribution_period_info(current_timestamp);
-    msg!("Distribution period info - Last: {}, Current: {}, Time until next: {}, Cursor: {}", 
-         period_info.last_distribution_ts, 
-         period_info.current_timestamp, 
-         period_info.time_until_next,
-         period_info.pagination_cursor);
-    
-    // Step 1: Claim fees from honorary position (only on new day)
-    let claimed_fees = if matches!(timing_state, DistributionTimingState::NewDay) {
-        claim_fees_from_position(&ctx)?
-    } else {
-        // For same-day continuation, no new fees to claim
-        FeeClaimResult {
-            quote_amount: 0,
-            base_amount: 0,
-            quote_mint: ctx.accounts.policy_config.quote_mint,
+    if let Some(pool_opened) = &step.pool_opened {
+        for epoch in &pool_opened.catch_up_shares {
+            emit!(CatchUpEpochDistributed {
+                vault: policy_config.vault,
+                quote_mint,
+                epoch_index: epoch.epoch_index,
+                window_start_ts: epoch.window.start_ts,
+                window_end_ts: epoch.window.end_ts,
+                claimed_quote: epoch.claimed_quote,
+                total_locked: epoch.total_locked,
+                investor_share: epoch.investor_share,
+                catch_up_mode: policy_config.catch_up_mode,
+                timestamp: current_timestamp,
+            });
         }
-    };
+    }
     
-    // Log fee claiming results
-    if claimed_fees.quote_amount > 0 {
-        msg!("Claimed {} quote fees for distribution", claimed_fees.quote_amount);
-        
-        // Emit QuoteFeesClaimed event
-        emit!(QuoteFeesClaimed {
-            vault: ctx.accounts.policy_config.vault,
-            claimed_amount: claimed_fees.quote_amount,
-            base_amount: claimed_fees.base_amount,
-            quote_mint: claimed_fees.quote_mint,
-            honorary_position: ctx.accounts.honorary_position.key(),
-            treasury_ata: ctx.accounts.treasury_ata.key(),
+    let day_start_deferred = step.pool_opened.as_ref().map_or(0, |pool_opened| pool_opened.opening.deferred);
+    if let Some(PageStep { page_start, page_end, payouts: Some(payouts), crank_reward, .. }) = &step.page {
+        emit!(InvestorPayoutPage {
+            vault: policy_config.vault,
+            quote_mint,
+            page_start: *page_start,
+            page_end: *page_end,
+            total_distributed: payouts.total_paid,
+            processed_count: payouts.processed_count as u32,
+            dust_carried_forward: payouts.total_dust,
+            cap_overflow_deferred: payouts.cap_overflow_deferred.saturating_add(day_start_deferred),
+            dust_paid: payouts.dust_paid,
+            dust_recipient: payouts.dust_recipient,
+            investor_accrued: payouts.investor_accrued,
+            investor_accrual_released: payouts.investor_accrual_released,
+            cumulative_day_distributed: progress.current_day_distributed,
             timestamp: current_timestamp,
         });
+        
+        if *crank_reward > 0 {
+            emit!(CrankRewardPaid {
+                vault: policy_config.vault,
+                quote_mint,
+                crank_caller: crank_caller.key(),
+                amount: *crank_reward,
+                page_end: *page_end,
+                day_crank_reward_paid: progress.day_crank_reward_paid,
+                timestamp: current_timestamp,
+            });
+        }
     }
     
-    // Step 2: Process investor distributions if we have Streamflow accounts
-    let streamflow_accounts = ctx.remaining_accounts;
-    
-    if !streamflow_accounts.is_empty() && (claimed_fees.quote_amount > 0 || ctx.accounts.distribution_progress.carry_over_dust > 0) {
-        process_investor_distributions(&mut ctx, &params, claimed_fees.quote_amount, current_timestamp)?;
-    } else {
-        msg!("No Streamflow accounts provided or no fees to distribute");
+    if let Some(close) = step.close {
+        emit!(crate::CreatorPayoutDayClosed {
+            vault: policy_config.vault,
+            quote_mint,
+            creator_payout: close.creator_payout,
+            creator_payout_held: close.creator_held,
+            creator_wallet: policy_config.creator_wallet,
+            total_day_distributed: progress.current_day_distributed + close.creator_payout,
+            total_investors_processed: progress.day_investor_count,
+            final_dust_amount: progress.carry_over_dust,
+            creator_payout_mode: policy_config.creator_payout_mode,
+            base_burned: close.base_burned,
+            timestamp: current_timestamp,
+        });
+        
+        msg!("Creator remainder payout completed: {} tokens", close.creator_payout);
     }
     
     Ok(())
 }
 
-/// Claims fees from the honorary DAMM V2 position
-fn claim_fees_from_position(ctx: &Context<DistributeFees>) -> Result<FeeClaimResult> {
-    let policy_config = &ctx.accounts.policy_config;
-    
-    // Validate treasury ATA
-    ensure_treasury_ata(
-        &ctx.accounts.treasury_ata,
-        &policy_config.quote_mint,
-        &ctx.accounts.position_owner_pda.key(),
-    )?;
-    
-    // Claim fees from DAMM V2 position
-    let claim_result = claim_position_fees(
-        &ctx.accounts.honorary_position.to_account_info(),
-        &ctx.accounts.position_owner_pda.to_account_info(),
-        &ctx.accounts.treasury_ata,
-        &policy_config.quote_mint,
-        &policy_config.vault,
-        policy_config.bump,
-        &ctx.accounts.cp_amm_program.to_account_info(),
-        &ctx.accounts.token_program,
-    )?;
-    
-    msg!("Fee claiming completed - Quote: {}, Base: {}", 
-         claim_result.quote_amount, claim_result.base_amount);
-    
-    Ok(claim_result)
+/// The instruction's token accounts and CPIs behind `run_distribution_step`
+struct InstructionAccounts<'a, 'info> {
+    policy_config: &'a PolicyConfig,
+    quote_mint: Pubkey,
+    crank_caller: Pubkey,
+    position_owner_pda: AccountInfo<'info>,
+    treasury_ata: &'a mut Account<'info, TokenAccount>,
+    creator_ata: &'a Account<'info, TokenAccount>,
+    base_fee_destination: Option<&'a Account<'info, TokenAccount>>,
+    buyback_pool: Option<AccountInfo<'info>>,
+    buyback_pool_base_vault: Option<&'a Account<'info, TokenAccount>>,
+    buyback_pool_quote_vault: Option<&'a Account<'info, TokenAccount>>,
+    base_mint: Option<&'a Account<'info, Mint>>,
+    base_treasury_ata: Option<&'a Account<'info, TokenAccount>>,
+    buyback_quote_mint: Option<AccountInfo<'info>>,
+    buyback_pool_authority: Option<AccountInfo<'info>>,
+    buyback_event_authority: Option<AccountInfo<'info>>,
+    crank_reward_ata: Option<&'a Account<'info, TokenAccount>>,
+    cp_amm_program: AccountInfo<'info>,
+    token_program: &'a Program<'info, Token>,
+    position_accounts: &'a [AccountInfo<'info>],
+    weight_accounts: &'a [AccountInfo<'info>],
+    payout_accounts: &'a [AccountInfo<'info>],
+    /// Accrual PDAs of the page's investors that have one, loaded with the page
+    accrual_accounts: HashMap<Pubkey, &'a AccountInfo<'info>>,
+    /// `DistributeFeesParams::next_registry_hash`
+    next_registry_hash: [u8; 32],
+    timestamp: i64,
 }
 
-/// Process investor distributions for the current page
-fn process_investor_distributions<'info>(
-    ctx: &mut Context<'_, '_, '_, 'info, DistributeFees<'info>>,
-    params: &DistributeFeesParams,
-    claimed_quote_amount: u64,
-    current_timestamp: i64,
-) -> Result<()> {
-    let policy_config = &ctx.accounts.policy_config;
-    let distribution_progress = &mut ctx.accounts.distribution_progress;
-    let streamflow_accounts = ctx.remaining_accounts;
-    
-    msg!("Processing investor distributions for {} Streamflow accounts", streamflow_accounts.len());
+impl<'a, 'info> InstructionAccounts<'a, 'info> {
+    fn payout_accounts(&self) -> PayoutAccounts<'_, 'info> {
+        PayoutAccounts {
+            token_program: self.token_program,
+            treasury_ata: self.treasury_ata,
+            position_owner_pda: &self.position_owner_pda,
+            payout_accounts: self.payout_accounts,
+        }
+    }
+}
+
+impl<'info> InstructionAccounts<'_, 'info> {
+    /// Buyback accounts, complete only when the crank passed all of them
+    fn buyback_accounts(&self) -> Option<BuybackAccounts<'_, 'info>> {
+        Some(BuybackAccounts {
+            pool: self.buyback_pool.as_ref()?,
+            pool_base_vault: self.buyback_pool_base_vault?,
+            pool_quote_vault: self.buyback_pool_quote_vault?,
+            base_mint: self.base_mint?,
+            quote_mint: self.buyback_quote_mint.as_ref()?,
+            pool_authority: self.buyback_pool_authority.as_ref()?,
+            event_authority: self.buyback_event_authority.as_ref()?,
+            base_treasury_ata: self.base_treasury_ata?,
+            cp_amm_program: &self.cp_amm_program,
+        })
+    }
+}
+
+impl<'a, 'info> DistributionAccounts for InstructionAccounts<'a, 'info> {
+    fn treasury_balance(&mut self) -> Result<u64> {
+        self.treasury_ata.reload()?;
+        Ok(self.treasury_ata.amount)
+    }
     
-    // Validate distribution parameters
-    InvestorDistribution::validate_distribution_params(
-        policy_config,
-        streamflow_accounts,
-        distribution_progress.pagination_cursor as usize,
-        params.page_size as usize,
-    )?;
+    /// Claims fees from each honorary DAMM V2 position
+    fn claim_position_fees(&mut self) -> Result<u64> {
+        let policy_config = self.policy_config;
+        ensure_treasury_ata(self.treasury_ata, &self.quote_mint, &self.position_owner_pda.key())?;
+        
+        // Where owed base fees go, per the policy
+        let base_fees = BaseFeeRouting {
+            policy: policy_config.base_fee_policy,
+            base_mint: policy_config.base_mint,
+            destination: self.base_fee_destination,
+            expected_owner: policy_config.base_fee_destination_owner(&self.position_owner_pda.key()),
+        };
+        
+        let claim_accounts = PositionClaimAccounts {
+            position_owner_pda: &self.position_owner_pda,
+            treasury_ata: self.treasury_ata,
+            cp_amm_program: &self.cp_amm_program,
+            token_program: self.token_program,
+            vault_key: policy_config.vault,
+            bump: policy_config.bump,
+        };
+        
+        let mut quote_amount = 0u64;
+        for position_account in self.position_accounts {
+            let claim = claim_position_fees(position_account, &claim_accounts, &self.quote_mint, &base_fees)?;
+            msg!("Fee claiming completed for position {} - Quote: {}, Base: {}",
+                 position_account.key(), claim.quote_amount, claim.base_amount);
+            
+            if claim.quote_amount > 0 {
+                emit!(QuoteFeesClaimed {
+                    vault: policy_config.vault,
+                    claimed_amount: claim.quote_amount,
+                    base_amount: claim.base_amount,
+                    quote_mint: claim.quote_mint,
+                    honorary_position: position_account.key(),
+                    treasury_ata: self.treasury_ata.key(),
+                    timestamp: self.timestamp,
+                });
+            }
+            
+            if claim.base_amount > 0 {
+                // Base fees were quarantined or forwarded instead of aborting the crank
+                emit!(BaseFeesHandled {
+                    vault: policy_config.vault,
+                    honorary_position: position_account.key(),
+                    base_mint: policy_config.base_mint,
+                    base_amount: claim.base_amount,
+                    base_fee_policy: policy_config.base_fee_policy,
+                    destination: self.base_fee_destination
+                        .map(|destination| destination.key())
+                        .unwrap_or_default(),
+                    timestamp: self.timestamp,
+                });
+            }
+            
+            quote_amount = quote_amount
+                .checked_add(claim.quote_amount)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+        }
+        
+        Ok(quote_amount)
+    }
     
-    // Calculate total locked amount across all investors
-    let total_locked_amount = StreamflowIntegration::calculate_total_locked(
-        streamflow_accounts,
-        &policy_config.quote_mint,
-        current_timestamp,
-    )?;
+    fn has_weight_accounts(&self) -> bool {
+        !self.weight_accounts.is_empty()
+    }
     
-    msg!("Total locked amount across all investors: {}", total_locked_amount);
+    /// Aggregate the supplied investors (locked or staked weight, per weight mode), one
+    /// entry per wallet; the weight accounts decide which investors the call carries
+    fn load_investors(
+        &mut self,
+        window: WeightWindow,
+        _opens_day: bool,
+        _cursor: usize,
+        page_size: usize,
+    ) -> Result<Vec<InvestorData>> {
+        msg!("Processing investor distributions for {} weight accounts", self.weight_accounts.len());
+        InvestorDistribution::validate_distribution_params(self.policy_config, self.weight_accounts, page_size)?;
+        InvestorDistribution::load_day_investors(self.policy_config, self.weight_accounts, window)
+    }
     
-    // Calculate total investor allocation from claimed fees
-    let (total_investor_amount, creator_amount) = crate::utils::math::calculate_distribution(
-        claimed_quote_amount,
-        total_locked_amount,
-        policy_config.y0_total_allocation,
-        policy_config.investor_fee_share_bps,
-    )?;
+    /// The page's weight accounts only cover its own investors: the hash of the entries
+    /// after it comes with the instruction
+    fn next_registry_hash(&self, _window: WeightWindow, _page_end: usize) -> Result<[u8; 32]> {
+        Ok(self.next_registry_hash)
+    }
     
-    msg!("Distribution calculation: investor_amount={}, creator_amount={}, total_locked={}", 
-         total_investor_amount, creator_amount, total_locked_amount);
+    fn locked_total(&self, window: WeightWindow) -> Result<u64> {
+        InvestorWeightSource::for_policy(self.policy_config).calculate_total_weight(self.weight_accounts, window)
+    }
     
-    // Validate daily cap before processing
-    if let Some(daily_cap) = policy_config.daily_cap_lamports {
-        let remaining_cap = daily_cap.saturating_sub(distribution_progress.current_day_distributed);
-        if remaining_cap == 0 {
-            msg!("Daily cap reached, no more distributions allowed today");
-            return Ok(());
+    /// Investors with an accrual PDA keep sub-threshold allocations attributed to them
+    fn load_accruals(&mut self, page_investors: &[InvestorData]) -> Result<HashMap<Pubkey, InvestorAccrual>> {
+        let loaded = InvestorDistribution::load_investor_accruals(
+            self.payout_accounts,
+            &self.policy_config.vault,
+            self.policy_config.ledger_seed(&self.quote_mint),
+            page_investors,
+        )?;
+        let mut accruals = HashMap::with_capacity(loaded.len());
+        for (investor, (account, accrual)) in loaded {
+            self.accrual_accounts.insert(investor, account);
+            accruals.insert(investor, accrual);
         }
-        msg!("Daily cap check: remaining={}, total_cap={}", remaining_cap, daily_cap);
+        Ok(accruals)
     }
     
-    // Check if there are eligible investors in this page
-    let has_eligible_investors = InvestorDistribution::validate_investor_eligibility(
-        streamflow_accounts,
-        &policy_config.quote_mint,
-        distribution_progress.pagination_cursor as usize,
-        params.page_size as usize,
-        current_timestamp,
-    )?;
-    
-    if !has_eligible_investors {
-        msg!("No eligible investors found in current page, advancing cursor");
-        distribution_progress.advance_cursor(params.page_size)?;
-        return Ok(());
+    fn execute_page(&mut self, page: &BatchPayoutResult, accruals: &HashMap<Pubkey, InvestorAccrual>) -> Result<()> {
+        InvestorDistribution::execute_investor_page(
+            page,
+            self.policy_config,
+            &self.quote_mint,
+            accruals,
+            &self.accrual_accounts,
+            &self.payout_accounts(),
+        )
     }
     
-    // Get cursor position before processing
-    let current_cursor = distribution_progress.pagination_cursor as usize;
-    
-    // Process the current page of investors
-    let batch_result = InvestorDistribution::process_investor_page(
-        policy_config,
-        distribution_progress,
-        streamflow_accounts,
-        total_investor_amount,
-        total_locked_amount,
-        current_cursor,
-        params.page_size as usize,
-        current_timestamp,
-        &ctx.accounts.token_program,
-        &ctx.accounts.associated_token_program,
-        &ctx.accounts.system_program,
-        &ctx.accounts.treasury_ata,
-        &ctx.accounts.position_owner_pda.to_account_info(),
-    )?;
+    fn has_crank_reward_account(&self) -> bool {
+        self.crank_reward_ata.is_some()
+    }
     
-    msg!("Batch processing complete: paid={}, dust={}, processed={}", 
-         batch_result.total_paid, batch_result.total_dust, batch_result.processed_count);
-    
-    // Emit investor payout page event
-    emit!(InvestorPayoutPage {
-        vault: policy_config.vault,
-        page_start: current_cursor as u32,
-        page_end: distribution_progress.pagination_cursor,
-        total_distributed: batch_result.total_paid,
-        processed_count: batch_result.processed_count as u32,
-        dust_carried_forward: batch_result.total_dust,
-        cumulative_day_distributed: distribution_progress.current_day_distributed,
-        timestamp: current_timestamp,
-    });
+    fn pay_crank_reward(&mut self, amount: u64) -> Result<()> {
+        let crank_reward_ata = self.crank_reward_ata.ok_or(ErrorCode::InvalidCrankRewardAta)?;
+        CreatorDistribution::validate_crank_reward_ata(crank_reward_ata, &self.crank_caller, &self.quote_mint)?;
+        self.treasury_ata.reload()?;
+        CreatorDistribution::execute_crank_reward(
+            amount,
+            self.policy_config,
+            self.token_program,
+            self.treasury_ata,
+            crank_reward_ata,
+            &self.position_owner_pda,
+        )
+    }
     
-    // Check if we've processed all investors for the day
-    let total_investors = streamflow_accounts.len();
-    if distribution_progress.pagination_cursor as usize >= total_investors {
-        msg!("All investors processed for the day, processing creator remainder payout");
+    /// Transfer to the creator, or buy back and burn the base token
+    fn pay_creator(&mut self, amount: u64) -> Result<Option<u64>> {
+        let policy_config = self.policy_config;
+        CreatorDistribution::validate_creator_ata(self.creator_ata, &policy_config.creator_wallet, &self.quote_mint)?;
+        self.treasury_ata.reload()?;
         
-        // Process creator remainder payout before marking day complete
-        let creator_payout_amount = CreatorDistribution::calculate_creator_remainder(
-            policy_config,
-            claimed_quote_amount,
-            total_locked_amount,
-        )?;
+        let buyback = self.buyback_accounts();
         
-        if creator_payout_amount > 0 {
-            // Validate creator ATA
-            CreatorDistribution::validate_creator_ata(
-                &ctx.accounts.creator_ata,
-                &policy_config.creator_wallet,
-                &policy_config.quote_mint,
-            )?;
-            
-            // Validate treasury has sufficient balance
-            require!(
-                ctx.accounts.treasury_ata.amount >= creator_payout_amount,
-                ErrorCode::InsufficientFunds
-            );
-            
-            // Execute creator transfer with proper error handling
-            let bump_seed = [policy_config.bump];
-            let signer_seeds: &[&[u8]] = &[
-                VAULT_SEED,
-                policy_config.vault.as_ref(),
-                b"investor_fee_pos_owner",
-                &bump_seed,
-            ];
-            let signer_seeds_slice = &[signer_seeds];
-            
-            let transfer_ctx = CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.treasury_ata.to_account_info(),
-                    to: ctx.accounts.creator_ata.to_account_info(),
-                    authority: ctx.accounts.position_owner_pda.to_account_info(),
-                },
-                signer_seeds_slice,
-            );
-            
-            transfer(transfer_ctx, creator_payout_amount)
-                .map_err(|_| ErrorCode::TreasuryTransferFailed)?;
-            
-            msg!("Creator transfer executed: {} tokens from treasury to creator", creator_payout_amount);
-        } else {
-            msg!("No creator payout required (amount: {})", creator_payout_amount);
-        }
-        
-        // Mark day complete after creator payout
-        distribution_progress.complete_day();
+        let payout_accounts = CreatorPayoutAccounts {
+            token_program: self.token_program,
+            treasury_ata: self.treasury_ata,
+            creator_ata: self.creator_ata,
+            position_owner_pda: &self.position_owner_pda,
+            buyback: buyback.as_ref(),
+        };
         
-        // Emit creator payout event
-        emit!(crate::CreatorPayoutDayClosed {
-            vault: policy_config.vault,
-            creator_payout: creator_payout_amount,
-            creator_wallet: policy_config.creator_wallet,
-            total_day_distributed: distribution_progress.current_day_distributed + creator_payout_amount,
-            total_investors_processed: streamflow_accounts.len() as u32,
-            final_dust_amount: distribution_progress.carry_over_dust,
-            timestamp: current_timestamp,
-        });
+        let base_burned = CreatorDistribution::execute_creator_payout(amount, policy_config, &payout_accounts)
+            .map_err(|e| {
+                msg!("Creator payout failed: {}", e);
+                e
+            })?;
         
-        msg!("Creator remainder payout completed: {} tokens", creator_payout_amount);
+        if let Some(base_burned) = base_burned {
+            msg!("Creator payout executed: {} tokens ({:?}), {} base burned",
+                 amount, policy_config.creator_payout_mode, base_burned);
+        }
+        Ok(base_burned)
     }
-    
-    Ok(())
 }
 
 #[cfg(test)]
@@ -411,6 +599,8 @@ mod timing_tests {
         DistributeFeesParams {
             page_size,
             cursor_position,
+            quote_mint: Pubkey::new_unique(),
+            next_registry_hash: [0; 32],
         }
     }
 
@@ -470,11 +660,9 @@ mod timing_tests {
         let progress = DistributionProgress {
             vault: Pubkey::new_unique(),
             last_distribution_ts: 1000,
-            current_day_distributed: 0,
-            carry_over_dust: 0,
             pagination_cursor: 20,
-            day_complete: false,
             bump: 255,
+            ..DistributionProgress::default()
         };
         
         // Test the logic for handling cursor positions
@@ -505,12 +693,8 @@ mod timing_tests {
     fn test_timing_system_integration_flow() {
         let mut progress = DistributionProgress {
             vault: Pubkey::new_unique(),
-            last_distribution_ts: 0,
-            current_day_distributed: 0,
-            carry_over_dust: 0,
-            pagination_cursor: 0,
-            day_complete: false,
             bump: 255,
+            ..DistributionProgress::default()
         };
         
         let start_time = 1000i64;
@@ -518,7 +702,7 @@ mod timing_tests {
         // Simulate the flow that would happen in the handler
         
         // 1. Prepare for distribution (first time)
-        let timing_state = progress.prepare_for_distribution(start_time).unwrap();
+        let timing_state = progress.prepare_for_distribution(start_time, TWENTY_FOUR_HOURS, None).unwrap();
         assert_eq!(timing_state, DistributionTimingState::NewDay);
         
         // 2. Process some pages in same day
@@ -537,7 +721,7 @@ mod timing_tests {
             
             // Verify can continue same day
             let current_time = start_time + 1800; // 30 minutes later
-            let timing_state = progress.prepare_for_distribution(current_time).unwrap();
+            let timing_state = progress.prepare_for_distribution(current_time, TWENTY_FOUR_HOURS, None).unwrap();
             assert_eq!(timing_state, DistributionTimingState::ContinueSameDay);
         }
         
@@ -546,7 +730,7 @@ mod timing_tests {
         
         // 4. Try to start new day after 24 hours
         let next_day = start_time + TWENTY_FOUR_HOURS;
-        let timing_state = progress.prepare_for_distribution(next_day).unwrap();
+        let timing_state = progress.prepare_for_distribution(next_day, TWENTY_FOUR_HOURS, None).unwrap();
         assert_eq!(timing_state, DistributionTimingState::NewDay);
         
         // Verify state reset for new day
@@ -3,8 +3,8 @@ use anchor_spl::token::{Token, TokenAccount};
 
 use crate::{
     constants::*,
-    state::{PolicyConfig, DistributionProgress},
-    utils::pda::PdaUtils,
+    state::{BaseFeePolicy, CatchUpMode, CrankReward, CreatorPayoutMode, HonoraryPool, PolicyConfig, PolicyTerms, DistributionProgress, DustPolicy, StakingLayout, WeightMode},
+    utils::{fee_claiming::validate_honorary_position_accounts, pda::PdaUtils, swap::validate_honorary_pool},
     error::ErrorCode,
     HonoraryPositionInitialized,
 };
@@ -66,10 +66,54 @@ pub struct InitializeHonoraryPosition<'info> {
 pub struct InitializeHonoraryPositionParams {
     pub quote_mint: Pubkey,
     pub creator_wallet: Pubkey,
+    /// Signs the policy's privileged instructions (base fee conversion, reference prices,
+    /// aborting stuck days)
+    pub authority: Pubkey,
     pub investor_fee_share_bps: u16,
     pub daily_cap_lamports: Option<u64>,
+    /// Optional maximum paid to a single investor per day
+    pub per_investor_daily_cap_lamports: Option<u64>,
+    /// Optional cap on investor payouts over a rolling 7-day window
+    pub rolling_cap_lamports: Option<u64>,
     pub min_payout_lamports: u64,
     pub y0_total_allocation: u64,
+    /// Vesting programs accepted as investor lock sources (defaults to Streamflow when empty)
+    pub vesting_programs: Vec<Pubkey>,
+    /// How investor weights are measured (locked vesting or staked balance)
+    pub weight_mode: WeightMode,
+    /// Stake account layout, required when `weight_mode` is `StakedBalance`
+    pub staking_layout: Option<StakingLayout>,
+    /// Where payout dust goes
+    pub dust_policy: DustPolicy,
+    /// What happens to base fees owed by the honorary positions (the base mint is taken
+    /// from `base_vault`)
+    pub base_fee_policy: BaseFeePolicy,
+    /// Maximum slippage in basis points accepted when converting base fees through a pool
+    pub max_swap_slippage_bps: u16,
+    /// What the creator's share is used for at day close (buyback and burn needs the base mint)
+    pub creator_payout_mode: CreatorPayoutMode,
+    /// Length of a distribution day in seconds, one hour to 30 days (24 hours when unset)
+    pub distribution_period_secs: Option<i64>,
+    /// Start of epoch 0 to align distribution days to (days start whenever the crank runs
+    /// when unset); cannot be in the future
+    pub epoch_anchor_ts: Option<i64>,
+    /// How a day opened after missed epochs distributes the fees accrued over them
+    pub catch_up_mode: CatchUpMode,
+    /// What crank callers are paid per day out of the creator's share, split across pages
+    /// by investors processed (unpaid when `None`)
+    pub crank_reward: CrankReward,
+    /// Most paid to crank callers per day (required when `crank_reward` pays)
+    pub crank_reward_daily_cap_lamports: u64,
+    /// Honorary DAMM V2 positions fees are claimed from (up to MAX_HONORARY_POSITIONS); each
+    /// is passed as a `[position, position_nft_account]` pair leading the remaining accounts
+    pub honorary_positions: Vec<Pubkey>,
+    /// Quote mint of each honorary position (all on `quote_mint` when empty); each other
+    /// mint gets its own treasury and day ledger
+    pub honorary_position_quote_mints: Vec<Pubkey>,
+    /// Pool of each honorary position with its base and quote vaults, which swaps through
+    /// the pool must use; entries for `pool` must name `base_vault` and `quote_vault`, and
+    /// every other pool is passed as a remaining account to be checked against
+    pub honorary_pools: Vec<HonoraryPool>,
 }
 
 pub fn initialize_honorary_position_handler(
@@ -85,43 +129,88 @@ pub fn initialize_honorary_position_handler(
     validate_initialization_params(&params)?;
     
     // Step 2: Validate account relationships
-    validate_account_relationships(&accounts, &params)?;
+    validate_account_relationships(accounts, &params)?;
+    let pool_accounts = validate_honorary_position_accounts(
+        &params.honorary_positions,
+        &accounts.position_owner_pda.key(),
+        ctx.remaining_accounts,
+    )?;
+    validate_honorary_pools(accounts, pool_accounts, &params)?;
     
     // Step 3: Perform quote-only validation (mock for now since we need DAMM V2 integration)
-    validate_quote_only_configuration(&accounts, &params)?;
+    validate_quote_only_configuration(accounts, &params)?;
     
     // Step 4: Initialize PolicyConfig account
     let policy_config = &mut accounts.policy_config;
-    policy_config.initialize(
-        accounts.vault.key(),
-        params.quote_mint,
-        params.creator_wallet,
-        params.investor_fee_share_bps,
-        params.daily_cap_lamports,
-        params.min_payout_lamports,
-        params.y0_total_allocation,
-        ctx.bumps.policy_config,
-    )?;
+    let terms = PolicyTerms {
+        vault: accounts.vault.key(),
+        quote_mint: params.quote_mint,
+        creator_wallet: params.creator_wallet,
+        investor_fee_share_bps: params.investor_fee_share_bps,
+        daily_cap_lamports: params.daily_cap_lamports,
+        min_payout_lamports: params.min_payout_lamports,
+        y0_total_allocation: params.y0_total_allocation,
+    };
+    policy_config.initialize(terms, ctx.bumps.policy_config)?;
+    if !params.vesting_programs.is_empty() {
+        policy_config.set_vesting_programs(&params.vesting_programs)?;
+    }
+    policy_config.configure_weight_mode(params.weight_mode, params.staking_layout)?;
+    policy_config.configure_caps(params.per_investor_daily_cap_lamports, params.rolling_cap_lamports)?;
+    policy_config.dust_policy = params.dust_policy;
+    policy_config.configure_base_fees(accounts.base_vault.mint, params.base_fee_policy)?;
+    policy_config.configure_swap_slippage(params.max_swap_slippage_bps)?;
+    policy_config.configure_creator_payout(params.creator_payout_mode)?;
+    policy_config.configure_distribution_period(params.distribution_period_secs)?;
+    policy_config.configure_epoch_alignment(params.epoch_anchor_ts, clock.unix_timestamp)?;
+    policy_config.catch_up_mode = params.catch_up_mode;
+    policy_config.configure_crank_reward(params.crank_reward, params.crank_reward_daily_cap_lamports)?;
+    policy_config.set_honorary_positions(&params.honorary_positions)?;
+    if !params.honorary_position_quote_mints.is_empty() {
+        policy_config.set_honorary_position_quote_mints(&params.honorary_position_quote_mints)?;
+    }
+    policy_config.set_honorary_pools(&params.honorary_pools)?;
+    policy_config.set_authority(params.authority)?;
     
     // Step 5: Initialize DistributionProgress account
     let distribution_progress = &mut accounts.distribution_progress;
     distribution_progress.initialize(
         accounts.vault.key(),
+        params.quote_mint,
         ctx.bumps.distribution_progress,
     )?;
     
     // Step 6: Validate PDA derivations
-    validate_pda_derivations(&accounts, &ctx.program_id)?;
+    validate_pda_derivations(accounts, ctx.program_id)?;
     
     // Step 7: Emit initialization event
     emit!(HonoraryPositionInitialized {
         vault: accounts.vault.key(),
         quote_mint: params.quote_mint,
         creator_wallet: params.creator_wallet,
+        authority: params.authority,
         investor_fee_share_bps: params.investor_fee_share_bps,
         daily_cap_lamports: params.daily_cap_lamports,
+        per_investor_daily_cap_lamports: params.per_investor_daily_cap_lamports,
+        rolling_cap_lamports: params.rolling_cap_lamports,
         min_payout_lamports: params.min_payout_lamports,
         y0_total_allocation: params.y0_total_allocation,
+        vesting_programs: accounts.policy_config.allowed_vesting_programs().to_vec(),
+        weight_mode: params.weight_mode,
+        dust_policy: params.dust_policy,
+        base_mint: accounts.base_vault.mint,
+        base_fee_policy: params.base_fee_policy,
+        max_swap_slippage_bps: params.max_swap_slippage_bps,
+        creator_payout_mode: params.creator_payout_mode,
+        distribution_period_secs: accounts.policy_config.distribution_period_secs,
+        epoch_anchor_ts: params.epoch_anchor_ts,
+        catch_up_mode: params.catch_up_mode,
+        crank_reward: params.crank_reward,
+        crank_reward_daily_cap_lamports: params.crank_reward_daily_cap_lamports,
+        honorary_positions: params.honorary_positions.clone(),
+        honorary_position_quote_mints: accounts.policy_config.honorary_position_quote_mints().to_vec(),
+        quote_mints: accounts.policy_config.quote_mints().to_vec(),
+        honorary_pools: params.honorary_pools.clone(),
         position_owner_pda: accounts.position_owner_pda.key(),
         policy_config: accounts.policy_config.key(),
         distribution_progress: accounts.distribution_progress.key(),
@@ -164,6 +253,34 @@ fn validate_initialization_params(params: &InitializeHonoraryPositionParams) ->
         );
     }
     
+    // Validate per-investor and rolling caps if provided
+    PolicyConfig::validate_caps(params.per_investor_daily_cap_lamports, params.rolling_cap_lamports)?;
+    
+    // Validate the distribution period if provided
+    if let Some(distribution_period_secs) = params.distribution_period_secs {
+        PolicyConfig::validate_distribution_period(distribution_period_secs)?;
+    }
+    PolicyConfig::validate_rolling_cap_window(
+        params.rolling_cap_lamports,
+        params.distribution_period_secs.unwrap_or(TWENTY_FOUR_HOURS),
+    )?;
+    
+    // Validate vesting program registry if provided
+    if !params.vesting_programs.is_empty() {
+        PolicyConfig::validate_vesting_programs(&params.vesting_programs)?;
+    }
+    
+    // Validate the honorary position list
+    PolicyConfig::validate_honorary_positions(&params.honorary_positions)?;
+    
+    // Validate staking layout for stake-weighted policies
+    if params.weight_mode == WeightMode::StakedBalance {
+        params.staking_layout
+            .as_ref()
+            .ok_or(ErrorCode::InvalidStakingLayout)?
+            .validate()?;
+    }
+    
     msg!("Parameter validation passed");
     Ok(())
 }
@@ -191,10 +308,47 @@ fn validate_account_relationships(
         ErrorCode::InvalidPoolConfiguration
     );
     
+    // Positions in the passed pool must record its vaults
+    for honorary_pool in params.honorary_pools.iter().filter(|entry| entry.pool == accounts.pool.key()) {
+        require!(
+            honorary_pool.base_vault == accounts.base_vault.key()
+                && honorary_pool.quote_vault == accounts.quote_vault.key(),
+            ErrorCode::InvalidPoolConfiguration
+        );
+    }
+    
     msg!("Account relationship validation passed");
     Ok(())
 }
 
+/// Check every honorary pool entry against its DAMM V2 pool account, which is `pool` or one
+/// of the remaining accounts after the position pairs
+fn validate_honorary_pools(
+    accounts: &InitializeHonoraryPosition,
+    remaining_accounts: &[AccountInfo],
+    params: &InitializeHonoraryPositionParams,
+) -> Result<()> {
+    let base_mint = accounts.base_vault.mint;
+
+    for (index, honorary_pool) in params.honorary_pools.iter().enumerate() {
+        let quote_mint = params.honorary_position_quote_mints
+            .get(index)
+            .copied()
+            .unwrap_or(params.quote_mint);
+        if honorary_pool.pool == accounts.pool.key() {
+            validate_honorary_pool(honorary_pool, &accounts.pool.to_account_info(), &base_mint, &quote_mint)?;
+        } else {
+            let pool = remaining_accounts
+                .iter()
+                .find(|account| account.key() == honorary_pool.pool)
+                .ok_or(ErrorCode::InvalidSwapPool)?;
+            validate_honorary_pool(honorary_pool, pool, &base_mint, &quote_mint)?;
+        }
+    }
+
+    Ok(())
+}
+
 /// Validate quote-only configuration
 /// This is a simplified version - full DAMM V2 integration would be needed for complete validation
 fn validate_quote_only_configuration(
@@ -0,0 +1,74 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::*,
+    state::{InvestorAccrual, PolicyConfig},
+    error::ErrorCode,
+    InvestorAccrualInitialized,
+};
+
+#[derive(Accounts)]
+#[instruction(quote_mint: Pubkey)]
+pub struct InitializeInvestorAccrual<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [POLICY_SEED, vault.key().as_ref()],
+        bump
+    )]
+    pub policy_config: Account<'info, PolicyConfig>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + InvestorAccrual::INIT_SPACE,
+        seeds = [ACCRUAL_SEED, vault.key().as_ref(), investor.key().as_ref(), policy_config.ledger_seed(&quote_mint)],
+        bump
+    )]
+    pub investor_accrual: Account<'info, InvestorAccrual>,
+
+    /// Investor wallet the accrual is attributed to
+    /// CHECK: Used only as seed
+    pub investor: UncheckedAccount<'info>,
+
+    /// The vault account (used as seed for PDAs)
+    /// CHECK: Used only as seed, validation handled by PDA derivation
+    pub vault: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_investor_accrual_handler(
+    mut ctx: Context<InitializeInvestorAccrual>,
+    quote_mint: Pubkey,
+) -> Result<()> {
+    let accounts = &mut ctx.accounts;
+
+    require!(
+        accounts.policy_config.vault == accounts.vault.key(),
+        ErrorCode::InvalidVaultAccount
+    );
+    require!(
+        accounts.policy_config.is_quote_mint(&quote_mint),
+        ErrorCode::InvalidQuoteMint
+    );
+
+    accounts.investor_accrual.initialize(
+        accounts.vault.key(),
+        accounts.investor.key(),
+        ctx.bumps.investor_accrual,
+    )?;
+
+    emit!(InvestorAccrualInitialized {
+        vault: accounts.vault.key(),
+        quote_mint,
+        investor: accounts.investor.key(),
+        investor_accrual: accounts.investor_accrual.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Investor accrual initialized for {} in the {} ledger", accounts.investor.key(), quote_mint);
+
+    Ok(())
+}
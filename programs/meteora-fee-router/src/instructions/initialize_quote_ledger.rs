@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::*,
+    state::{DistributionProgress, PolicyConfig},
+    error::ErrorCode,
+    QuoteLedgerInitialized,
+};
+
+#[derive(Accounts)]
+#[instruction(quote_mint: Pubkey)]
+pub struct InitializeQuoteLedger<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [POLICY_SEED, vault.key().as_ref()],
+        bump
+    )]
+    pub policy_config: Account<'info, PolicyConfig>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + DistributionProgress::INIT_SPACE,
+        seeds = [PROGRESS_SEED, vault.key().as_ref(), quote_mint.as_ref()],
+        bump
+    )]
+    pub distribution_progress: Account<'info, DistributionProgress>,
+
+    /// The vault account (used as seed for PDAs)
+    /// CHECK: Used only as seed, validation handled by PDA derivation
+    pub vault: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_quote_ledger_handler(
+    mut ctx: Context<InitializeQuoteLedger>,
+    quote_mint: Pubkey,
+) -> Result<()> {
+    let accounts = &mut ctx.accounts;
+
+    require!(
+        accounts.policy_config.vault == accounts.vault.key(),
+        ErrorCode::InvalidVaultAccount
+    );
+
+    // The primary quote mint's ledger is created with the policy
+    require!(
+        accounts.policy_config.is_quote_mint(&quote_mint)
+            && quote_mint != accounts.policy_config.quote_mint,
+        ErrorCode::InvalidQuoteMint
+    );
+
+    accounts.distribution_progress.initialize(
+        accounts.vault.key(),
+        quote_mint,
+        ctx.bumps.distribution_progress,
+    )?;
+
+    emit!(QuoteLedgerInitialized {
+        vault: accounts.vault.key(),
+        quote_mint,
+        distribution_progress: accounts.distribution_progress.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Quote ledger initialized for {}", quote_mint);
+
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+use crate::{
+    constants::*,
+    state::{DistributionProgress, PolicyConfig},
+    error::ErrorCode,
+    utils::{fee_claiming::validate_honorary_position_accounts, pda::PdaUtils},
+    AccountsMigrated,
+};
+
+#[derive(Accounts)]
+pub struct MigrateAccounts<'info> {
+    /// The policy's creator wallet, paying the rent of the grown accounts
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: Written under an older layout, so it cannot be loaded as `PolicyConfig` until
+    /// it has grown; owner and address are checked here, the discriminator in the handler
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [POLICY_SEED, vault.key().as_ref()],
+        bump
+    )]
+    pub policy_config: UncheckedAccount<'info>,
+
+    /// CHECK: The primary quote mint's day ledger, loaded like `policy_config`
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [PROGRESS_SEED, vault.key().as_ref()],
+        bump
+    )]
+    pub distribution_progress: UncheckedAccount<'info>,
+
+    /// The vault account (used as seed for PDAs)
+    /// CHECK: Used only as seed, validation handled by PDA derivation
+    pub vault: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct MigrateAccountsParams {
+    /// Honorary DAMM V2 positions fees are claimed from (unversioned policies took their
+    /// position per call); each is passed as a `[position, position_nft_account]` pair of
+    /// remaining accounts, in this order
+    pub honorary_positions: Vec<Pubkey>,
+}
+
+pub fn migrate_accounts_handler(
+    ctx: Context<MigrateAccounts>,
+    params: MigrateAccountsParams,
+) -> Result<()> {
+    let accounts = &ctx.accounts;
+    let policy_info = accounts.policy_config.to_account_info();
+    let progress_info = accounts.distribution_progress.to_account_info();
+
+    // Fields appended since the accounts were written read as zero once they have grown
+    grow_account(&policy_info, 8 + PolicyConfig::INIT_SPACE, accounts)?;
+    grow_account(&progress_info, 8 + DistributionProgress::INIT_SPACE, accounts)?;
+
+    let mut policy_config = PolicyConfig::try_deserialize(&mut &policy_info.try_borrow_data()?[..])?;
+    require!(
+        policy_config.vault == accounts.vault.key(),
+        ErrorCode::InvalidVaultAccount
+    );
+    require!(
+        policy_config.creator_wallet == accounts.authority.key(),
+        ErrorCode::UnauthorizedAuthority
+    );
+    let (position_owner, _) = PdaUtils::derive_position_owner_pda(ctx.program_id, &accounts.vault.key());
+    validate_honorary_position_accounts(&params.honorary_positions, &position_owner, ctx.remaining_accounts)?;
+    let from_version = policy_config.layout_version;
+    policy_config.migrate_layout(&params.honorary_positions)?;
+    policy_config.try_serialize(&mut &mut policy_info.try_borrow_mut_data()?[..])?;
+
+    let mut progress = DistributionProgress::try_deserialize(&mut &progress_info.try_borrow_data()?[..])?;
+    require!(
+        progress.vault == accounts.vault.key(),
+        ErrorCode::InvalidVaultAccount
+    );
+    progress.migrate_layout(policy_config.quote_mint)?;
+    progress.try_serialize(&mut &mut progress_info.try_borrow_mut_data()?[..])?;
+
+    emit!(AccountsMigrated {
+        vault: accounts.vault.key(),
+        authority: accounts.authority.key(),
+        from_version,
+        to_version: ACCOUNT_LAYOUT_VERSION,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Migrated accounts of {} from layout {} to {}", accounts.vault.key(), from_version, ACCOUNT_LAYOUT_VERSION);
+
+    Ok(())
+}
+
+/// Grow an account to `new_len` zero-filled bytes, topping its rent up from the authority
+fn grow_account<'info>(
+    account: &AccountInfo<'info>,
+    new_len: usize,
+    accounts: &MigrateAccounts<'info>,
+) -> Result<()> {
+    if account.data_len() >= new_len {
+        return Ok(());
+    }
+
+    let rent_due = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(account.lamports());
+    if rent_due > 0 {
+        transfer(
+            CpiContext::new(
+                accounts.system_program.to_account_info(),
+                Transfer {
+                    from: accounts.authority.to_account_info(),
+                    to: account.clone(),
+                },
+            ),
+            rent_due,
+        )?;
+    }
+    account.resize(new_len)?;
+
+    Ok(())
+}
@@ -1,5 +1,19 @@
 pub mod initialize_honorary_position;
 pub mod distribute_fees;
+pub mod initialize_investor_accrual;
+pub mod initialize_quote_ledger;
+pub mod convert_base_fees;
+pub mod abort_day;
+pub mod migrate_accounts;
+pub mod claim_investor_accrual;
+pub mod set_reference_price;
 
 pub use initialize_honorary_position::*;
-pub use distribute_fees::*;
\ No newline at end of file
+pub use distribute_fees::*;
+pub use initialize_investor_accrual::*;
+pub use initialize_quote_ledger::*;
+pub use convert_base_fees::*;
+pub use abort_day::*;
+pub use migrate_accounts::*;
+pub use claim_investor_accrual::*;
+pub use set_reference_price::*;
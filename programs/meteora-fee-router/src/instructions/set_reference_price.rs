@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::*,
+    state::PolicyConfig,
+    error::ErrorCode,
+    ReferencePriceUpdated,
+};
+
+#[derive(Accounts)]
+pub struct SetReferencePrice<'info> {
+    /// The policy's authority
+    #[account(
+        constraint = authority.key() == policy_config.authority @ ErrorCode::UnauthorizedAuthority
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [POLICY_SEED, vault.key().as_ref()],
+        bump
+    )]
+    pub policy_config: Account<'info, PolicyConfig>,
+
+    /// The vault account (used as seed for PDAs)
+    /// CHECK: Used only as seed, validation handled by PDA derivation
+    pub vault: UncheckedAccount<'info>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SetReferencePriceParams {
+    /// Honorary pool whose crank-driven swaps the price bounds
+    pub pool: Pubkey,
+    /// Quote per base in Q64.64
+    pub reference_price_q64: u128,
+}
+
+pub fn set_reference_price_handler(ctx: Context<SetReferencePrice>, params: SetReferencePriceParams) -> Result<()> {
+    let accounts = ctx.accounts;
+
+    require!(
+        accounts.policy_config.vault == accounts.vault.key(),
+        ErrorCode::InvalidVaultAccount
+    );
+
+    accounts.policy_config.set_reference_price(&params.pool, params.reference_price_q64)?;
+
+    emit!(ReferencePriceUpdated {
+        vault: accounts.policy_config.vault,
+        pool: params.pool,
+        authority: accounts.authority.key(),
+        reference_price_q64: params.reference_price_q64,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Reference price of pool {} set to {} (Q64.64)", params.pool, params.reference_price_q64);
+
+    Ok(())
+}
@@ -7,6 +7,9 @@ pub mod state;
 pub mod instructions;
 pub mod security_audit;
 
+#[cfg(test)]
+mod conservation_fuzz;
+
 pub use instructions::*;
 
 declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
@@ -23,13 +26,72 @@ pub mod meteora_fee_router {
         instructions::initialize_honorary_position::initialize_honorary_position_handler(ctx, params)
     }
 
-    /// Distribute fees to investors and creator via 24-hour crank system
+    /// Distribute fees to investors and creator via the per-period crank system
     pub fn distribute_fees<'info>(
         ctx: Context<'_, '_, '_, 'info, DistributeFees<'info>>,
         params: DistributeFeesParams,
     ) -> Result<()> {
         instructions::distribute_fees::distribute_fees_handler(ctx, params)
     }
+
+    /// Create an investor's accrual PDA holding allocations below the minimum payout
+    /// in one quote mint's ledger
+    pub fn initialize_investor_accrual(
+        ctx: Context<InitializeInvestorAccrual>,
+        quote_mint: Pubkey,
+    ) -> Result<()> {
+        instructions::initialize_investor_accrual::initialize_investor_accrual_handler(ctx, quote_mint)
+    }
+
+    /// Create the day ledger of one of the policy's additional quote mints
+    pub fn initialize_quote_ledger(
+        ctx: Context<InitializeQuoteLedger>,
+        quote_mint: Pubkey,
+    ) -> Result<()> {
+        instructions::initialize_quote_ledger::initialize_quote_ledger_handler(ctx, quote_mint)
+    }
+
+    /// Swap quarantined base fees into a quote mint through its honorary pool, crediting
+    /// the quote to that mint's next day claim
+    pub fn convert_base_fees(
+        ctx: Context<ConvertBaseFees>,
+        params: ConvertBaseFeesParams,
+    ) -> Result<()> {
+        instructions::convert_base_fees::convert_base_fees_handler(ctx, params)
+    }
+
+    /// Close a quote mint's stuck day, carrying what it left unpaid into the next day
+    /// (policy authority only)
+    pub fn abort_day(ctx: Context<AbortDay>, quote_mint: Pubkey) -> Result<()> {
+        instructions::abort_day::abort_day_handler(ctx, quote_mint)
+    }
+
+    /// Reallocate a vault's policy and primary day ledger written under an older layout
+    /// and fill in the fields added since (creator wallet only)
+    pub fn migrate_accounts(
+        ctx: Context<MigrateAccounts>,
+        params: MigrateAccountsParams,
+    ) -> Result<()> {
+        instructions::migrate_accounts::migrate_accounts_handler(ctx, params)
+    }
+
+    /// Release an investor's accrued balance in one quote mint's ledger to their quote
+    /// ATA, below the minimum payout too (investor only, between days)
+    pub fn claim_investor_accrual(
+        ctx: Context<ClaimInvestorAccrual>,
+        quote_mint: Pubkey,
+    ) -> Result<()> {
+        instructions::claim_investor_accrual::claim_investor_accrual_handler(ctx, quote_mint)
+    }
+
+    /// Set the reference price bounding crank-driven swaps through an honorary pool
+    /// (policy authority only)
+    pub fn set_reference_price(
+        ctx: Context<SetReferencePrice>,
+        params: SetReferencePriceParams,
+    ) -> Result<()> {
+        instructions::set_reference_price::set_reference_price_handler(ctx, params)
+    }
 }
 
 /// Events emitted by the program
@@ -38,16 +100,52 @@ pub struct HonoraryPositionInitialized {
     pub vault: Pubkey,
     pub quote_mint: Pubkey,
     pub creator_wallet: Pubkey,
+    pub authority: Pubkey,
     pub investor_fee_share_bps: u16,
     pub daily_cap_lamports: Option<u64>,
+    pub per_investor_daily_cap_lamports: Option<u64>,
+    pub rolling_cap_lamports: Option<u64>,
     pub min_payout_lamports: u64,
     pub y0_total_allocation: u64,
+    pub vesting_programs: Vec<Pubkey>,
+    pub weight_mode: state::WeightMode,
+    pub dust_policy: state::DustPolicy,
+    pub base_mint: Pubkey,
+    pub base_fee_policy: state::BaseFeePolicy,
+    pub max_swap_slippage_bps: u16,
+    pub creator_payout_mode: state::CreatorPayoutMode,
+    pub distribution_period_secs: i64,
+    pub epoch_anchor_ts: Option<i64>,
+    pub catch_up_mode: state::CatchUpMode,
+    pub crank_reward: state::CrankReward,
+    pub crank_reward_daily_cap_lamports: u64,
+    pub honorary_positions: Vec<Pubkey>,
+    pub honorary_position_quote_mints: Vec<Pubkey>,
+    pub quote_mints: Vec<Pubkey>,
+    pub honorary_pools: Vec<state::HonoraryPool>,
     pub position_owner_pda: Pubkey,
     pub policy_config: Pubkey,
     pub distribution_progress: Pubkey,
     pub timestamp: i64,
 }
 
+#[event]
+pub struct InvestorAccrualInitialized {
+    pub vault: Pubkey,
+    pub quote_mint: Pubkey,
+    pub investor: Pubkey,
+    pub investor_accrual: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct QuoteLedgerInitialized {
+    pub vault: Pubkey,
+    pub quote_mint: Pubkey,
+    pub distribution_progress: Pubkey,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct QuoteFeesClaimed {
     pub vault: Pubkey,
@@ -59,14 +157,100 @@ pub struct QuoteFeesClaimed {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct BaseFeesHandled {
+    pub vault: Pubkey,
+    pub honorary_position: Pubkey,
+    pub base_mint: Pubkey,
+    pub base_amount: u64,
+    pub base_fee_policy: state::BaseFeePolicy,
+    /// Base treasury or creator base ATA the fees were collected into
+    pub destination: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BaseFeesConverted {
+    pub vault: Pubkey,
+    pub quote_mint: Pubkey,
+    pub base_mint: Pubkey,
+    pub base_amount_in: u64,
+    pub quote_amount_out: u64,
+    pub minimum_amount_out: u64,
+    /// Converted quote waiting for the next day's claim, including this swap
+    pub pending_converted_quote: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DistributionEpochsMissed {
+    pub vault: Pubkey,
+    pub quote_mint: Pubkey,
+    /// Epoch of the day just opened
+    pub epoch_index: u64,
+    /// Epochs between the previous day and this one that never opened
+    pub missed_epochs: u32,
+    /// Start of the day just opened (the epoch start when days are epoch-aligned)
+    pub day_start_ts: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UnfinishedDayCarried {
+    pub vault: Pubkey,
+    pub quote_mint: Pubkey,
+    /// Epoch of the unfinished day
+    pub epoch_index: u64,
+    /// Investors paged before the day was left
+    pub pagination_cursor: u32,
+    pub investor_carried: u64,
+    pub creator_carried: u64,
+    pub claim_carried: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DayAborted {
+    pub vault: Pubkey,
+    pub quote_mint: Pubkey,
+    pub authority: Pubkey,
+    pub epoch_index: u64,
+    pub pagination_cursor: u32,
+    pub investor_carried: u64,
+    pub creator_carried: u64,
+    pub claim_carried: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CatchUpEpochDistributed {
+    pub vault: Pubkey,
+    pub quote_mint: Pubkey,
+    /// Missed epoch this part of the catch-up day's claim is attributed to
+    pub epoch_index: u64,
+    pub window_start_ts: i64,
+    pub window_end_ts: i64,
+    pub claimed_quote: u64,
+    pub total_locked: u64,
+    pub investor_share: u64,
+    pub catch_up_mode: state::CatchUpMode,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct InvestorPayoutPage {
     pub vault: Pubkey,
+    pub quote_mint: Pubkey,
     pub page_start: u32,
     pub page_end: u32,
     pub total_distributed: u64,
     pub processed_count: u32,
     pub dust_carried_forward: u64,
+    pub cap_overflow_deferred: u64,
+    pub dust_paid: u64,
+    pub dust_recipient: Option<Pubkey>,
+    pub investor_accrued: u64,
+    pub investor_accrual_released: u64,
     pub cumulative_day_distributed: u64,
     pub timestamp: i64,
 }
@@ -74,10 +258,64 @@ pub struct InvestorPayoutPage {
 #[event]
 pub struct CreatorPayoutDayClosed {
     pub vault: Pubkey,
+    pub quote_mint: Pubkey,
     pub creator_payout: u64,
+    /// Creator payout kept in the treasury as creator carry because the buyback could not
+    /// run (no buyback accounts or no reference price); paid at a later day's close
+    pub creator_payout_held: u64,
     pub creator_wallet: Pubkey,
     pub total_day_distributed: u64,
     pub total_investors_processed: u32,
     pub final_dust_amount: u64,
+    /// How the creator share was paid (transferred, or bought back and burned)
+    pub creator_payout_mode: state::CreatorPayoutMode,
+    /// Base tokens bought back with the creator share and burned (zero on transfer)
+    pub base_burned: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CrankRewardPaid {
+    pub vault: Pubkey,
+    pub quote_mint: Pubkey,
+    pub crank_caller: Pubkey,
+    /// Reward paid for this page, out of the creator's remainder
+    pub amount: u64,
+    /// Cursor after the rewarded page
+    pub page_end: u32,
+    /// Crank rewards paid for the day so far, including this one
+    pub day_crank_reward_paid: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AccountsMigrated {
+    pub vault: Pubkey,
+    pub authority: Pubkey,
+    /// Layout the policy was written under before the migration
+    pub from_version: u8,
+    pub to_version: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct InvestorAccrualClaimed {
+    pub vault: Pubkey,
+    pub quote_mint: Pubkey,
+    pub investor: Pubkey,
+    /// Accrued balance released by the claim
+    pub amount: u64,
+    /// Total released to the investor, including this claim
+    pub total_released: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReferencePriceUpdated {
+    pub vault: Pubkey,
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    /// Quote per base in Q64.64
+    pub reference_price_q64: u128,
     pub timestamp: i64,
 }
\ No newline at end of file
@@ -1,11 +1,18 @@
 use anchor_lang::prelude::*;
 use crate::constants::*;
 use crate::error::ErrorCode;
+use crate::utils::math::{
+    evaluate_cap_policy, largest_remainder_cutoff, CapConstraint, CapPolicy, RemainderCutoff,
+};
+use crate::utils::vesting::InvestorData;
+use crate::utils::weights::WeightWindow;
+use solana_sha256_hasher::hashv;
+use crate::state::{CatchUpMode, DustPolicy, PolicyConfig};
 
 /// Represents the timing state for distribution operations
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum DistributionTimingState {
-    /// Can start a new 24-hour distribution period
+    /// Can start a new distribution period
     NewDay,
     /// Can continue pagination within the same day
     ContinueSameDay,
@@ -39,98 +46,421 @@ pub struct DistributionProgress {
     pub day_complete: bool,
     /// PDA bump
     pub bump: u8,
+    /// Start of the window investor weights are averaged over for the current day
+    /// (the previous day's start; the window ends at `last_distribution_ts`)
+    pub weight_window_start_ts: i64,
+    /// Investor payouts withheld by the daily cap (or left unpaid by an unfinished day),
+    /// added to the next day's investor pool
+    pub cap_overflow_carry: u64,
+    /// Investor pool for the current day before the daily cap, fixed at day start
+    pub day_investor_pool: u64,
+    /// Portion of the day's investor pool payable under the daily cap, apportioned by every page
+    pub day_payable_pool: u64,
+    /// Ring buffer of amounts distributed per period over the rolling cap window
+    pub rolling_distributed: [u64; ROLLING_CAP_SLOTS],
+    /// Slot of `rolling_distributed` holding the current day
+    pub rolling_day_index: u8,
+    /// Registry index of the next round-robin dust recipient
+    pub dust_round_robin_cursor: u32,
+    /// Sub-threshold allocations held in investor accrual PDAs, still owed from the treasury
+    pub investor_accrued_total: u64,
+    /// Quote fees claimed for the current day
+    pub day_claimed_quote: u64,
+    /// Investor share of `day_claimed_quote`, fixed when the day's pool opens
+    pub day_investor_share: u64,
+    /// Outstanding liabilities (carried dust, cap overflow, accruals) at day start
+    pub day_opening_liabilities: u64,
+    /// Quote mint this ledger distributes
+    pub quote_mint: Pubkey,
+    /// Quote received from converting quarantined base fees, credited to the next day's claim
+    pub converted_base_quote: u64,
+    /// Distribution period the current day covers, counted from the policy's epoch anchor
+    /// (or from the first day when days are not epoch-aligned)
+    pub epoch_index: u64,
+    /// Periods that passed without a day between the previous day and the current one
+    pub missed_epochs: u32,
+    /// Creator remainder of unfinished days, paid with the next day's creator payout
+    pub creator_carry: u64,
+    /// Claim of an unfinished day whose investor pool never opened, added to the next
+    /// day's claim
+    pub carried_claim_quote: u64,
+    /// Crank rewards paid for the current day, out of the creator's remainder
+    pub day_crank_reward_paid: u64,
+    /// Layout the account was written under (ACCOUNT_LAYOUT_VERSION once migrated)
+    pub layout_version: u8,
+    /// Investors in the day's registry, committed when the day's pool opens
+    pub day_investor_count: u32,
+    /// Total investor weight of the day's registry
+    pub day_total_locked: u64,
+    /// Hash chain over the day's registry entries (wallet and weight accounts, in wallet order)
+    pub day_registry_hash: [u8; 32],
+    /// Hash chain over the registry entries from the cursor on, which the next page's
+    /// entries must chain to before anything is paid
+    pub unpaged_registry_hash: [u8; 32],
+    /// Largest-remainder cutoff of the day's payable pool over the registry weights
+    pub day_remainder_cutoff: u128,
+    /// Registry entries at the cutoff remainder still taking a leftover lamport
+    pub day_remainder_ties: u32,
+    /// Payable pool apportioned to the investors paged so far
+    pub day_investor_allocated: u64,
+    /// Treasury balance when the current day opened, before its fee claim
+    pub day_opening_treasury: u64,
+    /// Quote received into the treasury since the day opened (fee claims and base
+    /// conversions)
+    pub day_treasury_inflow: u64,
+}
+
+impl Default for DistributionProgress {
+    /// A ledger that has not opened a day yet
+    fn default() -> Self {
+        Self {
+            vault: Pubkey::default(),
+            last_distribution_ts: 0,
+            current_day_distributed: 0,
+            carry_over_dust: 0,
+            pagination_cursor: 0,
+            day_complete: false,
+            bump: 0,
+            weight_window_start_ts: 0,
+            cap_overflow_carry: 0,
+            day_investor_pool: 0,
+            day_payable_pool: 0,
+            rolling_distributed: [0; ROLLING_CAP_SLOTS],
+            rolling_day_index: 0,
+            dust_round_robin_cursor: 0,
+            investor_accrued_total: 0,
+            day_claimed_quote: 0,
+            day_investor_share: 0,
+            day_opening_liabilities: 0,
+            quote_mint: Pubkey::default(),
+            converted_base_quote: 0,
+            epoch_index: 0,
+            missed_epochs: 0,
+            creator_carry: 0,
+            carried_claim_quote: 0,
+            day_crank_reward_paid: 0,
+            layout_version: ACCOUNT_LAYOUT_VERSION,
+            day_investor_count: 0,
+            day_total_locked: 0,
+            day_registry_hash: [0; 32],
+            unpaged_registry_hash: [0; 32],
+            day_remainder_cutoff: RemainderCutoff::NONE.remainder,
+            day_remainder_ties: 0,
+            day_investor_allocated: 0,
+            day_opening_treasury: 0,
+            day_treasury_inflow: 0,
+        }
+    }
+}
+
+/// What an unfinished day left unsettled, carried into the next day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnfinishedDay {
+    /// Epoch of the unfinished day
+    pub epoch_index: u64,
+    /// Investors paged before the day was left
+    pub pagination_cursor: u32,
+    /// Unpaid investor pool, added to the next day's investor pool
+    pub investor_carried: u64,
+    /// Creator remainder, added to the next day's creator payout
+    pub creator_carried: u64,
+    /// Claim of a day whose investor pool never opened, added to the next day's claim
+    pub claim_carried: u64,
+}
+
+/// What a day whose pages covered the registry settles at close
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DaySettlement {
+    /// Part of the payable pool the pages left unallocated, deferred to the next day
+    pub unallocated: u64,
+    /// Creator remainder of the day's claim, less crank rewards, with any creator carry
+    pub creator_remainder: u64,
+    /// Carried dust swept to the creator (CreatorAtDayClose)
+    pub dust_sweep: u64,
+}
+
+impl DaySettlement {
+    /// Amount paid to the creator at day close
+    pub fn creator_payout(&self) -> Result<u64> {
+        self.creator_remainder
+            .checked_add(self.dust_sweep)
+            .ok_or(ErrorCode::ArithmeticOverflow.into())
+    }
+}
+
+/// Amounts moved into the investor pool when a day opens
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DayOpening {
+    /// Investor pool for the day before caps
+    pub investor_pool: u64,
+    /// Cap overflow withheld on previous days, added to the pool
+    pub cap_rollover: u64,
+    /// Carried dust added to the pool (CarryForward only)
+    pub dust_rollover: u64,
+    /// Part of the pool the caps withhold, deferred to the next day
+    pub deferred: u64,
+    /// Cap that limited the pool
+    pub binding: CapConstraint,
 }
 
 impl DistributionProgress {
     /// Calculate space needed for account
     /// 32 (vault) + 8 (last_distribution_ts) + 8 (current_day_distributed) 
     /// + 8 (carry_over_dust) + 4 (pagination_cursor) + 1 (day_complete) + 1 (bump)
-    pub const INIT_SPACE: usize = 32 + 8 + 8 + 8 + 4 + 1 + 1;
+    /// + 8 (weight_window_start_ts) + 8 (cap_overflow_carry)
+    /// + 8 (day_investor_pool) + 8 (day_payable_pool)
+    /// + 8 * ROLLING_CAP_SLOTS (rolling_distributed) + 1 (rolling_day_index)
+    /// + 4 (dust_round_robin_cursor) + 8 (investor_accrued_total)
+    /// + 8 (day_claimed_quote) + 8 (day_investor_share) + 8 (day_opening_liabilities)
+    /// + 32 (quote_mint) + 8 (converted_base_quote)
+    /// + 8 (epoch_index) + 4 (missed_epochs)
+    /// + 8 (creator_carry) + 8 (carried_claim_quote)
+    /// + 8 (day_crank_reward_paid) + 1 (layout_version)
+    /// + 4 (day_investor_count) + 8 (day_total_locked)
+    /// + 32 (day_registry_hash) + 32 (unpaged_registry_hash)
+    /// + 16 (day_remainder_cutoff) + 4 (day_remainder_ties) + 8 (day_investor_allocated)
+    /// + 8 (day_opening_treasury) + 8 (day_treasury_inflow)
+    pub const INIT_SPACE: usize = 32 + 8 + 8 + 8 + 4 + 1 + 1 + 8 + 8 + 8 + 8
+        + 8 * ROLLING_CAP_SLOTS + 1 + 4 + 8
+        + 8 + 8 + 8
+        + 32 + 8
+        + 8 + 4
+        + 8 + 8
+        + 8 + 1
+        + 4 + 8
+        + 32 + 32
+        + 16 + 4 + 8
+        + 8 + 8;
 
-    /// Initialize a new distribution progress tracker
+    /// Initialize a new distribution progress tracker for one quote mint
     pub fn initialize(
         &mut self,
         vault: Pubkey,
+        quote_mint: Pubkey,
         bump: u8,
     ) -> Result<()> {
-        self.vault = vault;
-        self.last_distribution_ts = 0;
-        self.current_day_distributed = 0;
-        self.carry_over_dust = 0;
-        self.pagination_cursor = 0;
-        self.day_complete = false;
-        self.bump = bump;
+        *self = Self {
+            vault,
+            quote_mint,
+            bump,
+            ..Self::default()
+        };
+
+        Ok(())
+    }
+
+    /// Bring a ledger written under an older layout up to ACCOUNT_LAYOUT_VERSION
+    ///
+    /// Fields appended since the account was written read as zero once it has been
+    /// reallocated; each step gives one layout's new fields the values `initialize` sets.
+    pub fn migrate_layout(&mut self, quote_mint: Pubkey) -> Result<()> {
+        require!(
+            self.layout_version < ACCOUNT_LAYOUT_VERSION,
+            ErrorCode::AccountAlreadyMigrated
+        );
 
+        // An unversioned day cannot be resumed by the current day logic, so only a
+        // ledger between days moves over; its timing and carried dust are kept
+        if self.layout_version == 0 {
+            require!(!self.is_day_in_progress(), ErrorCode::MigrationDuringDay);
+            *self = Self {
+                vault: self.vault,
+                last_distribution_ts: self.last_distribution_ts,
+                current_day_distributed: self.current_day_distributed,
+                carry_over_dust: self.carry_over_dust,
+                pagination_cursor: self.pagination_cursor,
+                day_complete: self.day_complete,
+                bump: self.bump,
+                weight_window_start_ts: self.last_distribution_ts,
+                quote_mint,
+                ..Self::default()
+            };
+        }
+
+        self.layout_version = ACCOUNT_LAYOUT_VERSION;
         Ok(())
     }
 
-    /// Check if 24 hours have passed since last distribution
-    pub fn can_start_new_day(&self, current_timestamp: i64) -> bool {
+    /// Check if a distribution period (`period_secs`) has passed since last distribution
+    pub fn can_start_new_day(&self, current_timestamp: i64, period_secs: i64) -> bool {
         if self.last_distribution_ts == 0 {
             // Allow the very first distribution immediately
             true
         } else {
-            current_timestamp >= self.last_distribution_ts + TWENTY_FOUR_HOURS
+            current_timestamp >= self.last_distribution_ts + period_secs
         }
     }
 
-    /// Check if we're in the same day as the last distribution
-    pub fn is_same_day(&self, current_timestamp: i64) -> bool {
+    /// Check if we're in the same distribution period as the last distribution
+    pub fn is_same_day(&self, current_timestamp: i64, period_secs: i64) -> bool {
         if self.last_distribution_ts == 0 {
             return false;
         }
         
         let time_diff = current_timestamp - self.last_distribution_ts;
-        time_diff >= 0 && time_diff < TWENTY_FOUR_HOURS
+        time_diff >= 0 && time_diff < period_secs
     }
 
     /// Get the day boundary timestamp for the current distribution period
-    pub fn get_day_boundary(&self) -> i64 {
-        self.last_distribution_ts + TWENTY_FOUR_HOURS
+    pub fn get_day_boundary(&self, period_secs: i64) -> i64 {
+        self.last_distribution_ts + period_secs
     }
 
     /// Check if we can continue distribution in the same day (for pagination)
-    pub fn can_continue_same_day(&self, current_timestamp: i64) -> bool {
-        self.is_same_day(current_timestamp) && !self.day_complete
+    pub fn can_continue_same_day(&self, current_timestamp: i64, period_secs: i64) -> bool {
+        self.is_same_day(current_timestamp, period_secs) && !self.day_complete
     }
 
     /// Validate timing for distribution operation
-    pub fn validate_distribution_timing(&self, current_timestamp: i64) -> Result<DistributionTimingState> {
+    pub fn validate_distribution_timing(&self, current_timestamp: i64, period_secs: i64) -> Result<DistributionTimingState> {
         if self.last_distribution_ts == 0 {
             // First distribution ever
             return Ok(DistributionTimingState::NewDay);
         }
 
-        if self.can_start_new_day(current_timestamp) {
-            // 24+ hours have passed, can start new day
+        if self.can_start_new_day(current_timestamp, period_secs) {
+            // A full period has passed, can start new day
             Ok(DistributionTimingState::NewDay)
-        } else if self.can_continue_same_day(current_timestamp) {
+        } else if self.can_continue_same_day(current_timestamp, period_secs) {
             // Within same day and not complete, can continue pagination
             Ok(DistributionTimingState::ContinueSameDay)
-        } else if self.is_same_day(current_timestamp) && self.day_complete {
+        } else if self.is_same_day(current_timestamp, period_secs) && self.day_complete {
             // Same day but already complete
             Err(ErrorCode::DayAlreadyComplete.into())
         } else {
-            // Within 24 hours but something is wrong
+            // Within the period but something is wrong
             Err(ErrorCode::CooldownNotElapsed.into())
         }
     }
 
-    /// Start a new distribution day
-    pub fn start_new_day(&mut self, current_timestamp: i64) -> Result<()> {
+    /// Start of the day opened at `current_timestamp`, and its epoch index
+    ///
+    /// With an epoch anchor, day N covers `[anchor + N * period, anchor + (N + 1) * period)`
+    /// however late the crank runs; without one the day starts when the crank runs.
+    pub fn day_start_for(
+        &self,
+        current_timestamp: i64,
+        period_secs: i64,
+        epoch_anchor_ts: Option<i64>,
+    ) -> Result<(i64, u64)> {
+        match epoch_anchor_ts {
+            Some(anchor) => {
+                require!(
+                    current_timestamp >= anchor,
+                    ErrorCode::CooldownNotElapsed
+                );
+                let epoch = (current_timestamp - anchor) / period_secs;
+                let day_start = epoch
+                    .checked_mul(period_secs)
+                    .and_then(|offset| anchor.checked_add(offset))
+                    .ok_or(ErrorCode::ArithmeticOverflow)?;
+                Ok((day_start, epoch as u64))
+            },
+            None => {
+                let epoch = if self.last_distribution_ts == 0 {
+                    0
+                } else {
+                    let elapsed = (current_timestamp - self.last_distribution_ts) / period_secs;
+                    self.epoch_index
+                        .checked_add(elapsed as u64)
+                        .ok_or(ErrorCode::ArithmeticOverflow)?
+                };
+                Ok((current_timestamp, epoch))
+            },
+        }
+    }
+
+    /// Start a new distribution day, aligned to the policy's epochs if it has an anchor
+    pub fn start_new_day(
+        &mut self,
+        current_timestamp: i64,
+        period_secs: i64,
+        epoch_anchor_ts: Option<i64>,
+    ) -> Result<()> {
         require!(
-            self.can_start_new_day(current_timestamp),
+            self.can_start_new_day(current_timestamp, period_secs),
             ErrorCode::CooldownNotElapsed
         );
 
-        self.last_distribution_ts = current_timestamp;
+        let (day_start, epoch_index) = self.day_start_for(current_timestamp, period_secs, epoch_anchor_ts)?;
+
+        // Whatever the previous day left unpaid moves into the carries before the day resets
+        if let Some(unfinished) = self.carry_unfinished_day()? {
+            msg!("Carried unfinished day {} (cursor {}): investors={}, creator={}, claim={}",
+                 unfinished.epoch_index, unfinished.pagination_cursor, unfinished.investor_carried,
+                 unfinished.creator_carried, unfinished.claim_carried);
+        }
+
+        // Epochs between the previous day and this one that never opened
+        let elapsed_epochs = if self.last_distribution_ts == 0 {
+            1
+        } else {
+            epoch_index.saturating_sub(self.epoch_index).max(1)
+        };
+        self.missed_epochs = u32::try_from(elapsed_epochs - 1).unwrap_or(u32::MAX);
+        self.epoch_index = epoch_index;
+
+        self.advance_rolling_window(elapsed_epochs, period_secs);
+
+        // Snapshot what is still owed from the treasury before the day moves anything
+        self.day_opening_liabilities = self.outstanding_liabilities()?;
+        self.day_claimed_quote = 0;
+        self.day_investor_share = 0;
+        self.day_crank_reward_paid = 0;
+
+        // Weights for the new day are averaged since the previous day's start
+        self.weight_window_start_ts = if self.last_distribution_ts == 0 {
+            day_start
+        } else {
+            self.last_distribution_ts
+        };
+        self.last_distribution_ts = day_start;
         self.current_day_distributed = 0;
+        self.day_investor_pool = 0;
+        self.day_payable_pool = 0;
         self.pagination_cursor = 0;
         self.day_complete = false;
+        self.clear_day_registry();
+        self.day_opening_treasury = 0;
+        self.day_treasury_inflow = 0;
+
+        Ok(())
+    }
 
+    /// Snapshot the treasury balance as the day opens, before its fee claim
+    pub fn record_treasury_opening(&mut self, treasury_balance: u64) {
+        self.day_opening_treasury = treasury_balance;
+        self.day_treasury_inflow = 0;
+    }
+
+    /// Record quote received into the treasury during the day
+    pub fn record_treasury_inflow(&mut self, amount: u64) -> Result<()> {
+        self.day_treasury_inflow = self.day_treasury_inflow
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
         Ok(())
     }
 
+    /// Epochs the current day's claim is distributed over: one, or under a catch-up mode
+    /// the missed epochs plus the day's own (at most MAX_CATCH_UP_EPOCHS)
+    pub fn catch_up_epochs(&self, catch_up_mode: CatchUpMode) -> u64 {
+        match catch_up_mode {
+            CatchUpMode::SingleDay => 1,
+            CatchUpMode::CapPerEpoch | CatchUpMode::SplitClaim => {
+                (self.missed_epochs as u64 + 1).min(MAX_CATCH_UP_EPOCHS)
+            },
+        }
+    }
+
+    /// Window investor weights are averaged over for the current day
+    /// Fixed at day start so every page of the day sees the same weights
+    pub fn weight_window(&self) -> WeightWindow {
+        WeightWindow {
+            start_ts: self.weight_window_start_ts,
+            end_ts: self.last_distribution_ts,
+        }
+    }
+
     /// Check if daily cap would be exceeded
     pub fn check_daily_cap(&self, additional_amount: u64, daily_cap: Option<u64>) -> Result<()> {
         if let Some(cap) = daily_cap {
@@ -151,9 +481,56 @@ impl DistributionProgress {
         self.current_day_distributed = self.current_day_distributed
             .checked_add(amount)
             .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        let slot = self.rolling_slot();
+        self.rolling_distributed[slot] = self.rolling_distributed[slot]
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
         Ok(())
     }
 
+    /// Total distributed over the rolling cap window, including the current day
+    pub fn rolling_window_distributed(&self) -> Result<u64> {
+        self.rolling_distributed
+            .iter()
+            .try_fold(0u64, |total, &amount| total.checked_add(amount))
+            .ok_or(ErrorCode::ArithmeticOverflow.into())
+    }
+
+    /// Periods of `period_secs` the rolling cap window spans, when it is a whole number
+    /// of them that fits the ring buffer
+    pub fn rolling_window_periods(period_secs: i64) -> Option<usize> {
+        if period_secs <= 0 || ROLLING_CAP_WINDOW_SECS % period_secs != 0 {
+            return None;
+        }
+
+        let periods = (ROLLING_CAP_WINDOW_SECS / period_secs) as usize;
+        (periods <= ROLLING_CAP_SLOTS).then_some(periods)
+    }
+
+    /// Ring buffer slot of the current day
+    fn rolling_slot(&self) -> usize {
+        self.rolling_day_index as usize % ROLLING_CAP_SLOTS
+    }
+
+    /// Move the rolling window forward one slot per elapsed period, clearing the slots
+    /// of periods that fell out of the window (including missed periods). Only the
+    /// window's periods are used, so it spans ROLLING_CAP_WINDOW_SECS; policies whose
+    /// period does not divide it have no rolling cap and use every slot.
+    fn advance_rolling_window(&mut self, elapsed_periods: u64, period_secs: i64) {
+        if self.last_distribution_ts == 0 {
+            return;
+        }
+
+        let window_periods = Self::rolling_window_periods(period_secs).unwrap_or(ROLLING_CAP_SLOTS);
+        let steps = elapsed_periods.clamp(1, window_periods as u64);
+        for _ in 0..steps {
+            let next_slot = (self.rolling_slot() + 1) % window_periods;
+            self.rolling_distributed[next_slot] = 0;
+            self.rolling_day_index = next_slot as u8;
+        }
+    }
+
     /// Update pagination cursor with validation
     pub fn update_cursor(&mut self, new_cursor: u32) -> Result<()> {
         require!(
@@ -225,7 +602,449 @@ impl DistributionProgress {
         Ok(consumed)
     }
 
-    /// Get the PDA seeds for this distribution progress (for signing)
+    /// Take all carried dust, leaving none on the progress account
+    pub fn take_carry_over_dust(&mut self) -> u64 {
+        std::mem::take(&mut self.carry_over_dust)
+    }
+
+    /// Registry index of the next round-robin dust recipient among `investor_count` investors
+    pub fn dust_round_robin_index(&self, investor_count: usize) -> Option<usize> {
+        if investor_count == 0 {
+            return None;
+        }
+        Some(self.dust_round_robin_cursor as usize % investor_count)
+    }
+
+    /// Move the round-robin dust cursor past the investor that just received dust
+    pub fn advance_dust_round_robin(&mut self, investor_count: usize) {
+        if let Some(index) = self.dust_round_robin_index(investor_count) {
+            self.dust_round_robin_cursor = ((index + 1) % investor_count) as u32;
+        }
+    }
+
+    /// Record quote fees claimed for the current day
+    pub fn record_day_claim(&mut self, claimed_quote_amount: u64) -> Result<()> {
+        self.day_claimed_quote = self.day_claimed_quote
+            .checked_add(claimed_quote_amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Hold quote received from converting base fees until the next day's claim
+    pub fn credit_converted_base_quote(&mut self, quote_amount: u64) -> Result<()> {
+        self.converted_base_quote = self.converted_base_quote
+            .checked_add(quote_amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Take the converted base fee quote to add to a new day's claim
+    pub fn take_converted_base_quote(&mut self) -> u64 {
+        std::mem::take(&mut self.converted_base_quote)
+    }
+
+    /// What the current day would leave unsettled if it ended now, or None when it
+    /// completed or left nothing
+    ///
+    /// The unsettled amount is what the day's accounting identity is still short of:
+    /// `claimed + opening liabilities - investor paid - crank rewards - liabilities`. Once the investor
+    /// pool opened (the cursor moved), the creator takes its remainder of the claim and
+    /// the rest is unpaid investor pool; before that the split is unknown and the whole
+    /// claim is carried.
+    pub fn unfinished_day(&self) -> Result<Option<UnfinishedDay>> {
+        if self.last_distribution_ts == 0 || self.day_complete {
+            return Ok(None);
+        }
+
+        let sources = (self.day_claimed_quote as u128) + (self.day_opening_liabilities as u128);
+        let uses = (self.current_day_distributed as u128)
+            + (self.day_crank_reward_paid as u128)
+            + (self.outstanding_liabilities()? as u128);
+        let unsettled = u64::try_from(sources.saturating_sub(uses))
+            .map_err(|_| ErrorCode::ArithmeticOverflow)?;
+        if unsettled == 0 {
+            return Ok(None);
+        }
+
+        let (investor_carried, creator_carried, claim_carried) = if self.pagination_cursor == 0 {
+            (0, 0, unsettled)
+        } else {
+            let creator_carried = self.day_claimed_quote
+                .saturating_sub(self.day_investor_share)
+                .saturating_sub(self.day_crank_reward_paid)
+                .min(unsettled);
+            (unsettled - creator_carried, creator_carried, 0)
+        };
+
+        Ok(Some(UnfinishedDay {
+            epoch_index: self.epoch_index,
+            pagination_cursor: self.pagination_cursor,
+            investor_carried,
+            creator_carried,
+            claim_carried,
+        }))
+    }
+
+    /// Carry what the current day left unsettled into the next day: unpaid investor pool
+    /// to the cap overflow carry, creator remainder to the creator carry and an unopened
+    /// claim to the next day's claim
+    pub fn carry_unfinished_day(&mut self) -> Result<Option<UnfinishedDay>> {
+        let Some(unfinished) = self.unfinished_day()? else {
+            return Ok(None);
+        };
+
+        self.add_cap_overflow(unfinished.investor_carried)?;
+        self.creator_carry = self.creator_carry
+            .checked_add(unfinished.creator_carried)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        self.carried_claim_quote = self.carried_claim_quote
+            .checked_add(unfinished.claim_carried)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        Ok(Some(unfinished))
+    }
+
+    /// Whether a day has opened and not yet closed
+    pub fn is_day_in_progress(&self) -> bool {
+        self.last_distribution_ts != 0 && !self.day_complete
+    }
+
+    /// Close a stuck day: carry what it left unsettled into the next day and mark it
+    /// complete, so no further pages run until the next day opens
+    pub fn abort_day(&mut self) -> Result<Option<UnfinishedDay>> {
+        require!(self.is_day_in_progress(), ErrorCode::NoDayInProgress);
+        let unfinished = self.carry_unfinished_day()?;
+        self.complete_day();
+        Ok(unfinished)
+    }
+
+    /// Take the claim carried from an unfinished day to add to a new day's claim
+    pub fn take_carried_claim_quote(&mut self) -> u64 {
+        std::mem::take(&mut self.carried_claim_quote)
+    }
+
+    /// Open the day's investor pool: the day's investor share plus cap overflow withheld
+    /// on previous days (and carried dust under CarryForward), capped once for the day
+    pub fn open_day(
+        &mut self,
+        investor_share: u64,
+        policy_config: &PolicyConfig,
+    ) -> Result<DayOpening> {
+        require!(
+            investor_share <= self.day_claimed_quote,
+            ErrorCode::AccountingInvariantViolated
+        );
+        self.day_investor_share = investor_share;
+
+        let cap_rollover = self.take_cap_overflow_carry();
+        let dust_rollover = if policy_config.dust_policy == DustPolicy::CarryForward {
+            self.take_carry_over_dust()
+        } else {
+            0
+        };
+        let investor_pool = investor_share
+            .checked_add(cap_rollover)
+            .and_then(|pool| pool.checked_add(dust_rollover))
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        let caps = CapPolicy::for_day(policy_config, self);
+        let (deferred, binding) = self.open_day_investor_pool(investor_pool, &caps)?;
+
+        Ok(DayOpening {
+            investor_pool,
+            cap_rollover,
+            dust_rollover,
+            deferred,
+            binding,
+        })
+    }
+
+    /// Forget the previous day's registry commitment
+    fn clear_day_registry(&mut self) {
+        self.day_investor_count = 0;
+        self.day_total_locked = 0;
+        self.day_registry_hash = [0; 32];
+        self.unpaged_registry_hash = [0; 32];
+        self.day_remainder_cutoff = RemainderCutoff::NONE.remainder;
+        self.day_remainder_ties = 0;
+        self.day_investor_allocated = 0;
+    }
+
+    /// Whether the day's investor registry has been committed
+    pub fn has_day_registry(&self) -> bool {
+        self.day_investor_count > 0
+    }
+
+    /// Commit the day's investor registry when its pool opens
+    ///
+    /// Stores the investor count, the total weight, a hash of the registry and where the
+    /// largest-remainder apportionment of `payable_pool` stops handing out lamports, so
+    /// later pages can carry only their own investors and still take exact shares.
+    pub fn commit_day_registry(&mut self, day_investors: &[InvestorData], payable_pool: u64) -> Result<()> {
+        require!(!day_investors.is_empty(), ErrorCode::InvestorRegistryMismatch);
+
+        let weights: Vec<u64> = day_investors.iter().map(|investor| investor.locked_amount).collect();
+        let total_locked = weights
+            .iter()
+            .try_fold(0u64, |total, &weight| total.checked_add(weight))
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        let cutoff = largest_remainder_cutoff(payable_pool, &weights)?;
+
+        self.clear_day_registry();
+        self.day_investor_count = u32::try_from(day_investors.len())
+            .map_err(|_| ErrorCode::ArithmeticOverflow)?;
+        self.day_total_locked = total_locked;
+        self.day_registry_hash = registry_hash(day_investors)?;
+        self.unpaged_registry_hash = self.day_registry_hash;
+        self.day_remainder_cutoff = cutoff.remainder;
+        self.day_remainder_ties = cutoff.ties;
+        Ok(())
+    }
+
+    /// Check a page's investors are the committed registry's entries at the cursor, before
+    /// anything is paid to them
+    ///
+    /// `next_registry_hash` is the registry hash of the entries after the page (all zero
+    /// when the page ends the registry): the page's entries chained onto it must give the
+    /// hash of the entries from the cursor on, which it then replaces.
+    pub fn record_registry_page(&mut self, page_investors: &[InvestorData], next_registry_hash: [u8; 32]) -> Result<()> {
+        let page_end = (self.pagination_cursor as usize)
+            .checked_add(page_investors.len())
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        require!(
+            self.has_day_registry()
+                && !page_investors.is_empty()
+                && page_end <= self.day_investor_count as usize
+                && (page_end < self.day_investor_count as usize || next_registry_hash == [0; 32]),
+            ErrorCode::InvestorRegistryMismatch
+        );
+        require!(
+            chain_registry_hash(next_registry_hash, page_investors)? == self.unpaged_registry_hash,
+            ErrorCode::InvestorRegistryMismatch
+        );
+
+        self.unpaged_registry_hash = next_registry_hash;
+        Ok(())
+    }
+
+    /// Shares of `payable_pool` for a page's investors, in registry order
+    ///
+    /// Each is the investor's largest-remainder share over the committed registry; the
+    /// shares never add up to more than the pool, even if a weight read on this page
+    /// grew since the registry was committed.
+    pub fn take_page_allocations(
+        &mut self,
+        page_investors: &[InvestorData],
+        payable_pool: u64,
+    ) -> Result<Vec<u64>> {
+        let mut cutoff = RemainderCutoff {
+            remainder: self.day_remainder_cutoff,
+            ties: self.day_remainder_ties,
+        };
+        let mut allocations = Vec::with_capacity(page_investors.len());
+        for investor in page_investors {
+            let share = cutoff
+                .take_share(payable_pool, investor.locked_amount, self.day_total_locked as u128)
+                .min(payable_pool.saturating_sub(self.day_investor_allocated));
+            self.day_investor_allocated = self.day_investor_allocated
+                .checked_add(share)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+            allocations.push(share);
+        }
+        self.day_remainder_ties = cutoff.ties;
+        Ok(allocations)
+    }
+
+    /// Check the day's pages covered exactly the committed registry, and defer any part
+    /// of `payable_pool` they did not apportion to the next day
+    /// Returns the deferred amount
+    pub fn finish_day_registry(&mut self, payable_pool: u64) -> Result<u64> {
+        require!(
+            self.has_day_registry()
+                && self.pagination_cursor == self.day_investor_count
+                && self.unpaged_registry_hash == [0; 32],
+            ErrorCode::InvestorRegistryMismatch
+        );
+
+        let unallocated = payable_pool.saturating_sub(self.day_investor_allocated);
+        self.add_cap_overflow(unallocated)?;
+        Ok(unallocated)
+    }
+
+    /// Settle a day whose pages covered the committed registry: defer what they left
+    /// unallocated of `payable_pool` and work out the creator payout
+    pub fn settle_day(&mut self, dust_policy: DustPolicy, payable_pool: u64) -> Result<DaySettlement> {
+        let unallocated = self.finish_day_registry(payable_pool)?;
+        let (creator_remainder, dust_sweep) = self.settle_creator_payout(dust_policy)?;
+        Ok(DaySettlement {
+            unallocated,
+            creator_remainder,
+            dust_sweep,
+        })
+    }
+
+    /// Close a settled day once the creator payout has left the treasury, checking the
+    /// treasury's `closing_balance` against what the day paid
+    pub fn close_day(&mut self, closing_balance: u64, creator_paid: u64) -> Result<()> {
+        self.verify_day_conservation(closing_balance, creator_paid)?;
+        self.complete_day();
+        Ok(())
+    }
+
+    /// Crank reward owed for the page of the current day that started at `page_start`
+    /// and ended at the cursor: the day's reward pro rata to the investors it processed,
+    /// limited by what the daily cap and the day's creator remainder have left
+    ///
+    /// The share is the day's reward earned through the page's end less that earned
+    /// before its start, so however the day is paged its pages add up to the day's reward.
+    pub fn crank_reward_due(&self, policy_config: &PolicyConfig, page_start: u32) -> Result<u64> {
+        let per_day = policy_config.crank_reward_per_day(self.day_claimed_quote);
+        if per_day == 0 || self.day_investor_count == 0 {
+            return Ok(0);
+        }
+
+        // u64 * u32 always fits in u128, and the result is at most per_day
+        let earned_through = |cursor: u32| {
+            let cursor = cursor.min(self.day_investor_count);
+            (per_day as u128 * cursor as u128 / self.day_investor_count as u128) as u64
+        };
+        let per_page = earned_through(self.pagination_cursor)
+            .checked_sub(earned_through(page_start))
+            .ok_or(ErrorCode::InvalidPaginationCursor)?;
+
+        let cap_left = policy_config.crank_reward_daily_cap_lamports
+            .saturating_sub(self.day_crank_reward_paid);
+        let creator_left = self.day_claimed_quote
+            .checked_sub(self.day_investor_share)
+            .and_then(|remainder| remainder.checked_sub(self.day_crank_reward_paid))
+            .ok_or(ErrorCode::AccountingInvariantViolated)?;
+
+        Ok(per_page.min(cap_left).min(creator_left))
+    }
+
+    /// Record a crank reward paid for the current day
+    pub fn record_crank_reward(&mut self, amount: u64) -> Result<()> {
+        self.day_crank_reward_paid = self.day_crank_reward_paid
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Creator payout at day close: the day's creator remainder less crank rewards (with
+    /// any creator carry from unfinished days) plus, under CreatorAtDayClose, the dust
+    /// still carried
+    /// Returns (creator_remainder, dust_sweep)
+    pub fn settle_creator_payout(&mut self, dust_policy: DustPolicy) -> Result<(u64, u64)> {
+        let creator_remainder = self.day_claimed_quote
+            .checked_sub(self.day_investor_share)
+            .and_then(|remainder| remainder.checked_sub(self.day_crank_reward_paid))
+            .ok_or(ErrorCode::AccountingInvariantViolated)?
+            .checked_add(std::mem::take(&mut self.creator_carry))
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        let dust_sweep = if dust_policy == DustPolicy::CreatorAtDayClose {
+            self.take_carry_over_dust()
+        } else {
+            0
+        };
+        Ok((creator_remainder, dust_sweep))
+    }
+
+    /// Keep a settled creator payout that could not be made (a buyback that cannot run) in
+    /// the treasury as creator carry, paid with a later day's creator payout
+    pub fn hold_creator_payout(&mut self, amount: u64) -> Result<()> {
+        self.creator_carry = self.creator_carry
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Amounts still owed from the treasury: carried dust, deferred cap overflow, investor
+    /// accruals and the creator carry
+    pub fn outstanding_liabilities(&self) -> Result<u64> {
+        self.carry_over_dust
+            .checked_add(self.cap_overflow_carry)
+            .and_then(|total| total.checked_add(self.investor_accrued_total))
+            .and_then(|total| total.checked_add(self.creator_carry))
+            .ok_or(ErrorCode::ArithmeticOverflow.into())
+    }
+
+    /// Check the day against the treasury's real balance at day close:
+    /// `opening balance + inflow - closing balance = investor paid + creator paid
+    /// + crank rewards + dust paid`, with the closing balance still covering what is owed
+    ///
+    /// `current_day_distributed` holds the investor payouts and dust paid on the day's pages.
+    pub fn verify_day_conservation(&self, closing_balance: u64, creator_paid: u64) -> Result<()> {
+        let sources = (self.day_opening_treasury as u128) + (self.day_treasury_inflow as u128);
+        let uses = (closing_balance as u128)
+            + (self.current_day_distributed as u128)
+            + (creator_paid as u128)
+            + (self.day_crank_reward_paid as u128);
+
+        if sources != uses {
+            msg!("Accounting invariant violated: opening={} inflow={} closing={} distributed={} creator={} crank={}",
+                 self.day_opening_treasury, self.day_treasury_inflow, closing_balance,
+                 self.current_day_distributed, creator_paid, self.day_crank_reward_paid);
+            return err!(ErrorCode::AccountingInvariantViolated);
+        }
+
+        // What the treasury keeps must cover everything still owed from it
+        require!(
+            closing_balance >= self.outstanding_liabilities()?,
+            ErrorCode::AccountingInvariantViolated
+        );
+
+        Ok(())
+    }
+
+    /// Track a change in the amounts held in investor accrual PDAs
+    pub fn update_investor_accrued(&mut self, accrued: u64, released: u64) -> Result<()> {
+        self.investor_accrued_total = self.investor_accrued_total
+            .checked_add(accrued)
+            .and_then(|total| total.checked_sub(released))
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Defer investor payouts withheld by the daily cap to the next day
+    pub fn add_cap_overflow(&mut self, amount: u64) -> Result<()> {
+        self.cap_overflow_carry = self.cap_overflow_carry
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Take the deferred cap overflow to add to a new day's investor pool
+    pub fn take_cap_overflow_carry(&mut self) -> u64 {
+        std::mem::take(&mut self.cap_overflow_carry)
+    }
+
+    /// Fix the day's investor pool and the part of it payable under the caps at day start
+    /// The part of the pool the caps withhold is deferred to the next day; returns it
+    /// together with the binding cap
+    pub fn open_day_investor_pool(
+        &mut self,
+        day_investor_pool: u64,
+        caps: &CapPolicy,
+    ) -> Result<(u64, CapConstraint)> {
+        let evaluation = evaluate_cap_policy(self, caps, day_investor_pool)?;
+
+        self.day_investor_pool = day_investor_pool;
+        self.day_payable_pool = evaluation.allowed.min(day_investor_pool);
+
+        let deferred = day_investor_pool
+            .checked_sub(self.day_payable_pool)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        self.add_cap_overflow(deferred)?;
+
+        Ok((deferred, evaluation.binding))
+    }
+
+    /// Portion of the day's investor pool payable under the daily cap
+    pub fn payable_investor_pool(&self) -> Result<u64> {
+        Ok(self.day_payable_pool)
+    }
+
+    /// Get the PDA seeds for this distribution progress (for signing; primary quote mint ledger)
     pub fn get_signer_seeds(&self) -> [&[u8]; 3] {
         [PROGRESS_SEED, self.vault.as_ref(), std::slice::from_ref(&self.bump)]
     }
@@ -239,12 +1058,12 @@ impl DistributionProgress {
     }
 
     /// Get time remaining until next distribution window
-    pub fn time_until_next_distribution(&self, current_timestamp: i64) -> i64 {
+    pub fn time_until_next_distribution(&self, current_timestamp: i64, period_secs: i64) -> i64 {
         if self.last_distribution_ts == 0 {
             return 0; // Can distribute immediately
         }
         
-        let next_allowed = self.last_distribution_ts + TWENTY_FOUR_HOURS;
+        let next_allowed = self.last_distribution_ts + period_secs;
         if current_timestamp >= next_allowed {
             0 // Can distribute now
         } else {
@@ -253,17 +1072,22 @@ impl DistributionProgress {
     }
 
     /// Check if distribution can be started or continued
-    pub fn can_distribute(&self, current_timestamp: i64) -> Result<DistributionTimingState> {
-        self.validate_distribution_timing(current_timestamp)
+    pub fn can_distribute(&self, current_timestamp: i64, period_secs: i64) -> Result<DistributionTimingState> {
+        self.validate_distribution_timing(current_timestamp, period_secs)
     }
 
     /// Prepare for distribution operation with timing validation
-    pub fn prepare_for_distribution(&mut self, current_timestamp: i64) -> Result<DistributionTimingState> {
-        let timing_state = self.validate_distribution_timing(current_timestamp)?;
+    pub fn prepare_for_distribution(
+        &mut self,
+        current_timestamp: i64,
+        period_secs: i64,
+        epoch_anchor_ts: Option<i64>,
+    ) -> Result<DistributionTimingState> {
+        let timing_state = self.validate_distribution_timing(current_timestamp, period_secs)?;
         
         match timing_state {
             DistributionTimingState::NewDay => {
-                self.start_new_day(current_timestamp)?;
+                self.start_new_day(current_timestamp, period_secs, epoch_anchor_ts)?;
             },
             DistributionTimingState::ContinueSameDay => {
                 // No state changes needed for continuation
@@ -288,24 +1112,60 @@ impl DistributionProgress {
     }
 
     /// Check if we're at the start of a new distribution period
-    pub fn is_new_distribution_period(&self, current_timestamp: i64) -> bool {
-        matches!(self.validate_distribution_timing(current_timestamp), Ok(DistributionTimingState::NewDay))
+    pub fn is_new_distribution_period(&self, current_timestamp: i64, period_secs: i64) -> bool {
+        matches!(self.validate_distribution_timing(current_timestamp, period_secs), Ok(DistributionTimingState::NewDay))
     }
 
     /// Get distribution period info for debugging/monitoring
-    pub fn get_distribution_period_info(&self, current_timestamp: i64) -> DistributionPeriodInfo {
+    pub fn get_distribution_period_info(&self, current_timestamp: i64, period_secs: i64) -> DistributionPeriodInfo {
         DistributionPeriodInfo {
             last_distribution_ts: self.last_distribution_ts,
             current_timestamp,
-            time_until_next: self.time_until_next_distribution(current_timestamp),
-            can_start_new_day: self.can_start_new_day(current_timestamp),
-            is_same_day: self.is_same_day(current_timestamp),
+            time_until_next: self.time_until_next_distribution(current_timestamp, period_secs),
+            can_start_new_day: self.can_start_new_day(current_timestamp, period_secs),
+            is_same_day: self.is_same_day(current_timestamp, period_secs),
             day_complete: self.day_complete,
             pagination_cursor: self.pagination_cursor,
         }
     }
 }
 
+/// Registry hash of investors in wallet order: the hash chain over their entries, ending
+/// in all zeros
+///
+/// A registry's hash is that of its first page chained onto the hash of the rest, so a
+/// page can be checked alone given the hash of the entries after it.
+pub fn registry_hash(investors: &[InvestorData]) -> Result<[u8; 32]> {
+    chain_registry_hash([0; 32], investors)
+}
+
+/// Chain investors in wallet order onto the registry hash of the entries after them
+///
+/// Each entry hashes the wallet with its weight accounts (sorted), so a page cannot
+/// split, drop or repeat an investor's accounts without the chain diverging.
+fn chain_registry_hash(next_hash: [u8; 32], investors: &[InvestorData]) -> Result<[u8; 32]> {
+    require!(
+        investors.windows(2).all(|pair| pair[0].wallet < pair[1].wallet),
+        ErrorCode::InvestorRegistryMismatch
+    );
+
+    let mut hash = next_hash;
+    for investor in investors.iter().rev() {
+        let mut accounts = investor.stream_accounts.clone();
+        accounts.sort_unstable();
+        require!(
+            accounts.windows(2).all(|pair| pair[0] != pair[1]),
+            ErrorCode::InvestorRegistryMismatch
+        );
+
+        let mut entry: Vec<&[u8]> = vec![investor.wallet.as_ref()];
+        entry.extend(accounts.iter().map(|account| account.as_ref()));
+        entry.push(&hash);
+        hash = hashv(&entry).to_bytes();
+    }
+    Ok(hash)
+}
+
 #[cfg(test)]
 mod timing_tests {
     use super::*;
@@ -314,12 +1174,8 @@ mod timing_tests {
     fn create_test_progress() -> DistributionProgress {
         DistributionProgress {
             vault: Pubkey::new_unique(),
-            last_distribution_ts: 0,
-            current_day_distributed: 0,
-            carry_over_dust: 0,
-            pagination_cursor: 0,
-            day_complete: false,
             bump: 255,
+            ..DistributionProgress::default()
         }
     }
 
@@ -328,41 +1184,41 @@ mod timing_tests {
         let mut progress = create_test_progress();
         
         // First distribution should be allowed
-        assert!(progress.can_start_new_day(1000));
+        assert!(progress.can_start_new_day(1000, TWENTY_FOUR_HOURS));
         
         // Set initial distribution time
-        progress.start_new_day(1000).unwrap();
+        progress.start_new_day(1000, TWENTY_FOUR_HOURS, None).unwrap();
         assert_eq!(progress.last_distribution_ts, 1000);
         
         // Should not allow distribution before 24 hours
-        assert!(!progress.can_start_new_day(1000 + TWENTY_FOUR_HOURS - 1));
+        assert!(!progress.can_start_new_day(1000 + TWENTY_FOUR_HOURS - 1, TWENTY_FOUR_HOURS));
         
         // Should allow distribution after exactly 24 hours
-        assert!(progress.can_start_new_day(1000 + TWENTY_FOUR_HOURS));
+        assert!(progress.can_start_new_day(1000 + TWENTY_FOUR_HOURS, TWENTY_FOUR_HOURS));
         
         // Should allow distribution after more than 24 hours
-        assert!(progress.can_start_new_day(1000 + TWENTY_FOUR_HOURS + 3600));
+        assert!(progress.can_start_new_day(1000 + TWENTY_FOUR_HOURS + 3600, TWENTY_FOUR_HOURS));
     }
 
     #[test]
     fn test_day_boundary_detection() {
         let mut progress = create_test_progress();
-        progress.start_new_day(1000).unwrap();
+        progress.start_new_day(1000, TWENTY_FOUR_HOURS, None).unwrap();
         
         // Same day detection
-        assert!(progress.is_same_day(1000));
-        assert!(progress.is_same_day(1000 + TWENTY_FOUR_HOURS - 1));
-        assert!(!progress.is_same_day(1000 + TWENTY_FOUR_HOURS));
+        assert!(progress.is_same_day(1000, TWENTY_FOUR_HOURS));
+        assert!(progress.is_same_day(1000 + TWENTY_FOUR_HOURS - 1, TWENTY_FOUR_HOURS));
+        assert!(!progress.is_same_day(1000 + TWENTY_FOUR_HOURS, TWENTY_FOUR_HOURS));
         
         // Day boundary calculation
-        assert_eq!(progress.get_day_boundary(), 1000 + TWENTY_FOUR_HOURS);
+        assert_eq!(progress.get_day_boundary(TWENTY_FOUR_HOURS), 1000 + TWENTY_FOUR_HOURS);
         
         // Same day continuation
-        assert!(progress.can_continue_same_day(1000 + 3600)); // 1 hour later
+        assert!(progress.can_continue_same_day(1000 + 3600, TWENTY_FOUR_HOURS)); // 1 hour later
         
         // Mark day complete and test continuation
         progress.complete_day();
-        assert!(!progress.can_continue_same_day(1000 + 3600));
+        assert!(!progress.can_continue_same_day(1000 + 3600, TWENTY_FOUR_HOURS));
     }
 
     #[test]
@@ -370,25 +1226,25 @@ mod timing_tests {
         let mut progress = create_test_progress();
         
         // First distribution
-        let result = progress.validate_distribution_timing(1000).unwrap();
+        let result = progress.validate_distribution_timing(1000, TWENTY_FOUR_HOURS).unwrap();
         assert_eq!(result, DistributionTimingState::NewDay);
         
         // Start the day
-        progress.start_new_day(1000).unwrap();
+        progress.start_new_day(1000, TWENTY_FOUR_HOURS, None).unwrap();
         
         // Same day continuation
-        let result = progress.validate_distribution_timing(1000 + 3600).unwrap();
+        let result = progress.validate_distribution_timing(1000 + 3600, TWENTY_FOUR_HOURS).unwrap();
         assert_eq!(result, DistributionTimingState::ContinueSameDay);
         
         // Complete the day
         progress.complete_day();
         
         // Should fail if trying to continue completed day
-        let result = progress.validate_distribution_timing(1000 + 3600);
+        let result = progress.validate_distribution_timing(1000 + 3600, TWENTY_FOUR_HOURS);
         assert!(result.is_err());
         
         // Should allow new day after 24 hours
-        let result = progress.validate_distribution_timing(1000 + TWENTY_FOUR_HOURS).unwrap();
+        let result = progress.validate_distribution_timing(1000 + TWENTY_FOUR_HOURS, TWENTY_FOUR_HOURS).unwrap();
         assert_eq!(result, DistributionTimingState::NewDay);
     }
 
@@ -460,16 +1316,16 @@ mod timing_tests {
         let mut progress = create_test_progress();
         
         // No previous distribution
-        assert_eq!(progress.time_until_next_distribution(1000), 0);
+        assert_eq!(progress.time_until_next_distribution(1000, TWENTY_FOUR_HOURS), 0);
         
         // Set distribution time
-        progress.start_new_day(1000).unwrap();
+        progress.start_new_day(1000, TWENTY_FOUR_HOURS, None).unwrap();
         
         // Check time remaining
-        assert_eq!(progress.time_until_next_distribution(1000 + 3600), TWENTY_FOUR_HOURS - 3600);
-        assert_eq!(progress.time_until_next_distribution(1000 + TWENTY_FOUR_HOURS - 1), 1);
-        assert_eq!(progress.time_until_next_distribution(1000 + TWENTY_FOUR_HOURS), 0);
-        assert_eq!(progress.time_until_next_distribution(1000 + TWENTY_FOUR_HOURS + 100), 0);
+        assert_eq!(progress.time_until_next_distribution(1000 + 3600, TWENTY_FOUR_HOURS), TWENTY_FOUR_HOURS - 3600);
+        assert_eq!(progress.time_until_next_distribution(1000 + TWENTY_FOUR_HOURS - 1, TWENTY_FOUR_HOURS), 1);
+        assert_eq!(progress.time_until_next_distribution(1000 + TWENTY_FOUR_HOURS, TWENTY_FOUR_HOURS), 0);
+        assert_eq!(progress.time_until_next_distribution(1000 + TWENTY_FOUR_HOURS + 100, TWENTY_FOUR_HOURS), 0);
     }
 
     #[test]
@@ -477,25 +1333,265 @@ mod timing_tests {
         let mut progress = create_test_progress();
         
         // First distribution
-        let state = progress.prepare_for_distribution(1000).unwrap();
+        let state = progress.prepare_for_distribution(1000, TWENTY_FOUR_HOURS, None).unwrap();
         assert_eq!(state, DistributionTimingState::NewDay);
         assert_eq!(progress.last_distribution_ts, 1000);
         assert_eq!(progress.pagination_cursor, 0);
         assert!(!progress.day_complete);
         
         // Same day continuation
-        let state = progress.prepare_for_distribution(1000 + 3600).unwrap();
+        let state = progress.prepare_for_distribution(1000 + 3600, TWENTY_FOUR_HOURS, None).unwrap();
         assert_eq!(state, DistributionTimingState::ContinueSameDay);
         assert_eq!(progress.last_distribution_ts, 1000); // Unchanged
     }
 
+    #[test]
+    fn test_weight_window_tracks_previous_day_start() {
+        let mut progress = create_test_progress();
+
+        // First day has no history, so weights are sampled at day start
+        progress.start_new_day(1000, TWENTY_FOUR_HOURS, None).unwrap();
+        assert_eq!(progress.weight_window(), WeightWindow { start_ts: 1000, end_ts: 1000 });
+
+        // Later days average over the time since the previous day started
+        let next_day = 1000 + TWENTY_FOUR_HOURS + 600;
+        progress.start_new_day(next_day, TWENTY_FOUR_HOURS, None).unwrap();
+        assert_eq!(progress.weight_window(), WeightWindow { start_ts: 1000, end_ts: next_day });
+
+        // Continuation pages keep the same window
+        progress.prepare_for_distribution(next_day + 3600, TWENTY_FOUR_HOURS, None).unwrap();
+        assert_eq!(progress.weight_window(), WeightWindow { start_ts: 1000, end_ts: next_day });
+    }
+
+    #[test]
+    fn test_cap_overflow_carry() {
+        let mut progress = create_test_progress();
+
+        progress.add_cap_overflow(400).unwrap();
+        progress.add_cap_overflow(100).unwrap();
+        assert_eq!(progress.cap_overflow_carry, 500);
+
+        // Starting a new day leaves the carry for the investor pool to pick up
+        progress.start_new_day(1000, TWENTY_FOUR_HOURS, None).unwrap();
+        assert_eq!(progress.cap_overflow_carry, 500);
+
+        assert_eq!(progress.take_cap_overflow_carry(), 500);
+        assert_eq!(progress.cap_overflow_carry, 0);
+        assert_eq!(progress.take_cap_overflow_carry(), 0);
+
+        progress.cap_overflow_carry = u64::MAX;
+        assert!(progress.add_cap_overflow(1).is_err());
+    }
+
+    #[test]
+    fn test_converted_base_quote_waits_for_next_day() {
+        let mut progress = create_test_progress();
+        progress.start_new_day(1000, TWENTY_FOUR_HOURS, None).unwrap();
+
+        // Conversions during the day accumulate without touching the day's claim
+        progress.credit_converted_base_quote(700).unwrap();
+        progress.credit_converted_base_quote(300).unwrap();
+        assert_eq!(progress.converted_base_quote, 1_000);
+        assert_eq!(progress.day_claimed_quote, 0);
+
+        // The next day takes it once as part of its claim
+        progress.start_new_day(1000 + TWENTY_FOUR_HOURS, TWENTY_FOUR_HOURS, None).unwrap();
+        let converted = progress.take_converted_base_quote();
+        progress.record_day_claim(5_000 + converted).unwrap();
+        assert_eq!(progress.day_claimed_quote, 6_000);
+        assert_eq!(progress.take_converted_base_quote(), 0);
+
+        progress.converted_base_quote = u64::MAX;
+        assert!(progress.credit_converted_base_quote(1).is_err());
+    }
+
+    #[test]
+    fn test_open_day_investor_pool() {
+        let mut progress = create_test_progress();
+        progress.start_new_day(1000, TWENTY_FOUR_HOURS, None).unwrap();
+
+        // Uncapped pool is fully payable
+        let (deferred, binding) = progress.open_day_investor_pool(10_000, &CapPolicy::default()).unwrap();
+        assert_eq!(deferred, 0);
+        assert_eq!(binding, CapConstraint::Unconstrained);
+        assert_eq!(progress.payable_investor_pool().unwrap(), 10_000);
+
+        // The cap fixes the whole day's payable pool once and defers the rest
+        progress.start_new_day(1000 + TWENTY_FOUR_HOURS, TWENTY_FOUR_HOURS, None).unwrap();
+        let caps = CapPolicy { daily_cap: Some(4_000), ..CapPolicy::default() };
+        let (deferred, binding) = progress.open_day_investor_pool(10_000, &caps).unwrap();
+        assert_eq!(deferred, 6_000);
+        assert_eq!(binding, CapConstraint::DailyCap);
+        assert_eq!(progress.payable_investor_pool().unwrap(), 4_000);
+        assert_eq!(progress.cap_overflow_carry, 6_000);
+
+        // Continuation pages see the same payable pool; a new day resets it
+        progress.prepare_for_distribution(1000 + TWENTY_FOUR_HOURS + 60, TWENTY_FOUR_HOURS, None).unwrap();
+        assert_eq!(progress.payable_investor_pool().unwrap(), 4_000);
+        progress.start_new_day(1000 + 2 * TWENTY_FOUR_HOURS, TWENTY_FOUR_HOURS, None).unwrap();
+        assert_eq!(progress.payable_investor_pool().unwrap(), 0);
+
+        // A pool over a million times the cap still pays the cap exactly
+        let caps = CapPolicy { daily_cap: Some(7), ..CapPolicy::default() };
+        let (deferred, _) = progress.open_day_investor_pool(10_000_000_000, &caps).unwrap();
+        assert_eq!(progress.payable_investor_pool().unwrap(), 7);
+        assert_eq!(deferred, 10_000_000_000 - 7);
+    }
+
+    #[test]
+    fn test_unfinished_day_is_carried() {
+        let mut progress = create_test_progress();
+        progress.start_new_day(1000, TWENTY_FOUR_HOURS, None).unwrap();
+        assert_eq!(progress.unfinished_day().unwrap(), None);
+
+        // The crank stops after the first pages of a day with a 6_000 investor share
+        progress.record_day_claim(10_000).unwrap();
+        progress.day_investor_share = 6_000;
+        progress.open_day_investor_pool(6_000, &CapPolicy::default()).unwrap();
+        progress.advance_cursor(40).unwrap();
+        progress.add_distributed(2_500).unwrap();
+
+        // The next day carries the unpaid pool and the creator remainder
+        progress.start_new_day(1000 + TWENTY_FOUR_HOURS, TWENTY_FOUR_HOURS, None).unwrap();
+        assert_eq!(progress.cap_overflow_carry, 3_500);
+        assert_eq!(progress.creator_carry, 4_000);
+        assert_eq!(progress.day_opening_liabilities, 7_500);
+
+        // The creator carry is paid with the next creator payout
+        let (creator_remainder, _) = progress.settle_creator_payout(DustPolicy::CarryForward).unwrap();
+        assert_eq!(creator_remainder, 4_000);
+        assert_eq!(progress.creator_carry, 0);
+    }
+
+    #[test]
+    fn test_close_day_checks_treasury_balance() {
+        let mut progress = create_test_progress();
+        progress.start_new_day(1000, TWENTY_FOUR_HOURS, None).unwrap();
+
+        // The treasury held 700 of dust owed from earlier days and claimed 10_000
+        progress.carry_over_dust = 700;
+        progress.record_treasury_opening(700);
+        progress.record_treasury_inflow(10_000).unwrap();
+        progress.record_day_claim(10_000).unwrap();
+        progress.day_investor_share = 6_000;
+        progress.add_distributed(6_000).unwrap();
+        progress.record_crank_reward(300).unwrap();
+
+        // A lamport missing from the treasury, or left in it unaccounted, breaks the identity
+        assert_eq!(
+            progress.verify_day_conservation(699, 3_700).unwrap_err(),
+            ErrorCode::AccountingInvariantViolated.into()
+        );
+        assert!(progress.verify_day_conservation(701, 3_700).is_err());
+
+        // A treasury that no longer covers the carried dust fails even when the flows match
+        assert!(progress.verify_day_conservation(600, 3_800).is_err());
+
+        progress.close_day(700, 3_700).unwrap();
+        assert!(progress.day_complete);
+        assert!(!progress.is_day_in_progress());
+
+        // The next day takes a fresh snapshot
+        progress.start_new_day(1000 + TWENTY_FOUR_HOURS, TWENTY_FOUR_HOURS, None).unwrap();
+        assert_eq!(progress.day_treasury_inflow, 0);
+    }
+
+    #[test]
+    fn test_abort_day() {
+        let mut progress = create_test_progress();
+        assert_eq!(progress.abort_day().unwrap_err(), ErrorCode::NoDayInProgress.into());
+
+        // A day whose pool never opened carries its whole claim
+        progress.start_new_day(1000, TWENTY_FOUR_HOURS, None).unwrap();
+        progress.record_day_claim(5_000).unwrap();
+        let unfinished = progress.abort_day().unwrap().unwrap();
+        assert_eq!(unfinished.claim_carried, 5_000);
+        assert_eq!(unfinished.investor_carried + unfinished.creator_carried, 0);
+        assert!(progress.day_complete);
+
+        // The aborted day takes no more pages and is not carried twice
+        assert_eq!(progress.abort_day().unwrap_err(), ErrorCode::NoDayInProgress.into());
+        assert!(progress.prepare_for_distribution(1000 + 60, TWENTY_FOUR_HOURS, None).is_err());
+        progress.start_new_day(1000 + TWENTY_FOUR_HOURS, TWENTY_FOUR_HOURS, None).unwrap();
+        assert_eq!(progress.take_carried_claim_quote(), 5_000);
+        assert_eq!(progress.day_opening_liabilities, 0);
+    }
+
+    #[test]
+    fn test_rolling_window_ring_buffer() {
+        let mut progress = create_test_progress();
+        progress.start_new_day(1000, TWENTY_FOUR_HOURS, None).unwrap();
+        progress.add_distributed(100).unwrap();
+
+        // One slot per day; each day adds to the window
+        for day in 1..ROLLING_CAP_SLOTS as i64 {
+            progress.start_new_day(1000 + day * TWENTY_FOUR_HOURS, TWENTY_FOUR_HOURS, None).unwrap();
+            progress.add_distributed(100).unwrap();
+        }
+        assert_eq!(progress.rolling_window_distributed().unwrap(), 700);
+
+        // The eighth day evicts the first
+        progress.start_new_day(1000 + 7 * TWENTY_FOUR_HOURS, TWENTY_FOUR_HOURS, None).unwrap();
+        assert_eq!(progress.rolling_window_distributed().unwrap(), 600);
+        assert_eq!(progress.current_day_distributed, 0);
+
+        // Missed days are cleared as well
+        progress.start_new_day(1000 + 10 * TWENTY_FOUR_HOURS, TWENTY_FOUR_HOURS, None).unwrap();
+        assert_eq!(progress.rolling_window_distributed().unwrap(), 300);
+
+        // A full window of missed days clears everything
+        progress.start_new_day(1000 + 30 * TWENTY_FOUR_HOURS, TWENTY_FOUR_HOURS, None).unwrap();
+        assert_eq!(progress.rolling_window_distributed().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_rolling_window_spans_seven_days() {
+        // A 3.5-day period splits the window into two slots
+        let period = 7 * TWENTY_FOUR_HOURS / 2;
+        assert_eq!(DistributionProgress::rolling_window_periods(period), Some(2));
+        assert_eq!(DistributionProgress::rolling_window_periods(TWENTY_FOUR_HOURS), Some(7));
+        assert_eq!(DistributionProgress::rolling_window_periods(3600), None);
+        assert_eq!(DistributionProgress::rolling_window_periods(2 * TWENTY_FOUR_HOURS), None);
+
+        let mut progress = create_test_progress();
+        progress.start_new_day(1000, period, None).unwrap();
+        progress.add_distributed(100).unwrap();
+        progress.start_new_day(1000 + period, period, None).unwrap();
+        progress.add_distributed(200).unwrap();
+        assert_eq!(progress.rolling_window_distributed().unwrap(), 300);
+
+        // The third period falls 7 days after the first, which leaves the window
+        progress.start_new_day(1000 + 2 * period, period, None).unwrap();
+        assert_eq!(progress.rolling_window_distributed().unwrap(), 200);
+        progress.add_distributed(50).unwrap();
+
+        // A missed period clears the whole window
+        progress.start_new_day(1000 + 4 * period, period, None).unwrap();
+        assert_eq!(progress.rolling_window_distributed().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_rolling_cap_binds_day_pool() {
+        let mut progress = create_test_progress();
+        progress.start_new_day(1000, TWENTY_FOUR_HOURS, None).unwrap();
+        progress.add_distributed(9_000).unwrap();
+
+        // 9_000 of a 10_000 rolling cap was used yesterday, so today's 5_000 daily cap is not binding
+        progress.start_new_day(1000 + TWENTY_FOUR_HOURS, TWENTY_FOUR_HOURS, None).unwrap();
+        let caps = CapPolicy { daily_cap: Some(5_000), rolling_cap: Some(10_000), per_investor_cap: None };
+        let (deferred, binding) = progress.open_day_investor_pool(4_000, &caps).unwrap();
+        assert_eq!(binding, CapConstraint::RollingWindowCap);
+        assert_eq!(deferred, 3_000);
+        assert_eq!(progress.payable_investor_pool().unwrap(), 1_000);
+    }
+
     #[test]
     fn test_distribution_period_info() {
         let mut progress = create_test_progress();
-        progress.start_new_day(1000).unwrap();
+        progress.start_new_day(1000, TWENTY_FOUR_HOURS, None).unwrap();
         progress.pagination_cursor = 15;
         
-        let info = progress.get_distribution_period_info(1000 + 3600);
+        let info = progress.get_distribution_period_info(1000 + 3600, TWENTY_FOUR_HOURS);
         
         assert_eq!(info.last_distribution_ts, 1000);
         assert_eq!(info.current_timestamp, 1000 + 3600);
@@ -511,19 +1607,19 @@ mod timing_tests {
         let mut progress = create_test_progress();
         
         // Test exact boundary conditions
-        progress.start_new_day(1000).unwrap();
+        progress.start_new_day(1000, TWENTY_FOUR_HOURS, None).unwrap();
         
         // One second before 24 hours
-        assert!(progress.is_same_day(1000 + TWENTY_FOUR_HOURS - 1));
-        assert!(!progress.can_start_new_day(1000 + TWENTY_FOUR_HOURS - 1));
+        assert!(progress.is_same_day(1000 + TWENTY_FOUR_HOURS - 1, TWENTY_FOUR_HOURS));
+        assert!(!progress.can_start_new_day(1000 + TWENTY_FOUR_HOURS - 1, TWENTY_FOUR_HOURS));
         
         // Exactly 24 hours
-        assert!(!progress.is_same_day(1000 + TWENTY_FOUR_HOURS));
-        assert!(progress.can_start_new_day(1000 + TWENTY_FOUR_HOURS));
+        assert!(!progress.is_same_day(1000 + TWENTY_FOUR_HOURS, TWENTY_FOUR_HOURS));
+        assert!(progress.can_start_new_day(1000 + TWENTY_FOUR_HOURS, TWENTY_FOUR_HOURS));
         
         // One second after 24 hours
-        assert!(!progress.is_same_day(1000 + TWENTY_FOUR_HOURS + 1));
-        assert!(progress.can_start_new_day(1000 + TWENTY_FOUR_HOURS + 1));
+        assert!(!progress.is_same_day(1000 + TWENTY_FOUR_HOURS + 1, TWENTY_FOUR_HOURS));
+        assert!(progress.can_start_new_day(1000 + TWENTY_FOUR_HOURS + 1, TWENTY_FOUR_HOURS));
     }
 
     #[test]
@@ -545,27 +1641,139 @@ mod timing_tests {
         let mut progress = create_test_progress();
         
         // Initially should be new period
-        assert!(progress.is_new_distribution_period(1000));
+        assert!(progress.is_new_distribution_period(1000, TWENTY_FOUR_HOURS));
         
         // After starting, same day should not be new period
-        progress.start_new_day(1000).unwrap();
-        assert!(!progress.is_new_distribution_period(1000 + 3600));
+        progress.start_new_day(1000, TWENTY_FOUR_HOURS, None).unwrap();
+        assert!(!progress.is_new_distribution_period(1000 + 3600, TWENTY_FOUR_HOURS));
         
         // After 24 hours should be new period
-        assert!(progress.is_new_distribution_period(1000 + TWENTY_FOUR_HOURS));
+        assert!(progress.is_new_distribution_period(1000 + TWENTY_FOUR_HOURS, TWENTY_FOUR_HOURS));
     }
 
     #[test]
     fn test_cooldown_error_conditions() {
         let mut progress = create_test_progress();
-        progress.start_new_day(1000).unwrap();
+        progress.start_new_day(1000, TWENTY_FOUR_HOURS, None).unwrap();
         
         // Should fail to start new day before cooldown
-        let result = progress.start_new_day(1000 + TWENTY_FOUR_HOURS - 1);
+        let result = progress.start_new_day(1000 + TWENTY_FOUR_HOURS - 1, TWENTY_FOUR_HOURS, None);
         assert!(result.is_err());
         
         // Should succeed after cooldown
-        let result = progress.start_new_day(1000 + TWENTY_FOUR_HOURS);
+        let result = progress.start_new_day(1000 + TWENTY_FOUR_HOURS, TWENTY_FOUR_HOURS, None);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_configured_distribution_period() {
+        const HOUR: i64 = 3600;
+        let mut progress = create_test_progress();
+        progress.start_new_day(1000, HOUR, None).unwrap();
+
+        // An hourly period closes the day after an hour
+        assert!(progress.is_same_day(1000 + HOUR - 1, HOUR));
+        assert!(!progress.can_start_new_day(1000 + HOUR - 1, HOUR));
+        assert_eq!(progress.time_until_next_distribution(1000 + 600, HOUR), HOUR - 600);
+        assert_eq!(progress.get_day_boundary(HOUR), 1000 + HOUR);
+        assert!(progress.start_new_day(1000 + HOUR - 1, HOUR, None).is_err());
+        assert_eq!(progress.prepare_for_distribution(1000 + HOUR, HOUR, None).unwrap(), DistributionTimingState::NewDay);
+
+        // A weekly period keeps the same day open for seven days
+        let week = 7 * TWENTY_FOUR_HOURS;
+        assert!(progress.is_same_day(1000 + HOUR + 3 * TWENTY_FOUR_HOURS, week));
+        assert!(!progress.can_start_new_day(1000 + HOUR + week - 1, week));
+        assert!(progress.can_start_new_day(1000 + HOUR + week, week));
+
+        // Missed periods are measured in the configured unit
+        progress.start_new_day(1000 + HOUR + 3 * HOUR, HOUR, None).unwrap();
+        assert_eq!(progress.rolling_day_index, 4);
+    }
+
+    #[test]
+    fn test_epoch_aligned_days() {
+        const ANCHOR: i64 = 10_000;
+        let mut progress = create_test_progress();
+
+        // Days cannot open before epoch 0
+        assert!(progress.start_new_day(ANCHOR - 1, TWENTY_FOUR_HOURS, Some(ANCHOR)).is_err());
+
+        // The first day snaps to the start of its epoch
+        progress.start_new_day(ANCHOR + 3 * TWENTY_FOUR_HOURS + 500, TWENTY_FOUR_HOURS, Some(ANCHOR)).unwrap();
+        assert_eq!(progress.epoch_index, 3);
+        assert_eq!(progress.last_distribution_ts, ANCHOR + 3 * TWENTY_FOUR_HOURS);
+        assert_eq!(progress.missed_epochs, 0);
+
+        // A late crank does not shift the next day's boundary
+        let late = ANCHOR + 4 * TWENTY_FOUR_HOURS + 7_000;
+        assert_eq!(progress.prepare_for_distribution(late, TWENTY_FOUR_HOURS, Some(ANCHOR)).unwrap(), DistributionTimingState::NewDay);
+        assert_eq!(progress.epoch_index, 4);
+        assert_eq!(progress.last_distribution_ts, ANCHOR + 4 * TWENTY_FOUR_HOURS);
+        assert_eq!(progress.missed_epochs, 0);
+        assert_eq!(progress.time_until_next_distribution(late, TWENTY_FOUR_HOURS), TWENTY_FOUR_HOURS - 7_000);
+        assert_eq!(progress.weight_window().start_ts, ANCHOR + 3 * TWENTY_FOUR_HOURS);
+
+        // Skipped epochs are counted and their rolling slots cleared
+        progress.start_new_day(ANCHOR + 7 * TWENTY_FOUR_HOURS + 10, TWENTY_FOUR_HOURS, Some(ANCHOR)).unwrap();
+        assert_eq!(progress.epoch_index, 7);
+        assert_eq!(progress.missed_epochs, 2);
+        assert_eq!(progress.rolling_day_index, 4);
+
+        // Rolling days count periods from the first day
+        let mut rolling = create_test_progress();
+        rolling.start_new_day(1000, TWENTY_FOUR_HOURS, None).unwrap();
+        rolling.start_new_day(1000 + 3 * TWENTY_FOUR_HOURS + 5, TWENTY_FOUR_HOURS, None).unwrap();
+        assert_eq!(rolling.epoch_index, 3);
+        assert_eq!(rolling.missed_epochs, 2);
+        assert_eq!(rolling.last_distribution_ts, 1000 + 3 * TWENTY_FOUR_HOURS + 5);
+    }
+
+    #[test]
+    fn test_migrate_unversioned_progress() {
+        /// Day ledger layout written before the account was versioned
+        #[derive(AnchorSerialize)]
+        struct LegacyDistributionProgress {
+            vault: Pubkey,
+            last_distribution_ts: i64,
+            current_day_distributed: u64,
+            carry_over_dust: u64,
+            pagination_cursor: u32,
+            day_complete: bool,
+            bump: u8,
+        }
+
+        let encode = |legacy: &LegacyDistributionProgress| {
+            let mut data = DistributionProgress::DISCRIMINATOR.to_vec();
+            legacy.serialize(&mut data).unwrap();
+            data.resize(8 + DistributionProgress::INIT_SPACE, 0);
+            DistributionProgress::try_deserialize(&mut &data[..]).unwrap()
+        };
+
+        let quote_mint = Pubkey::new_unique();
+        let mut legacy = LegacyDistributionProgress {
+            vault: Pubkey::new_unique(),
+            last_distribution_ts: 1000,
+            current_day_distributed: 40_000,
+            carry_over_dust: 17,
+            pagination_cursor: 12,
+            day_complete: true,
+            bump: 253,
+        };
+
+        let mut progress = encode(&legacy);
+        assert_eq!(progress.layout_version, 0);
+        progress.migrate_layout(quote_mint).unwrap();
+        assert_eq!(progress.layout_version, ACCOUNT_LAYOUT_VERSION);
+        assert_eq!(progress.vault, legacy.vault);
+        assert_eq!(progress.carry_over_dust, 17);
+        assert_eq!(progress.quote_mint, quote_mint);
+        assert_eq!(progress.day_payable_pool, 0);
+        assert_eq!(progress.weight_window().start_ts, 1000);
+        assert!(progress.can_start_new_day(1000 + TWENTY_FOUR_HOURS, TWENTY_FOUR_HOURS));
+        assert!(progress.migrate_layout(quote_mint).is_err());
+
+        // A ledger in the middle of a day stays on the old layout
+        legacy.day_complete = false;
+        assert!(encode(&legacy).migrate_layout(quote_mint).is_err());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,215 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::error::ErrorCode;
+
+#[account]
+pub struct InvestorAccrual {
+    /// The vault account used as seed for PDAs
+    pub vault: Pubkey,
+    /// Investor wallet the accrued amount belongs to
+    pub investor: Pubkey,
+    /// Allocations below the minimum payout held for the investor
+    pub accrued_amount: u64,
+    /// Total released to the investor once the threshold was crossed
+    pub total_released: u64,
+    /// Timestamp of the last accrual or release
+    pub last_update_ts: i64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl InvestorAccrual {
+    /// Calculate space needed for account
+    /// 32 (vault) + 32 (investor) + 8 (accrued_amount) + 8 (total_released)
+    /// + 8 (last_update_ts) + 1 (bump)
+    pub const INIT_SPACE: usize = 32 + 32 + 8 + 8 + 8 + 1;
+
+    /// Initialize a new accrual for an investor
+    pub fn initialize(
+        &mut self,
+        vault: Pubkey,
+        investor: Pubkey,
+        bump: u8,
+    ) -> Result<()> {
+        self.vault = vault;
+        self.investor = investor;
+        self.accrued_amount = 0;
+        self.total_released = 0;
+        self.last_update_ts = 0;
+        self.bump = bump;
+
+        Ok(())
+    }
+
+    /// Add an allocation to the accrued balance, releasing the whole balance once it
+    /// reaches the minimum payout
+    /// Returns the amount released (zero while the balance stays below the threshold)
+    pub fn accrue(
+        &mut self,
+        allocation: u64,
+        min_payout: u64,
+        current_timestamp: i64,
+    ) -> Result<u64> {
+        let balance = self.accrued_amount
+            .checked_add(allocation)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        self.last_update_ts = current_timestamp;
+
+        if balance < min_payout {
+            self.accrued_amount = balance;
+            return Ok(0);
+        }
+
+        self.accrued_amount = 0;
+        self.total_released = self.total_released
+            .checked_add(balance)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        Ok(balance)
+    }
+
+    /// Release the whole accrued balance on the investor's request, below the minimum
+    /// payout too
+    /// Returns the amount released
+    pub fn release(&mut self, current_timestamp: i64) -> Result<u64> {
+        let balance = std::mem::take(&mut self.accrued_amount);
+        self.total_released = self.total_released
+            .checked_add(balance)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        self.last_update_ts = current_timestamp;
+        Ok(balance)
+    }
+
+    /// Address of an investor's accrual PDA in the ledger of `ledger_seed`
+    pub fn address(vault: &Pubkey, investor: &Pubkey, ledger_seed: &[u8]) -> Pubkey {
+        Pubkey::find_program_address(
+            &[ACCRUAL_SEED, vault.as_ref(), investor.as_ref(), ledger_seed],
+            &crate::ID,
+        )
+        .0
+    }
+
+    /// Load the accrual stored in `account_info`, checking it belongs to `vault` and
+    /// sits at the accrual PDA of the investor it records in the ledger of `ledger_seed`
+    pub fn load(
+        account_info: &AccountInfo,
+        vault: &Pubkey,
+        ledger_seed: &[u8],
+    ) -> Result<Self> {
+        require!(
+            *account_info.owner == crate::ID && account_info.is_writable,
+            ErrorCode::InvalidInvestorAccrual
+        );
+
+        let data = account_info.try_borrow_data()?;
+        let accrual = Self::try_deserialize(&mut &data[..])
+            .map_err(|_| ErrorCode::InvalidInvestorAccrual)?;
+
+        require!(
+            accrual.vault == *vault,
+            ErrorCode::InvalidInvestorAccrual
+        );
+
+        let expected = Pubkey::create_program_address(
+            &[ACCRUAL_SEED, vault.as_ref(), accrual.investor.as_ref(), ledger_seed, &[accrual.bump]],
+            &crate::ID,
+        )
+        .map_err(|_| ErrorCode::InvalidInvestorAccrual)?;
+        require!(
+            expected == account_info.key(),
+            ErrorCode::InvalidInvestorAccrual
+        );
+
+        Ok(accrual)
+    }
+
+    /// Write the accrual back to its account
+    pub fn store(&self, account_info: &AccountInfo) -> Result<()> {
+        let mut data = account_info.try_borrow_mut_data()?;
+        self.try_serialize(&mut &mut data[..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_accrual(vault: Pubkey, investor: Pubkey) -> InvestorAccrual {
+        let (_, bump) = Pubkey::find_program_address(
+            &[ACCRUAL_SEED, vault.as_ref(), investor.as_ref()],
+            &crate::ID,
+        );
+        let mut accrual = InvestorAccrual {
+            vault: Pubkey::default(),
+            investor: Pubkey::default(),
+            accrued_amount: 0,
+            total_released: 0,
+            last_update_ts: 0,
+            bump: 0,
+        };
+        accrual.initialize(vault, investor, bump).unwrap();
+        accrual
+    }
+
+    #[test]
+    fn test_accrue_until_threshold() {
+        let mut accrual = create_accrual(Pubkey::new_unique(), Pubkey::new_unique());
+
+        // Below the threshold the allocation stays attributed to the investor
+        assert_eq!(accrual.accrue(400, 1_000, 100).unwrap(), 0);
+        assert_eq!(accrual.accrue(400, 1_000, 200).unwrap(), 0);
+        assert_eq!(accrual.accrued_amount, 800);
+
+        // Crossing it releases the whole balance
+        assert_eq!(accrual.accrue(300, 1_000, 300).unwrap(), 1_100);
+        assert_eq!(accrual.accrued_amount, 0);
+        assert_eq!(accrual.total_released, 1_100);
+        assert_eq!(accrual.last_update_ts, 300);
+
+        // An allocation at the threshold is released directly
+        assert_eq!(accrual.accrue(1_000, 1_000, 400).unwrap(), 1_000);
+        assert_eq!(accrual.total_released, 2_100);
+
+        // A claim releases a balance below the threshold
+        accrual.accrue(250, 1_000, 500).unwrap();
+        assert_eq!(accrual.release(600).unwrap(), 250);
+        assert_eq!(accrual.accrued_amount, 0);
+        assert_eq!(accrual.total_released, 2_350);
+        assert_eq!(accrual.release(700).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_load_and_store_round_trip() {
+        let vault = Pubkey::new_unique();
+        let investor = Pubkey::new_unique();
+        let mut accrual = create_accrual(vault, investor);
+        accrual.accrue(250, 1_000, 10).unwrap();
+
+        let key = InvestorAccrual::address(&vault, &investor, &[]);
+        let owner = crate::ID;
+        let mut lamports = 0u64;
+        let mut data = vec![0u8; 8 + InvestorAccrual::INIT_SPACE];
+        accrual.try_serialize(&mut &mut data[..]).unwrap();
+        let account_info = AccountInfo::new(&key, false, true, &mut lamports, &mut data, &owner, false, 0);
+
+        let mut loaded = InvestorAccrual::load(&account_info, &vault, &[]).unwrap();
+        assert_eq!(loaded.accrued_amount, 250);
+
+        loaded.accrue(100, 1_000, 20).unwrap();
+        loaded.store(&account_info).unwrap();
+        let reloaded = InvestorAccrual::load(&account_info, &vault, &[]).unwrap();
+        assert_eq!(reloaded.accrued_amount, 350);
+
+        // Another vault's accrual is rejected
+        assert!(InvestorAccrual::load(&account_info, &Pubkey::new_unique(), &[]).is_err());
+
+        // The same investor's accrual in another quote mint's ledger lives elsewhere
+        assert!(InvestorAccrual::load(&account_info, &vault, Pubkey::new_unique().as_ref()).is_err());
+
+        // So is an accrual whose investor does not match its address
+        let mut tampered = reloaded;
+        tampered.investor = Pubkey::new_unique();
+        tampered.store(&account_info).unwrap();
+        assert!(InvestorAccrual::load(&account_info, &vault, &[]).is_err());
+    }
+}
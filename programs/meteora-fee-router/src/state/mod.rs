@@ -1,5 +1,7 @@
 pub mod policy_config;
 pub mod distribution_progress;
+pub mod investor_accrual;
 
 pub use policy_config::*;
-pub use distribution_progress::*;
\ No newline at end of file
+pub use distribution_progress::*;
+pub use investor_accrual::*;
\ No newline at end of file
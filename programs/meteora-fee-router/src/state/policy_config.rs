@@ -1,6 +1,209 @@
 use anchor_lang::prelude::*;
 use crate::constants::*;
 use crate::error::ErrorCode;
+use crate::state::DistributionProgress;
+use crate::utils::streamflow::STREAMFLOW_PROGRAM_ID;
+use crate::utils::vesting::vesting_source_for;
+
+/// Source of the per-investor weights used to split the investor pool
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WeightMode {
+    /// Weight by still-locked amounts read from allowed vesting programs
+    #[default]
+    LockedVesting,
+    /// Weight by staked balances read through the policy's staking layout
+    StakedBalance,
+}
+
+/// Where payout dust (rounding remainders below the minimum payout) goes
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DustPolicy {
+    /// Keep dust on the progress account and fold it into the next day's investor pool
+    #[default]
+    CarryForward,
+    /// Add the day's remaining dust to the creator payout at day close
+    CreatorAtDayClose,
+    /// Pay dust, in multiples of the minimum payout, to the page's largest-weight investor
+    LargestWeightInvestor,
+    /// Pay dust, in multiples of the minimum payout, to investors in turn by registry index
+    RoundRobin,
+}
+
+impl DustPolicy {
+    /// Whether dust is paid to investors during the day
+    pub fn pays_investors(&self) -> bool {
+        matches!(self, Self::LargestWeightInvestor | Self::RoundRobin)
+    }
+}
+
+/// What happens to base-token fees owed by an honorary position
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BaseFeePolicy {
+    /// Fail the claim, and with it the crank, while any base fee is owed
+    #[default]
+    Abort,
+    /// Collect base fees into the base treasury (the position owner's base ATA)
+    Quarantine,
+    /// Collect base fees straight into the creator's base ATA
+    ForwardToCreator,
+}
+
+/// What the creator's share of each day is used for
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CreatorPayoutMode {
+    /// Transfer the creator share to the creator's quote ATA
+    #[default]
+    Transfer,
+    /// Swap the creator share into the base token through an honorary pool and burn it
+    BuybackAndBurn,
+}
+
+/// How a day that opens after missed epochs distributes the fees accrued over them
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CatchUpMode {
+    /// Distribute the whole claim as one day under one daily cap
+    #[default]
+    SingleDay,
+    /// Scale the daily and per-investor caps by the epochs the day covers
+    CapPerEpoch,
+    /// Split the claim evenly across the epochs the day covers, each epoch's investor
+    /// share set by its own locked snapshot, with caps scaled as in `CapPerEpoch`
+    SplitClaim,
+}
+
+/// What callers of `distribute_fees` are paid for a day, split across its pages by the
+/// investors each page processes
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CrankReward {
+    /// Crank callers are not paid
+    #[default]
+    None,
+    /// Basis points of the day's claimed quote (at most MAX_CRANK_REWARD_BPS)
+    Bps { bps: u16 },
+    /// Fixed quote amount per day
+    FlatLamports { lamports: u64 },
+}
+
+impl CrankReward {
+    /// Space taken by the largest variant
+    pub const INIT_SPACE: usize = 1 + 8;
+}
+
+/// DAMM V2 pool an honorary position sits in, with the vaults swaps through it must use
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HonoraryPool {
+    /// The pool account
+    pub pool: Pubkey,
+    /// Pool vault holding the base mint
+    pub base_vault: Pubkey,
+    /// Pool vault holding the position's quote mint
+    pub quote_vault: Pubkey,
+    /// Whether the base mint is the pool's token A (its vaults are passed in A/B order)
+    pub base_is_token_a: bool,
+    /// Quote per base in Q64.64 set by the policy authority, which bounds the minimum out
+    /// of crank-driven swaps in place of the pool's spot reserves (0 until set)
+    pub reference_price_q64: u128,
+}
+
+impl HonoraryPool {
+    /// 32 (pool) + 32 (base_vault) + 32 (quote_vault) + 1 (base_is_token_a)
+    /// + 16 (reference_price_q64)
+    pub const INIT_SPACE: usize = 32 + 32 + 32 + 1 + 16;
+
+    /// Check a swap's pool and vaults against this record, returning whether the swap
+    /// sells the base mint
+    pub fn input_is_base(&self, pool: &Pubkey, input_vault: &Pubkey, output_vault: &Pubkey) -> Result<bool> {
+        require!(*pool == self.pool, ErrorCode::InvalidSwapPool);
+
+        if (*input_vault, *output_vault) == (self.base_vault, self.quote_vault) {
+            Ok(true)
+        } else if (*input_vault, *output_vault) == (self.quote_vault, self.base_vault) {
+            Ok(false)
+        } else {
+            msg!("Swap vaults {} / {} are not the recorded vaults of pool {}", input_vault, output_vault, pool);
+            Err(ErrorCode::InvalidSwapPool.into())
+        }
+    }
+}
+
+/// Byte layout of a staking program's stake accounts
+///
+/// Lets a policy read staked balances from an arbitrary staking program without
+/// linking its crate: the owner, mint and amount fields are read at fixed offsets.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StakingLayout {
+    /// Program that owns the stake accounts
+    pub program_id: Pubkey,
+    /// Mint of the staked token
+    pub stake_mint: Pubkey,
+    /// Expected account discriminator (all zeros to skip the check)
+    pub discriminator: [u8; 8],
+    /// Offset of the staker's wallet (32 bytes)
+    pub owner_offset: u16,
+    /// Offset of the staked mint (32 bytes)
+    pub mint_offset: u16,
+    /// Offset of the staked amount (u64, little-endian)
+    pub amount_offset: u16,
+}
+
+impl StakingLayout {
+    /// 32 (program_id) + 32 (stake_mint) + 8 (discriminator) + 2 * 3 (offsets)
+    pub const INIT_SPACE: usize = 32 + 32 + 8 + 2 * 3;
+
+    /// Minimum account data length covering every field of the layout
+    pub fn min_account_len(&self) -> usize {
+        let discriminator_len = if self.has_discriminator() { 8 } else { 0 };
+        [
+            discriminator_len,
+            self.owner_offset as usize + 32,
+            self.mint_offset as usize + 32,
+            self.amount_offset as usize + 8,
+        ]
+        .into_iter()
+        .max()
+        .unwrap_or(0)
+    }
+
+    /// Whether stake accounts must start with `discriminator`
+    pub fn has_discriminator(&self) -> bool {
+        self.discriminator != [0u8; 8]
+    }
+
+    /// Validate the layout describes readable, non-overlapping fields
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.program_id != Pubkey::default() && self.stake_mint != Pubkey::default(),
+            ErrorCode::InvalidStakingLayout
+        );
+
+        // Fields must not overlap each other or the discriminator
+        let header = if self.has_discriminator() { 8 } else { 0 };
+        let mut fields = [
+            (self.owner_offset as usize, 32usize),
+            (self.mint_offset as usize, 32usize),
+            (self.amount_offset as usize, 8usize),
+        ];
+        fields.sort_unstable();
+        require!(fields[0].0 >= header, ErrorCode::InvalidStakingLayout);
+        for pair in fields.windows(2) {
+            require!(pair[0].0 + pair[0].1 <= pair[1].0, ErrorCode::InvalidStakingLayout);
+        }
+
+        Ok(())
+    }
+}
+
+/// Core terms a policy is initialized with; the other settings are configured after
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PolicyTerms {
+    pub vault: Pubkey,
+    pub quote_mint: Pubkey,
+    pub creator_wallet: Pubkey,
+    pub investor_fee_share_bps: u16,
+    pub daily_cap_lamports: Option<u64>,
+    pub min_payout_lamports: u64,
+    pub y0_total_allocation: u64,
+}
 
 #[account]
 pub struct PolicyConfig {
@@ -20,13 +223,132 @@ pub struct PolicyConfig {
     pub y0_total_allocation: u64,
     /// PDA bump
     pub bump: u8,
+    /// Vesting programs whose accounts are accepted as investor locks
+    pub vesting_programs: [Pubkey; MAX_VESTING_PROGRAMS],
+    /// Number of populated entries in `vesting_programs`
+    pub vesting_program_count: u8,
+    /// How investor weights are measured
+    pub weight_mode: WeightMode,
+    /// Stake account layout, used when `weight_mode` is `StakedBalance`
+    pub staking_layout: StakingLayout,
+    /// Optional maximum paid to a single investor per day in lamports
+    pub per_investor_daily_cap_lamports: Option<u64>,
+    /// Optional cap on investor payouts over the last ROLLING_CAP_WINDOW_SECS in lamports
+    pub rolling_cap_lamports: Option<u64>,
+    /// Where payout dust goes
+    pub dust_policy: DustPolicy,
+    /// Honorary DAMM V2 positions fees are claimed from
+    pub honorary_positions: [Pubkey; MAX_HONORARY_POSITIONS],
+    /// Number of populated entries in `honorary_positions`
+    pub honorary_position_count: u8,
+    /// Quote mints with their own treasury and day ledger; the first entry is `quote_mint`
+    pub quote_mints: [Pubkey; MAX_QUOTE_MINTS],
+    /// Number of populated entries in `quote_mints`
+    pub quote_mint_count: u8,
+    /// Quote mint of each honorary position, parallel to `honorary_positions`
+    pub honorary_position_quote_mints: [Pubkey; MAX_HONORARY_POSITIONS],
+    /// Base token mint of the honorary pools
+    pub base_mint: Pubkey,
+    /// What happens to base fees owed by the honorary positions
+    pub base_fee_policy: BaseFeePolicy,
+    /// Maximum slippage in basis points accepted when swapping through an honorary pool
+    pub max_swap_slippage_bps: u16,
+    /// What the creator's share of each day is used for
+    pub creator_payout_mode: CreatorPayoutMode,
+    /// Length of a distribution day in seconds (86400 unless configured)
+    pub distribution_period_secs: i64,
+    /// Start of epoch 0 when days are calendar-aligned to `distribution_period_secs`;
+    /// days start whenever the crank runs when `None`
+    pub epoch_anchor_ts: Option<i64>,
+    /// How a day opened after missed epochs is distributed
+    pub catch_up_mode: CatchUpMode,
+    /// What crank callers are paid per day, out of the creator's remainder
+    pub crank_reward: CrankReward,
+    /// Most paid to crank callers per day (required when `crank_reward` pays)
+    pub crank_reward_daily_cap_lamports: u64,
+    /// Layout the account was written under (ACCOUNT_LAYOUT_VERSION once migrated)
+    pub layout_version: u8,
+    /// Signs the policy's privileged instructions (base fee conversion, reference prices,
+    /// aborting stuck days)
+    pub authority: Pubkey,
+    /// Pool of each honorary position, parallel to `honorary_positions`
+    pub honorary_pools: [HonoraryPool; MAX_HONORARY_POSITIONS],
+}
+
+impl Default for PolicyConfig {
+    /// A policy with every optional setting at the value `initialize` gives it
+    fn default() -> Self {
+        let mut vesting_programs = [Pubkey::default(); MAX_VESTING_PROGRAMS];
+        vesting_programs[0] = STREAMFLOW_PROGRAM_ID;
+
+        Self {
+            vault: Pubkey::default(),
+            quote_mint: Pubkey::default(),
+            creator_wallet: Pubkey::default(),
+            investor_fee_share_bps: 0,
+            daily_cap_lamports: None,
+            min_payout_lamports: 0,
+            y0_total_allocation: 0,
+            bump: 0,
+            vesting_programs,
+            vesting_program_count: 1,
+            weight_mode: WeightMode::LockedVesting,
+            staking_layout: StakingLayout::default(),
+            per_investor_daily_cap_lamports: None,
+            rolling_cap_lamports: None,
+            dust_policy: DustPolicy::CarryForward,
+            honorary_positions: [Pubkey::default(); MAX_HONORARY_POSITIONS],
+            honorary_position_count: 0,
+            quote_mints: [Pubkey::default(); MAX_QUOTE_MINTS],
+            quote_mint_count: 0,
+            honorary_position_quote_mints: [Pubkey::default(); MAX_HONORARY_POSITIONS],
+            base_mint: Pubkey::default(),
+            base_fee_policy: BaseFeePolicy::Abort,
+            max_swap_slippage_bps: 0,
+            creator_payout_mode: CreatorPayoutMode::Transfer,
+            distribution_period_secs: TWENTY_FOUR_HOURS,
+            epoch_anchor_ts: None,
+            catch_up_mode: CatchUpMode::SingleDay,
+            crank_reward: CrankReward::None,
+            crank_reward_daily_cap_lamports: 0,
+            layout_version: ACCOUNT_LAYOUT_VERSION,
+            authority: Pubkey::default(),
+            honorary_pools: [HonoraryPool::default(); MAX_HONORARY_POSITIONS],
+        }
+    }
 }
 
 impl PolicyConfig {
     /// Calculate space needed for account
     /// 32 (vault) + 32 (quote_mint) + 32 (creator_wallet) + 2 (investor_fee_share_bps) 
     /// + 9 (daily_cap_lamports Option<u64>) + 8 (min_payout_lamports) + 8 (y0_total_allocation) + 1 (bump)
-    pub const INIT_SPACE: usize = 32 + 32 + 32 + 2 + 9 + 8 + 8 + 1;
+    /// + 32 * MAX_VESTING_PROGRAMS (vesting_programs) + 1 (vesting_program_count)
+    /// + 1 (weight_mode) + StakingLayout::INIT_SPACE (staking_layout)
+    /// + 9 (per_investor_daily_cap_lamports Option<u64>) + 9 (rolling_cap_lamports Option<u64>)
+    /// + 1 (dust_policy)
+    /// + 32 * MAX_HONORARY_POSITIONS (honorary_positions) + 1 (honorary_position_count)
+    /// + 32 * MAX_QUOTE_MINTS (quote_mints) + 1 (quote_mint_count)
+    /// + 32 * MAX_HONORARY_POSITIONS (honorary_position_quote_mints)
+    /// + 32 (base_mint) + 1 (base_fee_policy) + 2 (max_swap_slippage_bps)
+    /// + 1 (creator_payout_mode) + 8 (distribution_period_secs) + 9 (epoch_anchor_ts Option<i64>)
+    /// + 1 (catch_up_mode)
+    /// + CrankReward::INIT_SPACE (crank_reward) + 8 (crank_reward_daily_cap_lamports)
+    /// + 1 (layout_version) + 32 (authority)
+    /// + HonoraryPool::INIT_SPACE * MAX_HONORARY_POSITIONS (honorary_pools)
+    pub const INIT_SPACE: usize = 32 + 32 + 32 + 2 + 9 + 8 + 8 + 1
+        + 32 * MAX_VESTING_PROGRAMS + 1
+        + 1 + StakingLayout::INIT_SPACE
+        + 9 + 9
+        + 1
+        + 32 * MAX_HONORARY_POSITIONS + 1
+        + 32 * MAX_QUOTE_MINTS + 1
+        + 32 * MAX_HONORARY_POSITIONS
+        + 32 + 1 + 2
+        + 1 + 8 + 9
+        + 1
+        + CrankReward::INIT_SPACE + 8
+        + 1 + 32
+        + HonoraryPool::INIT_SPACE * MAX_HONORARY_POSITIONS;
 
     /// Validate policy configuration parameters
     pub fn validate(&self) -> Result<()> {
@@ -56,29 +378,148 @@ impl PolicyConfig {
             );
         }
 
+        // Validate per-investor and rolling caps if set
+        Self::validate_caps(self.per_investor_daily_cap_lamports, self.rolling_cap_lamports)?;
+
+        // Validate the vesting program registry
+        Self::validate_vesting_programs(self.allowed_vesting_programs())?;
+
+        // Stake-weighted policies need a usable staking layout
+        if self.weight_mode == WeightMode::StakedBalance {
+            self.staking_layout.validate()?;
+        }
+
+        // Base fees can only be routed somewhere if the base mint is known
+        Self::validate_base_fee_policy(self.base_fee_policy, &self.base_mint)?;
+
+        // Validate swap slippage is within valid range
+        require!(
+            self.max_swap_slippage_bps <= MAX_BASIS_POINTS,
+            ErrorCode::InvalidSwapSlippage
+        );
+
+        // Buying back the base token needs the base mint
+        Self::validate_creator_payout_mode(self.creator_payout_mode, &self.base_mint)?;
+
+        // Validate the distribution period is within bounds
+        Self::validate_distribution_period(self.distribution_period_secs)?;
+
+        // A rolling cap needs the period to divide its window
+        Self::validate_rolling_cap_window(self.rolling_cap_lamports, self.distribution_period_secs)?;
+
+        // Validate the epoch anchor if days are epoch-aligned
+        require!(
+            self.epoch_anchor_ts.is_none_or(|anchor| anchor > 0),
+            ErrorCode::InvalidEpochAnchor
+        );
+
+        // Validate the crank reward and its daily cap
+        Self::validate_crank_reward(self.crank_reward, self.crank_reward_daily_cap_lamports)?;
+
         Ok(())
     }
 
-    /// Initialize a new policy configuration
-    pub fn initialize(
-        &mut self,
-        vault: Pubkey,
-        quote_mint: Pubkey,
-        creator_wallet: Pubkey,
-        investor_fee_share_bps: u16,
-        daily_cap_lamports: Option<u64>,
-        min_payout_lamports: u64,
-        y0_total_allocation: u64,
-        bump: u8,
+    /// Validate a crank reward: a paying reward must be non-zero (bps at most
+    /// MAX_CRANK_REWARD_BPS) and have a non-zero daily cap
+    pub fn validate_crank_reward(crank_reward: CrankReward, daily_cap_lamports: u64) -> Result<()> {
+        let valid = match crank_reward {
+            CrankReward::None => true,
+            CrankReward::Bps { bps } => bps > 0 && bps <= MAX_CRANK_REWARD_BPS && daily_cap_lamports > 0,
+            CrankReward::FlatLamports { lamports } => lamports > 0 && daily_cap_lamports > 0,
+        };
+        require!(valid, ErrorCode::InvalidCrankReward);
+
+        Ok(())
+    }
+
+    /// Validate a distribution period: between one hour and 30 days
+    pub fn validate_distribution_period(distribution_period_secs: i64) -> Result<()> {
+        require!(
+            (MIN_DISTRIBUTION_PERIOD_SECS..=MAX_DISTRIBUTION_PERIOD_SECS).contains(&distribution_period_secs),
+            ErrorCode::InvalidDistributionPeriod
+        );
+
+        Ok(())
+    }
+
+    /// Validate a rolling cap against the distribution period: its 7-day window must be a
+    /// whole number of periods, at most ROLLING_CAP_SLOTS of them
+    pub fn validate_rolling_cap_window(rolling_cap_lamports: Option<u64>, distribution_period_secs: i64) -> Result<()> {
+        require!(
+            rolling_cap_lamports.is_none()
+                || DistributionProgress::rolling_window_periods(distribution_period_secs).is_some(),
+            ErrorCode::InvalidRollingCapWindow
+        );
+
+        Ok(())
+    }
+
+    /// Validate the optional per-investor and rolling caps: zero caps are rejected
+    pub fn validate_caps(
+        per_investor_daily_cap_lamports: Option<u64>,
+        rolling_cap_lamports: Option<u64>,
     ) -> Result<()> {
-        self.vault = vault;
-        self.quote_mint = quote_mint;
-        self.creator_wallet = creator_wallet;
-        self.investor_fee_share_bps = investor_fee_share_bps;
-        self.daily_cap_lamports = daily_cap_lamports;
-        self.min_payout_lamports = min_payout_lamports;
-        self.y0_total_allocation = y0_total_allocation;
-        self.bump = bump;
+        require!(
+            per_investor_daily_cap_lamports != Some(0) && rolling_cap_lamports != Some(0),
+            ErrorCode::InvalidDailyCap
+        );
+
+        Ok(())
+    }
+
+    /// Validate a base fee policy: routing base fees needs the base mint
+    pub fn validate_base_fee_policy(base_fee_policy: BaseFeePolicy, base_mint: &Pubkey) -> Result<()> {
+        require!(
+            base_fee_policy == BaseFeePolicy::Abort || *base_mint != Pubkey::default(),
+            ErrorCode::InvalidBaseFeeDestination
+        );
+
+        Ok(())
+    }
+
+    /// Validate a creator payout mode: buyback and burn needs the base mint
+    pub fn validate_creator_payout_mode(creator_payout_mode: CreatorPayoutMode, base_mint: &Pubkey) -> Result<()> {
+        require!(
+            creator_payout_mode == CreatorPayoutMode::Transfer || *base_mint != Pubkey::default(),
+            ErrorCode::InvalidBuybackAccounts
+        );
+
+        Ok(())
+    }
+
+    /// Validate a list of vesting programs: non-empty, bounded, unique and supported
+    pub fn validate_vesting_programs(programs: &[Pubkey]) -> Result<()> {
+        require!(
+            !programs.is_empty() && programs.len() <= MAX_VESTING_PROGRAMS,
+            ErrorCode::InvalidVestingProgramRegistry
+        );
+
+        for (index, program) in programs.iter().enumerate() {
+            require!(
+                !programs[..index].contains(program),
+                ErrorCode::InvalidVestingProgramRegistry
+            );
+            vesting_source_for(program)?;
+        }
+
+        Ok(())
+    }
+
+    /// Initialize a new policy configuration
+    pub fn initialize(&mut self, terms: PolicyTerms, bump: u8) -> Result<()> {
+        *self = Self {
+            vault: terms.vault,
+            quote_mint: terms.quote_mint,
+            creator_wallet: terms.creator_wallet,
+            investor_fee_share_bps: terms.investor_fee_share_bps,
+            daily_cap_lamports: terms.daily_cap_lamports,
+            min_payout_lamports: terms.min_payout_lamports,
+            y0_total_allocation: terms.y0_total_allocation,
+            bump,
+            ..Self::default()
+        };
+        self.quote_mints[0] = terms.quote_mint;
+        self.quote_mint_count = 1;
 
         // Validate the configuration
         self.validate()?;
@@ -86,8 +527,779 @@ impl PolicyConfig {
         Ok(())
     }
 
+    /// Bring a policy written under an older layout up to ACCOUNT_LAYOUT_VERSION
+    ///
+    /// Fields appended since the account was written read as zero once it has been
+    /// reallocated; each step gives one layout's new fields the values `initialize` sets.
+    pub fn migrate_layout(&mut self, honorary_positions: &[Pubkey]) -> Result<()> {
+        require!(
+            self.layout_version < ACCOUNT_LAYOUT_VERSION,
+            ErrorCode::AccountAlreadyMigrated
+        );
+
+        // Unversioned policies hold only the original fields and took their single
+        // honorary position per call, so the positions are supplied here
+        if self.layout_version == 0 {
+            let terms = PolicyTerms {
+                vault: self.vault,
+                quote_mint: self.quote_mint,
+                creator_wallet: self.creator_wallet,
+                investor_fee_share_bps: self.investor_fee_share_bps,
+                daily_cap_lamports: self.daily_cap_lamports,
+                min_payout_lamports: self.min_payout_lamports,
+                y0_total_allocation: self.y0_total_allocation,
+            };
+            self.initialize(terms, self.bump)?;
+            self.set_honorary_positions(honorary_positions)?;
+            // Unversioned policies were administered by the creator wallet
+            self.authority = self.creator_wallet;
+        }
+
+        self.layout_version = ACCOUNT_LAYOUT_VERSION;
+        Ok(())
+    }
+
     /// Get the PDA seeds for this policy config (for signing)
     pub fn get_signer_seeds(&self) -> [&[u8]; 3] {
         [POLICY_SEED, self.vault.as_ref(), std::slice::from_ref(&self.bump)]
     }
-}
\ No newline at end of file
+
+    /// Vesting programs accepted as sources of investor locks
+    pub fn allowed_vesting_programs(&self) -> &[Pubkey] {
+        let count = (self.vesting_program_count as usize).min(MAX_VESTING_PROGRAMS);
+        &self.vesting_programs[..count]
+    }
+
+    /// Replace the vesting program registry
+    pub fn set_vesting_programs(&mut self, programs: &[Pubkey]) -> Result<()> {
+        Self::validate_vesting_programs(programs)?;
+
+        self.vesting_programs = [Pubkey::default(); MAX_VESTING_PROGRAMS];
+        self.vesting_programs[..programs.len()].copy_from_slice(programs);
+        self.vesting_program_count = programs.len() as u8;
+
+        Ok(())
+    }
+
+    /// Validate a list of honorary positions: non-empty, bounded, unique and set
+    pub fn validate_honorary_positions(positions: &[Pubkey]) -> Result<()> {
+        require!(
+            !positions.is_empty() && positions.len() <= MAX_HONORARY_POSITIONS,
+            ErrorCode::InvalidHonoraryPositions
+        );
+
+        for (index, position) in positions.iter().enumerate() {
+            require!(
+                *position != Pubkey::default() && !positions[..index].contains(position),
+                ErrorCode::InvalidHonoraryPositions
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Honorary positions fees are claimed from, in the order `distribute_fees` takes them
+    pub fn honorary_positions(&self) -> &[Pubkey] {
+        let count = (self.honorary_position_count as usize).min(MAX_HONORARY_POSITIONS);
+        &self.honorary_positions[..count]
+    }
+
+    /// Replace the honorary position list; every position starts on the primary quote mint,
+    /// with no pool recorded
+    pub fn set_honorary_positions(&mut self, positions: &[Pubkey]) -> Result<()> {
+        Self::validate_honorary_positions(positions)?;
+
+        self.honorary_positions = [Pubkey::default(); MAX_HONORARY_POSITIONS];
+        self.honorary_positions[..positions.len()].copy_from_slice(positions);
+        self.honorary_position_count = positions.len() as u8;
+        self.honorary_pools = [HonoraryPool::default(); MAX_HONORARY_POSITIONS];
+        self.set_honorary_position_quote_mints(&vec![self.quote_mint; positions.len()])
+    }
+
+    /// Record the pool of each honorary position, in position order
+    pub fn set_honorary_pools(&mut self, pools: &[HonoraryPool]) -> Result<()> {
+        require!(
+            pools.len() == self.honorary_positions().len(),
+            ErrorCode::InvalidHonoraryPositions
+        );
+        for pool in pools {
+            require!(
+                pool.pool != Pubkey::default()
+                    && pool.base_vault != Pubkey::default()
+                    && pool.quote_vault != Pubkey::default()
+                    && pool.base_vault != pool.quote_vault,
+                ErrorCode::InvalidHonoraryPositions
+            );
+        }
+
+        self.honorary_pools = [HonoraryPool::default(); MAX_HONORARY_POSITIONS];
+        self.honorary_pools[..pools.len()].copy_from_slice(pools);
+
+        Ok(())
+    }
+
+    /// Pool of each honorary position, in position order
+    pub fn honorary_pools(&self) -> &[HonoraryPool] {
+        &self.honorary_pools[..self.honorary_positions().len()]
+    }
+
+    /// Recorded pool `pool` of an honorary position quoted in `quote_mint`
+    pub fn honorary_pool_for(&self, quote_mint: &Pubkey, pool: &Pubkey) -> Result<&HonoraryPool> {
+        self.honorary_pools()
+            .iter()
+            .zip(self.honorary_position_quote_mints())
+            .find(|(honorary_pool, mint)| honorary_pool.pool == *pool && *mint == quote_mint)
+            .map(|(honorary_pool, _)| honorary_pool)
+            .ok_or_else(|| {
+                msg!("Pool {} is not an honorary pool quoted in {}", pool, quote_mint);
+                ErrorCode::InvalidSwapPool.into()
+            })
+    }
+
+    /// Set the reference price of every honorary position in `pool`
+    pub fn set_reference_price(&mut self, pool: &Pubkey, reference_price_q64: u128) -> Result<()> {
+        require!(reference_price_q64 > 0, ErrorCode::ReferencePriceUnset);
+
+        let count = self.honorary_positions().len();
+        let mut found = false;
+        for honorary_pool in self.honorary_pools[..count].iter_mut().filter(|entry| entry.pool == *pool) {
+            honorary_pool.reference_price_q64 = reference_price_q64;
+            found = true;
+        }
+        require!(found, ErrorCode::InvalidSwapPool);
+
+        Ok(())
+    }
+
+    /// Set the authority signing the policy's privileged instructions
+    pub fn set_authority(&mut self, authority: Pubkey) -> Result<()> {
+        require!(authority != Pubkey::default(), ErrorCode::UnauthorizedAuthority);
+
+        self.authority = authority;
+
+        Ok(())
+    }
+
+    /// Quote mints with their own treasury and day ledger, primary quote mint first
+    pub fn quote_mints(&self) -> &[Pubkey] {
+        let count = (self.quote_mint_count as usize).min(MAX_QUOTE_MINTS);
+        &self.quote_mints[..count]
+    }
+
+    /// Whether `mint` has a treasury and day ledger under this policy
+    pub fn is_quote_mint(&self, mint: &Pubkey) -> bool {
+        self.quote_mints().contains(mint)
+    }
+
+    /// Extra PDA seed of a quote mint's day ledger and investor accruals
+    ///
+    /// The primary quote mint's ledger has no extra seed, so it keeps the addresses it
+    /// had before a vault could hold several quote mints.
+    pub fn ledger_seed<'a>(&self, quote_mint: &'a Pubkey) -> &'a [u8] {
+        if *quote_mint == self.quote_mint {
+            &[]
+        } else {
+            quote_mint.as_ref()
+        }
+    }
+
+    /// Quote mint of each honorary position, in position order
+    pub fn honorary_position_quote_mints(&self) -> &[Pubkey] {
+        &self.honorary_position_quote_mints[..self.honorary_positions().len()]
+    }
+
+    /// Honorary positions quoted in `quote_mint`, in the order `distribute_fees` takes them
+    pub fn honorary_positions_for(&self, quote_mint: &Pubkey) -> Vec<Pubkey> {
+        self.honorary_positions()
+            .iter()
+            .zip(self.honorary_position_quote_mints())
+            .filter(|(_, mint)| *mint == quote_mint)
+            .map(|(position, _)| *position)
+            .collect()
+    }
+
+    /// Assign a quote mint to each honorary position and rebuild the quote mint registry:
+    /// the primary quote mint, then every other assigned mint in first-use order
+    pub fn set_honorary_position_quote_mints(&mut self, mints: &[Pubkey]) -> Result<()> {
+        require!(
+            mints.len() == self.honorary_positions().len(),
+            ErrorCode::InvalidQuoteMintRegistry
+        );
+
+        let mut quote_mints = vec![self.quote_mint];
+        for mint in mints {
+            require!(*mint != Pubkey::default(), ErrorCode::InvalidQuoteMintRegistry);
+            if !quote_mints.contains(mint) {
+                quote_mints.push(*mint);
+            }
+        }
+        require!(quote_mints.len() <= MAX_QUOTE_MINTS, ErrorCode::InvalidQuoteMintRegistry);
+
+        self.quote_mints = [Pubkey::default(); MAX_QUOTE_MINTS];
+        self.quote_mints[..quote_mints.len()].copy_from_slice(&quote_mints);
+        self.quote_mint_count = quote_mints.len() as u8;
+        self.honorary_position_quote_mints = [Pubkey::default(); MAX_HONORARY_POSITIONS];
+        self.honorary_position_quote_mints[..mints.len()].copy_from_slice(mints);
+
+        Ok(())
+    }
+
+    /// Set the optional per-investor daily maximum and rolling-window cap
+    pub fn configure_caps(
+        &mut self,
+        per_investor_daily_cap_lamports: Option<u64>,
+        rolling_cap_lamports: Option<u64>,
+    ) -> Result<()> {
+        Self::validate_caps(per_investor_daily_cap_lamports, rolling_cap_lamports)?;
+
+        self.per_investor_daily_cap_lamports = per_investor_daily_cap_lamports;
+        self.rolling_cap_lamports = rolling_cap_lamports;
+
+        Ok(())
+    }
+
+    /// Set the pools' base mint and what happens to base fees
+    pub fn configure_base_fees(&mut self, base_mint: Pubkey, base_fee_policy: BaseFeePolicy) -> Result<()> {
+        Self::validate_base_fee_policy(base_fee_policy, &base_mint)?;
+
+        self.base_mint = base_mint;
+        self.base_fee_policy = base_fee_policy;
+
+        Ok(())
+    }
+
+    /// Set the maximum slippage accepted by swaps through an honorary pool
+    pub fn configure_swap_slippage(&mut self, max_swap_slippage_bps: u16) -> Result<()> {
+        require!(
+            max_swap_slippage_bps <= MAX_BASIS_POINTS,
+            ErrorCode::InvalidSwapSlippage
+        );
+
+        self.max_swap_slippage_bps = max_swap_slippage_bps;
+
+        Ok(())
+    }
+
+    /// Select what the creator's share is used for (after the base mint is configured)
+    pub fn configure_creator_payout(&mut self, creator_payout_mode: CreatorPayoutMode) -> Result<()> {
+        Self::validate_creator_payout_mode(creator_payout_mode, &self.base_mint)?;
+
+        self.creator_payout_mode = creator_payout_mode;
+
+        Ok(())
+    }
+
+    /// Set the length of a distribution day (24 hours when `None`, after the caps are configured)
+    pub fn configure_distribution_period(&mut self, distribution_period_secs: Option<i64>) -> Result<()> {
+        let distribution_period_secs = distribution_period_secs.unwrap_or(TWENTY_FOUR_HOURS);
+        Self::validate_distribution_period(distribution_period_secs)?;
+        Self::validate_rolling_cap_window(self.rolling_cap_lamports, distribution_period_secs)?;
+
+        self.distribution_period_secs = distribution_period_secs;
+
+        Ok(())
+    }
+
+    /// Align distribution days to epochs starting at `epoch_anchor_ts` (or let days start
+    /// whenever the crank runs when `None`); the anchor cannot be in the future
+    pub fn configure_epoch_alignment(&mut self, epoch_anchor_ts: Option<i64>, current_timestamp: i64) -> Result<()> {
+        if let Some(anchor) = epoch_anchor_ts {
+            require!(
+                anchor > 0 && anchor <= current_timestamp,
+                ErrorCode::InvalidEpochAnchor
+            );
+        }
+
+        self.epoch_anchor_ts = epoch_anchor_ts;
+
+        Ok(())
+    }
+
+    /// Set what crank callers are paid per day and the most paid to them per day
+    pub fn configure_crank_reward(&mut self, crank_reward: CrankReward, daily_cap_lamports: u64) -> Result<()> {
+        Self::validate_crank_reward(crank_reward, daily_cap_lamports)?;
+
+        self.crank_reward = crank_reward;
+        self.crank_reward_daily_cap_lamports = daily_cap_lamports;
+
+        Ok(())
+    }
+
+    /// Crank reward for all pages of a day that claimed `day_claimed_quote`, before the
+    /// daily cap
+    pub fn crank_reward_per_day(&self, day_claimed_quote: u64) -> u64 {
+        match self.crank_reward {
+            CrankReward::None => 0,
+            // u64 * u16 always fits in u128, and the result is below day_claimed_quote
+            CrankReward::Bps { bps } => {
+                (day_claimed_quote as u128 * bps as u128 / MAX_BASIS_POINTS as u128) as u64
+            },
+            CrankReward::FlatLamports { lamports } => lamports,
+        }
+    }
+
+    /// Owner the base fee destination account must have under the policy, if base fees are routed
+    pub fn base_fee_destination_owner(&self, position_owner: &Pubkey) -> Option<Pubkey> {
+        match self.base_fee_policy {
+            BaseFeePolicy::Abort => None,
+            BaseFeePolicy::Quarantine => Some(*position_owner),
+            BaseFeePolicy::ForwardToCreator => Some(self.creator_wallet),
+        }
+    }
+
+    /// Select how investor weights are measured
+    /// `staking_layout` is required for `StakedBalance` and ignored otherwise
+    pub fn configure_weight_mode(
+        &mut self,
+        weight_mode: WeightMode,
+        staking_layout: Option<StakingLayout>,
+    ) -> Result<()> {
+        let staking_layout = match weight_mode {
+            WeightMode::LockedVesting => StakingLayout::default(),
+            WeightMode::StakedBalance => {
+                let layout = staking_layout.ok_or(ErrorCode::InvalidStakingLayout)?;
+                layout.validate()?;
+                layout
+            }
+        };
+
+        self.weight_mode = weight_mode;
+        self.staking_layout = staking_layout;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::jupiter_lock::JUPITER_LOCK_PROGRAM_ID;
+
+    fn create_policy_config() -> PolicyConfig {
+        PolicyConfig {
+            vault: Pubkey::new_unique(),
+            quote_mint: Pubkey::new_unique(),
+            creator_wallet: Pubkey::new_unique(),
+            investor_fee_share_bps: 8000,
+            min_payout_lamports: 1000,
+            y0_total_allocation: 10_000_000,
+            bump: 255,
+            ..PolicyConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_vesting_program_registry() {
+        let mut policy_config = create_policy_config();
+        assert_eq!(policy_config.allowed_vesting_programs(), &[STREAMFLOW_PROGRAM_ID]);
+
+        policy_config
+            .set_vesting_programs(&[STREAMFLOW_PROGRAM_ID, JUPITER_LOCK_PROGRAM_ID])
+            .unwrap();
+        assert_eq!(
+            policy_config.allowed_vesting_programs(),
+            &[STREAMFLOW_PROGRAM_ID, JUPITER_LOCK_PROGRAM_ID]
+        );
+        assert!(policy_config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_invalid_vesting_program_registry() {
+        let mut policy_config = create_policy_config();
+
+        // Empty, duplicate and unsupported entries are rejected
+        assert!(policy_config.set_vesting_programs(&[]).is_err());
+        assert!(policy_config
+            .set_vesting_programs(&[STREAMFLOW_PROGRAM_ID, STREAMFLOW_PROGRAM_ID])
+            .is_err());
+        assert!(policy_config.set_vesting_programs(&[Pubkey::new_unique()]).is_err());
+        assert!(policy_config
+            .set_vesting_programs(&[STREAMFLOW_PROGRAM_ID; MAX_VESTING_PROGRAMS + 1])
+            .is_err());
+
+        // Registry is left untouched by failed updates
+        assert_eq!(policy_config.allowed_vesting_programs(), &[STREAMFLOW_PROGRAM_ID]);
+
+        policy_config.vesting_program_count = 0;
+        assert!(policy_config.validate().is_err());
+    }
+
+    #[test]
+    fn test_honorary_positions() {
+        let mut policy_config = create_policy_config();
+        assert!(policy_config.honorary_positions().is_empty());
+
+        let positions = [Pubkey::new_unique(), Pubkey::new_unique()];
+        policy_config.set_honorary_positions(&positions).unwrap();
+        assert_eq!(policy_config.honorary_positions(), &positions);
+
+        // Empty, duplicate, unset and oversized lists are rejected and leave the list untouched
+        assert!(policy_config.set_honorary_positions(&[]).is_err());
+        assert!(policy_config.set_honorary_positions(&[positions[0], positions[0]]).is_err());
+        assert!(policy_config.set_honorary_positions(&[Pubkey::default()]).is_err());
+        let too_many: Vec<Pubkey> = (0..=MAX_HONORARY_POSITIONS).map(|_| Pubkey::new_unique()).collect();
+        assert!(policy_config.set_honorary_positions(&too_many).is_err());
+        assert_eq!(policy_config.honorary_positions(), &positions);
+    }
+
+    #[test]
+    fn test_honorary_pools() {
+        let mut policy_config = create_policy_config();
+        let usdc = Pubkey::new_unique();
+        let positions = [Pubkey::new_unique(), Pubkey::new_unique()];
+        policy_config.set_honorary_positions(&positions).unwrap();
+        policy_config.set_honorary_position_quote_mints(&[policy_config.quote_mint, usdc]).unwrap();
+
+        let honorary_pool = |base_is_token_a| HonoraryPool {
+            pool: Pubkey::new_unique(),
+            base_vault: Pubkey::new_unique(),
+            quote_vault: Pubkey::new_unique(),
+            base_is_token_a,
+            reference_price_q64: 0,
+        };
+        let pools = [honorary_pool(true), honorary_pool(false)];
+        policy_config.set_honorary_pools(&pools).unwrap();
+        assert_eq!(policy_config.honorary_pools(), &pools);
+
+        // A pool is only found under its position's quote mint
+        let primary = policy_config.quote_mint;
+        assert_eq!(policy_config.honorary_pool_for(&primary, &pools[0].pool).unwrap(), &pools[0]);
+        assert_eq!(policy_config.honorary_pool_for(&usdc, &pools[1].pool).unwrap(), &pools[1]);
+        assert!(policy_config.honorary_pool_for(&usdc, &pools[0].pool).is_err());
+        assert!(policy_config.honorary_pool_for(&primary, &Pubkey::new_unique()).is_err());
+
+        // Swaps must use the recorded vaults, in either direction
+        let pool = &pools[0];
+        assert!(pool.input_is_base(&pool.pool, &pool.base_vault, &pool.quote_vault).unwrap());
+        assert!(!pool.input_is_base(&pool.pool, &pool.quote_vault, &pool.base_vault).unwrap());
+        assert!(pool.input_is_base(&pool.pool, &Pubkey::new_unique(), &pool.quote_vault).is_err());
+        assert!(pool.input_is_base(&pools[1].pool, &pool.base_vault, &pool.quote_vault).is_err());
+
+        // One pool per position, each fully set
+        assert!(policy_config.set_honorary_pools(&pools[..1]).is_err());
+        assert!(policy_config.set_honorary_pools(&[pools[0], HonoraryPool::default()]).is_err());
+        let same_vault = HonoraryPool { quote_vault: pools[1].base_vault, ..pools[1] };
+        assert!(policy_config.set_honorary_pools(&[pools[0], same_vault]).is_err());
+
+        // Reference prices are set per pool by its address
+        policy_config.set_reference_price(&pools[1].pool, 3 << 64).unwrap();
+        assert_eq!(policy_config.honorary_pools()[1].reference_price_q64, 3 << 64);
+        assert_eq!(policy_config.honorary_pools()[0].reference_price_q64, 0);
+        assert!(policy_config.set_reference_price(&pools[1].pool, 0).is_err());
+        assert!(policy_config.set_reference_price(&Pubkey::new_unique(), 1 << 64).is_err());
+
+        // Replacing the positions forgets their pools
+        policy_config.set_honorary_positions(&positions).unwrap();
+        assert!(policy_config.honorary_pool_for(&primary, &pools[0].pool).is_err());
+    }
+
+    #[test]
+    fn test_quote_mint_registry() {
+        let mut policy_config = create_policy_config();
+        let primary = policy_config.quote_mint;
+        let usdc = Pubkey::new_unique();
+        let positions = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        policy_config.set_honorary_positions(&positions).unwrap();
+
+        // Positions start on the primary quote mint
+        assert_eq!(policy_config.quote_mints(), &[primary]);
+        assert_eq!(policy_config.honorary_positions_for(&primary), positions.to_vec());
+        assert!(policy_config.honorary_positions_for(&usdc).is_empty());
+
+        policy_config
+            .set_honorary_position_quote_mints(&[usdc, primary, usdc])
+            .unwrap();
+        assert_eq!(policy_config.quote_mints(), &[primary, usdc]);
+        assert!(policy_config.is_quote_mint(&usdc));
+        assert!(!policy_config.is_quote_mint(&Pubkey::new_unique()));
+        assert_eq!(policy_config.honorary_positions_for(&primary), vec![positions[1]]);
+        assert_eq!(policy_config.honorary_positions_for(&usdc), vec![positions[0], positions[2]]);
+
+        // The primary ledger keeps its seedless addresses; others are seeded by mint
+        assert!(policy_config.ledger_seed(&primary).is_empty());
+        assert_eq!(policy_config.ledger_seed(&usdc), usdc.as_ref());
+
+        // Mismatched lengths, unset mints and too many mints are rejected and leave the registry untouched
+        assert!(policy_config.set_honorary_position_quote_mints(&[usdc]).is_err());
+        assert!(policy_config
+            .set_honorary_position_quote_mints(&[usdc, Pubkey::default(), usdc])
+            .is_err());
+        let too_many: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        assert!(policy_config.set_honorary_position_quote_mints(&too_many).is_err());
+        assert_eq!(policy_config.quote_mints(), &[primary, usdc]);
+    }
+
+    #[test]
+    fn test_base_fee_policy() {
+        let mut policy_config = create_policy_config();
+        let position_owner = Pubkey::new_unique();
+        assert_eq!(policy_config.base_fee_policy, BaseFeePolicy::Abort);
+        assert_eq!(policy_config.base_fee_destination_owner(&position_owner), None);
+
+        // Routing base fees needs the base mint
+        assert!(policy_config.configure_base_fees(Pubkey::default(), BaseFeePolicy::Quarantine).is_err());
+        assert_eq!(policy_config.base_fee_policy, BaseFeePolicy::Abort);
+
+        let base_mint = Pubkey::new_unique();
+        policy_config.configure_base_fees(base_mint, BaseFeePolicy::Quarantine).unwrap();
+        assert_eq!(policy_config.base_fee_destination_owner(&position_owner), Some(position_owner));
+        assert!(policy_config.validate().is_ok());
+
+        policy_config.configure_base_fees(base_mint, BaseFeePolicy::ForwardToCreator).unwrap();
+        assert_eq!(
+            policy_config.base_fee_destination_owner(&position_owner),
+            Some(policy_config.creator_wallet)
+        );
+    }
+
+    #[test]
+    fn test_swap_slippage() {
+        let mut policy_config = create_policy_config();
+        assert_eq!(policy_config.max_swap_slippage_bps, 0);
+
+        policy_config.configure_swap_slippage(150).unwrap();
+        assert_eq!(policy_config.max_swap_slippage_bps, 150);
+        assert!(policy_config.validate().is_ok());
+
+        // More than 100% slippage is rejected and leaves the bound unchanged
+        assert!(policy_config.configure_swap_slippage(MAX_BASIS_POINTS + 1).is_err());
+        assert_eq!(policy_config.max_swap_slippage_bps, 150);
+    }
+
+    #[test]
+    fn test_creator_payout_mode() {
+        let mut policy_config = create_policy_config();
+        assert_eq!(policy_config.creator_payout_mode, CreatorPayoutMode::Transfer);
+
+        // Buying back the base token needs the base mint
+        assert!(policy_config.configure_creator_payout(CreatorPayoutMode::BuybackAndBurn).is_err());
+        assert_eq!(policy_config.creator_payout_mode, CreatorPayoutMode::Transfer);
+
+        policy_config.configure_base_fees(Pubkey::new_unique(), BaseFeePolicy::Abort).unwrap();
+        policy_config.configure_creator_payout(CreatorPayoutMode::BuybackAndBurn).unwrap();
+        assert!(policy_config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_distribution_period() {
+        let mut policy_config = create_policy_config();
+        assert_eq!(policy_config.distribution_period_secs, TWENTY_FOUR_HOURS);
+
+        // Hourly and weekly periods are accepted
+        policy_config.configure_distribution_period(Some(3600)).unwrap();
+        assert_eq!(policy_config.distribution_period_secs, 3600);
+        policy_config.configure_distribution_period(Some(7 * TWENTY_FOUR_HOURS)).unwrap();
+        assert!(policy_config.validate().is_ok());
+
+        // Unset falls back to 24 hours
+        policy_config.configure_distribution_period(None).unwrap();
+        assert_eq!(policy_config.distribution_period_secs, TWENTY_FOUR_HOURS);
+
+        // Out-of-bounds periods are rejected and leave the policy unchanged
+        assert!(policy_config.configure_distribution_period(Some(0)).is_err());
+        assert!(policy_config.configure_distribution_period(Some(MIN_DISTRIBUTION_PERIOD_SECS - 1)).is_err());
+        assert!(policy_config.configure_distribution_period(Some(MAX_DISTRIBUTION_PERIOD_SECS + 1)).is_err());
+        assert_eq!(policy_config.distribution_period_secs, TWENTY_FOUR_HOURS);
+
+        policy_config.distribution_period_secs = -1;
+        assert!(policy_config.validate().is_err());
+    }
+
+    #[test]
+    fn test_rolling_cap_window() {
+        let mut policy_config = create_policy_config();
+        policy_config.configure_caps(None, Some(50_000)).unwrap();
+
+        // Periods dividing the 7-day window into at most ROLLING_CAP_SLOTS periods
+        for period in [TWENTY_FOUR_HOURS, 7 * TWENTY_FOUR_HOURS / 2, 7 * TWENTY_FOUR_HOURS] {
+            policy_config.configure_distribution_period(Some(period)).unwrap();
+            assert!(policy_config.validate().is_ok());
+        }
+
+        // Hourly periods need more slots, and 2 or 30 days do not divide the window
+        for period in [3600, 2 * TWENTY_FOUR_HOURS, 30 * TWENTY_FOUR_HOURS] {
+            assert!(policy_config.configure_distribution_period(Some(period)).is_err());
+        }
+        assert_eq!(policy_config.distribution_period_secs, 7 * TWENTY_FOUR_HOURS);
+
+        // Without a rolling cap any period is accepted
+        policy_config.configure_caps(None, None).unwrap();
+        policy_config.configure_distribution_period(Some(3600)).unwrap();
+        policy_config.rolling_cap_lamports = Some(50_000);
+        assert!(policy_config.validate().is_err());
+    }
+
+    #[test]
+    fn test_epoch_alignment() {
+        let mut policy_config = create_policy_config();
+        assert_eq!(policy_config.epoch_anchor_ts, None);
+
+        // Anchors must be set and not in the future
+        assert!(policy_config.configure_epoch_alignment(Some(0), 1_000).is_err());
+        assert!(policy_config.configure_epoch_alignment(Some(1_001), 1_000).is_err());
+        assert_eq!(policy_config.epoch_anchor_ts, None);
+
+        policy_config.configure_epoch_alignment(Some(1_000), 1_000).unwrap();
+        assert_eq!(policy_config.epoch_anchor_ts, Some(1_000));
+        assert!(policy_config.validate().is_ok());
+
+        policy_config.configure_epoch_alignment(None, 1_000).unwrap();
+        assert_eq!(policy_config.epoch_anchor_ts, None);
+    }
+
+    #[test]
+    fn test_configure_crank_reward() {
+        let mut policy_config = create_policy_config();
+        assert_eq!(policy_config.crank_reward, CrankReward::None);
+        assert_eq!(policy_config.crank_reward_per_day(1_000_000), 0);
+
+        // Paying rewards need a non-zero amount within bounds and a daily cap
+        assert!(policy_config.configure_crank_reward(CrankReward::Bps { bps: 0 }, 1_000).is_err());
+        assert!(policy_config.configure_crank_reward(CrankReward::Bps { bps: MAX_CRANK_REWARD_BPS + 1 }, 1_000).is_err());
+        assert!(policy_config.configure_crank_reward(CrankReward::FlatLamports { lamports: 0 }, 1_000).is_err());
+        assert!(policy_config.configure_crank_reward(CrankReward::Bps { bps: 10 }, 0).is_err());
+        assert_eq!(policy_config.crank_reward, CrankReward::None);
+
+        policy_config.configure_crank_reward(CrankReward::Bps { bps: 10 }, 1_000).unwrap();
+        assert_eq!(policy_config.crank_reward_per_day(1_000_000), 1_000);
+        assert!(policy_config.validate().is_ok());
+
+        policy_config.configure_crank_reward(CrankReward::FlatLamports { lamports: 250 }, 1_000).unwrap();
+        assert_eq!(policy_config.crank_reward_per_day(1_000_000), 250);
+        assert_eq!(policy_config.crank_reward_daily_cap_lamports, 1_000);
+
+        policy_config.configure_crank_reward(CrankReward::None, 0).unwrap();
+        assert_eq!(policy_config.crank_reward_per_day(1_000_000), 0);
+    }
+
+    fn create_staking_layout() -> StakingLayout {
+        StakingLayout {
+            program_id: Pubkey::new_unique(),
+            stake_mint: Pubkey::new_unique(),
+            discriminator: [1, 2, 3, 4, 5, 6, 7, 8],
+            owner_offset: 8,
+            mint_offset: 40,
+            amount_offset: 72,
+        }
+    }
+
+    #[test]
+    fn test_configure_staked_weight_mode() {
+        let mut policy_config = create_policy_config();
+        assert_eq!(policy_config.weight_mode, WeightMode::LockedVesting);
+
+        let layout = create_staking_layout();
+        assert_eq!(layout.min_account_len(), 80);
+
+        policy_config
+            .configure_weight_mode(WeightMode::StakedBalance, Some(layout))
+            .unwrap();
+        assert_eq!(policy_config.weight_mode, WeightMode::StakedBalance);
+        assert_eq!(policy_config.staking_layout, layout);
+        assert!(policy_config.validate().is_ok());
+
+        // Switching back clears the layout
+        policy_config.configure_weight_mode(WeightMode::LockedVesting, None).unwrap();
+        assert_eq!(policy_config.staking_layout, StakingLayout::default());
+    }
+
+    #[test]
+    fn test_invalid_staking_layout() {
+        let mut policy_config = create_policy_config();
+
+        // Stake mode requires a layout
+        assert!(policy_config.configure_weight_mode(WeightMode::StakedBalance, None).is_err());
+
+        // Overlapping fields
+        let mut layout = create_staking_layout();
+        layout.mint_offset = 20;
+        assert!(layout.validate().is_err());
+
+        // Field overlapping the discriminator
+        let mut layout = create_staking_layout();
+        layout.owner_offset = 4;
+        assert!(layout.validate().is_err());
+
+        // Missing program
+        let mut layout = create_staking_layout();
+        layout.program_id = Pubkey::default();
+        assert!(policy_config
+            .configure_weight_mode(WeightMode::StakedBalance, Some(layout))
+            .is_err());
+        assert_eq!(policy_config.weight_mode, WeightMode::LockedVesting);
+
+        // A stake-weighted policy without a layout fails validation
+        policy_config.weight_mode = WeightMode::StakedBalance;
+        assert!(policy_config.validate().is_err());
+    }
+
+    #[test]
+    fn test_configure_caps() {
+        let mut policy_config = create_policy_config();
+
+        policy_config.configure_caps(Some(5_000), Some(50_000)).unwrap();
+        assert_eq!(policy_config.per_investor_daily_cap_lamports, Some(5_000));
+        assert_eq!(policy_config.rolling_cap_lamports, Some(50_000));
+        assert!(policy_config.validate().is_ok());
+
+        // Zero caps are rejected and leave the policy untouched
+        assert!(policy_config.configure_caps(Some(0), None).is_err());
+        assert!(policy_config.configure_caps(None, Some(0)).is_err());
+        assert_eq!(policy_config.rolling_cap_lamports, Some(50_000));
+
+        policy_config.configure_caps(None, None).unwrap();
+        assert!(policy_config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_migrate_unversioned_policy() {
+        /// Policy layout written before the account was versioned
+        #[derive(AnchorSerialize)]
+        struct LegacyPolicyConfig {
+            vault: Pubkey,
+            quote_mint: Pubkey,
+            creator_wallet: Pubkey,
+            investor_fee_share_bps: u16,
+            daily_cap_lamports: Option<u64>,
+            min_payout_lamports: u64,
+            y0_total_allocation: u64,
+            bump: u8,
+        }
+
+        let legacy = LegacyPolicyConfig {
+            vault: Pubkey::new_unique(),
+            quote_mint: Pubkey::new_unique(),
+            creator_wallet: Pubkey::new_unique(),
+            investor_fee_share_bps: 7000,
+            daily_cap_lamports: Some(1_000_000),
+            min_payout_lamports: 1000,
+            y0_total_allocation: 5_000_000,
+            bump: 254,
+        };
+
+        // The account is grown to the current size with zero padding before decoding
+        let mut data = PolicyConfig::DISCRIMINATOR.to_vec();
+        legacy.serialize(&mut data).unwrap();
+        data.resize(8 + PolicyConfig::INIT_SPACE, 0);
+
+        let mut policy_config = PolicyConfig::try_deserialize(&mut &data[..]).unwrap();
+        assert_eq!(policy_config.layout_version, 0);
+
+        let position = Pubkey::new_unique();
+        policy_config.migrate_layout(&[position]).unwrap();
+        assert_eq!(policy_config.layout_version, ACCOUNT_LAYOUT_VERSION);
+        assert_eq!(policy_config.vault, legacy.vault);
+        assert_eq!(policy_config.creator_wallet, legacy.creator_wallet);
+        assert_eq!(policy_config.daily_cap_lamports, Some(1_000_000));
+        assert_eq!(policy_config.bump, 254);
+        assert_eq!(policy_config.allowed_vesting_programs(), &[STREAMFLOW_PROGRAM_ID]);
+        assert_eq!(policy_config.quote_mints(), &[legacy.quote_mint]);
+        assert_eq!(policy_config.honorary_positions(), &[position]);
+        assert_eq!(policy_config.authority, legacy.creator_wallet);
+        assert_eq!(policy_config.distribution_period_secs, TWENTY_FOUR_HOURS);
+        assert!(policy_config.validate().is_ok());
+
+        // A migrated policy is not migrated again
+        assert!(policy_config.migrate_layout(&[position]).is_err());
+    }
+}
@@ -1,76 +1,192 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount, Transfer, transfer};
+use anchor_spl::token::{Burn, Mint, Token, TokenAccount, Transfer, burn, transfer};
 use anchor_spl::associated_token::AssociatedToken;
 
 use crate::{
     constants::*,
     error::ErrorCode,
-    state::{PolicyConfig, DistributionProgress},
-    utils::math::calculate_distribution,
-    CreatorPayoutDayClosed,
+    state::{CreatorPayoutMode, PolicyConfig, DistributionProgress},
+    utils::{
+        math::calculate_distribution,
+        swap::{policy_minimum_out, swap_through_pool, PoolSwapAccounts},
+    },
 };
 
+/// Accounts used to buy back and burn the base token with the creator share
+pub struct BuybackAccounts<'a, 'info> {
+    /// Honorary pool trading the base mint against the payout's quote mint
+    pub pool: &'a AccountInfo<'info>,
+    pub pool_base_vault: &'a Account<'info, TokenAccount>,
+    pub pool_quote_vault: &'a Account<'info, TokenAccount>,
+    /// The policy's base mint, whose supply the burn reduces
+    pub base_mint: &'a Account<'info, Mint>,
+    /// The payout's quote mint (the swap's input mint)
+    pub quote_mint: &'a AccountInfo<'info>,
+    pub pool_authority: &'a AccountInfo<'info>,
+    pub event_authority: &'a AccountInfo<'info>,
+    /// Base treasury the bought-back base passes through before it is burned
+    pub base_treasury_ata: &'a Account<'info, TokenAccount>,
+    pub cp_amm_program: &'a AccountInfo<'info>,
+}
+
+/// Accounts the creator share is paid out of and into
+pub struct CreatorPayoutAccounts<'a, 'info> {
+    pub token_program: &'a Program<'info, Token>,
+    pub treasury_ata: &'a Account<'info, TokenAccount>,
+    pub creator_ata: &'a Account<'info, TokenAccount>,
+    pub position_owner_pda: &'a AccountInfo<'info>,
+    /// Required when the creator payout mode is BuybackAndBurn
+    pub buyback: Option<&'a BuybackAccounts<'a, 'info>>,
+}
+
 /// Creator distribution system for remainder fee payouts
 pub struct CreatorDistribution;
 
 impl CreatorDistribution {
-    /// Process creator remainder payout when day is complete
-    pub fn process_creator_payout<'info>(
+    /// Pay the creator share under the policy's creator payout mode
+    /// Returns the base amount burned (zero when the share is transferred), or `None` when
+    /// a buyback cannot run and the share is left in the treasury
+    pub fn execute_creator_payout(
+        amount: u64,
+        policy_config: &PolicyConfig,
+        accounts: &CreatorPayoutAccounts,
+    ) -> Result<Option<u64>> {
+        match policy_config.creator_payout_mode {
+            CreatorPayoutMode::Transfer => {
+                Self::execute_creator_transfer(
+                    amount,
+                    policy_config,
+                    accounts.token_program,
+                    accounts.treasury_ata,
+                    accounts.creator_ata,
+                    accounts.position_owner_pda,
+                )?;
+                Ok(Some(0))
+            },
+            CreatorPayoutMode::BuybackAndBurn => {
+                // Investor payouts and the day close must not wait on the swap: without
+                // buyback accounts or a reference price to bound it, the share is held
+                let Some(buyback) = accounts.buyback else {
+                    msg!("No buyback accounts passed, holding the creator share");
+                    return Ok(None);
+                };
+                let honorary_pool = policy_config.honorary_pool_for(&accounts.treasury_ata.mint, &buyback.pool.key())?;
+                if honorary_pool.reference_price_q64 == 0 {
+                    msg!("Pool {} has no reference price, holding the creator share", honorary_pool.pool);
+                    return Ok(None);
+                }
+
+                Self::execute_buyback_and_burn(
+                    amount,
+                    policy_config,
+                    accounts.token_program,
+                    accounts.treasury_ata,
+                    accounts.position_owner_pda,
+                    buyback,
+                )
+                .map(Some)
+            },
+        }
+    }
+
+    /// Validate buyback accounts: the policy's base mint and base treasury, and the
+    /// recorded honorary pool and vaults trading it against the treasury's quote mint
+    pub fn validate_buyback_accounts(
+        buyback: &BuybackAccounts,
+        policy_config: &PolicyConfig,
+        quote_mint: &Pubkey,
+        position_owner: &Pubkey,
+    ) -> Result<()> {
+        require!(
+            buyback.base_mint.key() == policy_config.base_mint
+                && buyback.base_treasury_ata.mint == policy_config.base_mint
+                && buyback.base_treasury_ata.owner == *position_owner,
+            ErrorCode::InvalidBuybackAccounts
+        );
+        require!(
+            buyback.quote_mint.key() == *quote_mint
+                && buyback.pool_base_vault.mint == policy_config.base_mint
+                && buyback.pool_quote_vault.mint == *quote_mint,
+            ErrorCode::InvalidBuybackAccounts
+        );
+        let input_is_base = policy_config.honorary_pool_for(quote_mint, &buyback.pool.key())?.input_is_base(
+            &buyback.pool.key(),
+            &buyback.pool_quote_vault.key(),
+            &buyback.pool_base_vault.key(),
+        )?;
+        require!(!input_is_base, ErrorCode::InvalidSwapPool);
+
+        Ok(())
+    }
+
+    /// Swap the creator share into the base token through the pool and burn what it buys
+    fn execute_buyback_and_burn<'info>(
+        amount: u64,
         policy_config: &PolicyConfig,
-        distribution_progress: &DistributionProgress,
-        claimed_quote_amount: u64,
-        total_locked_amount: u64,
-        current_timestamp: i64,
         token_program: &Program<'info, Token>,
-        _associated_token_program: &Program<'info, AssociatedToken>,
-        _system_program: &Program<'info, System>,
         treasury_ata: &Account<'info, TokenAccount>,
-        creator_ata: &Account<'info, TokenAccount>,
         position_owner_pda: &AccountInfo<'info>,
+        buyback: &BuybackAccounts<'_, 'info>,
     ) -> Result<u64> {
-        // Calculate creator remainder amount
-        let (total_investor_amount, creator_amount) = calculate_distribution(
-            claimed_quote_amount,
-            total_locked_amount,
-            policy_config.y0_total_allocation,
-            policy_config.investor_fee_share_bps,
-        )?;
-
-        msg!("Creator payout calculation: total_claimed={}, investor_amount={}, creator_amount={}", 
-             claimed_quote_amount, total_investor_amount, creator_amount);
-
-        // Validate creator ATA
-        Self::validate_creator_ata(creator_ata, &policy_config.creator_wallet, &policy_config.quote_mint)?;
+        Self::validate_buyback_accounts(buyback, policy_config, &treasury_ata.mint, &position_owner_pda.key())?;
+        require!(
+            treasury_ata.amount >= amount,
+            ErrorCode::InsufficientFunds
+        );
 
-        // Execute creator payout if there's an amount to transfer
-        if creator_amount > 0 {
-            Self::execute_creator_transfer(
-                creator_amount,
-                policy_config,
-                token_program,
-                treasury_ata,
-                creator_ata,
+        // The crank has no say in the price, and the pool's reserves can be moved before
+        // the swap: the minimum out is the policy's slippage bound below the authority's
+        // reference price
+        let honorary_pool = policy_config.honorary_pool_for(&treasury_ata.mint, &buyback.pool.key())?;
+        let minimum_amount_out = policy_minimum_out(honorary_pool, amount, false, policy_config.max_swap_slippage_bps)?;
+
+        let base_bought = swap_through_pool(
+            PoolSwapAccounts {
+                honorary_pool,
+                pool: buyback.pool,
+                pool_authority: buyback.pool_authority,
                 position_owner_pda,
-            )?;
+                input_account: treasury_ata,
+                output_account: buyback.base_treasury_ata,
+                pool_input_vault: buyback.pool_quote_vault,
+                pool_output_vault: buyback.pool_base_vault,
+                input_mint: buyback.quote_mint,
+                output_mint: &buyback.base_mint.to_account_info(),
+                event_authority: buyback.event_authority,
+                cp_amm_program: buyback.cp_amm_program,
+                token_program,
+            },
+            &policy_config.vault,
+            policy_config.bump,
+            amount,
+            minimum_amount_out,
+        )?;
 
-            msg!("Creator payout executed: {} tokens transferred to {}", 
-                 creator_amount, policy_config.creator_wallet);
-        } else {
-            msg!("No creator payout needed (amount: 0)");
+        if base_bought > 0 {
+            let bump_seed = [policy_config.bump];
+            let signer_seeds: &[&[u8]] = &[
+                VAULT_SEED,
+                policy_config.vault.as_ref(),
+                b"investor_fee_pos_owner",
+                &bump_seed,
+            ];
+            let signer_seeds_slice = &[signer_seeds];
+
+            let burn_ctx = CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                Burn {
+                    mint: buyback.base_mint.to_account_info(),
+                    from: buyback.base_treasury_ata.to_account_info(),
+                    authority: position_owner_pda.to_account_info(),
+                },
+                signer_seeds_slice,
+            );
+            burn(burn_ctx, base_bought)?;
         }
 
-        // Emit creator payout event
-        emit!(CreatorPayoutDayClosed {
-            vault: policy_config.vault,
-            creator_payout: creator_amount,
-            creator_wallet: policy_config.creator_wallet,
-            total_day_distributed: distribution_progress.current_day_distributed + creator_amount,
-            total_investors_processed: 0, // This will be set by the caller
-            final_dust_amount: distribution_progress.carry_over_dust,
-            timestamp: current_timestamp,
-        });
+        msg!("Creator buyback executed: {} quote swapped, {} base burned", amount, base_bought);
 
-        Ok(creator_amount)
+        Ok(base_bought)
     }
 
     /// Calculate creator remainder amount without executing transfer
@@ -157,6 +273,58 @@ impl CreatorDistribution {
         Ok(())
     }
 
+    /// Validate the crank caller's reward ATA: owned by the caller, in the day's quote mint
+    pub fn validate_crank_reward_ata(
+        crank_reward_ata: &Account<TokenAccount>,
+        crank_caller: &Pubkey,
+        expected_mint: &Pubkey,
+    ) -> Result<()> {
+        require!(
+            crank_reward_ata.owner == *crank_caller && crank_reward_ata.mint == *expected_mint,
+            ErrorCode::InvalidCrankRewardAta
+        );
+        Ok(())
+    }
+
+    /// Pay a crank reward from the treasury to the crank caller's reward ATA
+    pub fn execute_crank_reward<'info>(
+        amount: u64,
+        policy_config: &PolicyConfig,
+        token_program: &Program<'info, Token>,
+        treasury_ata: &Account<'info, TokenAccount>,
+        crank_reward_ata: &Account<'info, TokenAccount>,
+        position_owner_pda: &AccountInfo<'info>,
+    ) -> Result<()> {
+        require!(
+            treasury_ata.amount >= amount,
+            ErrorCode::InsufficientFunds
+        );
+
+        let bump_seed = [policy_config.bump];
+        let signer_seeds: &[&[u8]] = &[
+            VAULT_SEED,
+            policy_config.vault.as_ref(),
+            b"investor_fee_pos_owner",
+            &bump_seed,
+        ];
+        let signer_seeds_slice = &[signer_seeds];
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            Transfer {
+                from: treasury_ata.to_account_info(),
+                to: crank_reward_ata.to_account_info(),
+                authority: position_owner_pda.to_account_info(),
+            },
+            signer_seeds_slice,
+        );
+        transfer(transfer_ctx, amount)?;
+
+        msg!("Crank reward executed: {} tokens from treasury to crank caller", amount);
+
+        Ok(())
+    }
+
     /// Check if creator ATA exists and create if needed (for future enhancement)
     pub fn ensure_creator_ata<'info>(
         creator_wallet: &Pubkey,
@@ -244,6 +412,7 @@ pub struct DayCompletionStats {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::state::{CrankReward, DustPolicy};
     use anchor_lang::prelude::Pubkey;
 
     fn create_mock_policy_config() -> PolicyConfig {
@@ -256,6 +425,7 @@ mod tests {
             min_payout_lamports: 1000,
             y0_total_allocation: 10_000_000,
             bump: 255,
+            ..PolicyConfig::default()
         }
     }
 
@@ -268,9 +438,54 @@ mod tests {
             pagination_cursor: 100,
             day_complete: true,
             bump: 255,
+            ..DistributionProgress::default()
         }
     }
 
+    #[test]
+    fn test_crank_reward_comes_from_creator_remainder() {
+        let mut policy_config = create_mock_policy_config();
+        let mut progress = create_mock_distribution_progress();
+        progress.current_day_distributed = 0;
+        progress.carry_over_dust = 0;
+        progress.day_complete = false;
+        progress.record_treasury_opening(0);
+        progress.record_treasury_inflow(10_000).unwrap();
+        progress.record_day_claim(10_000).unwrap();
+        progress.day_investor_share = 9_000;
+        progress.day_investor_count = 4;
+        progress.pagination_cursor = 2;
+        assert_eq!(progress.crank_reward_due(&policy_config, 0).unwrap(), 0);
+
+        // 1% of the claim a day, half of it for a page covering half the investors
+        policy_config.configure_crank_reward(CrankReward::Bps { bps: 100 }, 250).unwrap();
+        assert_eq!(progress.crank_reward_due(&policy_config, 0).unwrap(), 50);
+        progress.record_crank_reward(50).unwrap();
+
+        // Capped at 250 a day
+        policy_config.configure_crank_reward(CrankReward::FlatLamports { lamports: 1_000 }, 250).unwrap();
+        progress.pagination_cursor = 3;
+        assert_eq!(progress.crank_reward_due(&policy_config, 2).unwrap(), 200);
+        progress.record_crank_reward(200).unwrap();
+        progress.pagination_cursor = 4;
+        assert_eq!(progress.crank_reward_due(&policy_config, 3).unwrap(), 0);
+
+        // Rewards never reach into the investor share
+        policy_config.configure_crank_reward(CrankReward::FlatLamports { lamports: 5_000 }, 5_000).unwrap();
+        assert_eq!(progress.crank_reward_due(&policy_config, 0).unwrap(), 750);
+
+        // The creator is paid what the rewards left and the treasury is emptied
+        progress.add_distributed(9_000).unwrap();
+        let (creator_remainder, _) = progress.settle_creator_payout(DustPolicy::CarryForward).unwrap();
+        assert_eq!(creator_remainder, 750);
+        progress.verify_day_conservation(0, creator_remainder).unwrap();
+
+        // The next day starts with nothing paid
+        progress.complete_day();
+        progress.start_new_day(1000 + TWENTY_FOUR_HOURS, TWENTY_FOUR_HOURS, None).unwrap();
+        assert_eq!(progress.day_crank_reward_paid, 0);
+    }
+
     #[test]
     fn test_calculate_creator_remainder_basic() {
     let policy_config = create_mock_policy_config();
@@ -0,0 +1,555 @@
+use anchor_lang::prelude::*;
+use std::collections::HashMap;
+
+use crate::{
+    constants::*,
+    error::ErrorCode,
+    state::{
+        registry_hash, DayOpening, DaySettlement, DistributionProgress, DistributionTimingState,
+        InvestorAccrual, PolicyConfig, UnfinishedDay,
+    },
+    utils::{
+        investor_distribution::{BatchPayoutResult, CatchUpEpochShare, InvestorDistribution},
+        math::{evaluate_cap_policy, CapPolicy},
+        vesting::InvestorData,
+        weights::WeightWindow,
+    },
+};
+
+/// The accounts a `distribute_fees` call resolves, behind the day ledger's state transitions
+///
+/// The instruction implements it over its token accounts and CPIs, the simulator over
+/// parsed streams and a modelled treasury balance.
+pub trait DistributionAccounts {
+    /// Current balance of the quote treasury
+    fn treasury_balance(&mut self) -> Result<u64>;
+
+    /// Claim the quote mint's honorary positions into the treasury, returning the quote claimed
+    fn claim_position_fees(&mut self) -> Result<u64>;
+
+    /// Whether the call carries any weight accounts
+    fn has_weight_accounts(&self) -> bool;
+
+    /// Investors of the call's weight accounts over `window`, one entry per wallet: the
+    /// whole registry on the call that opens the day, the page at `cursor` after it
+    fn load_investors(
+        &mut self,
+        window: WeightWindow,
+        opens_day: bool,
+        cursor: usize,
+        page_size: usize,
+    ) -> Result<Vec<InvestorData>>;
+
+    /// Registry hash of the committed registry's entries after a later page ending at
+    /// `page_end`, which the page's investors must chain to before they are paid
+    fn next_registry_hash(&self, window: WeightWindow, page_end: usize) -> Result<[u8; 32]>;
+
+    /// Locked total over a past epoch's window, for catch-up days
+    fn locked_total(&self, window: WeightWindow) -> Result<u64>;
+
+    /// Accrual balances of the page's investors that have one
+    fn load_accruals(&mut self, page_investors: &[InvestorData]) -> Result<HashMap<Pubkey, InvestorAccrual>>;
+
+    /// Persist the page's accruals and transfer its payouts and dust out of the treasury
+    fn execute_page(&mut self, page: &BatchPayoutResult, accruals: &HashMap<Pubkey, InvestorAccrual>) -> Result<()>;
+
+    /// Whether the call passed an account to receive the crank reward
+    fn has_crank_reward_account(&self) -> bool;
+
+    /// Transfer the crank reward out of the treasury
+    fn pay_crank_reward(&mut self, amount: u64) -> Result<()>;
+
+    /// Pay the creator at day close, returning the base token a buyback burned, or `None`
+    /// when the buyback cannot run and the payout stays in the treasury
+    fn pay_creator(&mut self, amount: u64) -> Result<Option<u64>>;
+}
+
+/// The day's investor pool, opened by the day's first page
+#[derive(Debug, Clone)]
+pub struct DayPoolOpened {
+    pub opening: DayOpening,
+    /// Investor share of the day's claim
+    pub investor_share: u64,
+    /// Per-epoch breakdown of a catch-up day's investor share
+    pub catch_up_shares: Vec<CatchUpEpochShare>,
+    /// Locked total of the committed registry
+    pub total_locked: u64,
+    /// Investors in the committed registry
+    pub investor_count: usize,
+}
+
+/// One page of investors
+#[derive(Debug)]
+pub struct PageStep {
+    pub page_start: u32,
+    pub page_end: u32,
+    /// Capped investor pool the page paid out of
+    pub payable_pool: u64,
+    /// Payouts, unless the page had no investor with a locked amount and was skipped
+    pub payouts: Option<BatchPayoutResult>,
+    /// Crank reward paid for the page
+    pub crank_reward: u64,
+}
+
+/// Settlement of a day whose pages covered the registry
+#[derive(Debug, Clone, Copy)]
+pub struct DayClose {
+    pub settlement: DaySettlement,
+    /// Creator payout that left the treasury
+    pub creator_payout: u64,
+    /// Creator payout held as creator carry because the buyback could not run
+    pub creator_held: u64,
+    pub base_burned: u64,
+}
+
+/// What one `distribute_fees` call did to the day ledger
+#[derive(Debug)]
+pub struct DistributionStep {
+    pub timing: DistributionTimingState,
+    /// The call replayed a page already processed and changed nothing
+    pub replayed: bool,
+    /// What the day this call replaced left unsettled
+    pub unfinished_day: Option<UnfinishedDay>,
+    /// Quote added to the day's claim: the positions' fees, with converted and carried quote
+    pub claimed_quote: u64,
+    pub pool_opened: Option<DayPoolOpened>,
+    pub page: Option<PageStep>,
+    pub close: Option<DayClose>,
+}
+
+/// Run one `distribute_fees` call against the day ledger
+///
+/// Opens the next day when due (snapshotting the treasury and claiming the positions),
+/// opens the day's investor pool and commits the registry on its first page, plans and
+/// executes the page at the cursor, pays the crank reward and, once the pages cover the
+/// registry, settles the creator and closes the day against the treasury balance.
+pub fn run_distribution_step<A: DistributionAccounts>(
+    policy_config: &PolicyConfig,
+    progress: &mut DistributionProgress,
+    accounts: &mut A,
+    page_size: u32,
+    cursor_position: Option<u32>,
+    current_timestamp: i64,
+) -> Result<DistributionStep> {
+    require!(
+        page_size > 0 && page_size <= MAX_PAGE_SIZE,
+        ErrorCode::InvalidPaginationCursor
+    );
+
+    // What the current day leaves unsettled if this call opens the next one
+    let unfinished_day = progress.unfinished_day()?;
+    let timing = progress.prepare_for_distribution(
+        current_timestamp,
+        policy_config.distribution_period_secs,
+        policy_config.epoch_anchor_ts,
+    )?;
+    let is_new_day = timing == DistributionTimingState::NewDay;
+
+    let mut step = DistributionStep {
+        timing,
+        replayed: false,
+        unfinished_day: if is_new_day { unfinished_day } else { None },
+        claimed_quote: 0,
+        pool_opened: None,
+        page: None,
+        close: None,
+    };
+
+    // A page pinned to a cursor already passed is a retry that already landed
+    if let Some(requested_cursor) = cursor_position {
+        if progress.validate_cursor_for_retry(requested_cursor)? {
+            msg!("Idempotent retry detected for cursor position: {}", requested_cursor);
+            step.replayed = true;
+            return Ok(step);
+        }
+    }
+
+    // Claim the positions on a new day; the day's close checks the treasury against its
+    // opening balance and what flows in
+    if is_new_day {
+        let opening_treasury = accounts.treasury_balance()?;
+        progress.record_treasury_opening(opening_treasury);
+        let position_quote = accounts.claim_position_fees()?;
+        let claim_inflow = accounts
+            .treasury_balance()?
+            .checked_sub(opening_treasury)
+            .ok_or(ErrorCode::AccountingInvariantViolated)?;
+        progress.record_treasury_inflow(claim_inflow)?;
+
+        // Quote converted from base fees since the last day joins today's claim, and so
+        // does the claim of an unfinished day whose investor pool never opened
+        let converted_quote = progress.take_converted_base_quote();
+        if converted_quote > 0 {
+            msg!("Crediting {} quote converted from base fees to today's claim", converted_quote);
+        }
+        let carried_claim = progress.take_carried_claim_quote();
+        if carried_claim > 0 {
+            msg!("Crediting {} quote carried from an unfinished day to today's claim", carried_claim);
+        }
+
+        step.claimed_quote = position_quote
+            .checked_add(converted_quote)
+            .and_then(|total| total.checked_add(carried_claim))
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+    }
+    progress.record_day_claim(step.claimed_quote)?;
+
+    // Page only while there is something to pay out of: today's claim, carried dust, cap
+    // overflow rolling into a new day, a continuing day's pool or a creator carry
+    let has_funds = step.claimed_quote > 0
+        || progress.carry_over_dust > 0
+        || (is_new_day && progress.cap_overflow_carry > 0)
+        || (!is_new_day && progress.day_investor_pool > 0)
+        || progress.creator_carry > 0;
+    if !accounts.has_weight_accounts() || !has_funds {
+        msg!("No weight accounts provided or no fees to distribute");
+        return Ok(step);
+    }
+
+    // The day's first call supplies the whole registry, later calls only their page
+    let weight_window = progress.weight_window();
+    let supplied_investors = accounts.load_investors(
+        weight_window,
+        is_new_day,
+        progress.pagination_cursor as usize,
+        page_size as usize,
+    )?;
+
+    // Open the day's investor pool once, at day start: today's share plus cap overflow
+    // withheld on previous days (and carried dust under CarryForward), with the part the
+    // daily cap allows fixed as the day's payable pool
+    let (page_investors, next_registry_hash) = if is_new_day {
+        let total_locked = supplied_investors
+            .iter()
+            .try_fold(0u64, |total, investor| total.checked_add(investor.locked_amount))
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        msg!("Total investor weight across {} investors ({:?}, window {}..{}): {}",
+             supplied_investors.len(), policy_config.weight_mode, weight_window.start_ts,
+             weight_window.end_ts, total_locked);
+
+        // Today's investor share of the claim, per missed epoch on a catch-up day
+        let (investor_share, catch_up_shares) = InvestorDistribution::day_investor_share(
+            policy_config,
+            progress,
+            step.claimed_quote,
+            total_locked,
+            |window| accounts.locked_total(window),
+        )?;
+
+        let opening = progress.open_day(investor_share, policy_config)?;
+        if opening.cap_rollover > 0 || opening.dust_rollover > 0 {
+            msg!("Rolled {} of cap overflow and {} of dust into today's investor pool",
+                 opening.cap_rollover, opening.dust_rollover);
+        }
+        msg!("Opened day investor pool {}: deferred={}, binding={:?}",
+             opening.investor_pool, opening.deferred, opening.binding);
+
+        // Commit the registry so later pages only carry their own investors
+        let payable_pool = progress.payable_investor_pool()?;
+        progress.commit_day_registry(&supplied_investors, payable_pool)?;
+        step.pool_opened = Some(DayPoolOpened {
+            opening,
+            investor_share,
+            catch_up_shares,
+            total_locked,
+            investor_count: supplied_investors.len(),
+        });
+
+        // The opening page sees the whole registry, so it hashes the entries after it
+        let mut page_investors = supplied_investors;
+        let next_registry_hash = registry_hash(page_investors.get(page_size as usize..).unwrap_or_default())?;
+        page_investors.truncate(page_size as usize);
+        (page_investors, next_registry_hash)
+    } else {
+        require!(
+            supplied_investors.len() <= page_size as usize,
+            ErrorCode::InvalidPaginationCursor
+        );
+        let page_end = progress.pagination_cursor as usize + supplied_investors.len();
+        let next_registry_hash = accounts.next_registry_hash(weight_window, page_end)?;
+        (supplied_investors, next_registry_hash)
+    };
+
+    // Every page pays out of the same capped pool
+    let payable_pool = progress.payable_investor_pool()?;
+    let cap_check = evaluate_cap_policy(
+        progress,
+        &CapPolicy::for_day(policy_config, progress),
+        payable_pool,
+    )?;
+    if let Some(remaining_cap) = cap_check.remaining_capacity {
+        msg!("Cap check: remaining={}, binding={:?}", remaining_cap, cap_check.binding);
+    }
+
+    let page_start = progress.pagination_cursor;
+    if !page_investors.iter().any(|investor| investor.locked_amount > 0) {
+        msg!("No eligible investors found in current page, advancing cursor");
+        progress.record_registry_page(&page_investors, next_registry_hash)?;
+        progress.advance_cursor(page_investors.len() as u32)?;
+        step.page = Some(PageStep {
+            page_start,
+            page_end: progress.pagination_cursor,
+            payable_pool,
+            payouts: None,
+            crank_reward: 0,
+        });
+        return Ok(step);
+    }
+
+    let mut accruals = accounts.load_accruals(&page_investors)?;
+    let payouts = InvestorDistribution::plan_investor_page(
+        policy_config,
+        progress,
+        &page_investors,
+        next_registry_hash,
+        payable_pool,
+        &mut accruals,
+        current_timestamp,
+    )?;
+    accounts.execute_page(&payouts, &accruals)?;
+    msg!("Batch processing complete: paid={}, dust={}, processed={}",
+         payouts.total_paid, payouts.total_dust, payouts.processed_count);
+
+    // Reward the crank caller for the investors the page processed, out of the creator's
+    // remainder
+    let mut crank_reward = 0;
+    if accounts.has_crank_reward_account() {
+        crank_reward = progress.crank_reward_due(policy_config, page_start)?;
+        if crank_reward > 0 {
+            accounts.pay_crank_reward(crank_reward)?;
+            progress.record_crank_reward(crank_reward)?;
+        }
+    }
+
+    step.page = Some(PageStep {
+        page_start,
+        page_end: progress.pagination_cursor,
+        payable_pool,
+        payouts: Some(payouts),
+        crank_reward,
+    });
+
+    // The pages must have covered exactly the committed registry; the creator takes the
+    // remainder of the day's claim (stored at day start, so continuation pages see it
+    // too), plus the day's remaining dust if the policy sweeps it
+    if progress.pagination_cursor >= progress.day_investor_count {
+        msg!("All investors processed for the day, processing creator remainder payout");
+        let settlement = progress.settle_day(policy_config.dust_policy, payable_pool)?;
+        if settlement.unallocated > 0 {
+            msg!("Deferred {} of the investor pool left unallocated by the day's pages", settlement.unallocated);
+        }
+        if settlement.dust_sweep > 0 {
+            msg!("Sweeping {} of dust to the creator at day close", settlement.dust_sweep);
+        }
+
+        let creator_due = settlement.creator_payout()?;
+        let (creator_payout, creator_held, base_burned) = if creator_due > 0 {
+            match accounts.pay_creator(creator_due)? {
+                Some(base_burned) => (creator_due, 0, base_burned),
+                None => {
+                    // The day still closes; a later day's close pays the carry
+                    msg!("Holding {} of creator payout as creator carry", creator_due);
+                    progress.hold_creator_payout(creator_due)?;
+                    (0, creator_due, 0)
+                }
+            }
+        } else {
+            msg!("No creator payout required (amount: {})", creator_due);
+            (0, 0, 0)
+        };
+
+        // Every token that left the treasury during the day must be accounted for, and
+        // what it keeps must cover still-owed amounts
+        let closing_treasury = accounts.treasury_balance()?;
+        progress.close_day(closing_treasury, creator_payout)?;
+        step.close = Some(DayClose {
+            settlement,
+            creator_payout,
+            creator_held,
+            base_burned,
+        });
+    }
+
+    Ok(step)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY_START: i64 = 1_700_000_000;
+
+    /// A fixed registry and a treasury balance moved by the step's transfers
+    struct MockAccounts {
+        investors: Vec<InvestorData>,
+        claim: u64,
+        treasury: u64,
+        /// Skip the creator transfer, leaving tokens the day cannot account for
+        keep_creator_payout: bool,
+        /// Report the creator payout as held, as a buyback that cannot run does
+        hold_creator_payout: bool,
+    }
+
+    impl DistributionAccounts for MockAccounts {
+        fn treasury_balance(&mut self) -> Result<u64> {
+            Ok(self.treasury)
+        }
+
+        fn claim_position_fees(&mut self) -> Result<u64> {
+            self.treasury += self.claim;
+            Ok(self.claim)
+        }
+
+        fn has_weight_accounts(&self) -> bool {
+            !self.investors.is_empty()
+        }
+
+        fn load_investors(&mut self, _: WeightWindow, opens_day: bool, cursor: usize, page_size: usize) -> Result<Vec<InvestorData>> {
+            if opens_day {
+                return Ok(self.investors.clone());
+            }
+            Ok(InvestorDistribution::investor_page(&self.investors, cursor, page_size)?.to_vec())
+        }
+
+        fn next_registry_hash(&self, _: WeightWindow, page_end: usize) -> Result<[u8; 32]> {
+            registry_hash(&self.investors[page_end..])
+        }
+
+        fn locked_total(&self, _: WeightWindow) -> Result<u64> {
+            Ok(self.investors.iter().map(|investor| investor.locked_amount).sum())
+        }
+
+        fn load_accruals(&mut self, _: &[InvestorData]) -> Result<HashMap<Pubkey, InvestorAccrual>> {
+            Ok(HashMap::new())
+        }
+
+        fn execute_page(&mut self, page: &BatchPayoutResult, _: &HashMap<Pubkey, InvestorAccrual>) -> Result<()> {
+            self.treasury -= page.total_paid;
+            Ok(())
+        }
+
+        fn has_crank_reward_account(&self) -> bool {
+            false
+        }
+
+        fn pay_crank_reward(&mut self, amount: u64) -> Result<()> {
+            self.treasury -= amount;
+            Ok(())
+        }
+
+        fn pay_creator(&mut self, amount: u64) -> Result<Option<u64>> {
+            if self.hold_creator_payout {
+                return Ok(None);
+            }
+            if !self.keep_creator_payout {
+                self.treasury -= amount;
+            }
+            Ok(Some(0))
+        }
+    }
+
+    fn setup(investor_count: u64) -> (PolicyConfig, DistributionProgress, MockAccounts) {
+        let policy_config = PolicyConfig {
+            vault: Pubkey::new_unique(),
+            investor_fee_share_bps: 5_000,
+            y0_total_allocation: 1_000_000,
+            bump: 255,
+            ..PolicyConfig::default()
+        };
+        let mut progress = DistributionProgress::default();
+        progress.initialize(policy_config.vault, Pubkey::default(), 0).unwrap();
+        let mut investors: Vec<InvestorData> = (1..=investor_count)
+            .map(|index| InvestorData {
+                wallet: Pubkey::new_unique(),
+                locked_amount: index * 10_000,
+                total_allocation: index * 10_000,
+                stream_accounts: Vec::new(),
+            })
+            .collect();
+        investors.sort_by_key(|investor| investor.wallet);
+        let accounts = MockAccounts {
+            investors,
+            claim: 100_000,
+            treasury: 0,
+            keep_creator_payout: false,
+            hold_creator_payout: false,
+        };
+        (policy_config, progress, accounts)
+    }
+
+    #[test]
+    fn test_steps_page_and_close_the_day() {
+        let (policy_config, mut progress, mut accounts) = setup(5);
+
+        let first = run_distribution_step(&policy_config, &mut progress, &mut accounts, 3, Some(0), DAY_START).unwrap();
+        assert_eq!(first.timing, DistributionTimingState::NewDay);
+        assert_eq!(first.claimed_quote, 100_000);
+        assert_eq!(first.pool_opened.as_ref().unwrap().investor_count, 5);
+        assert_eq!(first.page.as_ref().unwrap().page_end, 3);
+        assert!(first.close.is_none());
+
+        // A replay of the landed page changes nothing
+        let replay = run_distribution_step(&policy_config, &mut progress, &mut accounts, 3, Some(0), DAY_START).unwrap();
+        assert!(replay.replayed && replay.page.is_none());
+        assert_eq!(progress.pagination_cursor, 3);
+
+        let last = run_distribution_step(&policy_config, &mut progress, &mut accounts, 3, Some(3), DAY_START).unwrap();
+        assert_eq!(last.timing, DistributionTimingState::ContinueSameDay);
+        let close = last.close.unwrap();
+        assert!(progress.day_complete);
+        assert_eq!(accounts.treasury, progress.outstanding_liabilities().unwrap());
+        assert_eq!(
+            progress.current_day_distributed + close.creator_payout + accounts.treasury,
+            100_000
+        );
+    }
+
+    #[test]
+    fn test_tampered_middle_page_rejected_before_transfer() {
+        let (policy_config, mut progress, mut accounts) = setup(5);
+        run_distribution_step(&policy_config, &mut progress, &mut accounts, 2, Some(0), DAY_START).unwrap();
+        let treasury = accounts.treasury;
+
+        // The middle page passes another weight account for one of its investors
+        accounts.investors[3].stream_accounts = vec![Pubkey::new_unique()];
+        let result = run_distribution_step(&policy_config, &mut progress, &mut accounts, 2, Some(2), DAY_START);
+
+        assert_eq!(result.unwrap_err(), ErrorCode::InvestorRegistryMismatch.into());
+        assert_eq!(accounts.treasury, treasury);
+        assert_eq!(progress.pagination_cursor, 2);
+    }
+
+    #[test]
+    fn test_held_creator_payout_still_closes_the_day() {
+        let (policy_config, mut progress, mut accounts) = setup(2);
+        accounts.hold_creator_payout = true;
+
+        let step = run_distribution_step(&policy_config, &mut progress, &mut accounts, 3, None, DAY_START).unwrap();
+        let close = step.close.unwrap();
+        assert!(progress.day_complete);
+        assert_eq!(close.creator_payout, 0);
+        assert_eq!(close.creator_held, 100_000 - progress.day_investor_share);
+        assert_eq!(progress.creator_carry, close.creator_held);
+        assert_eq!(accounts.treasury, progress.outstanding_liabilities().unwrap());
+
+        // The next day's close pays the held share with its own remainder
+        accounts.hold_creator_payout = false;
+        let next_day = DAY_START + policy_config.distribution_period_secs;
+        let step = run_distribution_step(&policy_config, &mut progress, &mut accounts, 3, None, next_day).unwrap();
+        let next_close = step.close.unwrap();
+        assert_eq!(next_close.creator_held, 0);
+        assert_eq!(next_close.creator_payout, close.creator_held + 100_000 - progress.day_investor_share);
+        assert_eq!(progress.creator_carry, 0);
+        assert_eq!(accounts.treasury, progress.outstanding_liabilities().unwrap());
+    }
+
+    #[test]
+    fn test_close_fails_when_treasury_disagrees() {
+        let (policy_config, mut progress, mut accounts) = setup(2);
+        accounts.keep_creator_payout = true;
+
+        let result = run_distribution_step(&policy_config, &mut progress, &mut accounts, 3, None, DAY_START);
+
+        assert_eq!(result.unwrap_err(), ErrorCode::AccountingInvariantViolated.into());
+    }
+}
@@ -0,0 +1,392 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount, Transfer, transfer};
+
+use crate::{
+    constants::*,
+    error::ErrorCode,
+    state::{DistributionProgress, DustPolicy, PolicyConfig},
+    utils::{
+        investor_distribution::InvestorPayout,
+        math::calculate_dust_payout,
+    },
+};
+
+/// Dust routing for investor pages and day close
+pub struct DustDistribution;
+
+impl DustDistribution {
+    /// Split the dust accumulated after a page into the amount paid out now and the
+    /// amount carried on the progress account
+    ///
+    /// Investor dust policies pay whole multiples of the minimum payout; the other
+    /// policies carry everything until the next day start or day close.
+    pub fn split_page_dust(
+        dust_policy: DustPolicy,
+        accumulated_dust: u64,
+        min_payout_threshold: u64,
+    ) -> (u64, u64) {
+        if dust_policy.pays_investors() {
+            calculate_dust_payout(accumulated_dust, min_payout_threshold)
+        } else {
+            (0, accumulated_dust)
+        }
+    }
+
+    /// Select the investor receiving a page's dust payout, if the policy pays investors
+    ///
+    /// `LargestWeightInvestor` picks the page investor with the largest weight (the first
+    /// in registry order on ties); `RoundRobin` picks the registry entry at the progress
+    /// account's round-robin cursor, once a page starting at `page_start` reaches it.
+    pub fn select_page_recipient(
+        dust_policy: DustPolicy,
+        distribution_progress: &DistributionProgress,
+        page_start: usize,
+        page_payouts: &[InvestorPayout],
+    ) -> Option<Pubkey> {
+        match dust_policy {
+            DustPolicy::CarryForward | DustPolicy::CreatorAtDayClose => None,
+            DustPolicy::LargestWeightInvestor => page_payouts
+                .iter()
+                .fold(None, |largest: Option<&InvestorPayout>, payout| match largest {
+                    Some(current) if current.weight >= payout.weight => Some(current),
+                    _ => Some(payout),
+                })
+                .map(|payout| payout.wallet),
+            DustPolicy::RoundRobin => distribution_progress
+                .dust_round_robin_index(distribution_progress.day_investor_count as usize)
+                .and_then(|index| index.checked_sub(page_start))
+                .and_then(|offset| page_payouts.get(offset))
+                .map(|payout| payout.wallet),
+        }
+    }
+
+    /// Find the recipient's quote ATA among the payout accounts
+    pub fn find_recipient_ata<'a, 'info>(
+        payout_accounts: &'a [AccountInfo<'info>],
+        recipient: &Pubkey,
+        quote_mint: &Pubkey,
+    ) -> Result<&'a AccountInfo<'info>> {
+        let ata_address = anchor_spl::associated_token::get_associated_token_address(
+            recipient,
+            quote_mint,
+        );
+
+        payout_accounts
+            .iter()
+            .find(|account| account.key() == ata_address && *account.owner == anchor_spl::token::ID)
+            .ok_or(ErrorCode::InvalidDustRecipient.into())
+    }
+
+    /// Transfer dust from the treasury to the recipient's ATA
+    pub fn transfer_dust<'info>(
+        amount: u64,
+        policy_config: &PolicyConfig,
+        token_program: &Program<'info, Token>,
+        treasury_ata: &Account<'info, TokenAccount>,
+        recipient_ata: &AccountInfo<'info>,
+        position_owner_pda: &AccountInfo<'info>,
+    ) -> Result<()> {
+        require!(
+            treasury_ata.amount >= amount,
+            ErrorCode::InsufficientFunds
+        );
+
+        let bump_seed = [policy_config.bump];
+        let signer_seeds: &[&[u8]] = &[
+            VAULT_SEED,
+            policy_config.vault.as_ref(),
+            b"investor_fee_pos_owner",
+            &bump_seed,
+        ];
+        let signer_seeds_slice = &[signer_seeds];
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            Transfer {
+                from: treasury_ata.to_account_info(),
+                to: recipient_ata.clone(),
+                authority: position_owner_pda.to_account_info(),
+            },
+            signer_seeds_slice,
+        );
+
+        transfer(transfer_ctx, amount)
+            .map_err(|_| ErrorCode::TreasuryTransferFailed)?;
+
+        msg!("Dust transfer executed: {} tokens to {}", amount, recipient_ata.key());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        state::registry_hash,
+        utils::{investor_distribution::InvestorDistribution, vesting::InvestorData},
+    };
+    use std::collections::HashMap;
+
+    const DAY_START: i64 = 1_700_000_000;
+
+    fn create_progress() -> DistributionProgress {
+        DistributionProgress {
+            ..DistributionProgress::default()
+        }
+    }
+
+    fn create_day_investors(locked_amounts: &[u64]) -> Vec<InvestorData> {
+        let mut investors: Vec<InvestorData> = locked_amounts
+            .iter()
+            .map(|&locked_amount| InvestorData {
+                wallet: Pubkey::new_unique(),
+                locked_amount,
+                total_allocation: locked_amount,
+                stream_accounts: Vec::new(),
+            })
+            .collect();
+        investors.sort_by_key(|investor| investor.wallet);
+        investors
+    }
+
+    /// Outcome of a simulated day: amounts credited per investor, to the creator and carried
+    struct DaySettlement {
+        investor_totals: Vec<u64>,
+        creator_total: u64,
+        carried: u64,
+    }
+
+    /// Run a day's pages through `plan_investor_page` and close it the way
+    /// `distribute_fees` does, modelling the treasury from the transfers
+    fn settle_day(
+        dust_policy: DustPolicy,
+        progress: &mut DistributionProgress,
+        day_investors: &[InvestorData],
+        pool: u64,
+        min_payout: u64,
+        page_size: usize,
+    ) -> DaySettlement {
+        let policy_config = PolicyConfig {
+            dust_policy,
+            min_payout_lamports: min_payout,
+            ..PolicyConfig::default()
+        };
+        let day_start = progress.last_distribution_ts.max(DAY_START) + TWENTY_FOUR_HOURS;
+        progress.prepare_for_distribution(day_start, TWENTY_FOUR_HOURS, None).unwrap();
+
+        // The treasury holds the dust carried so far, and the claim flows in
+        let mut treasury_balance = progress.outstanding_liabilities().unwrap();
+        progress.record_treasury_opening(treasury_balance);
+        progress.record_treasury_inflow(pool).unwrap();
+        treasury_balance += pool;
+
+        // The whole claim is the investor pool, so the creator only receives swept dust
+        progress.record_day_claim(pool).unwrap();
+        progress.open_day(pool, &policy_config).unwrap();
+        let payable_pool = progress.payable_investor_pool().unwrap();
+        progress.commit_day_registry(day_investors, payable_pool).unwrap();
+
+        let mut investor_totals = vec![0u64; day_investors.len()];
+        let registry_index = |wallet: Pubkey| {
+            day_investors.iter().position(|investor| investor.wallet == wallet).unwrap()
+        };
+        for (index, page) in day_investors.chunks(page_size).enumerate() {
+            let page_end = index * page_size + page.len();
+            let result = InvestorDistribution::plan_investor_page(
+                &policy_config,
+                progress,
+                page,
+                registry_hash(&day_investors[page_end..]).unwrap(),
+                payable_pool,
+                &mut HashMap::new(),
+                day_start,
+            )
+            .unwrap();
+            for payout in &result.payouts {
+                investor_totals[registry_index(payout.wallet)] += payout.payout_amount;
+                treasury_balance -= payout.payout_amount;
+            }
+            if let Some(recipient) = result.dust_recipient {
+                investor_totals[registry_index(recipient)] += result.dust_paid;
+                treasury_balance -= result.dust_paid;
+            }
+        }
+
+        let settlement = progress.settle_day(dust_policy, payable_pool).unwrap();
+        let creator_total = settlement.creator_payout().unwrap();
+        treasury_balance -= creator_total;
+        progress.close_day(treasury_balance, creator_total).unwrap();
+
+        DaySettlement {
+            investor_totals,
+            creator_total,
+            carried: progress.carry_over_dust,
+        }
+    }
+
+    fn assert_conserved(settlement: &DaySettlement, pool: u64) {
+        let investor_total: u64 = settlement.investor_totals.iter().sum();
+        assert_eq!(investor_total + settlement.creator_total + settlement.carried, pool);
+    }
+
+    #[test]
+    fn test_split_page_dust() {
+        // Investor policies pay whole multiples of the minimum payout
+        assert_eq!(DustDistribution::split_page_dust(DustPolicy::LargestWeightInvestor, 2_500, 1_000), (2_000, 500));
+        assert_eq!(DustDistribution::split_page_dust(DustPolicy::RoundRobin, 999, 1_000), (0, 999));
+
+        // Other policies carry everything
+        assert_eq!(DustDistribution::split_page_dust(DustPolicy::CarryForward, 2_500, 1_000), (0, 2_500));
+        assert_eq!(DustDistribution::split_page_dust(DustPolicy::CreatorAtDayClose, 2_500, 1_000), (0, 2_500));
+    }
+
+    fn create_payouts(investors: &[InvestorData]) -> Vec<InvestorPayout> {
+        investors
+            .iter()
+            .map(|investor| InvestorPayout {
+                wallet: investor.wallet,
+                locked_amount: investor.locked_amount,
+                weight: investor.locked_amount as u128,
+                payout_amount: 0,
+                dust_amount: 0,
+                ata_address: Pubkey::default(),
+                needs_ata_creation: false,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_select_largest_weight_recipient() {
+        let progress = create_progress();
+        let day_investors = create_day_investors(&[100, 300, 300, 200]);
+        let payouts = create_payouts(&day_investors);
+
+        // Ties go to the first investor in registry order
+        let expected = day_investors.iter().find(|investor| investor.locked_amount == 300).unwrap().wallet;
+        assert_eq!(
+            DustDistribution::select_page_recipient(DustPolicy::LargestWeightInvestor, &progress, 0, &payouts),
+            Some(expected)
+        );
+        assert_eq!(
+            DustDistribution::select_page_recipient(DustPolicy::CarryForward, &progress, 0, &payouts),
+            None
+        );
+    }
+
+    #[test]
+    fn test_round_robin_walks_registry() {
+        let mut progress = create_progress();
+        let day_investors = create_day_investors(&[100, 200, 300]);
+        let payouts = create_payouts(&day_investors);
+        progress.day_investor_count = day_investors.len() as u32;
+
+        let mut recipients = Vec::new();
+        for _ in 0..4 {
+            recipients.push(
+                DustDistribution::select_page_recipient(DustPolicy::RoundRobin, &progress, 0, &payouts).unwrap(),
+            );
+            progress.advance_dust_round_robin(day_investors.len());
+        }
+
+        assert_eq!(recipients[0], day_investors[0].wallet);
+        assert_eq!(recipients[1], day_investors[1].wallet);
+        assert_eq!(recipients[2], day_investors[2].wallet);
+        assert_eq!(recipients[3], day_investors[0].wallet);
+
+        // A page only pays the cursor's investor once it reaches it
+        assert_eq!(
+            DustDistribution::select_page_recipient(DustPolicy::RoundRobin, &progress, 0, &payouts[..1]),
+            None
+        );
+        assert_eq!(
+            DustDistribution::select_page_recipient(DustPolicy::RoundRobin, &progress, 1, &payouts[1..2]),
+            Some(day_investors[1].wallet)
+        );
+
+        // An empty registry has no recipient
+        progress.day_investor_count = 0;
+        assert_eq!(
+            DustDistribution::select_page_recipient(DustPolicy::RoundRobin, &progress, 0, &payouts),
+            None
+        );
+    }
+
+    #[test]
+    fn test_find_recipient_ata() {
+        let recipient = Pubkey::new_unique();
+        let quote_mint = Pubkey::new_unique();
+        let ata_address = anchor_spl::associated_token::get_associated_token_address(&recipient, &quote_mint);
+        let other_address = Pubkey::new_unique();
+        let token_program = anchor_spl::token::ID;
+        let mut ata_lamports = 0u64;
+        let mut other_lamports = 0u64;
+        let mut ata_data = vec![0u8; 0];
+        let mut other_data = vec![0u8; 0];
+        let accounts = vec![
+            AccountInfo::new(&other_address, false, true, &mut other_lamports, &mut other_data, &token_program, false, 0),
+            AccountInfo::new(&ata_address, false, true, &mut ata_lamports, &mut ata_data, &token_program, false, 0),
+        ];
+
+        let found = DustDistribution::find_recipient_ata(&accounts, &recipient, &quote_mint).unwrap();
+        assert_eq!(found.key(), ata_address);
+
+        assert!(DustDistribution::find_recipient_ata(&accounts, &Pubkey::new_unique(), &quote_mint).is_err());
+    }
+
+    #[test]
+    fn test_dust_policies_conserve_pool() {
+        let locked_amounts = [333_333, 1_000_001, 7, 250_000, 999_999, 123_457, 42, 600_000, 77_777];
+        let pool = 10_000_003;
+        let min_payout = 1_000;
+
+        for dust_policy in [
+            DustPolicy::CarryForward,
+            DustPolicy::CreatorAtDayClose,
+            DustPolicy::LargestWeightInvestor,
+            DustPolicy::RoundRobin,
+        ] {
+            for page_size in [1, 2, 4, 9] {
+                let mut progress = create_progress();
+                let day_investors = create_day_investors(&locked_amounts);
+                let settlement = settle_day(dust_policy, &mut progress, &day_investors, pool, min_payout, page_size);
+                assert_conserved(&settlement, pool);
+
+                match dust_policy {
+                    DustPolicy::CarryForward => assert!(settlement.carried > 0),
+                    DustPolicy::CreatorAtDayClose => {
+                        assert!(settlement.creator_total > 0);
+                        assert_eq!(settlement.carried, 0);
+                    }
+                    DustPolicy::LargestWeightInvestor | DustPolicy::RoundRobin => {
+                        assert!(settlement.carried < min_payout);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_dust_conserved_across_days() {
+        let locked_amounts = [10, 20, 30, 40, 50, 60, 70];
+        let min_payout = 500;
+
+        for dust_policy in [DustPolicy::LargestWeightInvestor, DustPolicy::RoundRobin, DustPolicy::CarryForward] {
+            let mut progress = create_progress();
+            let day_investors = create_day_investors(&locked_amounts);
+            let mut credited = 0u64;
+            let mut funded = 0u64;
+
+            // Carried dust joins the next day's pool under CarryForward
+            for day in 0..5u64 {
+                let pool = 9_999 + day * 1_337;
+                funded += pool;
+
+                let settlement = settle_day(dust_policy, &mut progress, &day_investors, pool, min_payout, 3);
+                credited += settlement.investor_totals.iter().sum::<u64>() + settlement.creator_total;
+            }
+
+            assert_eq!(credited + progress.carry_over_dust, funded);
+        }
+    }
+}
@@ -7,6 +7,8 @@ use anchor_spl::associated_token::AssociatedToken;
 use crate::{
     error::ErrorCode,
     constants::*,
+    state::{BaseFeePolicy, PolicyConfig},
+    utils::swap::DAMM_V2_PROGRAM_ID,
 };
 
 /// DAMM V2 Position account structure (simplified for fee claiming)
@@ -27,23 +29,186 @@ pub struct FeeClaimResult {
     pub quote_mint: Pubkey,
 }
 
+/// Accounts shared by the fee claims of every honorary position in a quote mint
+pub struct PositionClaimAccounts<'a, 'info> {
+    pub position_owner_pda: &'a AccountInfo<'info>,
+    pub treasury_ata: &'a Account<'info, TokenAccount>,
+    pub cp_amm_program: &'a AccountInfo<'info>,
+    pub token_program: &'a Program<'info, Token>,
+    /// Vault the position owner PDA is derived from
+    pub vault_key: Pubkey,
+    /// Bump of the position owner PDA
+    pub bump: u8,
+}
+
+/// Where base fees owed by a position go, per the policy's `base_fee_policy`
+pub struct BaseFeeRouting<'a, 'info> {
+    pub policy: BaseFeePolicy,
+    /// Base mint of the honorary pools
+    pub base_mint: Pubkey,
+    /// Token account receiving base fees (base treasury or creator base ATA), if supplied
+    pub destination: Option<&'a Account<'info, TokenAccount>>,
+    /// Owner the destination must have under the policy
+    pub expected_owner: Option<Pubkey>,
+}
+
+impl<'a, 'info> BaseFeeRouting<'a, 'info> {
+    /// Destination the owed base fees are collected into, or `BaseFeeDetected` under `Abort`
+    pub fn destination_for(&self, base_amount: u64) -> Result<Option<&'a Account<'info, TokenAccount>>> {
+        if base_amount == 0 {
+            return Ok(None);
+        }
+
+        if self.policy == BaseFeePolicy::Abort {
+            msg!("CRITICAL: Base fees detected: {} lamports - aborting claim", base_amount);
+            return Err(ErrorCode::BaseFeeDetected.into());
+        }
+
+        let destination = self.destination.ok_or(ErrorCode::InvalidBaseFeeDestination)?;
+        require!(
+            destination.mint == self.base_mint && Some(destination.owner) == self.expected_owner,
+            ErrorCode::InvalidBaseFeeDestination
+        );
+
+        Ok(Some(destination))
+    }
+}
+
+/// Split the honorary positions quoted in `quote_mint` off the front of the remaining accounts
+///
+/// `distribute_fees` takes every honorary position of the quote mint it distributes, in
+/// policy order, as a fixed prefix of its remaining accounts on every page; weight and
+/// payout accounts follow.
+pub fn split_position_accounts<'a, 'info>(
+    policy_config: &PolicyConfig,
+    quote_mint: &Pubkey,
+    remaining_accounts: &'a [AccountInfo<'info>],
+) -> Result<(&'a [AccountInfo<'info>], &'a [AccountInfo<'info>])> {
+    let positions = policy_config.honorary_positions_for(quote_mint);
+    require!(
+        remaining_accounts.len() >= positions.len(),
+        ErrorCode::InvalidHonoraryPositions
+    );
+
+    let (position_accounts, other_accounts) = remaining_accounts.split_at(positions.len());
+    for (account, expected) in position_accounts.iter().zip(&positions) {
+        if account.key() != *expected || !account.is_writable {
+            msg!("Honorary position mismatch - expected: {}, actual: {}", expected, account.key());
+            return Err(ErrorCode::InvalidHonoraryPositions.into());
+        }
+    }
+
+    Ok((position_accounts, other_accounts))
+}
+
+/// Anchor discriminator of the DAMM V2 `Position` account (`sha256("account:Position")[..8]`)
+pub const DAMM_V2_POSITION_DISCRIMINATOR: [u8; 8] = [170, 188, 143, 228, 122, 64, 247, 208];
+
+/// Pool and NFT mint of a DAMM V2 `Position` account
+/// Layout verified against cp-amm `programs/cp-amm/src/state/position.rs`
+///
+/// Account Layout (offsets from start, including discriminator):
+/// - 0:    discriminator (8 bytes)
+/// - 8:    pool (32 bytes)
+/// - 40:   nft_mint (32 bytes)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DammV2Position {
+    pub pool: Pubkey,
+    pub nft_mint: Pubkey,
+}
+
+impl DammV2Position {
+    /// Bytes up to the end of `nft_mint`
+    pub const MIN_ACCOUNT_SIZE: usize = 72;
+
+    /// Read the pool and NFT mint of a position account owned by the DAMM V2 program
+    pub fn try_from_account(position: &AccountInfo) -> Result<Self> {
+        require!(*position.owner == DAMM_V2_PROGRAM_ID, ErrorCode::InvalidHonoraryPositions);
+
+        let data = position.try_borrow_data()?;
+        require!(
+            data.len() >= Self::MIN_ACCOUNT_SIZE && data[..8] == DAMM_V2_POSITION_DISCRIMINATOR,
+            ErrorCode::InvalidHonoraryPositions
+        );
+
+        let read_pubkey = |offset: usize| -> Result<Pubkey> {
+            Pubkey::try_from(&data[offset..offset + 32])
+                .map_err(|_| ErrorCode::InvalidHonoraryPositions.into())
+        };
+
+        Ok(DammV2Position {
+            pool: read_pubkey(8)?,
+            nft_mint: read_pubkey(40)?,
+        })
+    }
+}
+
+/// Check that an honorary position is a DAMM V2 position held by `position_owner`
+///
+/// DAMM V2 positions belong to whoever holds their NFT, so `position_nft_account` must be
+/// the token account holding the position's NFT, owned by `position_owner`.
+pub fn validate_honorary_position(
+    position: &AccountInfo,
+    position_nft_account: &AccountInfo,
+    position_owner: &Pubkey,
+) -> Result<()> {
+    let nft_mint = DammV2Position::try_from_account(position)?.nft_mint;
+
+    // Token and Token-2022 accounts share the base layout: mint, owner, amount
+    require!(
+        *position_nft_account.owner == anchor_spl::token::ID
+            || *position_nft_account.owner == anchor_spl::token_2022::ID,
+        ErrorCode::PositionOwnerMismatch
+    );
+    let data = position_nft_account.try_borrow_data()?;
+    require!(data.len() >= 72, ErrorCode::PositionOwnerMismatch);
+    let holds_nft = data[0..32] == nft_mint.to_bytes()
+        && data[32..64] == position_owner.to_bytes()
+        && data[64..72] == 1u64.to_le_bytes();
+
+    if !holds_nft {
+        msg!("Honorary position {} is not held by position owner {}", position.key(), position_owner);
+        return Err(ErrorCode::PositionOwnerMismatch.into());
+    }
+
+    Ok(())
+}
+
+/// Check each honorary position against the `[position, position_nft_account]` pair at the
+/// front of the remaining accounts, returning the accounts that follow the pairs
+pub fn validate_honorary_position_accounts<'a, 'info>(
+    honorary_positions: &[Pubkey],
+    position_owner: &Pubkey,
+    remaining_accounts: &'a [AccountInfo<'info>],
+) -> Result<&'a [AccountInfo<'info>]> {
+    let pair_accounts = honorary_positions.len() * 2;
+    require!(
+        remaining_accounts.len() >= pair_accounts,
+        ErrorCode::InvalidHonoraryPositions
+    );
+
+    let (position_pairs, other_accounts) = remaining_accounts.split_at(pair_accounts);
+    for (pair, expected) in position_pairs.chunks_exact(2).zip(honorary_positions) {
+        require!(pair[0].key() == *expected, ErrorCode::InvalidHonoraryPositions);
+        validate_honorary_position(&pair[0], &pair[1], position_owner)?;
+    }
+
+    Ok(other_accounts)
+}
+
 /// Claims fees from a DAMM V2 position via CPI
 /// This function handles the cross-program invocation to claim fees
-/// and enforces quote-only validation
+/// and routes any base fees per the policy's base fee policy
 pub fn claim_position_fees<'info>(
     position_account: &AccountInfo<'info>,
-    position_owner_pda: &AccountInfo<'info>,
-    treasury_ata: &Account<'info, TokenAccount>,
+    accounts: &PositionClaimAccounts<'_, 'info>,
     quote_mint: &Pubkey,
-    vault_key: &Pubkey,
-    bump: u8,
-    cp_amm_program: &AccountInfo<'info>,
-    token_program: &Program<'info, Token>,
+    base_fees: &BaseFeeRouting<'_, 'info>,
 ) -> Result<FeeClaimResult> {
     msg!("Starting fee claiming process for position: {}", position_account.key());
     
     // Pre-claim validation
-    validate_claim_preconditions(position_account, position_owner_pda, treasury_ata, quote_mint)?;
+    validate_claim_preconditions(position_account, accounts.position_owner_pda, accounts.treasury_ata, quote_mint)?;
     
     // Extract fee data from position with enhanced error handling
     let fee_data = extract_position_fee_data(position_account)
@@ -52,12 +217,13 @@ pub fn claim_position_fees<'info>(
             ErrorCode::PositionFeeDataExtractionFailed
         })?;
     
-    // Validate quote-only enforcement with detailed logging
-    validate_quote_only_fees(&fee_data, quote_mint)
-        .map_err(|e| {
-            msg!("Quote-only validation failed for position: {}", position_account.key());
-            e
-        })?;
+    // Validate quote-only enforcement with detailed logging (base fees are routed otherwise)
+    if base_fees.policy == BaseFeePolicy::Abort {
+        validate_quote_only_fees(&fee_data, quote_mint)
+            .inspect_err(|_| {
+                msg!("Quote-only validation failed for position: {}", position_account.key());
+            })?;
+    }
     
     // Determine which token is quote and which is base
     let (quote_amount, base_amount) = if fee_data.token_mint_a == *quote_mint {
@@ -70,14 +236,11 @@ pub fn claim_position_fees<'info>(
         return Err(ErrorCode::InvalidQuoteMint.into());
     };
     
-    // Enforce quote-only: base amount must be zero
-    if base_amount > 0 {
-        msg!("CRITICAL: Base fees detected: {} lamports - aborting claim", base_amount);
-        return Err(ErrorCode::BaseFeeDetected.into());
-    }
+    // Base fees abort the claim or go to the policy's base fee destination
+    let base_destination = base_fees.destination_for(base_amount)?;
     
-    // Only proceed if there are quote fees to claim
-    if quote_amount == 0 {
+    // Only proceed if there are fees to claim
+    if quote_amount == 0 && base_amount == 0 {
         msg!("No quote fees to claim for position: {}", position_account.key());
         return Ok(FeeClaimResult {
             quote_amount: 0,
@@ -87,28 +250,27 @@ pub fn claim_position_fees<'info>(
     }
     
     // Record treasury balance before claim
-    let treasury_balance_before = treasury_ata.amount;
+    let treasury_balance_before = accounts.treasury_ata.amount;
     msg!("Treasury balance before claim: {}", treasury_balance_before);
     
     // Perform the actual fee claiming via CPI with enhanced error handling
     claim_fees_cpi(
         position_account,
-        position_owner_pda,
-        treasury_ata,
-        vault_key,
-        bump,
-        cp_amm_program,
-        token_program,
+        accounts,
+        base_destination,
     ).map_err(|_e| {
         msg!("Fee claiming CPI failed for position: {}", position_account.key());
         ErrorCode::FeeClaimingFailed
     })?;
     
     msg!("Successfully claimed {} quote fees from position: {}", quote_amount, position_account.key());
+    if base_amount > 0 {
+        msg!("Routed {} base fees ({:?}) from position: {}", base_amount, base_fees.policy, position_account.key());
+    }
     
     Ok(FeeClaimResult {
         quote_amount,
-        base_amount: 0,
+        base_amount,
         quote_mint: *quote_mint,
     })
 }
@@ -187,23 +349,28 @@ fn extract_position_fee_data(_position_account: &AccountInfo) -> Result<Position
 }
 
 /// Performs the actual CPI call to claim fees from DAMM V2 position
+/// Base fees, when owed, are collected into `base_destination`
 fn claim_fees_cpi<'info>(
     position_account: &AccountInfo<'info>,
-    position_owner_pda: &AccountInfo<'info>,
-    treasury_ata: &Account<'info, TokenAccount>,
-    vault_key: &Pubkey,
-    bump: u8,
-    cp_amm_program: &AccountInfo<'info>,
-    token_program: &Program<'info, Token>,
+    accounts: &PositionClaimAccounts<'_, 'info>,
+    base_destination: Option<&Account<'info, TokenAccount>>,
 ) -> Result<()> {
+    let PositionClaimAccounts {
+        position_owner_pda,
+        treasury_ata,
+        cp_amm_program,
+        token_program,
+        ..
+    } = *accounts;
+
     // Create PDA signer seeds
-    let vault_seed = vault_key.as_ref();
+    let vault_seed = accounts.vault_key.as_ref();
     let position_owner_seed = b"investor_fee_pos_owner";
     let signer_seeds = &[
         VAULT_SEED,
         vault_seed,
         position_owner_seed,
-        &[bump],
+        &[accounts.bump],
     ];
     
     msg!("Performing CPI call to claim fees from DAMM V2 position");
@@ -215,26 +382,32 @@ fn claim_fees_cpi<'info>(
     let instruction_data = prepare_collect_fees_instruction_data()?;
     
     // Create the instruction for CPI call
+    let mut account_metas = vec![
+        AccountMeta::new(*position_account.key, false),
+        AccountMeta::new_readonly(*position_owner_pda.key, true),
+        AccountMeta::new(*treasury_ata.to_account_info().key, false),
+        AccountMeta::new_readonly(*token_program.key, false),
+    ];
+    let mut account_infos = vec![
+        position_account.clone(),
+        position_owner_pda.clone(),
+        treasury_ata.to_account_info(),
+        token_program.to_account_info(),
+    ];
+    if let Some(base_destination) = base_destination {
+        account_metas.push(AccountMeta::new(base_destination.key(), false));
+        account_infos.push(base_destination.to_account_info());
+    }
     let collect_fees_ix = Instruction {
         program_id: *cp_amm_program.key,
-        accounts: vec![
-            AccountMeta::new(*position_account.key, false),
-            AccountMeta::new_readonly(*position_owner_pda.key, true),
-            AccountMeta::new(*treasury_ata.to_account_info().key, false),
-            AccountMeta::new_readonly(*token_program.key, false),
-        ],
+        accounts: account_metas,
         data: instruction_data,
     };
     
     // Execute the CPI call with PDA signing
     invoke_signed(
         &collect_fees_ix,
-        &[
-            position_account.clone(),
-            position_owner_pda.clone(),
-            treasury_ata.to_account_info(),
-            token_program.to_account_info(),
-        ],
+        &account_infos,
         &[signer_seeds],
     ).map_err(|e| {
         msg!("DAMM V2 CPI call failed: {}", e);
@@ -293,17 +466,29 @@ pub fn ensure_treasury_ata(
     Ok(())
 }
 
+/// Accounts creating a treasury ATA owned by the position owner PDA
+pub struct TreasuryAtaAccounts<'a, 'info> {
+    pub payer: &'a Signer<'info>,
+    pub treasury_ata: &'a AccountInfo<'info>,
+    pub position_owner_pda: &'a AccountInfo<'info>,
+    pub quote_mint: &'a AccountInfo<'info>,
+    pub system_program: &'a Program<'info, System>,
+    pub token_program: &'a Program<'info, Token>,
+    pub associated_token_program: &'a Program<'info, AssociatedToken>,
+}
+
 /// Creates treasury ATA if it doesn't exist
-pub fn create_treasury_ata_if_needed<'info>(
-    payer: &Signer<'info>,
-    treasury_ata: &AccountInfo<'info>,
-    position_owner_pda: &AccountInfo<'info>,
-    quote_mint: &AccountInfo<'info>,
-    system_program: &Program<'info, System>,
-    token_program: &Program<'info, Token>,
-    associated_token_program: &Program<'info, AssociatedToken>,
-    _rent: &Sysvar<'info, Rent>,
-) -> Result<()> {
+pub fn create_treasury_ata_if_needed(accounts: &TreasuryAtaAccounts) -> Result<()> {
+    let TreasuryAtaAccounts {
+        payer,
+        treasury_ata,
+        position_owner_pda,
+        quote_mint,
+        system_program,
+        token_program,
+        associated_token_program,
+    } = *accounts;
+
     // Check if treasury ATA already exists
     if treasury_ata.data_is_empty() {
         msg!("Creating treasury ATA for quote mint: {}", quote_mint.key());
@@ -497,6 +682,217 @@ mod tests {
         assert_ne!(invalid_owner_ata.owner, program_authority);
     }
     
+    #[test]
+    fn test_base_fee_routing() {
+        use anchor_lang::solana_program::program_pack::Pack;
+        use anchor_spl::token::spl_token;
+        
+        let base_mint = Pubkey::new_unique();
+        let position_owner = Pubkey::new_unique();
+        let key = Pubkey::new_unique();
+        let mut lamports = 0u64;
+        let mut data = vec![0u8; spl_token::state::Account::LEN];
+        spl_token::state::Account {
+            mint: base_mint,
+            owner: position_owner,
+            state: spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut data);
+        let account_info = AccountInfo::new(&key, false, true, &mut lamports, &mut data, &spl_token::ID, false, 0);
+        let base_treasury: Account<TokenAccount> = Account::try_from(&account_info).unwrap();
+        
+        let routing = |policy, destination, expected_owner| BaseFeeRouting {
+            policy,
+            base_mint,
+            destination,
+            expected_owner,
+        };
+        
+        // Nothing owed needs no destination, whatever the policy
+        assert!(routing(BaseFeePolicy::Abort, None, None).destination_for(0).unwrap().is_none());
+        
+        // Abort keeps failing the claim
+        assert!(routing(BaseFeePolicy::Abort, Some(&base_treasury), None).destination_for(1).is_err());
+        
+        // Quarantine collects into the position owner's base treasury
+        let quarantine = routing(BaseFeePolicy::Quarantine, Some(&base_treasury), Some(position_owner));
+        assert_eq!(quarantine.destination_for(500).unwrap().unwrap().key(), key);
+        assert!(routing(BaseFeePolicy::Quarantine, None, Some(position_owner)).destination_for(500).is_err());
+        
+        // A destination owned by someone else is rejected
+        let forward = routing(BaseFeePolicy::ForwardToCreator, Some(&base_treasury), Some(Pubkey::new_unique()));
+        assert!(forward.destination_for(500).is_err());
+    }
+    
+    #[test]
+    fn test_split_position_accounts() {
+        
+        let keys: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let mut policy_config = PolicyConfig {
+            vault: Pubkey::new_unique(),
+            quote_mint: Pubkey::new_unique(),
+            creator_wallet: Pubkey::new_unique(),
+            investor_fee_share_bps: 5000,
+            min_payout_lamports: 1000,
+            y0_total_allocation: 1_000_000,
+            bump: 255,
+            vesting_programs: [Pubkey::default(); MAX_VESTING_PROGRAMS],
+            vesting_program_count: 0,
+            ..PolicyConfig::default()
+        };
+        policy_config.set_honorary_positions(&keys[..2]).unwrap();
+        
+        let owner = Pubkey::new_unique();
+        let mut lamports = [0u64; 4];
+        let mut data = [Vec::<u8>::new(), Vec::new(), Vec::new(), Vec::new()];
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut().zip(data.iter_mut()))
+            .map(|(key, (lamports, data))| AccountInfo::new(key, false, true, lamports, data, &owner, false, 0))
+            .collect();
+        
+        let quote_mint = policy_config.quote_mint;
+        let (positions, others) = split_position_accounts(&policy_config, &quote_mint, &accounts).unwrap();
+        assert_eq!(positions.len(), 2);
+        assert_eq!(positions[1].key(), keys[1]);
+        assert_eq!(others.len(), 2);
+        assert_eq!(others[0].key(), keys[2]);
+        
+        // Positions must come first, in policy order
+        let reordered = vec![accounts[1].clone(), accounts[0].clone(), accounts[2].clone()];
+        assert!(split_position_accounts(&policy_config, &quote_mint, &reordered).is_err());
+        assert!(split_position_accounts(&policy_config, &quote_mint, &accounts[..1]).is_err());
+        
+        // Only the positions quoted in the distributed mint lead the accounts
+        let usdc = Pubkey::new_unique();
+        policy_config.set_honorary_position_quote_mints(&[quote_mint, usdc]).unwrap();
+        let (positions, others) = split_position_accounts(&policy_config, &usdc, &accounts[1..]).unwrap();
+        assert_eq!(positions.len(), 1);
+        assert_eq!(positions[0].key(), keys[1]);
+        assert_eq!(others.len(), 2);
+    }
+    
+    fn position_data(nft_mint: &Pubkey) -> Vec<u8> {
+        let mut data = vec![0u8; DammV2Position::MIN_ACCOUNT_SIZE];
+        data[..8].copy_from_slice(&DAMM_V2_POSITION_DISCRIMINATOR);
+        data[8..40].copy_from_slice(Pubkey::new_unique().as_ref());
+        data[40..72].copy_from_slice(nft_mint.as_ref());
+        data
+    }
+    
+    fn nft_account_data(nft_mint: &Pubkey, holder: &Pubkey, amount: u64) -> Vec<u8> {
+        let mut data = vec![0u8; 165];
+        data[0..32].copy_from_slice(nft_mint.as_ref());
+        data[32..64].copy_from_slice(holder.as_ref());
+        data[64..72].copy_from_slice(&amount.to_le_bytes());
+        data
+    }
+    
+    #[test]
+    fn test_position_discriminator() {
+        use solana_sha256_hasher::hash;
+        
+        assert_eq!(&hash(b"account:Position").to_bytes()[..8], &DAMM_V2_POSITION_DISCRIMINATOR);
+    }
+    
+    #[test]
+    fn test_validate_honorary_position() {
+        let position_key = Pubkey::new_unique();
+        let nft_key = Pubkey::new_unique();
+        let nft_mint = Pubkey::new_unique();
+        let position_owner = Pubkey::new_unique();
+        let token_2022 = anchor_spl::token_2022::ID;
+        
+        let mut position_lamports = 0u64;
+        let mut position_bytes = position_data(&nft_mint);
+        let position = AccountInfo::new(&position_key, false, true, &mut position_lamports, &mut position_bytes, &DAMM_V2_PROGRAM_ID, false, 0);
+        
+        let mut nft_lamports = 0u64;
+        let mut nft_bytes = nft_account_data(&nft_mint, &position_owner, 1);
+        let nft_account = AccountInfo::new(&nft_key, false, false, &mut nft_lamports, &mut nft_bytes, &token_2022, false, 0);
+        assert!(validate_honorary_position(&position, &nft_account, &position_owner).is_ok());
+        
+        // The NFT must be held by the position owner PDA
+        assert!(validate_honorary_position(&position, &nft_account, &Pubkey::new_unique()).is_err());
+        
+        // An account holding another mint, or none of the NFT, does not prove ownership
+        let mut other_lamports = 0u64;
+        let mut other_bytes = nft_account_data(&Pubkey::new_unique(), &position_owner, 1);
+        let other_mint = AccountInfo::new(&nft_key, false, false, &mut other_lamports, &mut other_bytes, &token_2022, false, 0);
+        assert!(validate_honorary_position(&position, &other_mint, &position_owner).is_err());
+        
+        let mut empty_lamports = 0u64;
+        let mut empty_bytes = nft_account_data(&nft_mint, &position_owner, 0);
+        let empty = AccountInfo::new(&nft_key, false, false, &mut empty_lamports, &mut empty_bytes, &token_2022, false, 0);
+        assert!(validate_honorary_position(&position, &empty, &position_owner).is_err());
+        
+        // Token accounts must belong to a token program
+        let mut fake_lamports = 0u64;
+        let mut fake_bytes = nft_account_data(&nft_mint, &position_owner, 1);
+        let fake_owner = Pubkey::new_unique();
+        let fake = AccountInfo::new(&nft_key, false, false, &mut fake_lamports, &mut fake_bytes, &fake_owner, false, 0);
+        assert!(validate_honorary_position(&position, &fake, &position_owner).is_err());
+    }
+    
+    #[test]
+    fn test_position_account_must_be_damm_v2() {
+        use crate::utils::swap::DAMM_V2_POOL_DISCRIMINATOR;
+        
+        let position_key = Pubkey::new_unique();
+        let nft_mint = Pubkey::new_unique();
+        
+        let mut lamports = 0u64;
+        let mut data = position_data(&nft_mint);
+        let other_program = Pubkey::new_unique();
+        let foreign = AccountInfo::new(&position_key, false, true, &mut lamports, &mut data, &other_program, false, 0);
+        assert!(DammV2Position::try_from_account(&foreign).is_err());
+        
+        let mut pool_lamports = 0u64;
+        let mut pool_data = position_data(&nft_mint);
+        pool_data[..8].copy_from_slice(&DAMM_V2_POOL_DISCRIMINATOR);
+        let pool = AccountInfo::new(&position_key, false, true, &mut pool_lamports, &mut pool_data, &DAMM_V2_PROGRAM_ID, false, 0);
+        assert!(DammV2Position::try_from_account(&pool).is_err());
+    }
+    
+    #[test]
+    fn test_validate_honorary_position_accounts() {
+        let keys: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_unique()).collect();
+        let nft_mints = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let position_owner = Pubkey::new_unique();
+        let token_program = anchor_spl::token::ID;
+        let system_program = Pubkey::default();
+        
+        let mut lamports = [0u64; 5];
+        let mut data = [
+            position_data(&nft_mints[0]),
+            nft_account_data(&nft_mints[0], &position_owner, 1),
+            position_data(&nft_mints[1]),
+            nft_account_data(&nft_mints[1], &position_owner, 1),
+            Vec::new(),
+        ];
+        let owners = [DAMM_V2_PROGRAM_ID, token_program, DAMM_V2_PROGRAM_ID, token_program, system_program];
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(owners.iter())
+            .zip(lamports.iter_mut().zip(data.iter_mut()))
+            .map(|((key, owner), (lamports, data))| AccountInfo::new(key, false, false, lamports, data, owner, false, 0))
+            .collect();
+        
+        let positions = [keys[0], keys[2]];
+        let others = validate_honorary_position_accounts(&positions, &position_owner, &accounts).unwrap();
+        assert_eq!(others.len(), 1);
+        assert_eq!(others[0].key(), keys[4]);
+        
+        // Pairs must follow the position order, and every position needs its pair
+        assert!(validate_honorary_position_accounts(&[keys[2], keys[0]], &position_owner, &accounts).is_err());
+        assert!(validate_honorary_position_accounts(&positions, &position_owner, &accounts[..3]).is_err());
+        
+        // Each position is checked against its own NFT
+        let swapped = vec![accounts[0].clone(), accounts[3].clone(), accounts[2].clone(), accounts[1].clone()];
+        assert!(validate_honorary_position_accounts(&positions, &position_owner, &swapped).is_err());
+    }
+    
     #[test]
     fn test_fee_amount_calculations() {
         let quote_mint = Pubkey::new_unique();
@@ -1,20 +1,23 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount};
-use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{transfer, Token, TokenAccount, Transfer};
 
 use crate::{
     constants::*,
     error::ErrorCode,
-    state::{PolicyConfig, DistributionProgress},
+    state::{PolicyConfig, DistributionProgress, DustPolicy, InvestorAccrual, CatchUpMode},
     utils::{
         math::{
-            calculate_distribution, calculate_investor_weight, calculate_individual_payout,
-            enforce_daily_cap, calculate_dust_payout,
+            calculate_distribution, calculate_investor_weight, apportion_largest_remainder,
+            split_at_min_payout, evaluate_cap_policy, evaluate_investor_cap, apply_cap_scale, CapPolicy,
+            split_evenly,
         },
-        streamflow::StreamflowIntegration,
+        dust_distribution::DustDistribution,
+        vesting::InvestorData,
+        weights::{InvestorWeightSource, WeightWindow},
     },
 
 };
+use std::collections::HashMap;
 
 /// Individual investor payout information
 #[derive(Debug, Clone)]
@@ -35,79 +38,366 @@ pub struct BatchPayoutResult {
     pub total_dust: u64,
     pub processed_count: usize,
     pub payouts: Vec<InvestorPayout>,
+    /// Amount withheld by the daily cap and deferred to the next day
+    pub cap_overflow_deferred: u64,
+    /// Sub-threshold allocations added to investor accrual PDAs on this page
+    pub investor_accrued: u64,
+    /// Accrued balances released to investors on this page (included in `total_paid`)
+    pub investor_accrual_released: u64,
+    /// Dust paid out on this page (included in `total_paid`)
+    pub dust_paid: u64,
+    /// Investor that received the page's dust payout
+    pub dust_recipient: Option<Pubkey>,
+    /// Investor payouts after the page caps (excluding dust)
+    pub investor_paid: u64,
+    /// Investor payouts before the page caps
+    pub investor_requested: u64,
+}
+
+/// Token accounts a page's payouts move through
+pub struct PayoutAccounts<'a, 'info> {
+    pub token_program: &'a Program<'info, Token>,
+    pub treasury_ata: &'a Account<'info, TokenAccount>,
+    pub position_owner_pda: &'a AccountInfo<'info>,
+    /// The page's payout accounts: accrual PDAs and quote ATAs
+    pub payout_accounts: &'a [AccountInfo<'info>],
+}
+
+/// One missed epoch's part of a catch-up day's investor share
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CatchUpEpochShare {
+    pub epoch_index: u64,
+    /// Window the epoch's locked total is measured over
+    pub window: WeightWindow,
+    /// Slice of the day's claim attributed to the epoch
+    pub claimed_quote: u64,
+    pub total_locked: u64,
+    pub investor_share: u64,
 }
 
 /// Investor distribution system for paginated fee payouts
 pub struct InvestorDistribution;
 
 impl InvestorDistribution {
-    /// Process a page of investors for fee distribution
-    pub fn process_investor_page<'info>(
+    /// Aggregate every investor of the day from all weight accounts
+    ///
+    /// Accounts for the same wallet are merged regardless of where they appear, and
+    /// the result is sorted by wallet so pages index a stable, deduplicated registry.
+    pub fn load_day_investors(
         policy_config: &PolicyConfig,
-        distribution_progress: &mut DistributionProgress,
-        streamflow_accounts: &[AccountInfo<'info>],
+        weight_accounts: &[AccountInfo],
+        weight_window: WeightWindow,
+    ) -> Result<Vec<InvestorData>> {
+        InvestorWeightSource::for_policy(policy_config).aggregate_investor_data(
+            weight_accounts,
+            weight_window,
+        )
+    }
+
+    /// Investor share of a new day's claim under the policy's catch-up mode
+    ///
+    /// `SingleDay` and `CapPerEpoch` take the share of the whole claim at the day's
+    /// locked total. `SplitClaim` splits the claim evenly across the epochs the day
+    /// covers and takes each slice's share at the locked total over that epoch's
+    /// window, read through `locked_total_for`. Returns the day's investor share and,
+    /// when the day covers more than one epoch, its per-epoch breakdown.
+    pub fn day_investor_share(
+        policy_config: &PolicyConfig,
+        progress: &DistributionProgress,
+        claimed_quote: u64,
+        day_locked_total: u64,
+        mut locked_total_for: impl FnMut(WeightWindow) -> Result<u64>,
+    ) -> Result<(u64, Vec<CatchUpEpochShare>)> {
+        let (day_share, _) = calculate_distribution(
+            claimed_quote,
+            day_locked_total,
+            policy_config.y0_total_allocation,
+            policy_config.investor_fee_share_bps,
+        )?;
+
+        let epochs = progress.catch_up_epochs(policy_config.catch_up_mode);
+        if epochs <= 1 {
+            return Ok((day_share, Vec::new()));
+        }
+
+        // The day's own epoch is the newest; the claim accrued over the epochs before it
+        let first_epoch = progress.epoch_index.saturating_sub(epochs);
+        let windows = progress
+            .weight_window()
+            .split_epochs(epochs, policy_config.distribution_period_secs);
+        let claim_slices = split_evenly(claimed_quote, epochs);
+        let day_share_slices = split_evenly(day_share, epochs);
+
+        let mut investor_share = 0u64;
+        let mut breakdown = Vec::with_capacity(epochs as usize);
+        for (offset, window) in windows.into_iter().enumerate() {
+            let (total_locked, epoch_share) = match policy_config.catch_up_mode {
+                CatchUpMode::SplitClaim => {
+                    let total_locked = locked_total_for(window)?;
+                    let (epoch_share, _) = calculate_distribution(
+                        claim_slices[offset],
+                        total_locked,
+                        policy_config.y0_total_allocation,
+                        policy_config.investor_fee_share_bps,
+                    )?;
+                    (total_locked, epoch_share)
+                },
+                CatchUpMode::SingleDay | CatchUpMode::CapPerEpoch => {
+                    (day_locked_total, day_share_slices[offset])
+                },
+            };
+            investor_share = investor_share
+                .checked_add(epoch_share)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+            breakdown.push(CatchUpEpochShare {
+                epoch_index: first_epoch + offset as u64,
+                window,
+                claimed_quote: claim_slices[offset],
+                total_locked,
+                investor_share: epoch_share,
+            });
+        }
+
+        Ok((investor_share, breakdown))
+    }
+
+    /// Split the remaining accounts into weight accounts and payout accounts
+    ///
+    /// Weight accounts (vesting or stake accounts) come first; payout accounts follow
+    /// them: token accounts (e.g. dust recipient ATAs) and investor accrual PDAs, which
+    /// are empty system accounts until created.
+    pub fn split_remaining_accounts<'a, 'info>(
+        remaining_accounts: &'a [AccountInfo<'info>],
+    ) -> Result<(&'a [AccountInfo<'info>], &'a [AccountInfo<'info>])> {
+        let is_payout_account = |account: &AccountInfo| {
+            *account.owner == anchor_spl::token::ID
+                || *account.owner == crate::ID
+                || (*account.owner == anchor_lang::system_program::ID && account.data_is_empty())
+        };
+        let split_index = remaining_accounts
+            .iter()
+            .position(is_payout_account)
+            .unwrap_or(remaining_accounts.len());
+        let (weight_accounts, payout_accounts) = remaining_accounts.split_at(split_index);
+
+        require!(
+            payout_accounts.iter().all(is_payout_account),
+            ErrorCode::InvalidDustRecipient
+        );
+
+        Ok((weight_accounts, payout_accounts))
+    }
+
+    /// Apportion the investor pool across the whole day registry by weight
+    ///
+    /// Uses the largest-remainder method over the full registry, so every page slices the
+    /// same exact allocation and the pages of a day sum to the pool to the lamport.
+    pub fn apportion_day_pool(
+        day_investors: &[InvestorData],
         total_investor_amount: u64,
-        total_locked_amount: u64,
+    ) -> Result<Vec<u64>> {
+        let weights: Vec<u64> = day_investors
+            .iter()
+            .map(|investor| investor.locked_amount)
+            .collect();
+        apportion_largest_remainder(total_investor_amount, &weights)
+    }
+
+    /// Load the accrual PDAs of a page's investors in one quote mint's ledger, keyed by
+    /// investor
+    ///
+    /// Every investor's PDA address must be among the payout accounts, so an accrual that
+    /// exists cannot be left out of a page; an address not created yet is skipped.
+    pub fn load_investor_accruals<'a, 'info>(
+        payout_accounts: &'a [AccountInfo<'info>],
+        vault: &Pubkey,
+        ledger_seed: &[u8],
+        page_investors: &[InvestorData],
+    ) -> Result<HashMap<Pubkey, (&'a AccountInfo<'info>, InvestorAccrual)>> {
+        let mut accruals = HashMap::new();
+        for investor in page_investors {
+            let address = InvestorAccrual::address(vault, &investor.wallet, ledger_seed);
+            let account = payout_accounts
+                .iter()
+                .find(|account| account.key() == address)
+                .ok_or(ErrorCode::MissingInvestorAccrual)?;
+            if account.data_is_empty() {
+                continue;
+            }
+
+            let accrual = InvestorAccrual::load(account, vault, ledger_seed)?;
+            accruals.insert(investor.wallet, (account, accrual));
+        }
+        Ok(accruals)
+    }
+
+    /// Slice a page out of the day's investor registry
+    pub fn investor_page(
+        day_investors: &[InvestorData],
         page_start: usize,
         page_size: usize,
-        current_timestamp: i64,
-        token_program: &Program<'info, Token>,
-        associated_token_program: &Program<'info, AssociatedToken>,
-        system_program: &Program<'info, System>,
-        treasury_ata: &Account<'info, TokenAccount>,
-        position_owner_pda: &AccountInfo<'info>,
-    ) -> Result<BatchPayoutResult> {
-        // Validate page parameters
+    ) -> Result<&[InvestorData]> {
         require!(
             page_size > 0 && page_size <= MAX_PAGE_SIZE as usize,
             ErrorCode::InvalidPaginationCursor
         );
-        
+
         require!(
-            page_start < streamflow_accounts.len(),
+            page_start < day_investors.len(),
             ErrorCode::InvalidPaginationCursor
         );
+
+        let end_index = std::cmp::min(page_start + page_size, day_investors.len());
+        Ok(&day_investors[page_start..end_index])
+    }
+
+    /// Execute a planned page: persist the accruals of its investors that have an accrual
+    /// PDA, and transfer its payouts and dust out of the treasury
+    pub fn execute_investor_page<'info>(
+        page: &BatchPayoutResult,
+        policy_config: &PolicyConfig,
+        quote_mint: &Pubkey,
+        accruals: &HashMap<Pubkey, InvestorAccrual>,
+        accrual_accounts: &HashMap<Pubkey, &AccountInfo<'info>>,
+        accounts: &PayoutAccounts<'_, 'info>,
+    ) -> Result<()> {
+        for (investor, accrual_info) in accrual_accounts.iter() {
+            if let Some(accrual) = accruals.get(investor) {
+                accrual.store(accrual_info)?;
+            }
+        }
         
-        let end_index = std::cmp::min(page_start + page_size, streamflow_accounts.len());
-        let page_accounts = &streamflow_accounts[page_start..end_index];
+        // Transfer the page's payouts to the investors' quote ATAs
+        if page.investor_paid > 0 {
+            let transferred = Self::execute_investor_payouts(
+                &page.payouts,
+                policy_config,
+                quote_mint,
+                accounts.token_program,
+                accounts.treasury_ata,
+                accounts.position_owner_pda,
+                accounts.payout_accounts,
+            )?;
+            require!(
+                transferred == page.investor_paid,
+                ErrorCode::AccountingInvariantViolated
+            );
+        }
         
-        msg!("Processing investor page: start={}, size={}, end={}", page_start, page_size, end_index);
+        // Pay the page's dust to the investor the dust policy selected
+        if let Some(recipient) = page.dust_recipient {
+            let recipient_ata = DustDistribution::find_recipient_ata(
+                accounts.payout_accounts,
+                &recipient,
+                quote_mint,
+            )?;
+            DustDistribution::transfer_dust(
+                page.dust_paid,
+                policy_config,
+                accounts.token_program,
+                accounts.treasury_ata,
+                recipient_ata,
+                accounts.position_owner_pda,
+            )?;
+            msg!("Paid {} of dust to investor {} ({:?})", page.dust_paid, recipient, policy_config.dust_policy);
+        }
         
-        // Aggregate investor data from Streamflow accounts
-        let investor_data = StreamflowIntegration::aggregate_investor_data(
-            page_accounts,
-            &policy_config.quote_mint,
-            current_timestamp,
-        )?;
+        Ok(())
+    }
+    
+    /// Work out a page's payouts and apply them to the distribution progress without
+    /// moving tokens
+    ///
+    /// `page_investors` are the registry entries at the pagination cursor; chained onto
+    /// `next_registry_hash`, the registry hash of the entries after them, they are checked
+    /// against the day's committed registry. Updates `accruals` in place for investors
+    /// that have one; the caller executes the transfers described by the result and
+    /// persists the accruals.
+    pub fn plan_investor_page(
+        policy_config: &PolicyConfig,
+        distribution_progress: &mut DistributionProgress,
+        page_investors: &[InvestorData],
+        next_registry_hash: [u8; 32],
+        total_investor_amount: u64,
+        accruals: &mut HashMap<Pubkey, InvestorAccrual>,
+        current_timestamp: i64,
+    ) -> Result<BatchPayoutResult> {
+        // Each investor appears once in the day registry, so payouts are per wallet
+        let page_start = distribution_progress.pagination_cursor as usize;
+        distribution_progress.record_registry_page(page_investors, next_registry_hash)?;
+        let investor_data = page_investors;
+        
+        msg!("Processing investor page: start={}, end={}",
+             page_start, page_start + investor_data.len());
         
         msg!("Found {} unique investors in page", investor_data.len());
         
+        // This page's slice of the exact day allocation over the committed registry
+        let page_allocations = distribution_progress
+            .take_page_allocations(investor_data, total_investor_amount)?;
+        let total_locked_amount = distribution_progress.day_total_locked;
+        
         // Calculate individual payouts
+        let caps = CapPolicy::for_day(policy_config, distribution_progress);
         let mut payouts = Vec::new();
         let mut total_page_locked = 0u64;
+        let mut investor_cap_withheld = 0u64;
+        let mut investor_accrued = 0u64;
+        let mut investor_accrual_released = 0u64;
         
-        for investor in investor_data {
+        for (investor, &allocation) in investor_data.iter().zip(&page_allocations) {
             total_page_locked = total_page_locked
                 .checked_add(investor.locked_amount)
                 .ok_or(ErrorCode::ArithmeticOverflow)?;
             
-            // Calculate investor weight based on their locked amount
+            // Investor weight based on their locked amount (used to rank dust recipients)
             let weight = calculate_investor_weight(
                 investor.locked_amount,
                 total_locked_amount,
             )?;
             
-            // Calculate individual payout with dust handling
-            let (payout_amount, dust_amount) = calculate_individual_payout(
-                total_investor_amount,
-                weight,
-                policy_config.min_payout_lamports,
-            )?;
+            // Clamp to the per-investor daily maximum; the excess is deferred
+            let investor_cap = evaluate_investor_cap(&caps, allocation);
+            if investor_cap.withheld > 0 {
+                msg!("Investor {} limited by {:?}: withheld {}", investor.wallet, investor_cap.binding, investor_cap.withheld);
+                investor_cap_withheld = investor_cap_withheld
+                    .checked_add(investor_cap.withheld)
+                    .ok_or(ErrorCode::ArithmeticOverflow)?;
+            }
+            
+            // Allocations below the minimum payout accrue to the investor's PDA (released
+            // with the accrued balance once it reaches the threshold) or otherwise become dust
+            let (payout_amount, dust_amount) = match accruals.get_mut(&investor.wallet) {
+                Some(accrual) => {
+                    let previously_accrued = accrual.accrued_amount;
+                    let released = accrual.accrue(
+                        investor_cap.allowed,
+                        policy_config.min_payout_lamports,
+                        current_timestamp,
+                    )?;
+                    
+                    if released > 0 {
+                        investor_accrual_released = investor_accrual_released
+                            .checked_add(previously_accrued)
+                            .ok_or(ErrorCode::ArithmeticOverflow)?;
+                        msg!("Investor {}: released {} accrued", investor.wallet, previously_accrued);
+                    } else {
+                        investor_accrued = investor_accrued
+                            .checked_add(investor_cap.allowed)
+                            .ok_or(ErrorCode::ArithmeticOverflow)?;
+                    }
+                    (released, 0)
+                }
+                None => split_at_min_payout(
+                    investor_cap.allowed,
+                    policy_config.min_payout_lamports,
+                ),
+            };
             
             // Derive investor's ATA address
             let (ata_address, needs_creation) = Self::get_or_derive_investor_ata(
                 &investor.wallet,
-                &policy_config.quote_mint,
+                &distribution_progress.quote_mint,
             )?;
             
             payouts.push(InvestorPayout {
@@ -124,6 +414,8 @@ impl InvestorDistribution {
                  investor.wallet, investor.locked_amount, weight, payout_amount, dust_amount);
         }
         
+        distribution_progress.update_investor_accrued(investor_accrued, investor_accrual_released)?;
+        
         // Calculate batch totals
         let total_paid: u64 = payouts.iter().map(|p| p.payout_amount).sum();
         let total_dust: u64 = payouts.iter().map(|p| p.dust_amount).sum();
@@ -134,131 +426,196 @@ impl InvestorDistribution {
             .checked_add(carry_over_dust)
             .ok_or(ErrorCode::ArithmeticOverflow)?;
         
-        // Check if accumulated dust can be paid out
-        let (dust_payout, remaining_dust) = calculate_dust_payout(
+        // Split off the dust the policy pays out on this page
+        let (mut dust_payout, mut remaining_dust) = DustDistribution::split_page_dust(
+            policy_config.dust_policy,
             total_dust_with_carryover,
             policy_config.min_payout_lamports,
         );
         
-        let final_total_paid = total_paid
-            .checked_add(dust_payout)
-            .ok_or(ErrorCode::ArithmeticOverflow)?;
-        
-        // Enforce daily cap if configured
-        let capped_amount = enforce_daily_cap(
+        // Enforce the daily and rolling-window caps if configured, scaling the page's
+        // payouts down to what they allow
+        let cap_evaluation = evaluate_cap_policy(
             distribution_progress,
-            final_total_paid,
-            policy_config.daily_cap_lamports,
+            &caps,
+            total_paid,
         )?;
+        let scaled_payouts = Self::scale_page_payouts(&payouts, cap_evaluation.allowed, total_paid)?;
+        for (payout, scaled_payout) in payouts.iter_mut().zip(scaled_payouts) {
+            payout.payout_amount = scaled_payout;
+        }
+        let capped_amount: u64 = payouts.iter().map(|p| p.payout_amount).sum();
+        let page_cap_withheld = total_paid
+            .checked_sub(capped_amount)
+            .ok_or(ErrorCode::AccountingInvariantViolated)?;
+        
+        // Dust is only paid out of the capacity the page's payouts leave; otherwise
+        // it stays carried, as does dust without a recipient
+        let dust_capacity = cap_evaluation.remaining_capacity
+            .map(|capacity| capacity.saturating_sub(capped_amount));
+        let dust_recipient = if dust_payout == 0
+            || dust_capacity.is_some_and(|capacity| capacity < dust_payout)
+        {
+            None
+        } else {
+            DustDistribution::select_page_recipient(
+                policy_config.dust_policy,
+                distribution_progress,
+                page_start,
+                &payouts,
+            )
+        };
+        if dust_recipient.is_none() {
+            remaining_dust = remaining_dust
+                .checked_add(dust_payout)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+            dust_payout = 0;
+        }
         
-        msg!("Batch payout calculation: paid={}, dust={}, carryover={}, dust_payout={}, remaining_dust={}, capped={}",
-             total_paid, total_dust, carry_over_dust, dust_payout, remaining_dust, capped_amount);
+        // Whatever the caps withhold rolls into the next day's investor pool
+        let cap_overflow_deferred = page_cap_withheld
+            .checked_add(investor_cap_withheld)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        distribution_progress.add_cap_overflow(cap_overflow_deferred)?;
         
-        // Execute payouts if there's amount to distribute
-        if capped_amount > 0 {
-            Self::execute_investor_payouts(
-                &payouts,
-                capped_amount,
-                final_total_paid,
-                dust_payout,
-                policy_config,
-                token_program,
-                associated_token_program,
-                system_program,
-                treasury_ata,
-                position_owner_pda,
-            )?;
-            
-            // Update distribution progress
-            distribution_progress.add_distributed(capped_amount)?;
+        msg!("Batch payout calculation: paid={}, dust={}, carryover={}, dust_payout={}, remaining_dust={}, capped={}, deferred={}, binding={:?}",
+             total_paid, total_dust, carry_over_dust, dust_payout, remaining_dust, capped_amount,
+             cap_overflow_deferred, cap_evaluation.binding);
+        
+        if dust_recipient.is_some() && policy_config.dust_policy == DustPolicy::RoundRobin {
+            distribution_progress.advance_dust_round_robin(
+                distribution_progress.day_investor_count as usize,
+            );
+        }
+        
+        // Update distribution progress
+        let page_distributed = capped_amount
+            .checked_add(dust_payout)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        if page_distributed > 0 {
+            distribution_progress.add_distributed(page_distributed)?;
         }
         
         // Update dust tracking
         distribution_progress.carry_over_dust = remaining_dust;
         
         // Update pagination cursor
-        distribution_progress.advance_cursor(page_size as u32)?;
+        distribution_progress.advance_cursor(investor_data.len() as u32)?;
         
         Ok(BatchPayoutResult {
-            total_paid: capped_amount,
+            total_paid: page_distributed,
             total_dust: remaining_dust,
             processed_count: payouts.len(),
             payouts,
+            cap_overflow_deferred,
+            investor_accrued,
+            investor_accrual_released,
+            dust_paid: dust_payout,
+            dust_recipient,
+            investor_paid: capped_amount,
+            investor_requested: total_paid,
         })
     }
     
-    /// Execute actual token transfers to investors
+    /// Transfer each investor's payout from the treasury to their quote ATA, which must
+    /// be among the payout accounts
+    /// Returns the total transferred
     fn execute_investor_payouts<'info>(
         payouts: &[InvestorPayout],
-        capped_total: u64,
-        original_total: u64,
-        dust_payout: u64,
-        _policy_config: &PolicyConfig,
-        _token_program: &Program<'info, Token>,
-        _associated_token_program: &Program<'info, AssociatedToken>,
-        _system_program: &Program<'info, System>,
-        _treasury_ata: &Account<'info, TokenAccount>,
-        _position_owner_pda: &AccountInfo<'info>,
-    ) -> Result<()> {
-        let mut total_transferred = 0u64;
-        
-        // Calculate scaling factor if we hit daily cap
-        let scale_factor = if original_total > 0 {
-            (capped_total as u128 * WEIGHT_PRECISION) / original_total as u128
-        } else {
-            WEIGHT_PRECISION
-        };
+        policy_config: &PolicyConfig,
+        quote_mint: &Pubkey,
+        token_program: &Program<'info, Token>,
+        treasury_ata: &Account<'info, TokenAccount>,
+        position_owner_pda: &AccountInfo<'info>,
+        payout_accounts: &[AccountInfo<'info>],
+    ) -> Result<u64> {
+        let total_payout = payouts
+            .iter()
+            .try_fold(0u64, |total, payout| total.checked_add(payout.payout_amount))
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        require!(
+            treasury_ata.amount >= total_payout,
+            ErrorCode::InsufficientFunds
+        );
         
-        msg!("Executing payouts with scale factor: {}", scale_factor);
+        let bump_seed = [policy_config.bump];
+        let signer_seeds: &[&[u8]] = &[
+            VAULT_SEED,
+            policy_config.vault.as_ref(),
+            b"investor_fee_pos_owner",
+            &bump_seed,
+        ];
+        let signer_seeds_slice = &[signer_seeds];
         
-        // Process individual investor payouts
-        for payout in payouts {
-            if payout.payout_amount == 0 {
-                continue; // Skip zero payouts
-            }
-            
-            // Scale the payout if we hit daily cap
-            let scaled_payout = if scale_factor < WEIGHT_PRECISION {
-                ((payout.payout_amount as u128 * scale_factor) / WEIGHT_PRECISION) as u64
-            } else {
-                payout.payout_amount
-            };
-            
-            if scaled_payout == 0 {
-                continue; // Skip if scaling resulted in zero
-            }
-            
-            // Create ATA if needed (this would require additional accounts in real implementation)
-            if payout.needs_ata_creation {
-                msg!("Note: ATA creation needed for investor {} (not implemented in this demo)", payout.wallet);
-                // In full implementation, this would create the ATA
-                // For now, we'll assume ATAs exist or skip the payout
-                continue;
-            }
+        let mut total_transferred = 0u64;
+        for payout in payouts.iter().filter(|payout| payout.payout_amount > 0) {
+            let investor_ata = Self::find_investor_ata(payout_accounts, &payout.wallet, quote_mint)?;
             
-            // Execute transfer from treasury to investor
-            // Note: In real implementation, this would require the investor's ATA account
-            // For demo purposes, we'll just log the transfer
-            msg!("Transferring {} tokens to investor {}", scaled_payout, payout.wallet);
+            let transfer_ctx = CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                Transfer {
+                    from: treasury_ata.to_account_info(),
+                    to: investor_ata.clone(),
+                    authority: position_owner_pda.clone(),
+                },
+                signer_seeds_slice,
+            );
+            transfer(transfer_ctx, payout.payout_amount)?;
             
             total_transferred = total_transferred
-                .checked_add(scaled_payout)
-                .ok_or(ErrorCode::ArithmeticOverflow)?;
-        }
-        
-        // Handle dust payout if applicable
-        if dust_payout > 0 {
-            msg!("Processing dust payout of {} tokens", dust_payout);
-            // In real implementation, this might go to the first eligible investor
-            // or be handled according to policy
-            total_transferred = total_transferred
-                .checked_add(dust_payout)
+                .checked_add(payout.payout_amount)
                 .ok_or(ErrorCode::ArithmeticOverflow)?;
         }
         
         msg!("Total transferred in batch: {}", total_transferred);
         
-        Ok(())
+        Ok(total_transferred)
+    }
+    
+    /// Find an investor's quote ATA among the payout accounts
+    pub fn find_investor_ata<'a, 'info>(
+        payout_accounts: &'a [AccountInfo<'info>],
+        investor: &Pubkey,
+        quote_mint: &Pubkey,
+    ) -> Result<&'a AccountInfo<'info>> {
+        let ata_address = anchor_spl::associated_token::get_associated_token_address(
+            investor,
+            quote_mint,
+        );
+        
+        payout_accounts
+            .iter()
+            .find(|account| account.key() == ata_address && *account.owner == anchor_spl::token::ID)
+            .ok_or(ErrorCode::InvalidInvestorAta.into())
+    }
+    
+    /// Scale a page's payouts down to the page's capped total
+    ///
+    /// Returns each investor's transfer amount; payouts are left untouched unless the
+    /// caps leave less than the page requested.
+    pub fn scale_page_payouts(
+        payouts: &[InvestorPayout],
+        capped_total: u64,
+        original_total: u64,
+    ) -> Result<Vec<u64>> {
+        let scale_factor = if original_total > 0 {
+            (capped_total as u128 * WEIGHT_PRECISION) / original_total as u128
+        } else {
+            WEIGHT_PRECISION
+        };
+        
+        msg!("Executing payouts with scale factor: {}", scale_factor);
+        
+        payouts
+            .iter()
+            .map(|payout| {
+                if scale_factor < WEIGHT_PRECISION {
+                    apply_cap_scale(payout.payout_amount, scale_factor)
+                } else {
+                    Ok(payout.payout_amount)
+                }
+            })
+            .collect()
     }
     
     /// Get or derive investor's ATA address
@@ -282,12 +639,11 @@ impl InvestorDistribution {
     /// Calculate total distribution amounts for the current page
     pub fn calculate_page_distribution(
         policy_config: &PolicyConfig,
-        streamflow_accounts: &[AccountInfo],
+        day_investors: &[InvestorData],
         claimed_quote_amount: u64,
         total_locked_amount: u64,
         page_start: usize,
         page_size: usize,
-        current_timestamp: i64,
     ) -> Result<(u64, u64)> {
         // Calculate overall distribution split
         let (total_investor_amount, _creator_amount) = calculate_distribution(
@@ -297,16 +653,8 @@ impl InvestorDistribution {
             policy_config.investor_fee_share_bps,
         )?;
         
-        // Get page bounds
-        let end_index = std::cmp::min(page_start + page_size, streamflow_accounts.len());
-        let page_accounts = &streamflow_accounts[page_start..end_index];
-        
-        // Aggregate investor data for this page
-        let investor_data = StreamflowIntegration::aggregate_investor_data(
-            page_accounts,
-            &policy_config.quote_mint,
-            current_timestamp,
-        )?;
+        // Get the investors on this page
+        let investor_data = Self::investor_page(day_investors, page_start, page_size)?;
         
         // Calculate page locked amount
         let page_locked_amount: u64 = investor_data
@@ -314,21 +662,22 @@ impl InvestorDistribution {
             .map(|inv| inv.locked_amount)
             .sum();
         
-        // Calculate proportional amount for this page
-        let page_investor_amount = if total_locked_amount > 0 {
-            (total_investor_amount as u128 * page_locked_amount as u128 / total_locked_amount as u128) as u64
-        } else {
-            0
-        };
+        // The page's slice of the exact day allocation
+        let allocations = Self::apportion_day_pool(day_investors, total_investor_amount)?;
+        let page_investor_amount = allocations[page_start..page_start + investor_data.len()]
+            .iter()
+            .try_fold(0u64, |total, &allocation| total.checked_add(allocation))
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
         
         Ok((page_investor_amount, page_locked_amount))
     }
     
     /// Validate investor distribution parameters
+    ///
+    /// The page's position is checked against the day's committed registry instead.
     pub fn validate_distribution_params(
         policy_config: &PolicyConfig,
-        streamflow_accounts: &[AccountInfo],
-        page_start: usize,
+        weight_accounts: &[AccountInfo],
         page_size: usize,
     ) -> Result<()> {
         // Validate page parameters
@@ -337,17 +686,12 @@ impl InvestorDistribution {
             ErrorCode::InvalidPaginationCursor
         );
         
-        require!(
-            page_start < streamflow_accounts.len(),
-            ErrorCode::InvalidPaginationCursor
-        );
-        
         // Validate policy configuration
         policy_config.validate()?;
         
-        // Validate we have at least one Streamflow account
+        // Validate we have at least one weight account
         require!(
-            !streamflow_accounts.is_empty(),
+            !weight_accounts.is_empty(),
             ErrorCode::StreamflowValidationFailed
         );
         
@@ -356,20 +700,11 @@ impl InvestorDistribution {
     
     /// Check if all investors in page have sufficient locked amounts
     pub fn validate_investor_eligibility(
-        streamflow_accounts: &[AccountInfo],
-        expected_mint: &Pubkey,
+        day_investors: &[InvestorData],
         page_start: usize,
         page_size: usize,
-        current_timestamp: i64,
     ) -> Result<bool> {
-        let end_index = std::cmp::min(page_start + page_size, streamflow_accounts.len());
-        let page_accounts = &streamflow_accounts[page_start..end_index];
-        
-        let investor_data = StreamflowIntegration::aggregate_investor_data(
-            page_accounts,
-            expected_mint,
-            current_timestamp,
-        )?;
+        let investor_data = Self::investor_page(day_investors, page_start, page_size)?;
         
         // Check if any investors have locked amounts
         let has_eligible_investors = investor_data
@@ -381,20 +716,11 @@ impl InvestorDistribution {
     
     /// Get summary statistics for a page of investors
     pub fn get_page_statistics(
-        streamflow_accounts: &[AccountInfo],
-        expected_mint: &Pubkey,
+        day_investors: &[InvestorData],
         page_start: usize,
         page_size: usize,
-        current_timestamp: i64,
     ) -> Result<PageStatistics> {
-        let end_index = std::cmp::min(page_start + page_size, streamflow_accounts.len());
-        let page_accounts = &streamflow_accounts[page_start..end_index];
-        
-        let investor_data = StreamflowIntegration::aggregate_investor_data(
-            page_accounts,
-            expected_mint,
-            current_timestamp,
-        )?;
+        let investor_data = Self::investor_page(day_investors, page_start, page_size)?;
         
         let total_locked: u64 = investor_data.iter().map(|inv| inv.locked_amount).sum();
         let total_allocation: u64 = investor_data.iter().map(|inv| inv.total_allocation).sum();
@@ -406,7 +732,7 @@ impl InvestorDistribution {
             total_locked_amount: total_locked,
             total_allocation_amount: total_allocation,
             page_start,
-            page_size: end_index - page_start,
+            page_size: investor_data.len(),
         })
     }
 }
@@ -429,7 +755,9 @@ pub struct PageStatistics {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::state::{PolicyConfig, DistributionProgress};
+    use crate::utils::streamflow::STREAMFLOW_PROGRAM_ID;
+    use crate::utils::math::calculate_dust_payout;
+    use crate::state::{registry_hash, CatchUpMode, PolicyConfig, DistributionProgress};
     use anchor_lang::prelude::Pubkey;
 
     fn create_mock_policy_config() -> PolicyConfig {
@@ -442,6 +770,7 @@ mod tests {
             min_payout_lamports: 1000,
             y0_total_allocation: 10_000_000,
             bump: 255,
+            ..PolicyConfig::default()
         }
     }
 
@@ -449,11 +778,8 @@ mod tests {
         DistributionProgress {
             vault: Pubkey::new_unique(),
             last_distribution_ts: 1000,
-            current_day_distributed: 0,
-            carry_over_dust: 0,
-            pagination_cursor: 0,
-            day_complete: false,
             bump: 255,
+            ..DistributionProgress::default()
         }
     }
 
@@ -485,6 +811,380 @@ mod tests {
         assert_eq!(page_amount, 100); // 500 * (1M / 5M) = 100
     }
 
+    fn create_day_investors(locked_amounts: &[u64]) -> Vec<InvestorData> {
+        let mut investors: Vec<InvestorData> = locked_amounts
+            .iter()
+            .map(|&locked_amount| InvestorData {
+                wallet: Pubkey::new_unique(),
+                locked_amount,
+                total_allocation: locked_amount * 2,
+                stream_accounts: vec![Pubkey::new_unique()],
+            })
+            .collect();
+        investors.sort_by_key(|investor| investor.wallet);
+        investors
+    }
+
+    /// Registry hash of the day's entries after `page_end`
+    fn registry_hash_after(day_investors: &[InvestorData], page_end: usize) -> [u8; 32] {
+        registry_hash(&day_investors[page_end..]).unwrap()
+    }
+
+    #[test]
+    fn test_catch_up_day_investor_share() {
+        let mut policy_config = create_mock_policy_config();
+        let mut progress = create_mock_distribution_progress();
+        progress.weight_window_start_ts = 0;
+        progress.last_distribution_ts = 3 * TWENTY_FOUR_HOURS;
+        progress.epoch_index = 10;
+        progress.missed_epochs = 2;
+
+        // Locked totals per epoch window: fully locked, half locked, fully unlocked
+        let locked_total_for = |window: WeightWindow| -> Result<u64> {
+            Ok(match window.end_ts / TWENTY_FOUR_HOURS {
+                1 => 10_000_000,
+                2 => 5_000_000,
+                _ => 0,
+            })
+        };
+
+        // One day: the whole claim at the day's locked total (50% of Y0)
+        let (share, breakdown) = InvestorDistribution::day_investor_share(
+            &policy_config, &progress, 3000, 5_000_000, locked_total_for,
+        ).unwrap();
+        assert_eq!(share, 1500);
+        assert!(breakdown.is_empty());
+
+        // CapPerEpoch keeps the day's share and reports it per epoch
+        policy_config.catch_up_mode = CatchUpMode::CapPerEpoch;
+        let (share, breakdown) = InvestorDistribution::day_investor_share(
+            &policy_config, &progress, 3000, 5_000_000, locked_total_for,
+        ).unwrap();
+        assert_eq!(share, 1500);
+        assert_eq!(breakdown.iter().map(|epoch| epoch.epoch_index).collect::<Vec<_>>(), vec![7, 8, 9]);
+        assert!(breakdown.iter().all(|epoch| epoch.investor_share == 500 && epoch.claimed_quote == 1000));
+
+        // SplitClaim measures each epoch's slice at that epoch's locked total:
+        // 80% (capped by the fee share) + 50% + 0% of 1000 each
+        policy_config.catch_up_mode = CatchUpMode::SplitClaim;
+        let (share, breakdown) = InvestorDistribution::day_investor_share(
+            &policy_config, &progress, 3000, 5_000_000, locked_total_for,
+        ).unwrap();
+        assert_eq!(share, 1300);
+        assert_eq!(
+            breakdown.iter().map(|epoch| (epoch.total_locked, epoch.investor_share)).collect::<Vec<_>>(),
+            vec![(10_000_000, 800), (5_000_000, 500), (0, 0)]
+        );
+        assert_eq!(breakdown[0].window, WeightWindow { start_ts: 0, end_ts: TWENTY_FOUR_HOURS });
+
+        // The catch-up spans at most MAX_CATCH_UP_EPOCHS
+        progress.missed_epochs = 30;
+        assert_eq!(progress.catch_up_epochs(policy_config.catch_up_mode), MAX_CATCH_UP_EPOCHS);
+        assert_eq!(progress.catch_up_epochs(CatchUpMode::SingleDay), 1);
+    }
+
+    #[test]
+    fn test_split_remaining_accounts() {
+        let keys: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_unique()).collect();
+        let owners = [
+            STREAMFLOW_PROGRAM_ID,
+            STREAMFLOW_PROGRAM_ID,
+            anchor_spl::token::ID,
+            crate::ID,
+            anchor_lang::system_program::ID,
+        ];
+        let mut lamports = [0u64; 5];
+        let mut data = [Vec::<u8>::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new()];
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(owners.iter())
+            .zip(lamports.iter_mut().zip(data.iter_mut()))
+            .map(|((key, owner), (lamports, data))| {
+                AccountInfo::new(key, false, false, lamports, data, owner, false, 0)
+            })
+            .collect();
+
+        let (weight_accounts, payout_accounts) =
+            InvestorDistribution::split_remaining_accounts(&accounts).unwrap();
+        assert_eq!(weight_accounts.len(), 2);
+        // An accrual PDA not created yet is a payout account too
+        assert_eq!(payout_accounts.len(), 3);
+        assert_eq!(payout_accounts[0].key(), keys[2]);
+
+        // Weight accounts may not follow payout accounts
+        let reordered = vec![accounts[2].clone(), accounts[0].clone()];
+        assert!(InvestorDistribution::split_remaining_accounts(&reordered).is_err());
+    }
+
+    #[test]
+    fn test_page_requires_investor_accruals() {
+        let vault = Pubkey::new_unique();
+        let investors = create_day_investors(&[100, 200, 300]);
+        let keys: Vec<Pubkey> = investors
+            .iter()
+            .map(|investor| InvestorAccrual::address(&vault, &investor.wallet, &[]))
+            .collect();
+
+        // The first investor has an accrual; the second's is not created yet
+        let (_, bump) = Pubkey::find_program_address(
+            &[ACCRUAL_SEED, vault.as_ref(), investors[0].wallet.as_ref()],
+            &crate::ID,
+        );
+        let mut accrual = InvestorAccrual {
+            vault,
+            investor: investors[0].wallet,
+            accrued_amount: 0,
+            total_released: 0,
+            last_update_ts: 0,
+            bump,
+        };
+        accrual.accrue(40, 1_000, 10).unwrap();
+        let owners = [crate::ID, anchor_lang::system_program::ID];
+        let mut lamports = [0u64; 2];
+        let mut data = [vec![0u8; 8 + InvestorAccrual::INIT_SPACE], Vec::new()];
+        accrual.try_serialize(&mut &mut data[0][..]).unwrap();
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(owners.iter())
+            .zip(lamports.iter_mut().zip(data.iter_mut()))
+            .map(|((key, owner), (lamports, data))| {
+                AccountInfo::new(key, false, true, lamports, data, owner, false, 0)
+            })
+            .collect();
+
+        let accruals =
+            InvestorDistribution::load_investor_accruals(&accounts, &vault, &[], &investors[..2])
+                .unwrap();
+        assert_eq!(accruals.len(), 1);
+        assert_eq!(accruals[&investors[0].wallet].1.accrued_amount, 40);
+
+        // A page may not leave out an investor's accrual PDA
+        assert!(InvestorDistribution::load_investor_accruals(&accounts, &vault, &[], &investors).is_err());
+        assert!(InvestorDistribution::load_investor_accruals(&accounts[1..], &vault, &[], &investors[..2]).is_err());
+    }
+
+    #[test]
+    fn test_pages_index_unique_investors() {
+        let day_investors = create_day_investors(&[100, 0, 300, 400, 0]);
+
+        let page = InvestorDistribution::investor_page(&day_investors, 0, 2).unwrap();
+        assert_eq!(page.len(), 2);
+        let page = InvestorDistribution::investor_page(&day_investors, 4, 2).unwrap();
+        assert_eq!(page.len(), 1);
+
+        // Cursor past the last investor is rejected
+        assert!(InvestorDistribution::investor_page(&day_investors, 5, 2).is_err());
+        assert!(InvestorDistribution::investor_page(&day_investors, 0, 0).is_err());
+
+        let stats = InvestorDistribution::get_page_statistics(&day_investors, 0, 5).unwrap();
+        assert_eq!(stats.total_investors, 5);
+        assert_eq!(stats.eligible_investors, 3);
+        assert_eq!(stats.total_locked_amount, 800);
+        assert_eq!(stats.total_allocation_amount, 1600);
+        assert_eq!(stats.page_size, 5);
+    }
+
+    #[test]
+    fn test_page_distribution_over_day_registry() {
+        let policy_config = create_mock_policy_config();
+        let day_investors = create_day_investors(&[1_000_000, 1_000_000, 3_000_000]);
+        let total_locked = 5_000_000u64;
+
+        // Pages cover the investor pool exactly once
+        let mut total_page_amount = 0u64;
+        for page_start in 0..day_investors.len() {
+            let (page_amount, _) = InvestorDistribution::calculate_page_distribution(
+                &policy_config,
+                &day_investors,
+                1000,
+                total_locked,
+                page_start,
+                1,
+            ).unwrap();
+            total_page_amount += page_amount;
+        }
+        assert_eq!(total_page_amount, 500);
+
+        let eligible = InvestorDistribution::validate_investor_eligibility(&day_investors, 0, 3).unwrap();
+        assert!(eligible);
+    }
+
+    #[test]
+    fn test_pages_verify_committed_registry() {
+        let policy_config = PolicyConfig {
+            daily_cap_lamports: None,
+            min_payout_lamports: 0,
+            ..create_mock_policy_config()
+        };
+        let day_investors = create_day_investors(&[333, 1_000, 7, 250, 999, 0, 42]);
+        let pool = 10_007u64;
+        let expected = InvestorDistribution::apportion_day_pool(&day_investors, pool).unwrap();
+
+        // Pages carrying only their own investors take the registry-wide shares
+        let mut progress = create_mock_distribution_progress();
+        progress.commit_day_registry(&day_investors, pool).unwrap();
+        let mut accruals = HashMap::new();
+        let mut paid = Vec::new();
+        for (index, page) in day_investors.chunks(3).enumerate() {
+            let result = InvestorDistribution::plan_investor_page(
+                &policy_config,
+                &mut progress,
+                page,
+                registry_hash_after(&day_investors, index * 3 + page.len()),
+                pool,
+                &mut accruals,
+                0,
+            ).unwrap();
+            paid.extend(result.payouts.iter().map(|payout| payout.payout_amount));
+        }
+        assert_eq!(paid, expected);
+        assert_eq!(progress.finish_day_registry(pool).unwrap(), 0);
+
+        // A page out of registry order, or one past its end, is rejected
+        let mut progress = create_mock_distribution_progress();
+        progress.commit_day_registry(&day_investors, pool).unwrap();
+        let reversed = vec![day_investors[1].clone(), day_investors[0].clone()];
+        assert!(progress.record_registry_page(&reversed, registry_hash_after(&day_investors, 2)).is_err());
+        let last_page = day_investors[5..7].to_vec();
+        progress.pagination_cursor = 6;
+        assert!(progress.record_registry_page(&last_page, [0; 32]).is_err());
+
+        // A substituted registry is rejected on its first page
+        let substituted = create_day_investors(&[1, 2, 3, 4, 5, 6, 7]);
+        progress.pagination_cursor = 0;
+        assert!(progress.record_registry_page(&substituted[..4], registry_hash_after(&substituted, 4)).is_err());
+    }
+
+    #[test]
+    fn test_tampered_middle_page_rejected_before_payout() {
+        let policy_config = PolicyConfig {
+            daily_cap_lamports: None,
+            min_payout_lamports: 0,
+            ..create_mock_policy_config()
+        };
+        let day_investors = create_day_investors(&[333, 1_000, 7, 250, 999, 42]);
+        let pool = 10_007u64;
+        let mut progress = create_mock_distribution_progress();
+        progress.commit_day_registry(&day_investors, pool).unwrap();
+
+        let mut accruals = HashMap::new();
+        InvestorDistribution::plan_investor_page(
+            &policy_config,
+            &mut progress,
+            &day_investors[..2],
+            registry_hash_after(&day_investors, 2),
+            pool,
+            &mut accruals,
+            0,
+        ).unwrap();
+        let allocated = progress.day_investor_allocated;
+
+        // The middle page swaps an investor's weight account, or forges the hash of the
+        // entries after it; neither is planned, so nothing is paid
+        let mut tampered = day_investors.clone();
+        tampered[3].stream_accounts = vec![Pubkey::new_unique()];
+        tampered[5].stream_accounts = vec![Pubkey::new_unique()];
+        let forged_pages = [
+            (tampered[2..4].to_vec(), registry_hash_after(&day_investors, 4)),
+            (day_investors[2..4].to_vec(), registry_hash_after(&tampered, 4)),
+            (day_investors[2..4].to_vec(), [0; 32]),
+        ];
+        for (page, next_registry_hash) in forged_pages {
+            let result = InvestorDistribution::plan_investor_page(
+                &policy_config,
+                &mut progress,
+                &page,
+                next_registry_hash,
+                pool,
+                &mut accruals,
+                0,
+            );
+            assert_eq!(result.unwrap_err(), ErrorCode::InvestorRegistryMismatch.into());
+            assert_eq!(progress.pagination_cursor, 2);
+            assert_eq!(progress.day_investor_allocated, allocated);
+        }
+
+        // The committed middle page still pays
+        InvestorDistribution::plan_investor_page(
+            &policy_config,
+            &mut progress,
+            &day_investors[2..4],
+            registry_hash_after(&day_investors, 4),
+            pool,
+            &mut accruals,
+            0,
+        ).unwrap();
+        assert_eq!(progress.pagination_cursor, 4);
+    }
+
+    /// Open a day with `investor_share` and run every page of `page_size` investors through
+    /// the page planner, returning each investor's payout in registry order
+    fn run_day_pages(
+        policy_config: &PolicyConfig,
+        progress: &mut DistributionProgress,
+        day_investors: &[InvestorData],
+        investor_share: u64,
+        page_size: usize,
+        current_timestamp: i64,
+    ) -> Vec<u64> {
+        progress.prepare_for_distribution(current_timestamp, TWENTY_FOUR_HOURS, None).unwrap();
+        progress.record_day_claim(investor_share).unwrap();
+        progress.open_day(investor_share, policy_config).unwrap();
+        let pool = progress.payable_investor_pool().unwrap();
+        progress.commit_day_registry(day_investors, pool).unwrap();
+
+        let mut payouts = Vec::new();
+        for (index, page) in day_investors.chunks(page_size).enumerate() {
+            let result = InvestorDistribution::plan_investor_page(
+                policy_config,
+                progress,
+                page,
+                registry_hash_after(day_investors, index * page_size + page.len()),
+                pool,
+                &mut HashMap::new(),
+                current_timestamp,
+            ).unwrap();
+            payouts.extend(result.payouts.iter().map(|payout| payout.payout_amount));
+        }
+        progress.finish_day_registry(pool).unwrap();
+        progress.complete_day();
+        payouts
+    }
+
+    #[test]
+    fn test_cap_rollover_paid_across_every_page() {
+        let mut policy_config = PolicyConfig {
+            daily_cap_lamports: Some(4_000),
+            min_payout_lamports: 0,
+            ..create_mock_policy_config()
+        };
+        let mut progress = create_mock_distribution_progress();
+        let day_investors = create_day_investors(&[100, 200, 300, 400, 500, 600, 700]);
+        let weights: Vec<u64> = day_investors.iter().map(|investor| investor.locked_amount).collect();
+        let day_start = progress.last_distribution_ts + TWENTY_FOUR_HOURS;
+
+        // The cap withholds 6_000 of the first day's pool
+        let first_day = run_day_pages(&policy_config, &mut progress, &day_investors, 10_000, 3, day_start);
+        assert_eq!(first_day.iter().sum::<u64>(), 4_000);
+        assert_eq!(progress.cap_overflow_carry, 6_000);
+
+        // Next day the rollover joins the pool every page is apportioned from, so the
+        // investors on the last pages receive their share of it too
+        policy_config.daily_cap_lamports = Some(10_000);
+        let second_day = run_day_pages(
+            &policy_config,
+            &mut progress,
+            &day_investors,
+            1_000,
+            3,
+            day_start + TWENTY_FOUR_HOURS,
+        );
+        assert_eq!(second_day, apportion_largest_remainder(7_000, &weights).unwrap());
+        let without_rollover = apportion_largest_remainder(1_000, &weights).unwrap();
+        assert!(second_day[6] > without_rollover[6]);
+        assert_eq!(progress.cap_overflow_carry, 0);
+    }
+
     #[test]
     fn test_get_or_derive_investor_ata() {
         let investor_wallet = Pubkey::new_unique();
@@ -509,7 +1209,6 @@ mod tests {
         let result = InvestorDistribution::validate_distribution_params(
             &policy_config,
             &empty_accounts,
-            0,
             10,
         );
         assert!(result.is_err());
@@ -518,7 +1217,6 @@ mod tests {
         let result = InvestorDistribution::validate_distribution_params(
             &policy_config,
             &empty_accounts,
-            0,
             0, // Invalid page size
         );
         assert!(result.is_err());
@@ -526,7 +1224,6 @@ mod tests {
         let result = InvestorDistribution::validate_distribution_params(
             &policy_config,
             &empty_accounts,
-            0,
             (MAX_PAGE_SIZE + 1) as usize, // Too large
         );
         assert!(result.is_err());
@@ -560,6 +1257,13 @@ mod tests {
             total_dust: 50,
             processed_count: 2,
             payouts,
+            cap_overflow_deferred: 0,
+            investor_accrued: 0,
+            investor_accrual_released: 0,
+            dust_paid: 0,
+            dust_recipient: None,
+            investor_paid: 100,
+            investor_requested: 100,
         };
         
         assert_eq!(result.total_paid, 100);
@@ -651,7 +1355,6 @@ mod tests {
         let result = InvestorDistribution::validate_distribution_params(
             &policy_config,
             &empty_accounts,
-            0,
             0, // Invalid page size
         );
         assert!(result.is_err());
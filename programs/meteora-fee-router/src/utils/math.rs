@@ -1,13 +1,16 @@
 use anchor_lang::prelude::*;
 use crate::error::ErrorCode;
 use crate::constants::*;
-use crate::state::DistributionProgress;
+use crate::state::{DistributionProgress, PolicyConfig};
+use crate::utils::vesting::VestingSchedule;
 
 /// Calculate distribution amounts with overflow protection
+/// `weight_total` is the sum of investor weights (locked or staked amounts, per the
+/// policy's weight mode) and is measured against `y0_total`
 /// Returns (investor_amount, creator_amount)
 pub fn calculate_distribution(
     claimed_quote: u64,
-    locked_total: u64,
+    weight_total: u64,
     y0_total: u64,
     investor_fee_share_bps: u16,
 ) -> Result<(u64, u64)> {
@@ -21,8 +24,8 @@ pub fn calculate_distribution(
         return Ok((0, claimed_quote));
     }
 
-    // Calculate f_locked = locked_total / y0_total (in basis points)
-    let f_locked = (locked_total as u128)
+    // Calculate f_locked = weight_total / y0_total (in basis points)
+    let f_locked = (weight_total as u128)
         .checked_mul(MAX_BASIS_POINTS as u128)
         .ok_or(ErrorCode::ArithmeticOverflow)?
         .checked_div(y0_total as u128)
@@ -64,6 +67,112 @@ pub fn calculate_investor_weight(
         .ok_or(ErrorCode::ArithmeticOverflow.into())
 }
 
+/// Calculate the time-weighted average locked amount of a schedule over
+/// `[window_start, window_end)`, integrating the step-wise unlock analytically
+/// Falls back to the point-in-time locked amount for an empty window
+pub fn calculate_time_weighted_locked(
+    schedule: &VestingSchedule,
+    window_start: i64,
+    window_end: i64,
+) -> Result<u64> {
+    if window_end <= window_start {
+        return Ok(schedule.locked_at(window_end));
+    }
+
+    let integral = integrate_locked_amount(schedule, window_start, window_end)?;
+    let duration = (window_end as i128 - window_start as i128) as u128;
+
+    // Average never exceeds the largest locked amount, so it fits in u64
+    Ok((integral / duration) as u64)
+}
+
+/// Integrate the locked amount of a schedule over `[from, to)` (token-seconds)
+pub fn integrate_locked_amount(
+    schedule: &VestingSchedule,
+    from: i64,
+    to: i64,
+) -> Result<u128> {
+    if schedule.closed || to <= from {
+        return Ok(0);
+    }
+
+    let mut integral = 0u128;
+
+    // Before the start or the cliff, everything not yet withdrawn is locked
+    let unlock_start = std::cmp::max(schedule.start_time, schedule.cliff_time);
+    let fully_locked_end = std::cmp::min(to, unlock_start);
+    if fully_locked_end > from {
+        let duration = (fully_locked_end as i128 - from as i128) as u128;
+        integral = (schedule.deposited_amount.saturating_sub(schedule.withdrawn_amount) as u128)
+            .checked_mul(duration)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+    }
+
+    // Between the start/cliff and the end, the lock steps down once per period
+    // (an instant unlock with no period leaves nothing locked)
+    let periodic_start = std::cmp::max(from, unlock_start);
+    let periodic_end = std::cmp::min(to, schedule.end_time);
+    if periodic_end > periodic_start && schedule.period > 0 {
+        let periodic = periodic_locked_integral(schedule, periodic_end)?
+            .checked_sub(periodic_locked_integral(schedule, periodic_start)?)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        integral = integral
+            .checked_add(periodic)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+    }
+
+    // Past the end time nothing is locked
+    Ok(integral)
+}
+
+/// Integral of the periodic locked amount from `cliff_time` to `timestamp`
+///
+/// After `k` whole periods the locked amount is `max(B - k * amount_per_period, 0)`
+/// with `B = deposited - cliff_amount`, so the integral is `period * S(k)` for the
+/// completed periods plus the partial period, where `S(k)` is an arithmetic series
+/// truncated at the period the lock reaches zero.
+fn periodic_locked_integral(schedule: &VestingSchedule, timestamp: i64) -> Result<u128> {
+    let elapsed = (timestamp as i128 - schedule.cliff_time as i128).max(0) as u128;
+    let period = schedule.period as u128;
+    let amount_per_period = schedule.amount_per_period as u128;
+    let base_locked = schedule.deposited_amount.saturating_sub(schedule.cliff_amount) as u128;
+
+    let periods_elapsed = elapsed / period;
+    let partial_period = elapsed % period;
+
+    // Number of periods with a non-zero locked amount: ceil(B / amount_per_period)
+    let periods_until_unlocked = if amount_per_period == 0 {
+        u128::MAX
+    } else {
+        base_locked.div_ceil(amount_per_period)
+    };
+    let locked_periods = std::cmp::min(periods_elapsed, periods_until_unlocked);
+
+    // S(m) = m * B - amount_per_period * m * (m - 1) / 2
+    let series_base = locked_periods
+        .checked_mul(base_locked)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    let series_step = locked_periods
+        .checked_mul(locked_periods.saturating_sub(1))
+        .map(|pairs| pairs / 2)
+        .and_then(|pairs| pairs.checked_mul(amount_per_period))
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    let completed = series_base
+        .checked_sub(series_step)
+        .and_then(|series| series.checked_mul(period))
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    // Partial period at the locked amount after the completed periods
+    let locked_now = base_locked.saturating_sub(periods_elapsed.saturating_mul(amount_per_period));
+    let partial = partial_period
+        .checked_mul(locked_now)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    completed
+        .checked_add(partial)
+        .ok_or(ErrorCode::ArithmeticOverflow.into())
+}
+
 /// Calculate individual payout with dust handling
 /// Returns (payout_amount, dust_amount)
 pub fn calculate_individual_payout(
@@ -78,12 +187,142 @@ pub fn calculate_individual_payout(
         .checked_div(WEIGHT_PRECISION)
         .ok_or(ErrorCode::ArithmeticOverflow)? as u64;
     
-    // Check minimum payout threshold
-    if raw_payout < min_payout {
-        return Ok((0, raw_payout)); // Entire amount becomes dust
+    Ok(split_at_min_payout(raw_payout, min_payout))
+}
+
+/// Split an investor's allocation at the minimum payout threshold
+/// Returns (payout_amount, dust_amount): allocations below the threshold become dust
+pub fn split_at_min_payout(allocation: u64, min_payout: u64) -> (u64, u64) {
+    if allocation < min_payout {
+        (0, allocation) // Entire amount becomes dust
+    } else {
+        (allocation, 0)
     }
-    
-    Ok((raw_payout, 0))
+}
+
+/// Apportion `total_amount` across `weights` with the largest-remainder (Hamilton) method
+///
+/// Each share starts at `floor(total_amount * w_i / W)` in u128; the lamports left over
+/// (fewer than the number of weights) go one each to the largest remainders, ties going
+/// to the lower index. Shares sum to `total_amount` exactly and each is within one
+/// lamport of its exact quota. All shares are zero when the weights sum to zero.
+pub fn apportion_largest_remainder(total_amount: u64, weights: &[u64]) -> Result<Vec<u64>> {
+    let weight_total = weights
+        .iter()
+        .try_fold(0u128, |total, &weight| total.checked_add(weight as u128))
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    if weight_total == 0 {
+        return Ok(vec![0; weights.len()]);
+    }
+
+    let mut shares = Vec::with_capacity(weights.len());
+    let mut remainders = Vec::with_capacity(weights.len());
+    let mut allocated = 0u64;
+
+    for (index, &weight) in weights.iter().enumerate() {
+        // u64 * u64 always fits in u128
+        let numerator = total_amount as u128 * weight as u128;
+        let share = (numerator / weight_total) as u64;
+        allocated = allocated
+            .checked_add(share)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        shares.push(share);
+        remainders.push((numerator % weight_total, index));
+    }
+
+    let leftover = total_amount
+        .checked_sub(allocated)
+        .ok_or(ErrorCode::ArithmeticOverflow)? as usize;
+
+    // Largest remainders first, lower index first on ties
+    remainders.sort_unstable_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    for &(_, index) in remainders.iter().take(leftover) {
+        shares[index] += 1;
+    }
+
+    Ok(shares)
+}
+
+/// Where `apportion_largest_remainder` stops handing out leftover lamports
+///
+/// Lets a slice of the weights be apportioned without the others: walking the weights
+/// in index order, a share gets a leftover lamport when its remainder is above
+/// `remainder`, or equal to it while `ties` are left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RemainderCutoff {
+    pub remainder: u128,
+    pub ties: u32,
+}
+
+impl RemainderCutoff {
+    /// Cutoff that hands out no leftover lamports
+    pub const NONE: Self = Self { remainder: u128::MAX, ties: 0 };
+
+    /// Largest-remainder share of `total_amount` for `weight` out of `weight_total`,
+    /// consuming a tie when the share takes one
+    pub fn take_share(&mut self, total_amount: u64, weight: u64, weight_total: u128) -> u64 {
+        if weight_total == 0 {
+            return 0;
+        }
+
+        let numerator = total_amount as u128 * weight as u128;
+        let share = (numerator / weight_total) as u64;
+        let remainder = numerator % weight_total;
+        let takes_leftover = remainder > self.remainder
+            || (remainder == self.remainder && self.ties > 0);
+        if remainder == self.remainder && takes_leftover {
+            self.ties -= 1;
+        }
+        share + takes_leftover as u64
+    }
+}
+
+/// Cutoff reproducing `apportion_largest_remainder(total_amount, weights)` one share at a time
+pub fn largest_remainder_cutoff(total_amount: u64, weights: &[u64]) -> Result<RemainderCutoff> {
+    let weight_total = weights
+        .iter()
+        .try_fold(0u128, |total, &weight| total.checked_add(weight as u128))
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    if weight_total == 0 {
+        return Ok(RemainderCutoff::NONE);
+    }
+
+    let mut allocated = 0u64;
+    let mut remainders = Vec::with_capacity(weights.len());
+    for &weight in weights {
+        let numerator = total_amount as u128 * weight as u128;
+        allocated = allocated
+            .checked_add((numerator / weight_total) as u64)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        remainders.push(numerator % weight_total);
+    }
+
+    let leftover = total_amount
+        .checked_sub(allocated)
+        .ok_or(ErrorCode::ArithmeticOverflow)? as usize;
+    if leftover == 0 {
+        return Ok(RemainderCutoff::NONE);
+    }
+
+    // The smallest remainder still taking a lamport, and how many of its equals do
+    remainders.sort_unstable_by(|a, b| b.cmp(a));
+    let remainder = remainders[leftover - 1];
+    let ties = remainders[..leftover].iter().filter(|&&value| value == remainder).count();
+    Ok(RemainderCutoff {
+        remainder,
+        ties: u32::try_from(ties).map_err(|_| ErrorCode::ArithmeticOverflow)?,
+    })
+}
+
+/// Split `total_amount` into `parts` equal shares (at least one), the remainder going
+/// to the last share
+pub fn split_evenly(total_amount: u64, parts: u64) -> Vec<u64> {
+    let parts = parts.max(1);
+    let share = total_amount / parts;
+    let mut shares = vec![share; parts as usize];
+    // share * (parts - 1) <= total_amount, so this cannot underflow
+    shares[parts as usize - 1] = total_amount - share * (parts - 1);
+    shares
 }
 
 /// Calculate batch payout with dust accumulation
@@ -155,27 +394,146 @@ pub fn calculate_batch_payout(
     Ok((adjusted_paid, total_dust))
 }
 
+/// Payout limit that constrained an amount
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CapConstraint {
+    /// No cap limited the amount
+    Unconstrained,
+    /// Global daily cap (`daily_cap_lamports`)
+    DailyCap,
+    /// Rolling multi-day cap (`rolling_cap_lamports`)
+    RollingWindowCap,
+    /// Per-investor daily maximum (`per_investor_daily_cap_lamports`)
+    PerInvestorCap,
+}
+
+/// Optional payout limits configured on a policy
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CapPolicy {
+    pub daily_cap: Option<u64>,
+    pub rolling_cap: Option<u64>,
+    pub per_investor_cap: Option<u64>,
+}
+
+impl CapPolicy {
+    /// Caps configured on a policy
+    pub fn for_policy(policy_config: &PolicyConfig) -> Self {
+        Self {
+            daily_cap: policy_config.daily_cap_lamports,
+            rolling_cap: policy_config.rolling_cap_lamports,
+            per_investor_cap: policy_config.per_investor_daily_cap_lamports,
+        }
+    }
+
+    /// Caps for the current day: under a catch-up mode the daily and per-investor caps
+    /// scale with the epochs the day covers, while the rolling cap already spans them
+    pub fn for_day(policy_config: &PolicyConfig, progress: &DistributionProgress) -> Self {
+        let epochs = progress.catch_up_epochs(policy_config.catch_up_mode);
+        let caps = Self::for_policy(policy_config);
+        Self {
+            daily_cap: caps.daily_cap.map(|cap| cap.saturating_mul(epochs)),
+            rolling_cap: caps.rolling_cap,
+            per_investor_cap: caps.per_investor_cap.map(|cap| cap.saturating_mul(epochs)),
+        }
+    }
+}
+
+/// Result of evaluating caps against a requested amount
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CapEvaluation {
+    /// Amount that may be paid
+    pub allowed: u64,
+    /// Amount the binding cap withholds
+    pub withheld: u64,
+    /// Tightest cap, if it limited the amount
+    pub binding: CapConstraint,
+    /// Capacity left under the tightest configured cap (None when uncapped)
+    pub remaining_capacity: Option<u64>,
+}
+
+impl CapEvaluation {
+    /// Clamp `requested` to the tightest of the candidate capacities
+    fn bind(requested: u64, capacities: &[(Option<u64>, CapConstraint)]) -> Self {
+        let tightest = capacities
+            .iter()
+            .filter_map(|&(capacity, constraint)| capacity.map(|capacity| (capacity, constraint)))
+            .min_by_key(|&(capacity, _)| capacity);
+
+        match tightest {
+            Some((capacity, constraint)) if requested > capacity => CapEvaluation {
+                allowed: capacity,
+                withheld: requested - capacity,
+                binding: constraint,
+                remaining_capacity: Some(capacity),
+            },
+            _ => CapEvaluation {
+                allowed: requested,
+                withheld: 0,
+                binding: CapConstraint::Unconstrained,
+                remaining_capacity: tightest.map(|(capacity, _)| capacity),
+            },
+        }
+    }
+}
+
+/// Evaluate the aggregate caps (daily and rolling window) for an additional payout
+/// Returns the allowed amount and the binding constraint
+pub fn evaluate_cap_policy(
+    progress: &DistributionProgress,
+    caps: &CapPolicy,
+    additional_amount: u64,
+) -> Result<CapEvaluation> {
+    let daily_capacity = caps.daily_cap
+        .map(|cap| cap.saturating_sub(progress.current_day_distributed));
+    let rolling_capacity = match caps.rolling_cap {
+        Some(cap) => Some(cap.saturating_sub(progress.rolling_window_distributed()?)),
+        None => None,
+    };
+
+    Ok(CapEvaluation::bind(
+        additional_amount,
+        &[
+            (daily_capacity, CapConstraint::DailyCap),
+            (rolling_capacity, CapConstraint::RollingWindowCap),
+        ],
+    ))
+}
+
+/// Evaluate the per-investor daily maximum for one investor's payout
+pub fn evaluate_investor_cap(caps: &CapPolicy, payout_amount: u64) -> CapEvaluation {
+    CapEvaluation::bind(
+        payout_amount,
+        &[(caps.per_investor_cap, CapConstraint::PerInvestorCap)],
+    )
+}
+
 /// Enforce daily cap with current distribution progress
 pub fn enforce_daily_cap(
     progress: &DistributionProgress,
     additional_amount: u64,
     daily_cap: Option<u64>,
 ) -> Result<u64> {
-    let Some(cap) = daily_cap else {
-        return Ok(additional_amount); // No cap enforced
-    };
-    
-    let current_distributed = progress.current_day_distributed;
-    let available_capacity = cap
-        .checked_sub(current_distributed)
-        .ok_or(ErrorCode::DailyCapExceeded)?;
+    let caps = CapPolicy { daily_cap, ..CapPolicy::default() };
+    let evaluation = evaluate_cap_policy(progress, &caps, additional_amount)?;
 
-    if available_capacity == 0 {
+    // No capacity left at all is an error for callers of the single daily cap
+    if evaluation.remaining_capacity == Some(0) {
         return Err(ErrorCode::DailyCapExceeded.into());
     }
     
-    // Return the lesser of requested amount or available capacity
-    Ok(std::cmp::min(additional_amount, available_capacity))
+    Ok(evaluation.allowed)
+}
+
+/// Apply a cap scale factor to an amount (floor)
+pub fn apply_cap_scale(amount: u64, scale_factor: u128) -> Result<u64> {
+    require!(scale_factor <= WEIGHT_PRECISION, ErrorCode::ArithmeticOverflow);
+
+    let scaled = (amount as u128)
+        .checked_mul(scale_factor)
+        .ok_or(ErrorCode::ArithmeticOverflow)?
+        / WEIGHT_PRECISION;
+
+    Ok(scaled as u64)
 }
 
 /// Calculate dust threshold payout from accumulated dust
@@ -256,11 +614,7 @@ pub fn verify_weight_sum(weights: &[u128]) -> Result<()> {
     
     // Allow for small rounding errors (up to number of investors)
     let max_rounding_error = weights.len() as u128;
-    let diff = if total_weight > WEIGHT_PRECISION {
-        total_weight - WEIGHT_PRECISION
-    } else {
-        WEIGHT_PRECISION - total_weight
-    };
+    let diff = total_weight.abs_diff(WEIGHT_PRECISION);
     
     require!(
         diff <= max_rounding_error,
@@ -274,7 +628,6 @@ pub fn verify_weight_sum(weights: &[u128]) -> Result<()> {
 mod tests {
     use super::*;
     use crate::state::DistributionProgress;
-    use anchor_lang::prelude::Pubkey;
 
     #[test]
     fn test_calculate_distribution_basic() {
@@ -451,6 +804,99 @@ mod tests {
         assert_eq!(result, (0, 50));
     }
 
+    #[test]
+    fn test_apportion_largest_remainder() {
+        // 100 over 1:1:1 -> 33.33 each, the first remainder gets the extra lamport
+        assert_eq!(apportion_largest_remainder(100, &[1, 1, 1]).unwrap(), vec![34, 33, 33]);
+
+        // Largest remainder wins regardless of position
+        // quotas: 10 * 1/7 = 1.43, 10 * 2/7 = 2.86, 10 * 4/7 = 5.71
+        assert_eq!(apportion_largest_remainder(10, &[1, 2, 4]).unwrap(), vec![1, 3, 6]);
+
+        // Zero weights receive nothing, zero total weight allocates nothing
+        assert_eq!(apportion_largest_remainder(5, &[0, 3, 0]).unwrap(), vec![0, 5, 0]);
+        assert_eq!(apportion_largest_remainder(5, &[0, 0]).unwrap(), vec![0, 0]);
+        assert!(apportion_largest_remainder(5, &[]).unwrap().is_empty());
+
+        // Large pools keep full precision (no WEIGHT_PRECISION truncation)
+        let shares = apportion_largest_remainder(u64::MAX, &[u64::MAX, 1]).unwrap();
+        assert_eq!(shares, vec![u64::MAX - 1, 1]);
+    }
+
+    #[test]
+    fn test_remainder_cutoff_matches_apportionment() {
+        let mut seed = 0x2545_f491_4f6c_dd1du64;
+        let mut next = |bound: u64| {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 11) % bound
+        };
+
+        for round in 0..500 {
+            let count = next(40) as usize + 1;
+            // Few distinct weights so equal remainders (ties) are common
+            let weight_bound = if round % 2 == 0 { 4 } else { 1_000_000 };
+            let total_amount = next(10_000_000);
+            let weights: Vec<u64> = (0..count).map(|_| next(weight_bound)).collect();
+            let weight_total: u128 = weights.iter().map(|&weight| weight as u128).sum();
+
+            // Shares taken in index order, as pages take them, match the whole apportionment
+            let mut cutoff = largest_remainder_cutoff(total_amount, &weights).unwrap();
+            let shares: Vec<u64> = weights
+                .iter()
+                .map(|&weight| cutoff.take_share(total_amount, weight, weight_total))
+                .collect();
+            assert_eq!(shares, apportion_largest_remainder(total_amount, &weights).unwrap(), "round {}", round);
+            assert_eq!(cutoff.ties, 0);
+        }
+
+        assert_eq!(largest_remainder_cutoff(5, &[0, 0]).unwrap(), RemainderCutoff::NONE);
+        assert_eq!(largest_remainder_cutoff(9, &[1, 2]).unwrap(), RemainderCutoff::NONE);
+    }
+
+    #[test]
+    fn test_apportion_sums_exactly_with_sub_lamport_error() {
+        let mut seed = 0x9e37_79b9_7f4a_7c15u64;
+        let mut next = |bound: u64| {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 11) % bound
+        };
+
+        for round in 0..500 {
+            let count = next(40) as usize + 1;
+            // Mix small and huge magnitudes to stress the u128 arithmetic
+            let weight_bound = if round % 3 == 0 { u64::MAX / 64 } else { 1_000_000 };
+            let total_amount = if round % 5 == 0 { next(u64::MAX) } else { next(10_000_000) };
+            let weights: Vec<u64> = (0..count).map(|_| next(weight_bound)).collect();
+            let weight_total: u128 = weights.iter().map(|&weight| weight as u128).sum();
+
+            let shares = apportion_largest_remainder(total_amount, &weights).unwrap();
+            assert_eq!(shares.len(), count);
+
+            if weight_total == 0 {
+                assert!(shares.iter().all(|&share| share == 0));
+                continue;
+            }
+
+            // Shares sum exactly to the total
+            let share_total: u128 = shares.iter().map(|&share| share as u128).sum();
+            assert_eq!(share_total, total_amount as u128);
+
+            // |share_i - total * w_i / W| < 1, compared exactly as |share_i * W - total * w_i| < W
+            // (u128 fits: share <= total < 2^64 and W < 2^64 * 40 < 2^70)
+            for (&share, &weight) in shares.iter().zip(weights.iter()) {
+                let scaled_share = share as u128 * weight_total;
+                let exact = total_amount as u128 * weight as u128;
+                assert!(scaled_share.abs_diff(exact) < weight_total);
+            }
+        }
+    }
+
+    #[test]
+    fn test_split_at_min_payout() {
+        assert_eq!(split_at_min_payout(999, 1000), (0, 999));
+        assert_eq!(split_at_min_payout(1000, 1000), (1000, 0));
+    }
+
     #[test]
     fn test_calculate_batch_payout() {
         let investors = vec![
@@ -477,16 +923,20 @@ mod tests {
         assert_eq!(result, (expected_paid, expected_dust));
     }
 
+    #[test]
+    fn test_split_evenly() {
+        assert_eq!(split_evenly(100, 3), vec![33, 33, 34]);
+        assert_eq!(split_evenly(2, 3), vec![0, 0, 2]);
+        assert_eq!(split_evenly(u64::MAX, 7).iter().map(|&share| share as u128).sum::<u128>(), u64::MAX as u128);
+        // Zero parts is treated as one
+        assert_eq!(split_evenly(5, 0), vec![5]);
+    }
+
     #[test]
     fn test_enforce_daily_cap_no_cap() {
         let progress = DistributionProgress {
-            vault: Pubkey::default(),
-            last_distribution_ts: 0,
             current_day_distributed: 500,
-            carry_over_dust: 0,
-            pagination_cursor: 0,
-            day_complete: false,
-            bump: 0,
+            ..DistributionProgress::default()
         };
 
         let result = enforce_daily_cap(&progress, 1000, None).unwrap();
@@ -496,13 +946,8 @@ mod tests {
     #[test]
     fn test_enforce_daily_cap_within_limit() {
         let progress = DistributionProgress {
-            vault: Pubkey::default(),
-            last_distribution_ts: 0,
             current_day_distributed: 500,
-            carry_over_dust: 0,
-            pagination_cursor: 0,
-            day_complete: false,
-            bump: 0,
+            ..DistributionProgress::default()
         };
 
         let result = enforce_daily_cap(&progress, 300, Some(1000)).unwrap();
@@ -512,13 +957,8 @@ mod tests {
     #[test]
     fn test_enforce_daily_cap_exceeds_limit() {
         let progress = DistributionProgress {
-            vault: Pubkey::default(),
-            last_distribution_ts: 0,
             current_day_distributed: 800,
-            carry_over_dust: 0,
-            pagination_cursor: 0,
-            day_complete: false,
-            bump: 0,
+            ..DistributionProgress::default()
         };
 
         let result = enforce_daily_cap(&progress, 300, Some(1000)).unwrap();
@@ -528,19 +968,62 @@ mod tests {
     #[test]
     fn test_enforce_daily_cap_already_exceeded() {
         let progress = DistributionProgress {
-            vault: Pubkey::default(),
-            last_distribution_ts: 0,
             current_day_distributed: 1000,
-            carry_over_dust: 0,
-            pagination_cursor: 0,
-            day_complete: false,
-            bump: 0,
+            ..DistributionProgress::default()
         };
 
         let result = enforce_daily_cap(&progress, 100, Some(1000));
         assert!(result.is_err()); // Should fail when already at cap
     }
 
+    #[test]
+    fn test_evaluate_cap_policy_reports_binding_constraint() {
+        let mut progress = DistributionProgress {
+            current_day_distributed: 200,
+            ..DistributionProgress::default()
+        };
+        progress.rolling_distributed[0] = 200;
+        progress.rolling_distributed[3] = 1_500;
+
+        let mut caps = CapPolicy::default();
+        let evaluation = evaluate_cap_policy(&progress, &caps, 1_000).unwrap();
+        assert_eq!(evaluation.allowed, 1_000);
+        assert_eq!(evaluation.binding, CapConstraint::Unconstrained);
+        assert_eq!(evaluation.remaining_capacity, None);
+
+        // Daily cap leaves 800, rolling cap leaves 300 -> rolling binds
+        caps.daily_cap = Some(1_000);
+        caps.rolling_cap = Some(2_000);
+        let evaluation = evaluate_cap_policy(&progress, &caps, 1_000).unwrap();
+        assert_eq!(evaluation.allowed, 300);
+        assert_eq!(evaluation.withheld, 700);
+        assert_eq!(evaluation.binding, CapConstraint::RollingWindowCap);
+        assert_eq!(evaluation.remaining_capacity, Some(300));
+
+        // Looser rolling cap -> daily binds
+        caps.rolling_cap = Some(10_000);
+        let evaluation = evaluate_cap_policy(&progress, &caps, 1_000).unwrap();
+        assert_eq!(evaluation.allowed, 800);
+        assert_eq!(evaluation.binding, CapConstraint::DailyCap);
+    }
+
+    #[test]
+    fn test_evaluate_investor_cap() {
+        let caps = CapPolicy {
+            per_investor_cap: Some(500),
+            ..CapPolicy::default()
+        };
+
+        let evaluation = evaluate_investor_cap(&caps, 400);
+        assert_eq!(evaluation.allowed, 400);
+        assert_eq!(evaluation.binding, CapConstraint::Unconstrained);
+
+        let evaluation = evaluate_investor_cap(&caps, 750);
+        assert_eq!(evaluation.allowed, 500);
+        assert_eq!(evaluation.withheld, 250);
+        assert_eq!(evaluation.binding, CapConstraint::PerInvestorCap);
+    }
+
     #[test]
     fn test_calculate_dust_payout_above_threshold() {
         let accumulated_dust = 350u64;
@@ -732,4 +1215,161 @@ mod tests {
         // creator_amount = 1B - 750M = 250M
         assert_eq!(result, (750_000_000, 250_000_000));
     }
-}
\ No newline at end of file
+
+    fn create_linear_schedule() -> VestingSchedule {
+        VestingSchedule {
+            deposited_amount: 1_000_000,
+            withdrawn_amount: 0,
+            start_time: 1000,
+            cliff_time: 1000,
+            cliff_amount: 0,
+            period: 100,
+            amount_per_period: 100_000,
+            end_time: 2000,
+            closed: false,
+        }
+    }
+
+    #[test]
+    fn test_time_weighted_locked_full_schedule() {
+        let schedule = create_linear_schedule();
+
+        // Fully locked before the start
+        assert_eq!(calculate_time_weighted_locked(&schedule, 0, 1000).unwrap(), 1_000_000);
+
+        // 100s each at 1M, 900k, ..., 100k = 550M token-seconds over 1000s
+        assert_eq!(integrate_locked_amount(&schedule, 1000, 2000).unwrap(), 550_000_000);
+        assert_eq!(calculate_time_weighted_locked(&schedule, 1000, 2000).unwrap(), 550_000);
+
+        // Nothing locked after the end
+        assert_eq!(calculate_time_weighted_locked(&schedule, 2000, 3000).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_time_weighted_locked_partial_windows() {
+        let schedule = create_linear_schedule();
+
+        // 500s at 1M, then 100s each at 1M, 900k, 800k, 700k, 600k = 900M over 1000s
+        assert_eq!(calculate_time_weighted_locked(&schedule, 500, 1500).unwrap(), 900_000);
+
+        // Window splitting a period: 50s at 1M + 50s at 900k
+        assert_eq!(calculate_time_weighted_locked(&schedule, 1050, 1150).unwrap(), 950_000);
+
+        // Window crossing the end: 50s at 100k + 50s at 0
+        assert_eq!(calculate_time_weighted_locked(&schedule, 1950, 2050).unwrap(), 50_000);
+    }
+
+    #[test]
+    fn test_time_weighted_locked_cliff() {
+        let mut schedule = create_linear_schedule();
+        schedule.cliff_time = 1500;
+        schedule.cliff_amount = 250_000;
+
+        // 100s at 1M before the cliff, 100s at 750k after it
+        assert_eq!(calculate_time_weighted_locked(&schedule, 1400, 1600).unwrap(), 875_000);
+    }
+
+    #[test]
+    fn test_time_weighted_locked_cliff_just_before_crank() {
+        // Whole allocation unlocks at the cliff, 5 minutes before the crank
+        let schedule = VestingSchedule {
+            deposited_amount: 1_000_000,
+            withdrawn_amount: 0,
+            start_time: 0,
+            cliff_time: TWENTY_FOUR_HOURS - 300,
+            cliff_amount: 1_000_000,
+            period: 1,
+            amount_per_period: 0,
+            end_time: TWENTY_FOUR_HOURS - 299,
+            closed: false,
+        };
+
+        // A point-in-time snapshot sees nothing locked
+        assert_eq!(schedule.locked_at(TWENTY_FOUR_HOURS), 0);
+
+        // The average still credits the 86100s the tokens were locked
+        assert_eq!(
+            calculate_time_weighted_locked(&schedule, 0, TWENTY_FOUR_HOURS).unwrap(),
+            996_527
+        );
+    }
+
+    #[test]
+    fn test_time_weighted_locked_edge_cases() {
+        let mut schedule = create_linear_schedule();
+
+        // Empty window falls back to the point-in-time amount
+        assert_eq!(calculate_time_weighted_locked(&schedule, 1500, 1500).unwrap(), 500_000);
+
+        // Instant unlock at the cliff
+        schedule.period = 0;
+        assert_eq!(calculate_time_weighted_locked(&schedule, 500, 1500).unwrap(), 500_000);
+
+        // Closed locks carry no weight
+        schedule.closed = true;
+        assert_eq!(calculate_time_weighted_locked(&schedule, 0, 1000).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_time_weighted_locked_matches_per_second_sum() {
+        // Compare the analytic integral against a brute-force per-second sum
+        let mut seed = 0x2545_f491_4f6c_dd1du64;
+        let mut next = |bound: u64| {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 33) % bound
+        };
+
+        for _ in 0..50 {
+            let start_time = next(500) as i64;
+            let cliff_time = start_time + next(300) as i64;
+            let deposited_amount = next(10_000_000) + 1;
+            let schedule = VestingSchedule {
+                deposited_amount,
+                withdrawn_amount: next(deposited_amount / 10 + 1),
+                start_time,
+                cliff_time,
+                cliff_amount: next(deposited_amount),
+                period: next(50) + 1,
+                amount_per_period: next(deposited_amount / 4 + 1),
+                end_time: cliff_time + next(1500) as i64,
+                closed: false,
+            };
+
+            let from = next(1000) as i64;
+            let to = from + next(2500) as i64 + 1;
+            let expected: u128 = (from..to).map(|t| schedule.locked_at(t) as u128).sum();
+
+            assert_eq!(integrate_locked_amount(&schedule, from, to).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_apply_cap_scale() {
+        assert_eq!(apply_cap_scale(10_000, WEIGHT_PRECISION).unwrap(), 10_000);
+        assert_eq!(apply_cap_scale(10_000, 250_000).unwrap(), 2_500);
+
+        // Scale factors above 100% are rejected
+        assert!(apply_cap_scale(1, WEIGHT_PRECISION + 1).is_err());
+    }
+
+    #[test]
+    fn test_capped_pool_apportioned_across_pages() {
+        // 3 pages of investors sharing a 10_000 pool capped at 4_000
+        let payable_pool = 4_000;
+        let pages: [&[u64]; 3] = [&[1_000, 1_000], &[2_000], &[4_000, 2_000]];
+        let weights: Vec<u64> = pages.iter().flat_map(|page| page.iter().copied()).collect();
+
+        // Every investor gets 40% of their uncapped entitlement, whatever the page, and
+        // the pages pay the capped pool exactly
+        let payouts = apportion_largest_remainder(payable_pool, &weights).unwrap();
+        let uncapped = apportion_largest_remainder(10_000, &weights).unwrap();
+        for (payout, uncapped) in payouts.iter().zip(&uncapped) {
+            assert_eq!(payout * 10, uncapped * 4);
+        }
+        assert_eq!(payouts.iter().sum::<u64>(), 4_000);
+
+        // A cap far below the pool is still paid in full
+        let payouts = apportion_largest_remainder(7, &weights).unwrap();
+        assert_eq!(payouts.iter().sum::<u64>(), 7);
+    }
+}
@@ -2,14 +2,30 @@ pub mod math;
 pub mod validation;
 pub mod pda;
 pub mod streamflow;
+pub mod jupiter_lock;
+pub mod vesting;
+pub mod staking;
+pub mod weights;
 pub mod fee_claiming;
+pub mod swap;
 pub mod investor_distribution;
 pub mod creator_distribution;
+pub mod dust_distribution;
+pub mod day_distribution;
+pub mod simulation;
 
 pub use math::*;
 pub use validation::*;
 pub use pda::*;
 pub use streamflow::*;
+pub use jupiter_lock::*;
+pub use vesting::*;
+pub use staking::*;
+pub use weights::*;
 pub use fee_claiming::*;
+pub use swap::*;
 pub use investor_distribution::*;
-pub use creator_distribution::*;
\ No newline at end of file
+pub use creator_distribution::*;
+pub use dust_distribution::*;
+pub use day_distribution::*;
+pub use simulation::*;
\ No newline at end of file
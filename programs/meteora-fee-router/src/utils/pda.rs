@@ -27,6 +27,19 @@ impl PdaUtils {
         )
     }
 
+    /// Derive the day ledger PDA of a quote mint (the distribution progress PDA for the
+    /// policy's primary quote mint)
+    pub fn derive_quote_ledger_pda(
+        program_id: &Pubkey,
+        vault: &Pubkey,
+        ledger_seed: &[u8],
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[PROGRESS_SEED, vault.as_ref(), ledger_seed],
+            program_id,
+        )
+    }
+
     /// Derive position owner PDA
     pub fn derive_position_owner_pda(
         program_id: &Pubkey,
@@ -38,6 +51,28 @@ impl PdaUtils {
         )
     }
 
+    /// Derive an investor's accrual PDA in the primary quote mint's ledger
+    pub fn derive_investor_accrual_pda(
+        program_id: &Pubkey,
+        vault: &Pubkey,
+        investor: &Pubkey,
+    ) -> (Pubkey, u8) {
+        Self::derive_ledger_accrual_pda(program_id, vault, investor, &[])
+    }
+
+    /// Derive an investor's accrual PDA in the ledger of `ledger_seed`
+    pub fn derive_ledger_accrual_pda(
+        program_id: &Pubkey,
+        vault: &Pubkey,
+        investor: &Pubkey,
+        ledger_seed: &[u8],
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[ACCRUAL_SEED, vault.as_ref(), investor.as_ref(), ledger_seed],
+            program_id,
+        )
+    }
+
     /// Derive treasury ATA PDA (if using program-owned treasury)
     pub fn derive_treasury_ata_pda(
         program_id: &Pubkey,
@@ -137,6 +172,45 @@ mod tests {
         assert!(PdaUtils::validate_distribution_progress_pda(&program_id, &vault, &pda, bump));
     }
 
+    #[test]
+    fn test_quote_ledger_pda() {
+        let program_id = Pubkey::new_unique();
+        let vault = Pubkey::new_unique();
+        let quote_mint = Pubkey::new_unique();
+
+        // The primary ledger (no extra seed) is the distribution progress PDA
+        assert_eq!(
+            PdaUtils::derive_quote_ledger_pda(&program_id, &vault, &[]),
+            PdaUtils::derive_distribution_progress_pda(&program_id, &vault)
+        );
+
+        // Other quote mints get their own ledger and accruals
+        let (ledger, _) = PdaUtils::derive_quote_ledger_pda(&program_id, &vault, quote_mint.as_ref());
+        assert_ne!(ledger, PdaUtils::derive_distribution_progress_pda(&program_id, &vault).0);
+
+        let investor = Pubkey::new_unique();
+        let (accrual, _) = PdaUtils::derive_ledger_accrual_pda(&program_id, &vault, &investor, quote_mint.as_ref());
+        assert_ne!(accrual, PdaUtils::derive_investor_accrual_pda(&program_id, &vault, &investor).0);
+    }
+
+    #[test]
+    fn test_investor_accrual_pda() {
+        let program_id = Pubkey::new_unique();
+        let vault = Pubkey::new_unique();
+        let investor = Pubkey::new_unique();
+
+        let (pda, bump) = PdaUtils::derive_investor_accrual_pda(&program_id, &vault, &investor);
+        let expected = Pubkey::create_program_address(
+            &[ACCRUAL_SEED, vault.as_ref(), investor.as_ref(), &[bump]],
+            &program_id,
+        ).unwrap();
+        assert_eq!(pda, expected);
+
+        // Accruals are per investor
+        let (other, _) = PdaUtils::derive_investor_accrual_pda(&program_id, &vault, &Pubkey::new_unique());
+        assert_ne!(pda, other);
+    }
+
     #[test]
     fn test_position_owner_pda() {
         let program_id = Pubkey::new_unique();
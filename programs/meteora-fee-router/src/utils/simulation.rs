@@ -0,0 +1,712 @@
+use anchor_lang::prelude::*;
+use crate::{
+    constants::*,
+    error::ErrorCode,
+    state::{
+        registry_hash, DayOpening, DistributionProgress, DistributionTimingState, InvestorAccrual,
+        PolicyConfig, WeightMode,
+    },
+    utils::{
+        day_distribution::{run_distribution_step, DistributionAccounts},
+        investor_distribution::{BatchPayoutResult, InvestorDistribution},
+        streamflow::{StreamflowIntegration, StreamflowStream, STREAMFLOW_PROGRAM_ID},
+        vesting::{InvestorData, VestingRegistry},
+        weights::WeightWindow,
+    },
+};
+use std::collections::{BTreeMap, HashMap};
+
+/// One investor's share of a simulated page
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvestorPayoutForecast {
+    pub wallet: Pubkey,
+    pub locked_amount: u64,
+    /// Amount transferred to the investor (after the page caps)
+    pub payout: u64,
+    /// Sub-threshold allocation that became dust
+    pub dust: u64,
+}
+
+/// Outcome of one simulated `distribute_fees` page
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageSimulation {
+    pub page_start: u32,
+    pub page_end: u32,
+    /// Page had no investor with a locked amount; the cursor advanced without payouts
+    pub skipped: bool,
+    pub investors: Vec<InvestorPayoutForecast>,
+    /// Investor payouts after the page caps (excluding dust)
+    pub investor_paid: u64,
+    /// Dust paid out on this page
+    pub dust_paid: u64,
+    pub dust_recipient: Option<Pubkey>,
+    /// Dust still carried after this page
+    pub dust_carried: u64,
+    /// Amount the caps deferred to the next day (the day-start deferral is reported on the first page)
+    pub cap_overflow_deferred: u64,
+    pub investor_accrued: u64,
+    pub investor_accrual_released: u64,
+    /// Crank reward paid for the page, out of the creator's remainder
+    pub crank_reward: u64,
+}
+
+impl PageSimulation {
+    /// Everything the page pays investors (the crank reward is not included)
+    pub fn total_paid(&self) -> u64 {
+        self.investor_paid.saturating_add(self.dust_paid)
+    }
+}
+
+/// Creator payout at day close
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CreatorSettlement {
+    /// Creator remainder of the day's claim
+    pub remainder: u64,
+    /// Carried dust swept to the creator (CreatorAtDayClose)
+    pub dust_sweep: u64,
+}
+
+impl CreatorSettlement {
+    pub fn total(&self) -> u64 {
+        self.remainder.saturating_add(self.dust_sweep)
+    }
+}
+
+/// Forecast of a day's distribution, page by page
+#[derive(Clone)]
+pub struct DaySimulation {
+    pub timing: DistributionTimingState,
+    /// Quote fees recorded as claimed by this run (zero when continuing a day)
+    pub claimed_quote: u64,
+    /// Investor share of the day's claim
+    pub investor_share: u64,
+    /// Pool opening, when the run starts a new day
+    pub opening: Option<DayOpening>,
+    /// Capped investor pool every page pays out of
+    pub payable_pool: u64,
+    /// The day's investor registry, aggregated per wallet
+    pub investors: Vec<InvestorData>,
+    pub pages: Vec<PageSimulation>,
+    /// Creator payout, when the last page closes the day
+    pub creator: Option<CreatorSettlement>,
+    /// Distribution progress after the last simulated page
+    pub progress: DistributionProgress,
+    /// Investor accruals after the last simulated page
+    pub accruals: HashMap<Pubkey, InvestorAccrual>,
+    /// Treasury balance after the last simulated page, modelled from the run's transfers
+    pub treasury_balance: u64,
+}
+
+impl DaySimulation {
+    /// Whether the simulated pages close the day
+    pub fn is_day_closed(&self) -> bool {
+        self.creator.is_some()
+    }
+
+    /// Total paid to investors (payouts and dust) across all pages
+    pub fn total_investor_paid(&self) -> u64 {
+        self.pages.iter().map(PageSimulation::total_paid).sum()
+    }
+
+    /// Total crank rewards across all pages
+    pub fn total_crank_reward(&self) -> u64 {
+        self.pages.iter().map(|page| page.crank_reward).sum()
+    }
+}
+
+/// Knobs for `simulate_day_with_options`
+#[derive(Clone)]
+pub struct SimulationOptions {
+    /// Investors per `distribute_fees` page
+    pub page_size: u32,
+    /// Investor accrual PDAs, assumed to be supplied on every page
+    pub accruals: HashMap<Pubkey, InvestorAccrual>,
+    /// Whether the crank supplies its reward ATA, so each page pays the policy's crank reward
+    pub crank_reward_ata: bool,
+    /// Treasury balance before the run (by default what the ledger expects it to hold)
+    pub treasury_balance: Option<u64>,
+}
+
+impl Default for SimulationOptions {
+    fn default() -> Self {
+        Self {
+            page_size: MAX_PAGE_SIZE,
+            accruals: HashMap::new(),
+            crank_reward_ata: false,
+            treasury_balance: None,
+        }
+    }
+}
+
+/// Simulate a crank run of `distribute_fees` at `current_timestamp` without accounts
+///
+/// See `simulate_day_with_options`; pages are `MAX_PAGE_SIZE` investors and no accrual
+/// PDAs are supplied.
+pub fn simulate_day(
+    policy_config: &PolicyConfig,
+    progress: &DistributionProgress,
+    streams: &[StreamflowStream],
+    claimed_quote_amount: u64,
+    current_timestamp: i64,
+) -> Result<DaySimulation> {
+    simulate_day_with_options(
+        policy_config,
+        progress,
+        streams,
+        claimed_quote_amount,
+        current_timestamp,
+        SimulationOptions::default(),
+    )
+}
+
+/// Simulate a crank run of `distribute_fees` at `current_timestamp` without accounts
+///
+/// Runs the instruction's own state transitions (`run_distribution_step`) on a copy of
+/// `progress`, page by page, until the day closes or the instruction would stop paying
+/// out. `claimed_quote_amount` is only taken on a new day, together with any quote
+/// converted from base fees. A day already in progress must have been opened over the
+/// same streams. The treasury balance is modelled from the transfers the instruction makes.
+pub fn simulate_day_with_options(
+    policy_config: &PolicyConfig,
+    progress: &DistributionProgress,
+    streams: &[StreamflowStream],
+    claimed_quote_amount: u64,
+    current_timestamp: i64,
+    options: SimulationOptions,
+) -> Result<DaySimulation> {
+    let mut progress = progress.clone();
+    let treasury_balance = match options.treasury_balance {
+        Some(balance) => balance,
+        None => expected_treasury_balance(&progress)?,
+    };
+    let mut accounts = SimulatedAccounts {
+        policy_config,
+        streams,
+        claimed_quote_amount,
+        treasury_balance,
+        accruals: options.accruals,
+        crank_reward_ata: options.crank_reward_ata,
+    };
+
+    let first = run_distribution_step(
+        policy_config,
+        &mut progress,
+        &mut accounts,
+        options.page_size,
+        None,
+        current_timestamp,
+    )?;
+    let mut simulation = DaySimulation {
+        timing: first.timing,
+        claimed_quote: first.claimed_quote,
+        investor_share: first
+            .pool_opened
+            .as_ref()
+            .map_or(progress.day_investor_share, |pool_opened| pool_opened.investor_share),
+        opening: first.pool_opened.as_ref().map(|pool_opened| pool_opened.opening),
+        payable_pool: 0,
+        investors: Vec::new(),
+        pages: Vec::new(),
+        creator: None,
+        progress: progress.clone(),
+        accruals: HashMap::new(),
+        treasury_balance: 0,
+    };
+
+    let mut step = first;
+    while let Some(page) = step.page {
+        let day_start_deferred = step
+            .pool_opened
+            .as_ref()
+            .map_or(0, |pool_opened| pool_opened.opening.deferred);
+        simulation.payable_pool = page.payable_pool;
+        simulation.pages.push(match &page.payouts {
+            Some(payouts) => PageSimulation {
+                page_start: page.page_start,
+                page_end: page.page_end,
+                skipped: false,
+                investors: payouts
+                    .payouts
+                    .iter()
+                    .map(|payout| InvestorPayoutForecast {
+                        wallet: payout.wallet,
+                        locked_amount: payout.locked_amount,
+                        payout: payout.payout_amount,
+                        dust: payout.dust_amount,
+                    })
+                    .collect(),
+                investor_paid: payouts.investor_paid,
+                dust_paid: payouts.dust_paid,
+                dust_recipient: payouts.dust_recipient,
+                dust_carried: payouts.total_dust,
+                cap_overflow_deferred: payouts.cap_overflow_deferred.saturating_add(day_start_deferred),
+                investor_accrued: payouts.investor_accrued,
+                investor_accrual_released: payouts.investor_accrual_released,
+                crank_reward: page.crank_reward,
+            },
+            // Skipped pages advance the cursor without closing the day
+            None => PageSimulation {
+                page_start: page.page_start,
+                page_end: page.page_end,
+                skipped: true,
+                investors: Vec::new(),
+                investor_paid: 0,
+                dust_paid: 0,
+                dust_recipient: None,
+                dust_carried: progress.carry_over_dust,
+                cap_overflow_deferred: day_start_deferred,
+                investor_accrued: 0,
+                investor_accrual_released: 0,
+                crank_reward: 0,
+            },
+        });
+
+        if let Some(close) = step.close {
+            simulation.creator = Some(CreatorSettlement {
+                remainder: close.settlement.creator_remainder,
+                dust_sweep: close.settlement.dust_sweep,
+            });
+            break;
+        }
+        if page.payouts.is_none() && progress.pagination_cursor >= progress.day_investor_count {
+            break;
+        }
+
+        step = run_distribution_step(
+            policy_config,
+            &mut progress,
+            &mut accounts,
+            options.page_size,
+            None,
+            current_timestamp,
+        )?;
+    }
+
+    simulation.investors = aggregate_stream_investors(policy_config, streams, progress.weight_window())?;
+    simulation.progress = progress;
+    simulation.accruals = accounts.accruals;
+    simulation.treasury_balance = accounts.treasury_balance;
+    Ok(simulation)
+}
+
+/// Parsed streams and a modelled treasury behind `run_distribution_step`
+struct SimulatedAccounts<'a> {
+    policy_config: &'a PolicyConfig,
+    streams: &'a [StreamflowStream],
+    /// Quote the positions yield when a new day claims them
+    claimed_quote_amount: u64,
+    treasury_balance: u64,
+    /// Investor accrual PDAs, all supplied on every page
+    accruals: HashMap<Pubkey, InvestorAccrual>,
+    crank_reward_ata: bool,
+}
+
+impl SimulatedAccounts<'_> {
+    fn pay_out(&mut self, amount: u64) -> Result<()> {
+        self.treasury_balance = self
+            .treasury_balance
+            .checked_sub(amount)
+            .ok_or(ErrorCode::InsufficientFunds)?;
+        Ok(())
+    }
+}
+
+impl DistributionAccounts for SimulatedAccounts<'_> {
+    fn treasury_balance(&mut self) -> Result<u64> {
+        Ok(self.treasury_balance)
+    }
+
+    fn claim_position_fees(&mut self) -> Result<u64> {
+        self.treasury_balance = self
+            .treasury_balance
+            .checked_add(self.claimed_quote_amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        Ok(self.claimed_quote_amount)
+    }
+
+    fn has_weight_accounts(&self) -> bool {
+        !self.streams.is_empty()
+    }
+
+    fn load_investors(
+        &mut self,
+        window: WeightWindow,
+        opens_day: bool,
+        cursor: usize,
+        page_size: usize,
+    ) -> Result<Vec<InvestorData>> {
+        let registry = aggregate_stream_investors(self.policy_config, self.streams, window)?;
+        if opens_day {
+            return Ok(registry);
+        }
+        Ok(InvestorDistribution::investor_page(&registry, cursor, page_size)?.to_vec())
+    }
+
+    fn next_registry_hash(&self, window: WeightWindow, page_end: usize) -> Result<[u8; 32]> {
+        let registry = aggregate_stream_investors(self.policy_config, self.streams, window)?;
+        registry_hash(registry.get(page_end..).unwrap_or_default())
+    }
+
+    /// A catch-up day measures each missed epoch's slice at that epoch's locked total
+    fn locked_total(&self, window: WeightWindow) -> Result<u64> {
+        Ok(aggregate_stream_investors(self.policy_config, self.streams, window)?
+            .iter()
+            .try_fold(0u64, |total, investor| total.checked_add(investor.locked_amount))
+            .ok_or(ErrorCode::ArithmeticOverflow)?)
+    }
+
+    fn load_accruals(&mut self, page_investors: &[InvestorData]) -> Result<HashMap<Pubkey, InvestorAccrual>> {
+        Ok(page_investors
+            .iter()
+            .filter_map(|investor| {
+                self.accruals
+                    .get(&investor.wallet)
+                    .map(|accrual| (investor.wallet, accrual.clone()))
+            })
+            .collect())
+    }
+
+    /// The page's transfers out of the treasury: each payout and the dust
+    fn execute_page(&mut self, page: &BatchPayoutResult, accruals: &HashMap<Pubkey, InvestorAccrual>) -> Result<()> {
+        self.accruals.extend(accruals.iter().map(|(investor, accrual)| (*investor, accrual.clone())));
+        let transfers = page
+            .payouts
+            .iter()
+            .try_fold(page.dust_paid, |total, payout| total.checked_add(payout.payout_amount))
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        self.pay_out(transfers)
+    }
+
+    fn has_crank_reward_account(&self) -> bool {
+        self.crank_reward_ata
+    }
+
+    fn pay_crank_reward(&mut self, amount: u64) -> Result<()> {
+        self.pay_out(amount)
+    }
+
+    fn pay_creator(&mut self, amount: u64) -> Result<Option<u64>> {
+        self.pay_out(amount)?;
+        Ok(Some(0))
+    }
+}
+
+/// What the ledger expects its treasury to hold: a day in progress holds what it opened
+/// with and received less what it paid; between days the treasury holds what is still
+/// owed and the quote waiting for the next day's claim
+fn expected_treasury_balance(progress: &DistributionProgress) -> Result<u64> {
+    if progress.is_day_in_progress() {
+        progress.day_opening_treasury
+            .checked_add(progress.day_treasury_inflow)
+            .and_then(|total| total.checked_sub(progress.current_day_distributed))
+            .and_then(|total| total.checked_sub(progress.day_crank_reward_paid))
+            .ok_or(ErrorCode::AccountingInvariantViolated.into())
+    } else {
+        progress.outstanding_liabilities()?
+            .checked_add(progress.converted_base_quote)
+            .and_then(|total| total.checked_add(progress.carried_claim_quote))
+            .ok_or(ErrorCode::ArithmeticOverflow.into())
+    }
+}
+
+/// Aggregate Streamflow streams into the day's investor registry, one entry per wallet
+///
+/// Mirrors `VestingRegistry::aggregate_investor_data` for already-parsed streams.
+fn aggregate_stream_investors(
+    policy_config: &PolicyConfig,
+    streams: &[StreamflowStream],
+    weight_window: WeightWindow,
+) -> Result<Vec<InvestorData>> {
+    require!(
+        policy_config.weight_mode == WeightMode::LockedVesting,
+        ErrorCode::VestingProgramNotAllowed
+    );
+    VestingRegistry::for_policy(policy_config).source_for(&STREAMFLOW_PROGRAM_ID)?;
+
+    let mut investor_map: BTreeMap<Pubkey, InvestorData> = BTreeMap::new();
+    for stream in streams {
+        StreamflowIntegration::validate_stream(stream)?;
+        require!(stream.mint == policy_config.quote_mint, ErrorCode::InvalidStreamMint);
+
+        // A point window is the locked amount at that instant
+        let locked_amount = if weight_window.end_ts <= weight_window.start_ts {
+            StreamflowIntegration::calculate_locked_amount(stream, weight_window.end_ts)?
+        } else {
+            stream.to_position().average_locked(weight_window)?
+        };
+
+        let investor_data = investor_map.entry(stream.recipient).or_insert(InvestorData {
+            wallet: stream.recipient,
+            locked_amount: 0,
+            total_allocation: 0,
+            stream_accounts: Vec::new(),
+        });
+        investor_data.locked_amount = investor_data.locked_amount.saturating_add(locked_amount);
+        investor_data.total_allocation = investor_data.total_allocation.saturating_add(stream.deposited_amount);
+    }
+
+    // Sorted by wallet, like the on-chain registry
+    Ok(investor_map.into_values().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::CrankReward;
+
+    const DAY_START: i64 = 1_700_000_000;
+
+    fn create_policy(quote_mint: Pubkey) -> PolicyConfig {
+        PolicyConfig {
+            vault: Pubkey::new_unique(),
+            quote_mint,
+            creator_wallet: Pubkey::new_unique(),
+            investor_fee_share_bps: 5_000,
+            min_payout_lamports: 100,
+            y0_total_allocation: 10_000_000,
+            bump: 255,
+            ..PolicyConfig::default()
+        }
+    }
+
+    fn create_progress(vault: Pubkey) -> DistributionProgress {
+        let mut progress = DistributionProgress {
+            ..DistributionProgress::default()
+        };
+        progress.initialize(vault, Pubkey::default(), 0).unwrap();
+        progress
+    }
+
+    /// Stream fully locked until well after `DAY_START`
+    fn create_stream(recipient: Pubkey, mint: Pubkey, deposited_amount: u64) -> StreamflowStream {
+        let start_time = DAY_START + 30 * TWENTY_FOUR_HOURS;
+        StreamflowStream {
+            sender: Pubkey::new_unique(),
+            recipient,
+            mint,
+            deposited_amount,
+            withdrawn_amount: 0,
+            start_time,
+            end_time: start_time + 100 * TWENTY_FOUR_HOURS,
+            cliff_time: start_time,
+            cliff_amount: 0,
+            amount_per_period: deposited_amount / 100,
+            period: TWENTY_FOUR_HOURS as u64,
+            cancelable_by_sender: true,
+            cancelable_by_recipient: false,
+            automatic_withdrawal: false,
+            transferable_by_sender: false,
+            transferable_by_recipient: false,
+            can_topup: false,
+            stream_name: [0u8; 64],
+            last_withdrawn_at: 0,
+            closed: false,
+        }
+    }
+
+    fn create_streams(mint: Pubkey, count: u64) -> Vec<StreamflowStream> {
+        (1..=count)
+            .map(|index| create_stream(Pubkey::new_unique(), mint, index * 37_000))
+            .collect()
+    }
+
+    #[test]
+    fn test_simulate_day_closes_and_conserves() {
+        let mint = Pubkey::new_unique();
+        let policy_config = create_policy(mint);
+        let progress = create_progress(policy_config.vault);
+        let streams = create_streams(mint, 75);
+
+        let simulation = simulate_day(&policy_config, &progress, &streams, 1_234_567, DAY_START).unwrap();
+
+        assert_eq!(simulation.timing, DistributionTimingState::NewDay);
+        assert_eq!(simulation.investors.len(), 75);
+        assert_eq!(simulation.pages.len(), 2);
+        assert!(simulation.is_day_closed());
+        assert!(simulation.progress.day_complete);
+
+        // Per-investor payouts add up to each page's total
+        for page in &simulation.pages {
+            let paid: u64 = page.investors.iter().map(|investor| investor.payout).sum();
+            assert_eq!(paid, page.investor_paid);
+        }
+
+        // Claim = investors + creator + what is still carried
+        let creator = simulation.creator.unwrap();
+        assert_eq!(creator.remainder, simulation.claimed_quote - simulation.investor_share);
+        assert_eq!(
+            simulation.claimed_quote,
+            simulation.total_investor_paid()
+                + creator.total()
+                + simulation.progress.outstanding_liabilities().unwrap()
+        );
+
+        // The modelled treasury keeps exactly what is still owed
+        assert_eq!(simulation.treasury_balance, simulation.progress.outstanding_liabilities().unwrap());
+    }
+
+    #[test]
+    fn test_simulate_day_page_size_does_not_change_payouts() {
+        let mint = Pubkey::new_unique();
+        let policy_config = create_policy(mint);
+        let progress = create_progress(policy_config.vault);
+        let streams = create_streams(mint, 30);
+
+        let payouts_by_wallet = |page_size: u32| {
+            let options = SimulationOptions { page_size, ..SimulationOptions::default() };
+            let simulation = simulate_day_with_options(
+                &policy_config, &progress, &streams, 900_000, DAY_START, options,
+            ).unwrap();
+            simulation
+                .pages
+                .iter()
+                .flat_map(|page| page.investors.iter().map(|investor| (investor.wallet, investor.payout)))
+                .collect::<BTreeMap<_, _>>()
+        };
+
+        let single_page = payouts_by_wallet(MAX_PAGE_SIZE);
+        assert_eq!(single_page.len(), 30);
+        assert_eq!(payouts_by_wallet(7), single_page);
+    }
+
+    #[test]
+    fn test_simulate_day_pays_crank_rewards() {
+        let mint = Pubkey::new_unique();
+        let mut policy_config = create_policy(mint);
+        policy_config.configure_crank_reward(CrankReward::FlatLamports { lamports: 400 }, 1_000).unwrap();
+        let progress = create_progress(policy_config.vault);
+        let streams = create_streams(mint, 30);
+        let options = SimulationOptions { page_size: 7, crank_reward_ata: true, ..SimulationOptions::default() };
+
+        let simulation = simulate_day_with_options(
+            &policy_config, &progress, &streams, 1_000_000, DAY_START, options,
+        ).unwrap();
+        let unrewarded = simulate_day_with_options(
+            &policy_config, &progress, &streams, 1_000_000, DAY_START,
+            SimulationOptions { page_size: 7, ..SimulationOptions::default() },
+        ).unwrap();
+
+        // The day's reward is split by investors processed, out of the creator's remainder only
+        let rewards: Vec<u64> = simulation.pages.iter().map(|page| page.crank_reward).collect();
+        assert_eq!(rewards, vec![93, 93, 94, 93, 27]);
+        assert_eq!(simulation.total_investor_paid(), unrewarded.total_investor_paid());
+        assert_eq!(
+            simulation.creator.unwrap().remainder,
+            unrewarded.creator.unwrap().remainder - simulation.total_crank_reward()
+        );
+        assert_eq!(unrewarded.total_crank_reward(), 0);
+    }
+
+    #[test]
+    fn test_simulate_day_crank_reward_does_not_depend_on_page_size() {
+        let mint = Pubkey::new_unique();
+        let mut policy_config = create_policy(mint);
+        policy_config.configure_crank_reward(CrankReward::FlatLamports { lamports: 400 }, 1_000).unwrap();
+        let progress = create_progress(policy_config.vault);
+        let streams = create_streams(mint, 30);
+
+        let paged = |page_size: u32| {
+            let options = SimulationOptions { page_size, crank_reward_ata: true, ..SimulationOptions::default() };
+            simulate_day_with_options(&policy_config, &progress, &streams, 1_000_000, DAY_START, options).unwrap()
+        };
+
+        // One-investor pages earn a thirtieth of the day's reward each, not the flat
+        // amount per page, so they cannot drain the daily cap
+        let single = paged(1);
+        assert_eq!(single.pages.len(), 30);
+        assert!(single.pages.iter().all(|page| page.crank_reward == 13 || page.crank_reward == 14));
+        assert_eq!(single.total_crank_reward(), 400);
+        assert_eq!(paged(7).total_crank_reward(), 400);
+        assert_eq!(paged(MAX_PAGE_SIZE).total_crank_reward(), 400);
+        assert_eq!(single.creator.unwrap().remainder, paged(MAX_PAGE_SIZE).creator.unwrap().remainder);
+    }
+
+    #[test]
+    fn test_simulate_day_defers_capped_pool() {
+        let mint = Pubkey::new_unique();
+        let mut policy_config = create_policy(mint);
+        policy_config.daily_cap_lamports = Some(100_000);
+        let progress = create_progress(policy_config.vault);
+        let streams = create_streams(mint, 10);
+
+        let simulation = simulate_day(&policy_config, &progress, &streams, 2_000_000, DAY_START).unwrap();
+
+        let opening = simulation.opening.unwrap();
+        assert!(opening.deferred > 0);
+        assert!(simulation.payable_pool <= 100_000);
+        assert!(simulation.total_investor_paid() <= 100_000);
+        assert_eq!(simulation.pages[0].cap_overflow_deferred, opening.deferred);
+        assert_eq!(simulation.progress.cap_overflow_carry, opening.deferred);
+    }
+
+    #[test]
+    fn test_simulate_day_leaves_input_untouched() {
+        let mint = Pubkey::new_unique();
+        let policy_config = create_policy(mint);
+        let progress = create_progress(policy_config.vault);
+        let streams = create_streams(mint, 3);
+
+        simulate_day(&policy_config, &progress, &streams, 500_000, DAY_START).unwrap();
+
+        assert_eq!(progress.last_distribution_ts, 0);
+        assert_eq!(progress.day_claimed_quote, 0);
+        assert_eq!(progress.pagination_cursor, 0);
+    }
+
+    #[test]
+    fn test_simulate_day_credits_converted_base_quote() {
+        let mint = Pubkey::new_unique();
+        let policy_config = create_policy(mint);
+        let mut progress = create_progress(policy_config.vault);
+        progress.credit_converted_base_quote(250_000).unwrap();
+        let streams = create_streams(mint, 3);
+
+        let simulation = simulate_day(&policy_config, &progress, &streams, 500_000, DAY_START).unwrap();
+
+        assert_eq!(simulation.claimed_quote, 750_000);
+        assert_eq!(simulation.progress.converted_base_quote, 0);
+    }
+
+    #[test]
+    fn test_simulate_day_without_fees_opens_empty_day() {
+        let mint = Pubkey::new_unique();
+        let policy_config = create_policy(mint);
+        let progress = create_progress(policy_config.vault);
+        let streams = create_streams(mint, 3);
+
+        let simulation = simulate_day(&policy_config, &progress, &streams, 0, DAY_START).unwrap();
+
+        assert!(simulation.pages.is_empty());
+        assert!(!simulation.is_day_closed());
+        assert_eq!(simulation.progress.last_distribution_ts, DAY_START);
+    }
+
+    #[test]
+    fn test_simulate_day_rejects_foreign_mint() {
+        let policy_config = create_policy(Pubkey::new_unique());
+        let progress = create_progress(policy_config.vault);
+        let streams = create_streams(Pubkey::new_unique(), 2);
+
+        let result = simulate_day(&policy_config, &progress, &streams, 1_000, DAY_START);
+        assert_eq!(result.err().unwrap(), ErrorCode::InvalidStreamMint.into());
+    }
+
+    #[test]
+    fn test_aggregate_stream_investors_merges_wallets() {
+        let mint = Pubkey::new_unique();
+        let policy_config = create_policy(mint);
+        let wallet = Pubkey::new_unique();
+        let streams = vec![
+            create_stream(wallet, mint, 1_000),
+            create_stream(Pubkey::new_unique(), mint, 500),
+            create_stream(wallet, mint, 2_000),
+        ];
+
+        let investors = aggregate_stream_investors(&policy_config, &streams, WeightWindow::at(DAY_START)).unwrap();
+
+        assert_eq!(investors.len(), 2);
+        let merged = investors.iter().find(|investor| investor.wallet == wallet).unwrap();
+        assert_eq!(merged.locked_amount, 3_000);
+        assert!(investors.windows(2).all(|pair| pair[0].wallet < pair[1].wallet));
+    }
+}
@@ -0,0 +1,162 @@
+use anchor_lang::prelude::*;
+use crate::error::ErrorCode;
+use crate::state::StakingLayout;
+
+/// Staked balance read from a stake account
+#[derive(Debug, Clone, PartialEq)]
+pub struct StakePosition {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub staked_amount: u64,
+}
+
+/// Reader for stake accounts described by a policy's `StakingLayout`
+pub struct StakingReader<'a> {
+    pub layout: &'a StakingLayout,
+}
+
+impl<'a> StakingReader<'a> {
+    /// Create a reader for `layout`
+    pub fn new(layout: &'a StakingLayout) -> Self {
+        Self { layout }
+    }
+
+    /// Validate and parse a stake account
+    pub fn read_position(&self, account: &AccountInfo) -> Result<StakePosition> {
+        // Validate account ownership - MUST be owned by the configured staking program
+        if account.owner != &self.layout.program_id {
+            msg!("Invalid stake account owner: expected {}, got {}",
+                 self.layout.program_id, account.owner);
+            return Err(ErrorCode::InvalidStakeAccount.into());
+        }
+
+        let position = Self::parse_stake_data(self.layout, &account.data.borrow())?;
+
+        // Validate the staked mint matches the configured stake mint
+        if position.mint != self.layout.stake_mint {
+            msg!("Stake account {} mint mismatch: expected {}, got {}",
+                 account.key(), self.layout.stake_mint, position.mint);
+            return Err(ErrorCode::InvalidStakeAccount.into());
+        }
+
+        Ok(position)
+    }
+
+    /// Parse raw stake account data according to `layout`
+    pub fn parse_stake_data(layout: &StakingLayout, data: &[u8]) -> Result<StakePosition> {
+        if data.len() < layout.min_account_len() {
+            msg!("Stake account data too small: {} < {}", data.len(), layout.min_account_len());
+            return Err(ErrorCode::InvalidStakeAccount.into());
+        }
+
+        if layout.has_discriminator() && data[0..8] != layout.discriminator {
+            return Err(ErrorCode::InvalidStakeAccount.into());
+        }
+
+        let read_pubkey = |offset: u16| -> Result<Pubkey> {
+            let offset = offset as usize;
+            Pubkey::try_from(&data[offset..offset + 32])
+                .map_err(|_| ErrorCode::InvalidStakeAccount.into())
+        };
+        let amount_offset = layout.amount_offset as usize;
+        let staked_amount = u64::from_le_bytes(
+            data[amount_offset..amount_offset + 8].try_into()
+                .map_err(|_| ErrorCode::InvalidStakeAccount)?
+        );
+
+        Ok(StakePosition {
+            owner: read_pubkey(layout.owner_offset)?,
+            mint: read_pubkey(layout.mint_offset)?,
+            staked_amount,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DISCRIMINATOR: [u8; 8] = [9, 8, 7, 6, 5, 4, 3, 2];
+
+    fn create_layout() -> StakingLayout {
+        StakingLayout {
+            program_id: Pubkey::new_unique(),
+            stake_mint: Pubkey::new_unique(),
+            discriminator: DISCRIMINATOR,
+            owner_offset: 8,
+            mint_offset: 40,
+            amount_offset: 80,
+        }
+    }
+
+    fn create_stake_data(layout: &StakingLayout, owner: Pubkey, mint: Pubkey, amount: u64) -> Vec<u8> {
+        let mut data = vec![0u8; layout.min_account_len() + 16];
+        data[0..8].copy_from_slice(&DISCRIMINATOR);
+        let owner_offset = layout.owner_offset as usize;
+        let mint_offset = layout.mint_offset as usize;
+        let amount_offset = layout.amount_offset as usize;
+        data[owner_offset..owner_offset + 32].copy_from_slice(owner.as_ref());
+        data[mint_offset..mint_offset + 32].copy_from_slice(mint.as_ref());
+        data[amount_offset..amount_offset + 8].copy_from_slice(&amount.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn test_parse_stake_account() {
+        let layout = create_layout();
+        let key = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let mut lamports = 0u64;
+        let mut data = create_stake_data(&layout, owner, layout.stake_mint, 750_000);
+        let account = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &layout.program_id, false, 0);
+
+        let position = StakingReader::new(&layout).read_position(&account).unwrap();
+        assert_eq!(position.owner, owner);
+        assert_eq!(position.mint, layout.stake_mint);
+        assert_eq!(position.staked_amount, 750_000);
+    }
+
+    #[test]
+    fn test_reject_invalid_stake_accounts() {
+        let layout = create_layout();
+        let reader = StakingReader::new(&layout);
+        let key = Pubkey::new_unique();
+        let mut lamports = 0u64;
+
+        // Foreign owner
+        let foreign_owner = Pubkey::new_unique();
+        let mut data = create_stake_data(&layout, Pubkey::new_unique(), layout.stake_mint, 1);
+        let account = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &foreign_owner, false, 0);
+        assert!(reader.read_position(&account).is_err());
+
+        // Wrong mint
+        let mut data = create_stake_data(&layout, Pubkey::new_unique(), Pubkey::new_unique(), 1);
+        let account = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &layout.program_id, false, 0);
+        assert!(reader.read_position(&account).is_err());
+
+        // Wrong discriminator and truncated data
+        let mut data = create_stake_data(&layout, Pubkey::new_unique(), layout.stake_mint, 1);
+        data[0] ^= 0xff;
+        assert!(StakingReader::parse_stake_data(&layout, &data).is_err());
+        assert!(StakingReader::parse_stake_data(&layout, &data[..layout.min_account_len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_layout_without_discriminator() {
+        let mut layout = create_layout();
+        layout.discriminator = [0u8; 8];
+        layout.owner_offset = 0;
+        layout.mint_offset = 32;
+        layout.amount_offset = 64;
+
+        let owner = Pubkey::new_unique();
+        let mut data = vec![0u8; 72];
+        data[0..32].copy_from_slice(owner.as_ref());
+        data[32..64].copy_from_slice(layout.stake_mint.as_ref());
+        data[64..72].copy_from_slice(&42u64.to_le_bytes());
+
+        let position = StakingReader::parse_stake_data(&layout, &data).unwrap();
+        assert_eq!(position.owner, owner);
+        assert_eq!(position.staked_amount, 42);
+    }
+}
@@ -1,7 +1,7 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::clock::Clock;
 use crate::error::ErrorCode;
-use std::collections::{HashMap, HashSet};
+use crate::utils::vesting::{VestingPosition, VestingSchedule, VestingSource};
 
 /// Official Streamflow Token Vesting Program ID (Mainnet & Devnet)
 /// Source: https://docs.streamflow.finance/
@@ -229,15 +229,43 @@ impl StreamflowStream {
             closed,
         })
     }
+
+    /// Convert the stream into the shared cliff-plus-periodic schedule
+    pub fn to_schedule(&self) -> VestingSchedule {
+        VestingSchedule {
+            deposited_amount: self.deposited_amount,
+            withdrawn_amount: self.withdrawn_amount,
+            start_time: self.start_time,
+            cliff_time: self.cliff_time,
+            cliff_amount: self.cliff_amount,
+            period: self.period,
+            amount_per_period: self.amount_per_period,
+            end_time: self.end_time,
+            closed: self.closed,
+        }
+    }
+
+    /// The stream as a vesting position of its recipient
+    pub fn to_position(&self) -> VestingPosition {
+        VestingPosition {
+            recipient: self.recipient,
+            mint: self.mint,
+            schedule: self.to_schedule(),
+        }
+    }
 }
 
-/// Investor data aggregated from Streamflow accounts
-#[derive(Debug, Clone)]
-pub struct InvestorData {
-    pub wallet: Pubkey,
-    pub locked_amount: u64,
-    pub total_allocation: u64,
-    pub stream_accounts: Vec<Pubkey>,
+/// Streamflow streams as a vesting source
+pub struct StreamflowSource;
+
+impl VestingSource for StreamflowSource {
+    fn owner_program(&self) -> Pubkey {
+        STREAMFLOW_PROGRAM_ID
+    }
+
+    fn parse(&self, account: &AccountInfo) -> Result<VestingPosition> {
+        Ok(StreamflowIntegration::parse_stream(account)?.to_position())
+    }
 }
 
 /// Streamflow integration utilities
@@ -250,51 +278,7 @@ impl StreamflowIntegration {
         stream: &StreamflowStream,
         current_timestamp: i64,
     ) -> Result<u64> {
-        // If stream is closed, no tokens are locked
-        if stream.closed {
-            return Ok(0);
-        }
-        
-        let remaining = stream.deposited_amount.saturating_sub(stream.withdrawn_amount);
-        
-        // If we haven't reached the start time, all tokens are locked
-        if current_timestamp < stream.start_time {
-            return Ok(remaining);
-        }
-        
-        // If we haven't reached the cliff, all tokens are locked (minus cliff_amount unlocked at cliff)
-        if current_timestamp < stream.cliff_time {
-            return Ok(remaining);
-        }
-        
-        // If we've passed the end time, no tokens are locked
-        if current_timestamp >= stream.end_time {
-            return Ok(0);
-        }
-        
-        // Calculate unlocked amount using Streamflow's formula:
-        // unlocked = cliff_amount + ((current - cliff) / period) * amount_per_period
-        // But capped at deposited_amount
-        
-        if stream.period == 0 {
-            // Instant unlock if no period
-            return Ok(0);
-        }
-        
-        let time_since_cliff = (current_timestamp - stream.cliff_time) as u64;
-        let periods_elapsed = time_since_cliff / stream.period;
-        
-        // Calculate total unlocked: cliff_amount + (periods * amount_per_period)
-        let unlocked = stream.cliff_amount
-            .saturating_add(periods_elapsed.saturating_mul(stream.amount_per_period));
-        
-        // Cap at deposited amount
-        let unlocked = unlocked.min(stream.deposited_amount);
-        
-        // Locked = deposited - unlocked (but consider already withdrawn)
-        let locked = stream.deposited_amount.saturating_sub(unlocked);
-        
-        Ok(locked)
+        Ok(stream.to_schedule().locked_at(current_timestamp))
     }
     
     /// Validate a Streamflow account and extract stream data
@@ -302,6 +286,18 @@ impl StreamflowIntegration {
         stream_account: &AccountInfo,
         expected_mint: &Pubkey,
     ) -> Result<StreamflowStream> {
+        let stream = Self::parse_stream(stream_account)?;
+        
+        // Validate the mint matches expected token
+        if stream.mint != *expected_mint {
+            return Err(ErrorCode::StreamflowValidationFailed.into());
+        }
+        
+        Ok(stream)
+    }
+    
+    /// Validate a Streamflow account's owner, state and schedule without checking its mint
+    pub fn parse_stream(stream_account: &AccountInfo) -> Result<StreamflowStream> {
         // Validate account ownership - MUST be owned by Streamflow program
         if stream_account.owner != &STREAMFLOW_PROGRAM_ID {
             msg!("Invalid Streamflow account owner: expected {}, got {}", 
@@ -316,12 +312,13 @@ impl StreamflowIntegration {
         
         // Parse the stream data
         let stream = StreamflowStream::try_from_account_data(&stream_account.data.borrow())?;
+        Self::validate_stream(&stream)?;
         
-        // Validate the mint matches expected token
-        if stream.mint != *expected_mint {
-            return Err(ErrorCode::StreamflowValidationFailed.into());
-        }
-        
+        Ok(stream)
+    }
+    
+    /// Validate a parsed stream's state and schedule
+    pub fn validate_stream(stream: &StreamflowStream) -> Result<()> {
         // Validate stream is not closed
         if stream.closed {
             return Err(ErrorCode::StreamflowValidationFailed.into());
@@ -336,55 +333,7 @@ impl StreamflowIntegration {
             return Err(ErrorCode::StreamflowValidationFailed.into());
         }
         
-        Ok(stream)
-    }
-    
-    /// Aggregate investor data from multiple Streamflow accounts
-    pub fn aggregate_investor_data(
-        stream_accounts: &[AccountInfo],
-        expected_mint: &Pubkey,
-        current_timestamp: i64,
-    ) -> Result<Vec<InvestorData>> {
-        let mut investor_map: HashMap<Pubkey, InvestorData> = HashMap::new();
-        
-        for stream_account in stream_accounts {
-            // Validate and parse the stream
-            let stream = Self::validate_and_parse_stream(stream_account, expected_mint)?;
-            
-            // Calculate locked amount for this stream
-            let locked_amount = Self::calculate_locked_amount(&stream, current_timestamp)?;
-            
-            // Aggregate data by recipient (investor wallet)
-            let investor_data = investor_map.entry(stream.recipient).or_insert(InvestorData {
-                wallet: stream.recipient,
-                locked_amount: 0,
-                total_allocation: 0,
-                stream_accounts: Vec::new(),
-            });
-            
-            investor_data.locked_amount = investor_data.locked_amount.saturating_add(locked_amount);
-            investor_data.total_allocation = investor_data.total_allocation.saturating_add(stream.deposited_amount);
-            investor_data.stream_accounts.push(stream_account.key());
-        }
-        
-        Ok(investor_map.into_values().collect())
-    }
-    
-    /// Calculate total locked amount across all investors
-    pub fn calculate_total_locked(
-        stream_accounts: &[AccountInfo],
-        expected_mint: &Pubkey,
-        current_timestamp: i64,
-    ) -> Result<u64> {
-        let mut total_locked = 0u64;
-        
-        for stream_account in stream_accounts {
-            let stream = Self::validate_and_parse_stream(stream_account, expected_mint)?;
-            let locked_amount = Self::calculate_locked_amount(&stream, current_timestamp)?;
-            total_locked = total_locked.saturating_add(locked_amount);
-        }
-        
-        Ok(total_locked)
+        Ok(())
     }
     
     /// Get current timestamp from Solana clock
@@ -392,58 +341,6 @@ impl StreamflowIntegration {
         let clock = Clock::get()?;
         Ok(clock.unix_timestamp)
     }
-    
-    /// Process a page of investors for fee distribution
-    /// Returns (processed_count, total_locked_in_page, investor_payouts)
-    pub fn process_investor_page(
-        stream_accounts: &[AccountInfo],
-        expected_mint: &Pubkey,
-        current_timestamp: i64,
-        page_start: usize,
-        page_size: usize,
-    ) -> Result<(usize, u64, Vec<(Pubkey, u64)>)> {
-        let end_index = std::cmp::min(page_start + page_size, stream_accounts.len());
-        let page_accounts = &stream_accounts[page_start..end_index];
-        
-        let investor_data = Self::aggregate_investor_data(page_accounts, expected_mint, current_timestamp)?;
-        
-        let mut total_locked = 0u64;
-        let mut payouts = Vec::new();
-        
-        for investor in investor_data {
-            total_locked = total_locked.saturating_add(investor.locked_amount);
-            payouts.push((investor.wallet, investor.locked_amount));
-        }
-        
-        Ok((end_index - page_start, total_locked, payouts))
-    }
-    
-    /// Validate that all provided stream accounts belong to the expected mint
-    pub fn validate_all_streams_mint(
-        stream_accounts: &[AccountInfo],
-        expected_mint: &Pubkey,
-    ) -> Result<()> {
-        for stream_account in stream_accounts {
-            let _ = Self::validate_and_parse_stream(stream_account, expected_mint)?;
-            // Additional validation can be added here
-        }
-        Ok(())
-    }
-    
-    /// Get investor count from stream accounts (deduplicated by recipient)
-    pub fn get_unique_investor_count(
-        stream_accounts: &[AccountInfo],
-        expected_mint: &Pubkey,
-    ) -> Result<usize> {
-        let mut unique_recipients = HashSet::new();
-        
-        for stream_account in stream_accounts {
-            let stream = Self::validate_and_parse_stream(stream_account, expected_mint)?;
-            unique_recipients.insert(stream.recipient);
-        }
-        
-        Ok(unique_recipients.len())
-    }
 }
 
 #[cfg(test)]
@@ -580,4 +477,45 @@ mod tests {
         let locked = StreamflowIntegration::calculate_locked_amount(&stream, 1600).unwrap();
         assert_eq!(locked, 0); // No tokens locked in closed stream
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_streamflow_source_parse() {
+        let key = Pubkey::new_unique();
+        let recipient = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let mut lamports = 0u64;
+        let mut data = vec![0u8; StreamflowStream::MIN_ACCOUNT_SIZE];
+        data[0..8].copy_from_slice(&1u64.to_le_bytes());
+        data[33..41].copy_from_slice(&2000i64.to_le_bytes());
+        data[113..145].copy_from_slice(recipient.as_ref());
+        data[177..209].copy_from_slice(mint.as_ref());
+        data[409..417].copy_from_slice(&1000i64.to_le_bytes());
+        data[417..425].copy_from_slice(&1_000_000u64.to_le_bytes());
+        data[425..433].copy_from_slice(&100u64.to_le_bytes());
+        data[433..441].copy_from_slice(&100_000u64.to_le_bytes());
+        data[441..449].copy_from_slice(&1000i64.to_le_bytes());
+        let owner = STREAMFLOW_PROGRAM_ID;
+        let account = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+        
+        let position = StreamflowSource.parse(&account).unwrap();
+        assert_eq!(position.recipient, recipient);
+        assert_eq!(position.mint, mint);
+        assert_eq!(position.total_allocation(), 1_000_000);
+        assert_eq!(position.locked_at(1500), 500_000);
+        
+        // Mint is checked by the Streamflow-specific entry point
+        assert!(StreamflowIntegration::validate_and_parse_stream(&account, &mint).is_ok());
+        assert!(StreamflowIntegration::validate_and_parse_stream(&account, &Pubkey::new_unique()).is_err());
+    }
+    
+    #[test]
+    fn test_streamflow_source_rejects_foreign_owner() {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0u64;
+        let mut data = vec![1u8; StreamflowStream::MIN_ACCOUNT_SIZE];
+        let owner = Pubkey::new_unique();
+        let account = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+        
+        assert!(StreamflowSource.parse(&account).is_err());
+    }
+}
@@ -0,0 +1,545 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::instruction::{Instruction, AccountMeta};
+use anchor_spl::token::{Token, TokenAccount};
+
+use crate::{
+    error::ErrorCode,
+    constants::*,
+    state::HonoraryPool,
+};
+
+/// DAMM V2 (cp-amm) program swaps go through
+pub const DAMM_V2_PROGRAM_ID: Pubkey = pubkey!("cpamdpZCGKUy5JxQXB4dcpGPiikHawvSWAd6mEn1sGG");
+
+/// DAMM V2 pool authority PDA (`["pool_authority"]`), owner of every pool vault
+pub const DAMM_V2_POOL_AUTHORITY: Pubkey = pubkey!("HLnpSz9h2S4hiLQ43rnSD9XkcUThA7B8hQMKmDaiTLcC");
+
+/// DAMM V2 event authority PDA (`["__event_authority"]`) its swaps emit events through
+pub const DAMM_V2_EVENT_AUTHORITY: Pubkey = pubkey!("3rmHSu74h1ZcmAisVcWerTCiRDQbUrBKmcwptYGjHfet");
+
+/// Anchor discriminator of the DAMM V2 `swap` instruction (`sha256("global:swap")[..8]`)
+pub const DAMM_V2_SWAP_DISCRIMINATOR: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
+
+/// Anchor discriminator of the DAMM V2 `Pool` account (`sha256("account:Pool")[..8]`)
+pub const DAMM_V2_POOL_DISCRIMINATOR: [u8; 8] = [241, 154, 109, 4, 17, 177, 109, 188];
+
+/// Token mints and vaults of a DAMM V2 `Pool` account
+/// Layout verified against cp-amm `programs/cp-amm/src/state/pool.rs`
+///
+/// Account Layout (offsets from start, including discriminator):
+/// - 0:    discriminator (8 bytes)
+/// - 8:    pool_fees (160 bytes)
+/// - 168:  token_a_mint (32 bytes)
+/// - 200:  token_b_mint (32 bytes)
+/// - 232:  token_a_vault (32 bytes)
+/// - 264:  token_b_vault (32 bytes)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DammV2PoolTokens {
+    pub token_a_mint: Pubkey,
+    pub token_b_mint: Pubkey,
+    pub token_a_vault: Pubkey,
+    pub token_b_vault: Pubkey,
+}
+
+impl DammV2PoolTokens {
+    /// Bytes up to the end of `token_b_vault`
+    pub const MIN_ACCOUNT_SIZE: usize = 296;
+
+    /// Read the mints and vaults of a pool account owned by the DAMM V2 program
+    pub fn try_from_account(pool: &AccountInfo) -> Result<Self> {
+        require!(*pool.owner == DAMM_V2_PROGRAM_ID, ErrorCode::InvalidSwapPool);
+
+        let data = pool.try_borrow_data()?;
+        require!(
+            data.len() >= Self::MIN_ACCOUNT_SIZE && data[..8] == DAMM_V2_POOL_DISCRIMINATOR,
+            ErrorCode::InvalidSwapPool
+        );
+
+        let read_pubkey = |offset: usize| -> Result<Pubkey> {
+            Pubkey::try_from(&data[offset..offset + 32])
+                .map_err(|_| ErrorCode::InvalidSwapPool.into())
+        };
+
+        Ok(DammV2PoolTokens {
+            token_a_mint: read_pubkey(168)?,
+            token_b_mint: read_pubkey(200)?,
+            token_a_vault: read_pubkey(232)?,
+            token_b_vault: read_pubkey(264)?,
+        })
+    }
+}
+
+/// Check a recorded honorary pool against its on-chain pool account: the pool must trade
+/// `base_mint` against `quote_mint` through the recorded vaults, in the recorded A/B order
+pub fn validate_honorary_pool(
+    honorary_pool: &HonoraryPool,
+    pool: &AccountInfo,
+    base_mint: &Pubkey,
+    quote_mint: &Pubkey,
+) -> Result<()> {
+    require!(pool.key() == honorary_pool.pool, ErrorCode::InvalidSwapPool);
+
+    let tokens = DammV2PoolTokens::try_from_account(pool)?;
+    let (pool_base_mint, pool_quote_mint, pool_base_vault, pool_quote_vault) = if honorary_pool.base_is_token_a {
+        (tokens.token_a_mint, tokens.token_b_mint, tokens.token_a_vault, tokens.token_b_vault)
+    } else {
+        (tokens.token_b_mint, tokens.token_a_mint, tokens.token_b_vault, tokens.token_a_vault)
+    };
+
+    if (pool_base_mint, pool_quote_mint, pool_base_vault, pool_quote_vault)
+        != (*base_mint, *quote_mint, honorary_pool.base_vault, honorary_pool.quote_vault)
+    {
+        msg!("Honorary pool {} does not trade {} against {} through its recorded vaults",
+             honorary_pool.pool, base_mint, quote_mint);
+        return Err(ErrorCode::InvalidSwapPool.into());
+    }
+
+    Ok(())
+}
+
+/// Keys of a DAMM V2 `swap`, which takes the pool's vaults and mints in token A/B order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DammV2SwapKeys {
+    pub pool: Pubkey,
+    pub input_token_account: Pubkey,
+    pub output_token_account: Pubkey,
+    pub token_a_vault: Pubkey,
+    pub token_b_vault: Pubkey,
+    pub token_a_mint: Pubkey,
+    pub token_b_mint: Pubkey,
+    /// Owner of the input and output token accounts, signing the swap
+    pub payer: Pubkey,
+    pub token_program: Pubkey,
+}
+
+/// Build a DAMM V2 `swap` without a referral account
+pub fn damm_v2_swap_instruction(keys: &DammV2SwapKeys, amount_in: u64, minimum_amount_out: u64) -> Instruction {
+    Instruction {
+        program_id: DAMM_V2_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new_readonly(DAMM_V2_POOL_AUTHORITY, false),
+            AccountMeta::new(keys.pool, false),
+            AccountMeta::new(keys.input_token_account, false),
+            AccountMeta::new(keys.output_token_account, false),
+            AccountMeta::new(keys.token_a_vault, false),
+            AccountMeta::new(keys.token_b_vault, false),
+            AccountMeta::new_readonly(keys.token_a_mint, false),
+            AccountMeta::new_readonly(keys.token_b_mint, false),
+            AccountMeta::new_readonly(keys.payer, true),
+            AccountMeta::new_readonly(keys.token_program, false),
+            AccountMeta::new_readonly(keys.token_program, false),
+            // No referral: Anchor reads the program id in an optional account's slot as `None`
+            AccountMeta::new_readonly(DAMM_V2_PROGRAM_ID, false),
+            AccountMeta::new_readonly(DAMM_V2_EVENT_AUTHORITY, false),
+            AccountMeta::new_readonly(DAMM_V2_PROGRAM_ID, false),
+        ],
+        data: prepare_swap_instruction_data(amount_in, minimum_amount_out),
+    }
+}
+
+/// Accounts of a swap through an honorary DAMM V2 pool, signed by the position owner PDA
+pub struct PoolSwapAccounts<'a, 'info> {
+    /// The policy's record of the pool, which the pool and vaults below must match
+    pub honorary_pool: &'a HonoraryPool,
+    pub pool: &'a AccountInfo<'info>,
+    pub pool_authority: &'a AccountInfo<'info>,
+    pub position_owner_pda: &'a AccountInfo<'info>,
+    /// Position owner's token account the input is taken from
+    pub input_account: &'a Account<'info, TokenAccount>,
+    /// Position owner's token account the output is paid into
+    pub output_account: &'a Account<'info, TokenAccount>,
+    /// Pool vault holding the input token
+    pub pool_input_vault: &'a Account<'info, TokenAccount>,
+    /// Pool vault holding the output token
+    pub pool_output_vault: &'a Account<'info, TokenAccount>,
+    pub input_mint: &'a AccountInfo<'info>,
+    pub output_mint: &'a AccountInfo<'info>,
+    pub event_authority: &'a AccountInfo<'info>,
+    pub cp_amm_program: &'a AccountInfo<'info>,
+    pub token_program: &'a Program<'info, Token>,
+}
+
+impl PoolSwapAccounts<'_, '_> {
+    /// Check the pool, vaults, mints and DAMM V2 accounts against the policy's record of
+    /// the pool, returning whether the input is the pool's token A
+    pub fn validate(&self) -> Result<bool> {
+        require!(
+            self.cp_amm_program.key() == DAMM_V2_PROGRAM_ID
+                && self.pool_authority.key() == DAMM_V2_POOL_AUTHORITY
+                && self.event_authority.key() == DAMM_V2_EVENT_AUTHORITY,
+            ErrorCode::InvalidSwapPool
+        );
+        let input_is_base = self.honorary_pool.input_is_base(
+            &self.pool.key(),
+            &self.pool_input_vault.key(),
+            &self.pool_output_vault.key(),
+        )?;
+        require!(
+            self.pool_input_vault.mint == self.input_mint.key()
+                && self.pool_output_vault.mint == self.output_mint.key()
+                && self.input_account.mint == self.input_mint.key()
+                && self.output_account.mint == self.output_mint.key(),
+            ErrorCode::InvalidSwapPool
+        );
+
+        Ok(input_is_base == self.honorary_pool.base_is_token_a)
+    }
+}
+
+/// Output of swapping `amount_in` at a reference price of quote per base in Q64.64
+pub fn reference_swap_out(amount_in: u64, reference_price_q64: u128, input_is_base: bool) -> Result<u64> {
+    require!(
+        reference_price_q64 > 0,
+        ErrorCode::ReferencePriceUnset
+    );
+
+    let amount_out = if input_is_base {
+        (amount_in as u128)
+            .checked_mul(reference_price_q64)
+            .map(|quote_q64| quote_q64 >> 64)
+    } else {
+        ((amount_in as u128) << 64).checked_div(reference_price_q64)
+    }
+    .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    u64::try_from(amount_out).map_err(|_| ErrorCode::ArithmeticOverflow.into())
+}
+
+/// Lowest output accepted for an expected output under a slippage bound in basis points
+pub fn minimum_out_floor(expected_out: u64, max_slippage_bps: u16) -> Result<u64> {
+    require!(
+        max_slippage_bps <= MAX_BASIS_POINTS,
+        ErrorCode::InvalidSwapSlippage
+    );
+
+    let floor = (expected_out as u128)
+        .checked_mul((MAX_BASIS_POINTS - max_slippage_bps) as u128)
+        .and_then(|scaled| scaled.checked_div(MAX_BASIS_POINTS as u128))
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    Ok(floor as u64)
+}
+
+/// Lowest output the policy accepts for swapping `amount_in` through an honorary pool:
+/// the pool's reference price less the policy's slippage bound
+pub fn policy_minimum_out(
+    honorary_pool: &HonoraryPool,
+    amount_in: u64,
+    input_is_base: bool,
+    max_slippage_bps: u16,
+) -> Result<u64> {
+    let reference_out = reference_swap_out(amount_in, honorary_pool.reference_price_q64, input_is_base)?;
+    minimum_out_floor(reference_out, max_slippage_bps)
+}
+
+/// Check a caller's minimum out against the policy's floor for the swap
+pub fn validate_minimum_out(minimum_amount_out: u64, policy_floor: u64) -> Result<()> {
+    require!(
+        minimum_amount_out > 0 && minimum_amount_out >= policy_floor,
+        ErrorCode::SwapSlippageExceeded
+    );
+
+    Ok(())
+}
+
+/// Swap `amount_in` through an honorary pool via CPI, returning the output received
+///
+/// The pool and its vaults must be the ones the policy recorded for it. The output is
+/// measured from the output account's balance, so the pool cannot report more than it
+/// paid; anything below `minimum_amount_out` fails the swap.
+pub fn swap_through_pool(
+    accounts: PoolSwapAccounts,
+    vault_key: &Pubkey,
+    bump: u8,
+    amount_in: u64,
+    minimum_amount_out: u64,
+) -> Result<u64> {
+    let input_is_token_a = accounts.validate()?;
+
+    let vault_seed = vault_key.as_ref();
+    let position_owner_seed = b"investor_fee_pos_owner";
+    let signer_seeds = &[
+        VAULT_SEED,
+        vault_seed,
+        position_owner_seed,
+        &[bump],
+    ];
+
+    let balance_before = accounts.output_account.amount;
+
+    msg!("Swapping {} through DAMM V2 pool {} (minimum out {})",
+         amount_in, accounts.pool.key(), minimum_amount_out);
+
+    let (token_a_vault, token_b_vault, token_a_mint, token_b_mint) = if input_is_token_a {
+        (accounts.pool_input_vault, accounts.pool_output_vault, accounts.input_mint, accounts.output_mint)
+    } else {
+        (accounts.pool_output_vault, accounts.pool_input_vault, accounts.output_mint, accounts.input_mint)
+    };
+    let swap_ix = damm_v2_swap_instruction(
+        &DammV2SwapKeys {
+            pool: accounts.pool.key(),
+            input_token_account: accounts.input_account.key(),
+            output_token_account: accounts.output_account.key(),
+            token_a_vault: token_a_vault.key(),
+            token_b_vault: token_b_vault.key(),
+            token_a_mint: token_a_mint.key(),
+            token_b_mint: token_b_mint.key(),
+            payer: accounts.position_owner_pda.key(),
+            token_program: accounts.token_program.key(),
+        },
+        amount_in,
+        minimum_amount_out,
+    );
+
+    invoke_signed(
+        &swap_ix,
+        &[
+            accounts.pool_authority.clone(),
+            accounts.pool.clone(),
+            accounts.input_account.to_account_info(),
+            accounts.output_account.to_account_info(),
+            token_a_vault.to_account_info(),
+            token_b_vault.to_account_info(),
+            token_a_mint.clone(),
+            token_b_mint.clone(),
+            accounts.position_owner_pda.clone(),
+            accounts.token_program.to_account_info(),
+            accounts.event_authority.clone(),
+            accounts.cp_amm_program.clone(),
+        ],
+        &[signer_seeds],
+    ).map_err(|e| {
+        msg!("DAMM V2 swap CPI failed: {}", e);
+        ErrorCode::CpiCallFailed
+    })?;
+
+    let balance_after = token_account_amount(&accounts.output_account.to_account_info())?;
+    let amount_out = balance_after
+        .checked_sub(balance_before)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    require!(
+        amount_out >= minimum_amount_out,
+        ErrorCode::SwapOutputBelowMinimum
+    );
+
+    Ok(amount_out)
+}
+
+/// Prepares instruction data for the DAMM V2 swap call: the discriminator, then the
+/// amount in and minimum out
+pub fn prepare_swap_instruction_data(amount_in: u64, minimum_amount_out: u64) -> Vec<u8> {
+    let mut instruction_data = Vec::with_capacity(8 + 8 + 8);
+    instruction_data.extend_from_slice(&DAMM_V2_SWAP_DISCRIMINATOR);
+    instruction_data.extend_from_slice(&amount_in.to_le_bytes());
+    instruction_data.extend_from_slice(&minimum_amount_out.to_le_bytes());
+
+    instruction_data
+}
+
+/// Current balance of a token account, read after a CPI has changed it
+fn token_account_amount(account: &AccountInfo) -> Result<u64> {
+    let data = account.try_borrow_data()?;
+    let mut slice: &[u8] = &data;
+    let token_account = TokenAccount::try_deserialize(&mut slice)?;
+    Ok(token_account.amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reference_swap_out() {
+        // 2 quote per base
+        let price = 2u128 << 64;
+        assert_eq!(reference_swap_out(1_000, price, true).unwrap(), 2_000);
+        assert_eq!(reference_swap_out(1_000, price, false).unwrap(), 500);
+
+        // Half a quote per base, rounding down
+        let price = 1u128 << 63;
+        assert_eq!(reference_swap_out(1_001, price, true).unwrap(), 500);
+        assert_eq!(reference_swap_out(1_001, price, false).unwrap(), 2_002);
+
+        // An unset price cannot bound a swap, and outputs past u64 fail
+        assert!(reference_swap_out(1_000, 0, true).is_err());
+        assert!(reference_swap_out(u64::MAX, u128::MAX, true).is_err());
+        assert!(reference_swap_out(u64::MAX, 1, false).is_err());
+    }
+
+    #[test]
+    fn test_minimum_out_within_slippage() {
+        assert_eq!(minimum_out_floor(10_000, 0).unwrap(), 10_000);
+        assert_eq!(minimum_out_floor(10_000, 150).unwrap(), 9_850);
+        assert_eq!(minimum_out_floor(10_000, MAX_BASIS_POINTS).unwrap(), 0);
+        assert!(minimum_out_floor(10_000, MAX_BASIS_POINTS + 1).is_err());
+
+    }
+
+    #[test]
+    fn test_policy_minimum_out() {
+        let mut honorary_pool = HonoraryPool {
+            pool: Pubkey::new_unique(),
+            base_vault: Pubkey::new_unique(),
+            quote_vault: Pubkey::new_unique(),
+            base_is_token_a: true,
+            reference_price_q64: 0,
+        };
+
+        // No floor can be set without a reference price
+        assert!(policy_minimum_out(&honorary_pool, 1_000, true, 100).is_err());
+
+        // 2 quote per base less 1%, either way through the pool
+        honorary_pool.reference_price_q64 = 2 << 64;
+        assert_eq!(policy_minimum_out(&honorary_pool, 1_000, true, 100).unwrap(), 1_980);
+        assert_eq!(policy_minimum_out(&honorary_pool, 1_000, false, 100).unwrap(), 495);
+
+        // A caller cannot ask for less than the floor, however small
+        let floor = policy_minimum_out(&honorary_pool, 1_000, true, 100).unwrap();
+        assert!(validate_minimum_out(1, floor).is_err());
+        assert!(validate_minimum_out(floor - 1, floor).is_err());
+        assert!(validate_minimum_out(floor, floor).is_ok());
+        assert!(validate_minimum_out(2_000, floor).is_ok());
+        assert!(validate_minimum_out(0, 0).is_err());
+    }
+
+    #[test]
+    fn test_damm_v2_addresses() {
+        use solana_sha256_hasher::hash;
+
+        assert_eq!(&hash(b"global:swap").to_bytes()[..8], &DAMM_V2_SWAP_DISCRIMINATOR);
+        assert_eq!(&hash(b"account:Pool").to_bytes()[..8], &DAMM_V2_POOL_DISCRIMINATOR);
+        assert_eq!(
+            Pubkey::find_program_address(&[b"pool_authority"], &DAMM_V2_PROGRAM_ID).0,
+            DAMM_V2_POOL_AUTHORITY
+        );
+        assert_eq!(
+            Pubkey::find_program_address(&[b"__event_authority"], &DAMM_V2_PROGRAM_ID).0,
+            DAMM_V2_EVENT_AUTHORITY
+        );
+    }
+
+    #[test]
+    fn test_swap_instruction_decodes() {
+        let keys = DammV2SwapKeys {
+            pool: Pubkey::new_unique(),
+            input_token_account: Pubkey::new_unique(),
+            output_token_account: Pubkey::new_unique(),
+            token_a_vault: Pubkey::new_unique(),
+            token_b_vault: Pubkey::new_unique(),
+            token_a_mint: Pubkey::new_unique(),
+            token_b_mint: Pubkey::new_unique(),
+            payer: Pubkey::new_unique(),
+            token_program: anchor_spl::token::ID,
+        };
+        let instruction = damm_v2_swap_instruction(&keys, 1_000, 950);
+        assert_eq!(instruction.program_id, DAMM_V2_PROGRAM_ID);
+
+        // Accounts in cp-amm's `Swap` order, with the event CPI accounts last
+        let expected = [
+            (DAMM_V2_POOL_AUTHORITY, false, false),
+            (keys.pool, true, false),
+            (keys.input_token_account, true, false),
+            (keys.output_token_account, true, false),
+            (keys.token_a_vault, true, false),
+            (keys.token_b_vault, true, false),
+            (keys.token_a_mint, false, false),
+            (keys.token_b_mint, false, false),
+            (keys.payer, false, true),
+            (keys.token_program, false, false),
+            (keys.token_program, false, false),
+            (DAMM_V2_PROGRAM_ID, false, false),
+            (DAMM_V2_EVENT_AUTHORITY, false, false),
+            (DAMM_V2_PROGRAM_ID, false, false),
+        ];
+        let accounts: Vec<_> = instruction.accounts
+            .iter()
+            .map(|meta| (meta.pubkey, meta.is_writable, meta.is_signer))
+            .collect();
+        assert_eq!(accounts, expected);
+
+        // Swap parameters decode as `{ amount_in: u64, minimum_amount_out: u64 }`
+        let (discriminator, mut args) = instruction.data.split_at(8);
+        assert_eq!(discriminator, &DAMM_V2_SWAP_DISCRIMINATOR);
+        let amount_in = u64::deserialize(&mut args).unwrap();
+        let minimum_amount_out = u64::deserialize(&mut args).unwrap();
+        assert_eq!((amount_in, minimum_amount_out), (1_000, 950));
+        assert!(args.is_empty());
+    }
+
+    #[test]
+    fn test_swap_instruction_data() {
+        let data = prepare_swap_instruction_data(1_000, 950);
+        assert_eq!(data.len(), 24);
+        assert_eq!(data[..8], DAMM_V2_SWAP_DISCRIMINATOR);
+        assert_eq!(u64::from_le_bytes(data[8..16].try_into().unwrap()), 1_000);
+        assert_eq!(u64::from_le_bytes(data[16..24].try_into().unwrap()), 950);
+    }
+
+    fn create_pool_data(tokens: &DammV2PoolTokens) -> Vec<u8> {
+        let mut data = vec![0u8; DammV2PoolTokens::MIN_ACCOUNT_SIZE + 64];
+        data[..8].copy_from_slice(&DAMM_V2_POOL_DISCRIMINATOR);
+        data[168..200].copy_from_slice(tokens.token_a_mint.as_ref());
+        data[200..232].copy_from_slice(tokens.token_b_mint.as_ref());
+        data[232..264].copy_from_slice(tokens.token_a_vault.as_ref());
+        data[264..296].copy_from_slice(tokens.token_b_vault.as_ref());
+        data
+    }
+
+    #[test]
+    fn test_validate_honorary_pool() {
+        let (base_mint, quote_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let tokens = DammV2PoolTokens {
+            token_a_mint: quote_mint,
+            token_b_mint: base_mint,
+            token_a_vault: Pubkey::new_unique(),
+            token_b_vault: Pubkey::new_unique(),
+        };
+        let honorary_pool = HonoraryPool {
+            pool: Pubkey::new_unique(),
+            base_vault: tokens.token_b_vault,
+            quote_vault: tokens.token_a_vault,
+            base_is_token_a: false,
+            reference_price_q64: 0,
+        };
+        let mut lamports = 0u64;
+        let mut data = create_pool_data(&tokens);
+        let owner = DAMM_V2_PROGRAM_ID;
+        let pool = AccountInfo::new(&honorary_pool.pool, false, false, &mut lamports, &mut data, &owner, false, 0);
+
+        assert_eq!(DammV2PoolTokens::try_from_account(&pool).unwrap(), tokens);
+        validate_honorary_pool(&honorary_pool, &pool, &base_mint, &quote_mint).unwrap();
+
+        // Swapped vaults, the wrong token order or another quote mint are rejected
+        let swapped = HonoraryPool { base_vault: tokens.token_a_vault, quote_vault: tokens.token_b_vault, ..honorary_pool };
+        assert!(validate_honorary_pool(&swapped, &pool, &base_mint, &quote_mint).is_err());
+        let reordered = HonoraryPool { base_is_token_a: true, ..honorary_pool };
+        assert!(validate_honorary_pool(&reordered, &pool, &base_mint, &quote_mint).is_err());
+        assert!(validate_honorary_pool(&honorary_pool, &pool, &base_mint, &Pubkey::new_unique()).is_err());
+        let elsewhere = HonoraryPool { pool: Pubkey::new_unique(), ..honorary_pool };
+        assert!(validate_honorary_pool(&elsewhere, &pool, &base_mint, &quote_mint).is_err());
+    }
+
+    #[test]
+    fn test_pool_account_must_be_damm_v2() {
+        let tokens = DammV2PoolTokens {
+            token_a_mint: Pubkey::new_unique(),
+            token_b_mint: Pubkey::new_unique(),
+            token_a_vault: Pubkey::new_unique(),
+            token_b_vault: Pubkey::new_unique(),
+        };
+        let key = Pubkey::new_unique();
+        let mut lamports = 0u64;
+        let mut data = create_pool_data(&tokens);
+        let owner = Pubkey::new_unique();
+        let pool = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+        assert!(DammV2PoolTokens::try_from_account(&pool).is_err());
+
+        let mut lamports = 0u64;
+        let mut data = create_pool_data(&tokens);
+        data[0] ^= 1;
+        let owner = DAMM_V2_PROGRAM_ID;
+        let pool = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+        assert!(DammV2PoolTokens::try_from_account(&pool).is_err());
+    }
+}
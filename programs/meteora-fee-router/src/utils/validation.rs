@@ -741,14 +741,15 @@ pub fn validate_pagination_params(
     Ok(())
 }
 
-/// Validate 24-hour cooldown
+/// Validate the distribution period cooldown (`period_secs`, 24 hours by default)
 pub fn validate_cooldown(
     last_distribution_ts: i64,
     current_ts: i64,
+    period_secs: i64,
 ) -> Result<()> {
     let elapsed = current_ts.saturating_sub(last_distribution_ts);
     require!(
-        elapsed >= crate::constants::TWENTY_FOUR_HOURS,
+        elapsed >= period_secs,
         ErrorCode::CooldownNotElapsed
     );
     
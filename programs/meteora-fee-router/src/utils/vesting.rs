@@ -0,0 +1,415 @@
+use anchor_lang::prelude::*;
+use crate::error::ErrorCode;
+use crate::state::PolicyConfig;
+use crate::utils::jupiter_lock::JupiterLockSource;
+use crate::utils::math::calculate_time_weighted_locked;
+use crate::utils::streamflow::StreamflowSource;
+use crate::utils::weights::WeightWindow;
+use std::collections::{HashMap, HashSet};
+
+/// Cliff-plus-periodic unlock schedule shared by all supported lockers
+///
+/// Both Streamflow streams and Jupiter Lock escrows release a fixed amount at the
+/// cliff and then `amount_per_period` every `period` seconds until `end_time`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VestingSchedule {
+    pub deposited_amount: u64,
+    pub withdrawn_amount: u64,
+    pub start_time: i64,
+    pub cliff_time: i64,
+    pub cliff_amount: u64,
+    pub period: u64,
+    pub amount_per_period: u64,
+    pub end_time: i64,
+    pub closed: bool,
+}
+
+impl VestingSchedule {
+    /// Calculate the still-locked amount at `current_timestamp`
+    pub fn locked_at(&self, current_timestamp: i64) -> u64 {
+        // If the lock is closed, no tokens are locked
+        if self.closed {
+            return 0;
+        }
+
+        // Before the start or the cliff, everything not yet withdrawn is locked
+        if current_timestamp < self.start_time || current_timestamp < self.cliff_time {
+            return self.deposited_amount.saturating_sub(self.withdrawn_amount);
+        }
+
+        // Past the end time, nothing is locked
+        if current_timestamp >= self.end_time {
+            return 0;
+        }
+
+        // Instant unlock if no period
+        if self.period == 0 {
+            return 0;
+        }
+
+        // unlocked = cliff_amount + periods_elapsed * amount_per_period, capped at deposited
+        let periods_elapsed = (current_timestamp - self.cliff_time) as u64 / self.period;
+        let unlocked = self.cliff_amount
+            .saturating_add(periods_elapsed.saturating_mul(self.amount_per_period))
+            .min(self.deposited_amount);
+
+        self.deposited_amount.saturating_sub(unlocked)
+    }
+}
+
+/// Investor position parsed from a vesting account of any supported locker
+#[derive(Debug, Clone)]
+pub struct VestingPosition {
+    pub recipient: Pubkey,
+    pub mint: Pubkey,
+    pub schedule: VestingSchedule,
+}
+
+impl VestingPosition {
+    /// Locked amount of this position at `current_timestamp`
+    pub fn locked_at(&self, current_timestamp: i64) -> u64 {
+        self.schedule.locked_at(current_timestamp)
+    }
+
+    /// Average locked amount of this position over `window`
+    pub fn average_locked(&self, window: WeightWindow) -> Result<u64> {
+        calculate_time_weighted_locked(&self.schedule, window.start_ts, window.end_ts)
+    }
+
+    /// Total amount originally deposited into the lock
+    pub fn total_allocation(&self) -> u64 {
+        self.schedule.deposited_amount
+    }
+}
+
+/// A vesting program whose accounts can be used as a source of investor locked amounts
+pub trait VestingSource: Sync {
+    /// Program that owns this locker's vesting accounts
+    fn owner_program(&self) -> Pubkey;
+
+    /// Validate and parse a vesting account owned by `owner_program()`
+    fn parse(&self, account: &AccountInfo) -> Result<VestingPosition>;
+}
+
+/// All vesting programs the router can read locked amounts from
+pub static VESTING_SOURCES: [&dyn VestingSource; 2] = [&StreamflowSource, &JupiterLockSource];
+
+/// Find the vesting source owning `owner`
+pub fn vesting_source_for(owner: &Pubkey) -> Result<&'static dyn VestingSource> {
+    VESTING_SOURCES
+        .iter()
+        .copied()
+        .find(|source| source.owner_program() == *owner)
+        .ok_or_else(|| {
+            msg!("Unsupported vesting program owner: {}", owner);
+            ErrorCode::UnsupportedVestingProgram.into()
+        })
+}
+
+/// Investor data aggregated from vesting accounts
+#[derive(Debug, Clone)]
+pub struct InvestorData {
+    pub wallet: Pubkey,
+    pub locked_amount: u64,
+    pub total_allocation: u64,
+    pub stream_accounts: Vec<Pubkey>,
+}
+
+/// Vesting programs accepted by a policy, used to read investor positions
+pub struct VestingRegistry<'a> {
+    pub allowed_programs: &'a [Pubkey],
+    pub expected_mint: Pubkey,
+}
+
+impl<'a> VestingRegistry<'a> {
+    /// Registry of the vesting programs allowed by a policy
+    pub fn for_policy(policy_config: &'a PolicyConfig) -> Self {
+        Self {
+            allowed_programs: policy_config.allowed_vesting_programs(),
+            expected_mint: policy_config.quote_mint,
+        }
+    }
+
+    /// Find the vesting source for `owner` if the policy allows it
+    pub fn source_for(&self, owner: &Pubkey) -> Result<&'static dyn VestingSource> {
+        if !self.allowed_programs.contains(owner) {
+            msg!("Vesting program {} is not allowed by policy", owner);
+            return Err(ErrorCode::VestingProgramNotAllowed.into());
+        }
+
+        vesting_source_for(owner)
+    }
+
+    /// Parse a vesting account by dispatching on its owner and validate its mint
+    pub fn read_position(&self, account: &AccountInfo) -> Result<VestingPosition> {
+        let position = self.source_for(account.owner)?.parse(account)?;
+
+        // Validate the mint matches expected token
+        if position.mint != self.expected_mint {
+            msg!("Vesting account {} mint mismatch: expected {}, got {}",
+                 account.key(), self.expected_mint, position.mint);
+            return Err(ErrorCode::InvalidStreamMint.into());
+        }
+
+        Ok(position)
+    }
+
+    /// Aggregate investor data from multiple vesting accounts, one entry per recipient
+    /// Accounts may belong to any allowed locker and are dispatched by owner
+    pub fn aggregate_investor_data(
+        &self,
+        vesting_accounts: &[AccountInfo],
+        weight_window: WeightWindow,
+    ) -> Result<Vec<InvestorData>> {
+        let mut investor_map: HashMap<Pubkey, InvestorData> = HashMap::new();
+
+        for vesting_account in vesting_accounts {
+            // Validate and parse the vesting account
+            let position = self.read_position(vesting_account)?;
+
+            // Average locked amount for this position over the weight window
+            let locked_amount = position.average_locked(weight_window)?;
+
+            // Aggregate data by recipient (investor wallet)
+            let investor_data = investor_map.entry(position.recipient).or_insert(InvestorData {
+                wallet: position.recipient,
+                locked_amount: 0,
+                total_allocation: 0,
+                stream_accounts: Vec::new(),
+            });
+
+            investor_data.locked_amount = investor_data.locked_amount.saturating_add(locked_amount);
+            investor_data.total_allocation = investor_data.total_allocation.saturating_add(position.total_allocation());
+            investor_data.stream_accounts.push(vesting_account.key());
+        }
+
+        // Sorted by wallet so every page of the day sees the same investor order
+        let mut investors: Vec<InvestorData> = investor_map.into_values().collect();
+        investors.sort_by_key(|investor| investor.wallet);
+        Ok(investors)
+    }
+
+    /// Calculate total locked amount across all investors
+    pub fn calculate_total_locked(
+        &self,
+        vesting_accounts: &[AccountInfo],
+        weight_window: WeightWindow,
+    ) -> Result<u64> {
+        let mut total_locked = 0u64;
+
+        for vesting_account in vesting_accounts {
+            let position = self.read_position(vesting_account)?;
+            total_locked = total_locked.saturating_add(position.average_locked(weight_window)?);
+        }
+
+        Ok(total_locked)
+    }
+
+    /// Get investor count from vesting accounts (deduplicated by recipient)
+    pub fn get_unique_investor_count(&self, vesting_accounts: &[AccountInfo]) -> Result<usize> {
+        let mut unique_recipients = HashSet::new();
+
+        for vesting_account in vesting_accounts {
+            let position = self.read_position(vesting_account)?;
+            unique_recipients.insert(position.recipient);
+        }
+
+        Ok(unique_recipients.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::jupiter_lock::{JUPITER_LOCK_PROGRAM_ID, VestingEscrow, VESTING_ESCROW_DISCRIMINATOR};
+    use crate::utils::streamflow::{StreamflowStream, STREAMFLOW_PROGRAM_ID};
+
+    fn create_schedule() -> VestingSchedule {
+        VestingSchedule {
+            deposited_amount: 1_000_000,
+            withdrawn_amount: 0,
+            start_time: 1000,
+            cliff_time: 1000,
+            cliff_amount: 0,
+            period: 100,
+            amount_per_period: 100_000,
+            end_time: 2000,
+            closed: false,
+        }
+    }
+
+    #[test]
+    fn test_schedule_locked_at_boundaries() {
+        let schedule = create_schedule();
+
+        assert_eq!(schedule.locked_at(500), 1_000_000);
+        assert_eq!(schedule.locked_at(1000), 1_000_000);
+        assert_eq!(schedule.locked_at(1099), 1_000_000);
+        assert_eq!(schedule.locked_at(1100), 900_000);
+        assert_eq!(schedule.locked_at(1999), 100_000);
+        assert_eq!(schedule.locked_at(2000), 0);
+    }
+
+    #[test]
+    fn test_schedule_cliff_amount_released_at_cliff() {
+        let mut schedule = create_schedule();
+        schedule.cliff_time = 1500;
+        schedule.cliff_amount = 250_000;
+
+        assert_eq!(schedule.locked_at(1499), 1_000_000);
+        assert_eq!(schedule.locked_at(1500), 750_000);
+        assert_eq!(schedule.locked_at(1600), 650_000);
+    }
+
+    #[test]
+    fn test_schedule_closed_is_unlocked() {
+        let mut schedule = create_schedule();
+        schedule.closed = true;
+
+        assert_eq!(schedule.locked_at(500), 0);
+    }
+
+    #[test]
+    fn test_vesting_source_dispatch_by_owner() {
+        let streamflow = vesting_source_for(&STREAMFLOW_PROGRAM_ID).unwrap();
+        assert_eq!(streamflow.owner_program(), STREAMFLOW_PROGRAM_ID);
+
+        let jupiter = vesting_source_for(&JUPITER_LOCK_PROGRAM_ID).unwrap();
+        assert_eq!(jupiter.owner_program(), JUPITER_LOCK_PROGRAM_ID);
+
+        assert!(vesting_source_for(&Pubkey::new_unique()).is_err());
+    }
+
+    fn create_stream_account_data(investor: Pubkey, mint: Pubkey) -> Vec<u8> {
+        // Streamflow stream: 1M over 1000..2000 in 100s periods
+        let mut data = vec![0u8; StreamflowStream::MIN_ACCOUNT_SIZE];
+        data[0..8].copy_from_slice(&1u64.to_le_bytes());
+        data[33..41].copy_from_slice(&2000i64.to_le_bytes());
+        data[113..145].copy_from_slice(investor.as_ref());
+        data[177..209].copy_from_slice(mint.as_ref());
+        data[409..417].copy_from_slice(&1000i64.to_le_bytes());
+        data[417..425].copy_from_slice(&1_000_000u64.to_le_bytes());
+        data[425..433].copy_from_slice(&100u64.to_le_bytes());
+        data[433..441].copy_from_slice(&100_000u64.to_le_bytes());
+        data[441..449].copy_from_slice(&1000i64.to_le_bytes());
+        data
+    }
+
+    fn create_escrow_account_data(investor: Pubkey, mint: Pubkey) -> Vec<u8> {
+        // Jupiter Lock escrow: 500k over 1000..2000 in 100s periods
+        let mut data = vec![0u8; VestingEscrow::ACCOUNT_SIZE];
+        data[0..8].copy_from_slice(&VESTING_ESCROW_DISCRIMINATOR);
+        data[8..40].copy_from_slice(investor.as_ref());
+        data[40..72].copy_from_slice(mint.as_ref());
+        data[144..152].copy_from_slice(&1000u64.to_le_bytes());
+        data[152..160].copy_from_slice(&100u64.to_le_bytes());
+        data[168..176].copy_from_slice(&50_000u64.to_le_bytes());
+        data[176..184].copy_from_slice(&10u64.to_le_bytes());
+        data[192..200].copy_from_slice(&1000u64.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn test_aggregate_mixed_streamflow_and_jupiter_lock() {
+        let investor = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+
+        let stream_key = Pubkey::new_unique();
+        let mut stream_lamports = 0u64;
+        let mut stream_data = create_stream_account_data(investor, mint);
+        let stream_account = AccountInfo::new(
+            &stream_key, false, false, &mut stream_lamports, &mut stream_data, &STREAMFLOW_PROGRAM_ID, false, 0,
+        );
+
+        let escrow_key = Pubkey::new_unique();
+        let mut escrow_lamports = 0u64;
+        let mut escrow_data = create_escrow_account_data(investor, mint);
+        let escrow_account = AccountInfo::new(
+            &escrow_key, false, false, &mut escrow_lamports, &mut escrow_data, &JUPITER_LOCK_PROGRAM_ID, false, 0,
+        );
+
+        let accounts = vec![stream_account, escrow_account];
+        let allowed = [STREAMFLOW_PROGRAM_ID, JUPITER_LOCK_PROGRAM_ID];
+        let registry = VestingRegistry { allowed_programs: &allowed, expected_mint: mint };
+
+        let investors = registry.aggregate_investor_data(&accounts, WeightWindow::at(1500)).unwrap();
+        assert_eq!(investors.len(), 1);
+        assert_eq!(investors[0].wallet, investor);
+        assert_eq!(investors[0].locked_amount, 500_000 + 250_000);
+        assert_eq!(investors[0].total_allocation, 1_500_000);
+        assert_eq!(investors[0].stream_accounts, vec![stream_key, escrow_key]);
+
+        assert_eq!(registry.calculate_total_locked(&accounts, WeightWindow::at(1500)).unwrap(), 750_000);
+        assert_eq!(registry.get_unique_investor_count(&accounts).unwrap(), 1);
+
+        // Averaged over the whole schedule each lock counts 55% of its deposit
+        let window = WeightWindow { start_ts: 1000, end_ts: 2000 };
+        assert_eq!(registry.calculate_total_locked(&accounts, window).unwrap(), 550_000 + 275_000);
+
+        // Wrong mint is rejected for either locker
+        let wrong_mint = VestingRegistry { allowed_programs: &allowed, expected_mint: Pubkey::new_unique() };
+        assert!(wrong_mint.calculate_total_locked(&accounts[..1], WeightWindow::at(1500)).is_err());
+        assert!(wrong_mint.calculate_total_locked(&accounts[1..], WeightWindow::at(1500)).is_err());
+    }
+
+    #[test]
+    fn test_registry_rejects_programs_not_allowed_by_policy() {
+        let investor = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+
+        let escrow_key = Pubkey::new_unique();
+        let mut escrow_lamports = 0u64;
+        let mut escrow_data = create_escrow_account_data(investor, mint);
+        let escrow_account = AccountInfo::new(
+            &escrow_key, false, false, &mut escrow_lamports, &mut escrow_data, &JUPITER_LOCK_PROGRAM_ID, false, 0,
+        );
+
+        // Jupiter Lock is a known source but this policy only accepts Streamflow
+        let allowed = [STREAMFLOW_PROGRAM_ID];
+        let registry = VestingRegistry { allowed_programs: &allowed, expected_mint: mint };
+        assert!(registry.read_position(&escrow_account).is_err());
+
+        // Allowed but unknown programs are still rejected
+        let unknown = Pubkey::new_unique();
+        let allowed = [unknown];
+        let registry = VestingRegistry { allowed_programs: &allowed, expected_mint: mint };
+        assert!(registry.source_for(&unknown).is_err());
+    }
+
+    #[test]
+    fn test_aggregate_dedups_recipients_and_sorts_by_wallet() {
+        let mint = Pubkey::new_unique();
+        let investor_a = Pubkey::new_unique();
+        let investor_b = Pubkey::new_unique();
+
+        // Investor A's locks are split around investor B's, as they would be across pages
+        let keys = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        let mut lamports = [0u64; 3];
+        let mut data = [
+            create_stream_account_data(investor_a, mint),
+            create_stream_account_data(investor_b, mint),
+            create_escrow_account_data(investor_a, mint),
+        ];
+        let owners = [STREAMFLOW_PROGRAM_ID, STREAMFLOW_PROGRAM_ID, JUPITER_LOCK_PROGRAM_ID];
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .zip(owners.iter())
+            .map(|(((key, lamports), data), owner)| {
+                AccountInfo::new(key, false, false, lamports, data, owner, false, 0)
+            })
+            .collect();
+
+        let allowed = [STREAMFLOW_PROGRAM_ID, JUPITER_LOCK_PROGRAM_ID];
+        let registry = VestingRegistry { allowed_programs: &allowed, expected_mint: mint };
+        let investors = registry.aggregate_investor_data(&accounts, WeightWindow::at(1500)).unwrap();
+
+        assert_eq!(investors.len(), 2);
+        assert!(investors[0].wallet < investors[1].wallet);
+
+        let a = investors.iter().find(|inv| inv.wallet == investor_a).unwrap();
+        assert_eq!(a.locked_amount, 750_000);
+        assert_eq!(a.stream_accounts, vec![keys[0], keys[2]]);
+    }
+}
@@ -0,0 +1,247 @@
+use anchor_lang::prelude::*;
+use crate::state::{PolicyConfig, WeightMode};
+use crate::utils::staking::StakingReader;
+use crate::utils::vesting::{InvestorData, VestingRegistry};
+use std::collections::{HashMap, HashSet};
+
+/// Time window investor weights are measured over
+///
+/// Vesting weights are the average locked amount over `[start_ts, end_ts)`; staked
+/// balances are read as they are now. An empty window samples at `end_ts`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WeightWindow {
+    pub start_ts: i64,
+    pub end_ts: i64,
+}
+
+impl WeightWindow {
+    /// Point-in-time window at `timestamp`
+    pub fn at(timestamp: i64) -> Self {
+        Self { start_ts: timestamp, end_ts: timestamp }
+    }
+
+    /// Split the window into `epochs` consecutive windows, oldest first
+    ///
+    /// Each window ends `period_secs` after the previous one, the last ending at
+    /// `end_ts`; the oldest also covers anything before it back to `start_ts`.
+    pub fn split_epochs(&self, epochs: u64, period_secs: i64) -> Vec<WeightWindow> {
+        let epochs = epochs.max(1);
+        let mut windows = Vec::with_capacity(epochs as usize);
+        let mut start_ts = self.start_ts;
+        for remaining in (0..epochs).rev() {
+            let offset = (remaining as i64).saturating_mul(period_secs);
+            let end_ts = self.end_ts.saturating_sub(offset).max(start_ts);
+            windows.push(WeightWindow { start_ts, end_ts });
+            start_ts = end_ts;
+        }
+        windows
+    }
+}
+
+/// Source of investor weights selected by the policy's `weight_mode`
+///
+/// In `LockedVesting` mode an investor's weight is their still-locked amount; in
+/// `StakedBalance` mode it is their staked balance. Either way the weights are
+/// reported through `InvestorData::locked_amount` so the payout math is shared.
+pub enum InvestorWeightSource<'a> {
+    Vesting(VestingRegistry<'a>),
+    Staking(StakingReader<'a>),
+}
+
+impl<'a> InvestorWeightSource<'a> {
+    /// Weight source configured by a policy
+    pub fn for_policy(policy_config: &'a PolicyConfig) -> Self {
+        match policy_config.weight_mode {
+            WeightMode::LockedVesting => Self::Vesting(VestingRegistry::for_policy(policy_config)),
+            WeightMode::StakedBalance => Self::Staking(StakingReader::new(&policy_config.staking_layout)),
+        }
+    }
+
+    /// Aggregate investor weights from the weight accounts, one entry per wallet sorted by wallet
+    pub fn aggregate_investor_data(
+        &self,
+        weight_accounts: &[AccountInfo],
+        weight_window: WeightWindow,
+    ) -> Result<Vec<InvestorData>> {
+        match self {
+            Self::Vesting(registry) => registry.aggregate_investor_data(weight_accounts, weight_window),
+            Self::Staking(reader) => {
+                let mut investor_map: HashMap<Pubkey, InvestorData> = HashMap::new();
+
+                for stake_account in weight_accounts {
+                    let position = reader.read_position(stake_account)?;
+
+                    let investor_data = investor_map.entry(position.owner).or_insert(InvestorData {
+                        wallet: position.owner,
+                        locked_amount: 0,
+                        total_allocation: 0,
+                        stream_accounts: Vec::new(),
+                    });
+
+                    investor_data.locked_amount = investor_data.locked_amount.saturating_add(position.staked_amount);
+                    investor_data.total_allocation = investor_data.total_allocation.saturating_add(position.staked_amount);
+                    investor_data.stream_accounts.push(stake_account.key());
+                }
+
+                // Sorted by wallet so every page of the day sees the same investor order
+                let mut investors: Vec<InvestorData> = investor_map.into_values().collect();
+                investors.sort_by_key(|investor| investor.wallet);
+                Ok(investors)
+            }
+        }
+    }
+
+    /// Sum of all investor weights
+    pub fn calculate_total_weight(
+        &self,
+        weight_accounts: &[AccountInfo],
+        weight_window: WeightWindow,
+    ) -> Result<u64> {
+        match self {
+            Self::Vesting(registry) => registry.calculate_total_locked(weight_accounts, weight_window),
+            Self::Staking(reader) => {
+                let mut total_staked = 0u64;
+
+                for stake_account in weight_accounts {
+                    let position = reader.read_position(stake_account)?;
+                    total_staked = total_staked.saturating_add(position.staked_amount);
+                }
+
+                Ok(total_staked)
+            }
+        }
+    }
+
+    /// Get investor count from weight accounts (deduplicated by wallet)
+    pub fn get_unique_investor_count(&self, weight_accounts: &[AccountInfo]) -> Result<usize> {
+        match self {
+            Self::Vesting(registry) => registry.get_unique_investor_count(weight_accounts),
+            Self::Staking(reader) => {
+                let mut unique_owners = HashSet::new();
+
+                for stake_account in weight_accounts {
+                    unique_owners.insert(reader.read_position(stake_account)?.owner);
+                }
+
+                Ok(unique_owners.len())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::TWENTY_FOUR_HOURS;
+    use crate::state::StakingLayout;
+    use crate::utils::math::calculate_distribution;
+
+    fn create_stake_policy() -> PolicyConfig {
+        let mut policy_config = PolicyConfig {
+            vault: Pubkey::new_unique(),
+            quote_mint: Pubkey::new_unique(),
+            creator_wallet: Pubkey::new_unique(),
+            investor_fee_share_bps: 8000,
+            min_payout_lamports: 1000,
+            y0_total_allocation: 4_000_000,
+            bump: 255,
+            ..PolicyConfig::default()
+        };
+        policy_config
+            .configure_weight_mode(
+                WeightMode::StakedBalance,
+                Some(StakingLayout {
+                    program_id: Pubkey::new_unique(),
+                    stake_mint: Pubkey::new_unique(),
+                    discriminator: [0u8; 8],
+                    owner_offset: 0,
+                    mint_offset: 32,
+                    amount_offset: 64,
+                }),
+            )
+            .unwrap();
+        policy_config
+    }
+
+    fn create_stake_data(owner: Pubkey, mint: Pubkey, amount: u64) -> Vec<u8> {
+        let mut data = vec![0u8; 72];
+        data[0..32].copy_from_slice(owner.as_ref());
+        data[32..64].copy_from_slice(mint.as_ref());
+        data[64..72].copy_from_slice(&amount.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn test_stake_weighted_aggregation() {
+        let policy_config = create_stake_policy();
+        let layout = policy_config.staking_layout;
+        let alice = Pubkey::new_unique();
+        let bob = Pubkey::new_unique();
+
+        let keys = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        let mut lamports = [0u64; 3];
+        let mut data = [
+            create_stake_data(alice, layout.stake_mint, 1_000_000),
+            create_stake_data(alice, layout.stake_mint, 500_000),
+            create_stake_data(bob, layout.stake_mint, 500_000),
+        ];
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .map(|((key, lamports), data)| {
+                AccountInfo::new(key, false, false, lamports, data, &layout.program_id, false, 0)
+            })
+            .collect();
+
+        let source = InvestorWeightSource::for_policy(&policy_config);
+        assert!(matches!(source, InvestorWeightSource::Staking(_)));
+
+        let total_weight = source.calculate_total_weight(&accounts, WeightWindow::at(0)).unwrap();
+        assert_eq!(total_weight, 2_000_000);
+        assert_eq!(source.get_unique_investor_count(&accounts).unwrap(), 2);
+
+        let investors = source.aggregate_investor_data(&accounts, WeightWindow::at(0)).unwrap();
+        let alice_data = investors.iter().find(|inv| inv.wallet == alice).unwrap();
+        assert_eq!(alice_data.locked_amount, 1_500_000);
+        assert_eq!(alice_data.stream_accounts.len(), 2);
+
+        // Half of Y0 is staked, so the investor share is limited to 50%
+        let (investor_amount, creator_amount) = calculate_distribution(
+            100_000,
+            total_weight,
+            policy_config.y0_total_allocation,
+            policy_config.investor_fee_share_bps,
+        )
+        .unwrap();
+        assert_eq!(investor_amount, 50_000);
+        assert_eq!(creator_amount, 50_000);
+    }
+
+    #[test]
+    fn test_vesting_mode_is_default() {
+        let mut policy_config = create_stake_policy();
+        policy_config.configure_weight_mode(WeightMode::LockedVesting, None).unwrap();
+
+        assert!(matches!(
+            InvestorWeightSource::for_policy(&policy_config),
+            InvestorWeightSource::Vesting(_)
+        ));
+    }
+
+    #[test]
+    fn test_split_epochs() {
+        let day = TWENTY_FOUR_HOURS;
+        let window = WeightWindow { start_ts: 1_000, end_ts: 1_000 + 3 * day + 500 };
+
+        let windows = window.split_epochs(3, day);
+        assert_eq!(windows, vec![
+            WeightWindow { start_ts: 1_000, end_ts: 1_000 + day + 500 },
+            WeightWindow { start_ts: 1_000 + day + 500, end_ts: 1_000 + 2 * day + 500 },
+            WeightWindow { start_ts: 1_000 + 2 * day + 500, end_ts: 1_000 + 3 * day + 500 },
+        ]);
+
+        // A single epoch is the window itself
+        assert_eq!(window.split_epochs(1, day), vec![window]);
+    }
+}